@@ -40,6 +40,12 @@ else
   sudo cp "$PACKAGE_DIR/default_config.toml" "$CONFIG_PATH"
 fi
 
+# Earlier installs had the socket held by a socket unit
+if [ -f /etc/systemd/system/cooled-slit-controller.socket ]; then
+  sudo systemctl disable --now cooled-slit-controller.socket
+  sudo rm /etc/systemd/system/cooled-slit-controller.socket
+fi
+
 echo "Copying systemd service files..."
 sudo cp "$SCRIPT_DIR/service_files/cooled-slit-controller.service" /etc/systemd/system/
 sudo cp "$SCRIPT_DIR/service_files/cooled-slit-controller-restart.timer" /etc/systemd/system/
@@ -1,5 +1,5 @@
 use crate::command_executor::motor::commands::{CommandResponse, MotorCommand};
-use em2rs::StateParams;
+use em2rs::{SoftLimits, StateParams};
 use std::io;
 use utilities::command_executor::CommandSender;
 
@@ -102,4 +102,94 @@ impl Em2rsCommandSender {
             )),
         }
     }
+
+    pub async fn get_drive_temperature(&self, axis: usize) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetDriveTemperature { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Value(value) => Ok(value),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_bus_voltage(&self, axis: usize) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetBusVoltage { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Value(value) => Ok(value),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_soft_limits(&self, axis: usize) -> io::Result<SoftLimits> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetSoftLimits { axis })
+            .await?;
+
+        match response {
+            CommandResponse::SoftLimits(limits) => Ok(limits),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_soft_limits(&self, axis: usize, limits: SoftLimits) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::SetSoftLimits { axis, limits })
+            .await?;
+
+        match response {
+            CommandResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_soft_limits_enabled(&self, axis: usize) -> io::Result<bool> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetSoftLimitsEnabled { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Enabled(enabled) => Ok(enabled),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_soft_limits_enabled(&self, axis: usize, enabled: bool) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::SetSoftLimitsEnabled { axis, enabled })
+            .await?;
+
+        match response {
+            CommandResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
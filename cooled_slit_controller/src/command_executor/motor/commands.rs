@@ -1,4 +1,4 @@
-use em2rs::StateParams;
+use em2rs::{SoftLimits, StateParams};
 use std::io;
 use utilities::command_executor::Command;
 
@@ -12,10 +12,19 @@ pub enum MotorCommand {
     SetDeceleration { axis: usize, deceleration: u16 },
     Stop { axis: usize },
     Move { axis: usize, steps: i32 },
+    GetDriveTemperature { axis: usize },
+    GetBusVoltage { axis: usize },
+    GetSoftLimits { axis: usize },
+    SetSoftLimits { axis: usize, limits: SoftLimits },
+    GetSoftLimitsEnabled { axis: usize },
+    SetSoftLimitsEnabled { axis: usize, enabled: bool },
 }
 
 pub enum CommandResponse {
     State(StateParams),
+    Value(f32),
+    SoftLimits(SoftLimits),
+    Enabled(bool),
     Ok,
 }
 
@@ -35,6 +44,14 @@ impl Command for MotorCommand {
             }
             MotorCommand::Stop { axis } => handler.stop(axis),
             MotorCommand::Move { axis, steps } => handler.move_relative(axis, steps),
+            MotorCommand::GetDriveTemperature { axis } => handler.get_drive_temperature(axis),
+            MotorCommand::GetBusVoltage { axis } => handler.get_bus_voltage(axis),
+            MotorCommand::GetSoftLimits { axis } => handler.get_soft_limits(axis),
+            MotorCommand::SetSoftLimits { axis, limits } => handler.set_soft_limits(axis, limits),
+            MotorCommand::GetSoftLimitsEnabled { axis } => handler.get_soft_limits_enabled(axis),
+            MotorCommand::SetSoftLimitsEnabled { axis, enabled } => {
+                handler.set_soft_limits_enabled(axis, enabled)
+            }
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::command_executor::sensors::commands::{CommandResponse, SensorsCommand};
+use icpcon::RtdType;
 use std::io;
 use utilities::command_executor::CommandSender;
 
@@ -41,4 +42,49 @@ impl SensorsCommandSender {
             )),
         }
     }
+
+    pub async fn get_flow(&self, channel: u8) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::Flow { channel })
+            .await?;
+
+        match response {
+            CommandResponse::Flow(flow) => Ok(flow),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_rtd_type(&self, channel: u8) -> io::Result<RtdType> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::GetRtdType { channel })
+            .await?;
+
+        match response {
+            CommandResponse::RtdType(rtd_type) => Ok(rtd_type),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_rtd_type(&self, channel: u8, rtd_type: RtdType) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::SetRtdType { channel, rtd_type })
+            .await?;
+
+        match response {
+            CommandResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
@@ -1,18 +1,25 @@
 use std::io;
 
 use crate::command_executor::sensors::SensorsHandler;
+use icpcon::RtdType;
 use utilities::command_executor::Command;
 
 #[derive(Clone)]
 pub enum SensorsCommand {
     Position { axis: u8 },
     Temperature { axis: u8 },
+    Flow { channel: u8 },
+    GetRtdType { channel: u8 },
+    SetRtdType { channel: u8, rtd_type: RtdType },
 }
 
 #[derive(Debug)]
 pub enum CommandResponse {
     Temperature(f32),
     Position(f32),
+    Flow(f32),
+    RtdType(RtdType),
+    Ok,
 }
 
 impl Command for SensorsCommand {
@@ -27,6 +34,15 @@ impl Command for SensorsCommand {
             SensorsCommand::Temperature { axis } => handler
                 .get_temperature(axis)
                 .map(|temperature| CommandResponse::Temperature(temperature)),
+            SensorsCommand::Flow { channel } => handler
+                .get_flow(channel)
+                .map(|flow| CommandResponse::Flow(flow)),
+            SensorsCommand::GetRtdType { channel } => handler
+                .get_rtd_type(channel)
+                .map(|rtd_type| CommandResponse::RtdType(rtd_type)),
+            SensorsCommand::SetRtdType { channel, rtd_type } => handler
+                .set_rtd_type(channel, rtd_type)
+                .map(|_| CommandResponse::Ok),
         }
     }
 }
@@ -1,44 +1,170 @@
 use crate::command_executor::sensors::commands::SensorsCommand;
-use icpcon::M7015;
-use lir::LIR;
-use std::io;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, modbus::ModbusError};
+use icpcon::{AlarmOutput, IcpconModule as _, M7015, M7017, RtdType};
+use lir::{LIR, LirBus};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+use utilities::{
+    command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, modbus::ModbusError,
+    transport::Transport,
+};
 pub mod command_sender;
 pub mod commands;
 
-pub struct SensorsHandler {
-    tcp_stream: LazyTcpStream,
-    encoders: Vec<LIR>,
+// Axes poll their positions one after the other, the first request reads all
+// encoders and the rest are answered from that batch
+const BATCH_MAX_AGE: Duration = Duration::from_millis(20);
+
+struct PositionBatch {
+    at: Instant,
+    positions: Vec<Result<f32, String>>,
+}
+
+// Knives at or above the threshold, the output stays asserted while any is.
+// A knife that cannot be read keeps its last state.
+struct OverTemperature {
+    output: AlarmOutput,
+    threshold: f32,
+    hot: Vec<bool>,
+}
+
+pub struct SensorsHandler<S = LazyTcpStream> {
+    tcp_stream: S,
+    encoders: LirBus,
+    // Encoder ids in axis order
+    encoder_ids: Vec<u8>,
+    positions: Option<PositionBatch>,
     temperature: M7015,
+    flow: Option<M7017>,
+    over_temperature: Option<OverTemperature>,
 }
 
-impl SensorsHandler {
-    pub fn new(tcp_stream: LazyTcpStream, encoders: Vec<LIR>, temperature: M7015) -> Self {
+impl<S: Transport> SensorsHandler<S> {
+    pub fn new(tcp_stream: S, encoders: Vec<LIR>, temperature: M7015) -> Self {
+        let encoders = LirBus::new(encoders);
         Self {
             tcp_stream,
+            encoder_ids: encoders.ids(),
             encoders,
+            positions: None,
             temperature,
+            flow: None,
+            over_temperature: None,
+        }
+    }
+
+    pub fn with_flow(mut self, flow: M7017) -> Self {
+        self.flow = Some(flow);
+        self
+    }
+
+    pub fn with_over_temperature(mut self, output: AlarmOutput, threshold: f32) -> Self {
+        self.over_temperature = Some(OverTemperature {
+            output,
+            threshold,
+            hot: vec![false; self.encoder_ids.len()],
+        });
+        self
+    }
+
+    fn check_over_temperature(&mut self, axis: u8, temperature: f32) {
+        let Some(alarm) = &mut self.over_temperature else {
+            return;
+        };
+
+        if let Some(hot) = alarm.hot.get_mut(axis as usize) {
+            *hot = temperature >= alarm.threshold;
+        }
+
+        let asserted = alarm.hot.iter().any(|hot| *hot);
+        let changed = alarm.output.is_asserted() != Some(asserted);
+        match alarm.output.set(&mut self.tcp_stream, asserted) {
+            Ok(()) if changed && asserted => eprintln!(
+                "Knife {} at {} degrees, over temperature interlock tripped",
+                axis, temperature
+            ),
+            Ok(()) if changed => eprintln!("Over temperature interlock released"),
+            Ok(()) => {}
+            Err(e) => eprintln!("Failed to set the over temperature output: {}", e),
         }
     }
 
     fn get_position(&mut self, axis: u8) -> io::Result<f32> {
-        self.encoders
-            .get(axis as usize)
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, format!("Invalid axis")))?
-            .get_current_measurement(&mut self.tcp_stream, 3)
+        if axis as usize >= self.encoder_ids.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Invalid axis"),
+            ));
+        }
+
+        let fresh = self
+            .positions
+            .as_ref()
+            .is_some_and(|batch| batch.at.elapsed() < BATCH_MAX_AGE);
+        if !fresh {
+            let positions = self
+                .encoders
+                .read_all(&mut self.tcp_stream, &self.encoder_ids)
+                .into_iter()
+                .map(|result| result.map_err(|e| format!("{e}")))
+                .collect();
+            self.positions = Some(PositionBatch {
+                at: Instant::now(),
+                positions,
+            });
+        }
+
+        self.positions
+            .as_ref()
+            .and_then(|batch| batch.positions.get(axis as usize))
+            .cloned()
+            .unwrap_or_else(|| Err(format!("Invalid axis")))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    fn get_temperature(&mut self, axis: u8) -> io::Result<f32> {
+        let temperature = self
+            .temperature
+            .get_current_measurement(&mut self.tcp_stream, axis)
             .map_err(|e| match e {
                 ModbusError::IoError(error) => io::Error::from(error),
                 _ => io::Error::new(io::ErrorKind::Other, format!("{e}")),
-            })
+            })?
+            .checked()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        self.check_over_temperature(axis, temperature);
+        Ok(temperature)
     }
 
-    fn get_temperature(&mut self, axis: u8) -> io::Result<f32> {
+    fn get_rtd_type(&mut self, channel: u8) -> io::Result<RtdType> {
+        self.temperature
+            .get_channel_type(&mut self.tcp_stream, channel)
+            .map_err(io::Error::from)
+    }
+
+    fn set_rtd_type(&mut self, channel: u8, rtd_type: RtdType) -> io::Result<()> {
         self.temperature
-            .get_current_measurement(&mut self.tcp_stream, axis, 3)
+            .set_channel_type(&mut self.tcp_stream, channel, rtd_type)
+            .map_err(io::Error::from)
+    }
+
+    fn get_flow(&mut self, channel: u8) -> io::Result<f32> {
+        let Some(flow) = &self.flow else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "No flow meter configured",
+            ));
+        };
+
+        flow.get_current_measurement(&mut self.tcp_stream, channel)
             .map_err(|e| match e {
                 ModbusError::IoError(error) => io::Error::from(error),
                 _ => io::Error::new(io::ErrorKind::Other, format!("{e}")),
-            })
+            })?
+            .checked()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     }
 }
 
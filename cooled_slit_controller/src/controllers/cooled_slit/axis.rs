@@ -9,14 +9,17 @@ use motarem::axis::{
     state_info::AxisStateInfo,
 };
 use tokio::{sync::Mutex, task::JoinHandle};
-use utilities::motor_controller::{Motor as _, MotorHolder};
+use utilities::{
+    lazy_tcp::ActiveEndpoint,
+    motor_controller::{Motor as _, MotorHolder},
+};
 
 use super::params::MotorParameters;
 use crate::{
     command_executor::{
         motor::command_sender::Em2rsCommandSender, sensors::command_sender::SensorsCommandSender,
     },
-    controllers::cooled_slit::motor::CooledSlitMotor,
+    controllers::cooled_slit::{config::SettlingConfig, motor::CooledSlitMotor, settling::settle},
 };
 
 pub struct CooledSlitAxis {
@@ -29,7 +32,19 @@ pub struct CooledSlitAxis {
     move_thread: Arc<Mutex<Option<JoinHandle<Result<(), String>>>>>,
     is_moving: Arc<AtomicBool>,
 
+    // Set between the end of a move and a level temperature
+    settling: Arc<AtomicBool>,
+    settling_config: Option<SettlingConfig>,
+    temperature_slope: Arc<std::sync::Mutex<Option<f32>>>,
+    // Why the last settling phase ended early, cleared by the next move
+    settling_error: Arc<std::sync::Mutex<Option<String>>>,
+
     steps_per_mm: i32,
+    inverted: bool,
+
+    sensors_gateway: ActiveEndpoint,
+    // Flow meter input of this blade's cooling water
+    flow_channel: Option<u8>,
 }
 
 impl CooledSlitAxis {
@@ -47,10 +62,37 @@ impl CooledSlitAxis {
             motor_cs,
             move_thread: Arc::new(Mutex::new(None)),
             is_moving: Arc::new(AtomicBool::new(false)),
+            settling: Arc::new(AtomicBool::new(false)),
+            settling_config: None,
+            temperature_slope: Arc::new(std::sync::Mutex::new(None)),
+            settling_error: Arc::new(std::sync::Mutex::new(None)),
             steps_per_mm,
+            inverted: false,
+            sensors_gateway: ActiveEndpoint::default(),
+            flow_channel: None,
         }
     }
 
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    pub fn with_settling(mut self, settling_config: Option<SettlingConfig>) -> Self {
+        self.settling_config = settling_config;
+        self
+    }
+
+    pub fn with_sensors_gateway(mut self, sensors_gateway: ActiveEndpoint) -> Self {
+        self.sensors_gateway = sensors_gateway;
+        self
+    }
+
+    pub fn with_flow_channel(mut self, flow_channel: Option<u8>) -> Self {
+        self.flow_channel = flow_channel;
+        self
+    }
+
     pub async fn get_temperature(&self) -> Result<f32, String> {
         self.sensors_cs
             .get_temperature(self.axis as u8)
@@ -66,7 +108,14 @@ impl Axis for CooledSlitAxis {
     }
 
     async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
-        let motor_params = parameters.unwrap_or_default().into();
+        let motor_params: MotorParameters = parameters
+            .unwrap_or_default()
+            .try_into()
+            .map_err(anyhow::Error::msg)?;
+
+        if motor_params.settle == Some(true) && self.settling_config.is_none() {
+            return Err(anyhow::Error::msg("Thermal settling is not configured"));
+        }
 
         self.move_to(position as f32, motor_params)
             .await
@@ -87,17 +136,26 @@ impl Axis for CooledSlitAxis {
             .map_err(|e| anyhow::Error::msg(format!("Failed to get motor state: {}", e)))?;
 
         let is_moving = self.is_moving.load(Ordering::Relaxed);
+        let settling = self.settling.load(Ordering::Relaxed);
 
-        let state = if is_moving {
+        // Settling counts as moving, so clients waiting for On wait for it
+        let state = if is_moving || settling {
             AxisState::Moving
         } else {
             AxisState::On
         };
 
-        let limit_switches = match (
-            motor_state.low_limit_triggered(),
-            motor_state.high_limit_triggered(),
-        ) {
+        let (lower_switch, upper_switch) = match self.inverted {
+            false => (
+                motor_state.low_limit_triggered(),
+                motor_state.high_limit_triggered(),
+            ),
+            true => (
+                motor_state.high_limit_triggered(),
+                motor_state.low_limit_triggered(),
+            ),
+        };
+        let limit_switches = match (lower_switch, upper_switch) {
             (true, true) => LimitSwitches::Both,
             (true, false) => LimitSwitches::Lower,
             (false, true) => LimitSwitches::Upper,
@@ -106,7 +164,11 @@ impl Axis for CooledSlitAxis {
 
         let message = match (motor_state.is_moving(), is_moving) {
             (true, false) => Some("Motor is moving, but axis is not".to_string()),
-            _ => None,
+            _ if settling => Some(match *self.temperature_slope.lock().unwrap() {
+                Some(slope) => format!("settling, {:.3} K/min", slope),
+                None => "settling".to_string(),
+            }),
+            _ => self.settling_error.lock().unwrap().clone(),
         };
 
         Ok(AxisStateInfo {
@@ -127,12 +189,54 @@ impl Axis for CooledSlitAxis {
                 .await
                 .map(|temp| temp as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get temperature: {}", err))),
+            "drive_temperature" => self
+                .motor_cs
+                .get_drive_temperature(self.axis)
+                .await
+                .map(|temp| temp as f64)
+                .map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to get drive temperature: {}", err))
+                }),
+            "bus_voltage" => self
+                .motor_cs
+                .get_bus_voltage(self.axis)
+                .await
+                .map(|voltage| voltage as f64)
+                .map_err(|err| anyhow::Error::msg(format!("Failed to get bus voltage: {}", err))),
+            "settling" => Ok(self.settling.load(Ordering::Relaxed) as u8 as f64),
+            "temperature_slope" => Ok(self
+                .temperature_slope
+                .lock()
+                .unwrap()
+                .map(|slope| slope as f64)
+                .unwrap_or(f64::NAN)),
+            // 0 on the primary gateway, 1 on the secondary
+            "sensors_gateway" => Ok(self.sensors_gateway.is_secondary() as u8 as f64),
+            "water_flow" if self.flow_channel.is_some() => self
+                .sensors_cs
+                .get_flow(self.flow_channel.unwrap())
+                .await
+                .map(|flow| flow as f64)
+                .map_err(|err| anyhow::Error::msg(format!("Failed to get water flow: {}", err))),
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
 
     async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec!["position".to_string(), "temperature".to_string()])
+        let mut params = vec![
+            "position".to_string(),
+            "temperature".to_string(),
+            "drive_temperature".to_string(),
+            "bus_voltage".to_string(),
+            "settling".to_string(),
+            "temperature_slope".to_string(),
+            "sensors_gateway".to_string(),
+        ];
+        if self.flow_channel.is_some() {
+            params.push("water_flow".to_string());
+        }
+
+        Ok(params)
     }
 
     async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
@@ -140,6 +244,9 @@ impl Axis for CooledSlitAxis {
             "velocity".to_string(),
             "acceleration".to_string(),
             "deceleration".to_string(),
+            "position_window".to_string(),
+            "time_limit".to_string(),
+            "settle".to_string(),
         ])
     }
 }
@@ -149,6 +256,8 @@ impl MotorHolder for CooledSlitAxis {
     type MotorState = StateParams;
 
     async fn stop(&self) -> Result<(), String> {
+        self.settling.store(false, Ordering::Relaxed);
+
         if self.is_moving() {
             self.is_moving.store(false, Ordering::Relaxed);
 
@@ -209,6 +318,18 @@ impl MotorHolder for CooledSlitAxis {
         target: f32,
         parameters: &Self::MovementParameters,
     ) -> Result<(), String> {
+        // Only a settling phase can still be running here
+        if let Some(previous) = self.move_thread.lock().await.take() {
+            previous.abort();
+        }
+        self.settling.store(false, Ordering::Relaxed);
+
+        let settling_config = match parameters.settle {
+            Some(true) => self.settling_config.clone(),
+            Some(false) => None,
+            None => self.settling_config.clone().filter(|config| config.always),
+        };
+
         let mut move_thread = CooledSlitMotor::new(
             self.axis,
             self.sensors_cs.clone(),
@@ -218,9 +339,38 @@ impl MotorHolder for CooledSlitAxis {
             parameters.time_limit,
             self.is_moving.clone(),
             self.steps_per_mm,
-        );
+        )
+        .with_inverted(self.inverted);
+
+        let axis = self.axis as u8;
+        let sensors_cs = self.sensors_cs.clone();
+        let settling = self.settling.clone();
+        let temperature_slope = self.temperature_slope.clone();
+        let settling_error = self.settling_error.clone();
+
+        *settling_error.lock().unwrap() = None;
+
+        let handle = tokio::spawn(async move {
+            let result = move_thread.run().await;
+            // Dropping the motor clears is_moving
+            drop(move_thread);
+            result?;
+
+            let Some(config) = settling_config else {
+                return Ok(());
+            };
+
+            settling.store(true, Ordering::Relaxed);
+            let result = settle(axis, &sensors_cs, &config, &settling, &temperature_slope).await;
+            settling.store(false, Ordering::Relaxed);
+
+            if let Err(e) = &result {
+                eprintln!("Axis {}: {}", axis, e);
+                *settling_error.lock().unwrap() = Some(e.clone());
+            }
 
-        let handle = tokio::spawn(async move { move_thread.run().await });
+            result
+        });
 
         let mut move_thread = self.move_thread.lock().await;
         *move_thread = Some(handle);
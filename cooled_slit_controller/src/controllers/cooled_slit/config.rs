@@ -1,4 +1,10 @@
+use icpcon::{RtdType, Scaling};
+use lir::LirLayout;
 use serde::{Deserialize, Serialize};
+use utilities::{
+    archiver::ArchiverConfig, derived::DerivedAttributeConfig, groups::AxisGroupConfig,
+    presets::PresetConfig,
+};
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct CooledSlitAxisConfig {
@@ -9,22 +15,138 @@ pub struct CooledSlitAxisConfig {
     pub em2rs_low_limit: u8,
     pub em2rs_high_limit: u8,
     pub steps_per_mm: i32,
+    // Motor mounted the other way round: steps are sent with the opposite
+    // sign and its limit switches swap places, steps_per_mm stays positive
+    #[serde(default)]
+    pub inverted: bool,
+
+    // Programmed into the drive on startup, so it stops on its own whatever
+    // the host does
+    #[serde(default)]
+    pub soft_limits: Option<SoftLimitsConfig>,
+
+    // Input of the flow meter module that watches this blade's cooling water
+    #[serde(default)]
+    pub flow_channel: Option<u8>,
+
+    // Correction of the knife temperature, e.g. for a module whose range
+    // does not report tenths of a degree
+    #[serde(default)]
+    pub temperature_scaling: Option<Scaling>,
+}
+
+// Steps of the drive's position counter, which starts at 0 on power up
+#[derive(Deserialize, Debug, Serialize, Clone, Copy)]
+pub struct SoftLimitsConfig {
+    pub negative: i32,
+    pub positive: i32,
+}
+
+fn default_flow_range() -> f32 {
+    20.0
+}
+
+// Water-flow transmitters on an M-7017 on the sensors bus, read in the unit
+// of the module's range, mA for the 4-20 mA transmitters
+#[derive(Deserialize, Debug, Serialize, Clone, Copy)]
+pub struct FlowConfig {
+    pub icpcon_id: u8,
+    #[serde(default = "default_flow_range")]
+    pub range: f32,
+}
+
+// Output on the sensors bus that trips the external interlock while any knife
+// is at or above the threshold, in degrees
+#[derive(Deserialize, Debug, Serialize, Clone, Copy)]
+pub struct OverTemperatureConfig {
+    pub icpcon_id: u8,
+    pub channel: u16,
+    pub threshold: f32,
+    #[serde(default)]
+    pub active_low: bool,
+}
+
+fn default_window_s() -> u64 {
+    30
+}
+
+fn default_timeout_s() -> u64 {
+    600
+}
+
+// Post-move wait for the knife temperature to level off, requested per move
+// with the "settle" parameter or for every move with `always`
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct SettlingConfig {
+    // Degrees per minute
+    pub max_slope: f32,
+    #[serde(default = "default_window_s")]
+    pub window_s: u64,
+    #[serde(default = "default_timeout_s")]
+    pub timeout_s: u64,
+    #[serde(default)]
+    pub always: bool,
 }
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct CooledSlitControllerConfig {
     pub sensors_ip: String,
     pub sensors_port: u16,
+    // Spare gateway wired in parallel, taken over when the primary stops
+    // answering. The port defaults to sensors_port.
+    #[serde(default)]
+    pub sensors_secondary_ip: Option<String>,
+    #[serde(default)]
+    pub sensors_secondary_port: Option<u16>,
 
     pub em2rs_ip: String,
     pub em2rs_port: u16,
 
     pub icpcon_id: u8,
+    // Sensor type of the knife RTDs, written to the module on startup when
+    // it differs
+    #[serde(default)]
+    pub rtd_type: Option<RtdType>,
+
+    #[serde(default)]
+    pub flow: Option<FlowConfig>,
+
+    #[serde(default)]
+    pub over_temperature: Option<OverTemperatureConfig>,
+
+    // Register layout of the LIR interface modules, the commissioned one
+    // when unset
+    #[serde(default)]
+    pub lir_layout: LirLayout,
 
     pub upper_axis: CooledSlitAxisConfig,
     pub lower_axis: CooledSlitAxisConfig,
     pub left_axis: CooledSlitAxisConfig,
     pub right_axis: CooledSlitAxisConfig,
+
+    #[serde(default)]
+    pub settling: Option<SettlingConfig>,
+
+    // Attributes computed from other attributes of the same controller
+    #[serde(default)]
+    pub derived_attributes: Vec<DerivedAttributeConfig>,
+
+    // Named sets of axes addressed like a single axis, e.g. `STOP vertical`
+    #[serde(default)]
+    pub axis_groups: Vec<AxisGroupConfig>,
+
+    // Named target sets for all blades, e.g. beam sizes, served as the
+    // "preset" axis
+    #[serde(default)]
+    pub presets: Vec<PresetConfig>,
+
+    // Read-only socket for status displays, motion commands are refused there
+    #[serde(default)]
+    pub observer_socket: Option<String>,
+
+    // Periodic axis samples sent to the facility archive
+    #[serde(default)]
+    pub archiver: Option<ArchiverConfig>,
 }
 
 impl Default for CooledSlitControllerConfig {
@@ -32,11 +154,17 @@ impl Default for CooledSlitControllerConfig {
         Self {
             sensors_ip: "127.0.0.1".to_string(),
             sensors_port: 50051,
+            sensors_secondary_ip: None,
+            sensors_secondary_port: None,
 
             em2rs_ip: "127.0.0.1".to_string(),
             em2rs_port: 50052,
 
             icpcon_id: 1,
+            rtd_type: None,
+            flow: None,
+            over_temperature: None,
+            lir_layout: LirLayout::default(),
 
             upper_axis: CooledSlitAxisConfig {
                 lir_id: 1,
@@ -46,6 +174,10 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                soft_limits: None,
+                flow_channel: None,
+                temperature_scaling: None,
             },
             lower_axis: CooledSlitAxisConfig {
                 lir_id: 2,
@@ -55,6 +187,10 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                soft_limits: None,
+                flow_channel: None,
+                temperature_scaling: None,
             },
             left_axis: CooledSlitAxisConfig {
                 lir_id: 3,
@@ -64,6 +200,10 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                soft_limits: None,
+                flow_channel: None,
+                temperature_scaling: None,
             },
             right_axis: CooledSlitAxisConfig {
                 lir_id: 4,
@@ -73,7 +213,19 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                soft_limits: None,
+                flow_channel: None,
+                temperature_scaling: None,
             },
+
+            settling: None,
+
+            derived_attributes: Vec::new(),
+            axis_groups: Vec::new(),
+            presets: Vec::new(),
+            observer_socket: None,
+            archiver: None,
         }
     }
 }
@@ -3,14 +3,18 @@ pub mod config;
 pub mod controller;
 pub mod motor;
 pub mod params;
+pub mod settling;
 
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use config::CooledSlitControllerConfig;
-use em2rs::Em2rs;
-use icpcon::M7015;
+use config::{CooledSlitAxisConfig, CooledSlitControllerConfig, SoftLimitsConfig};
+use em2rs::{Em2rs, SoftLimits};
+use icpcon::{AlarmOutput, M7015, M7017, RtdType};
 use lir::LIR;
-use utilities::{command_executor::CommandExecutor, lazy_tcp::LazyTcpStream};
+use utilities::{
+    command_executor::CommandExecutor,
+    lazy_tcp::{ActiveEndpoint, LazyTcpStream},
+};
 
 use crate::{
     command_executor::{
@@ -27,33 +31,79 @@ const MAX_RETRIES: u32 = 3;
 
 pub fn create_sensors(
     config: &CooledSlitControllerConfig,
-) -> (CommandExecutor<SensorsHandler>, SensorsCommandSender) {
+) -> (
+    CommandExecutor<SensorsHandler>,
+    SensorsCommandSender,
+    ActiveEndpoint,
+) {
     let sensors_scoket_addr =
         SocketAddr::new(config.sensors_ip.parse().unwrap(), config.sensors_port);
 
-    let sensors_tcp_stream = LazyTcpStream::new(
+    let mut sensors_tcp_stream = LazyTcpStream::new(
         sensors_scoket_addr,
         MAX_RETRIES,
         READ_TIMEOUT,
         WRITE_TIMEOUT,
         CONNECT_TIMEOUT,
     );
+    if let Some(secondary_ip) = &config.sensors_secondary_ip {
+        let secondary_addr = SocketAddr::new(
+            secondary_ip.parse().unwrap(),
+            config.sensors_secondary_port.unwrap_or(config.sensors_port),
+        );
+        sensors_tcp_stream = sensors_tcp_stream.with_secondary(secondary_addr);
+    }
+    let sensors_gateway = sensors_tcp_stream.active_endpoint();
+
+    // Temperature channels follow the axis order of the encoders
+    let mut temperature = M7015::new(config.icpcon_id);
+    for (channel, axis) in [
+        &config.upper_axis,
+        &config.lower_axis,
+        &config.right_axis,
+        &config.left_axis,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if let Some(scaling) = axis.temperature_scaling {
+            temperature = temperature.with_scaling(channel as u8, scaling);
+        }
+    }
 
-    let sensors_handler = SensorsHandler::new(
+    let mut sensors_handler = SensorsHandler::new(
         sensors_tcp_stream,
         vec![
-            LIR::new(config.upper_axis.lir_id, config.upper_axis.lir_step),
-            LIR::new(config.lower_axis.lir_id, config.lower_axis.lir_step),
-            LIR::new(config.right_axis.lir_id, config.right_axis.lir_step),
-            LIR::new(config.left_axis.lir_id, config.left_axis.lir_step),
+            LIR::new(config.upper_axis.lir_id, config.upper_axis.lir_step)
+                .with_layout(config.lir_layout),
+            LIR::new(config.lower_axis.lir_id, config.lower_axis.lir_step)
+                .with_layout(config.lir_layout),
+            LIR::new(config.right_axis.lir_id, config.right_axis.lir_step)
+                .with_layout(config.lir_layout),
+            LIR::new(config.left_axis.lir_id, config.left_axis.lir_step)
+                .with_layout(config.lir_layout),
         ],
-        M7015::new(config.icpcon_id),
+        temperature,
     );
+    if let Some(flow) = config.flow {
+        sensors_handler =
+            sensors_handler.with_flow(M7017::new(flow.icpcon_id).with_range(flow.range));
+    }
+    if let Some(alarm) = config.over_temperature {
+        sensors_handler = sensors_handler.with_over_temperature(
+            AlarmOutput::new(alarm.icpcon_id, alarm.channel).with_active_low(alarm.active_low),
+            alarm.threshold,
+        );
+    }
 
     let sensors_command_executor = CommandExecutor::new(sensors_handler);
     let sensors_command_sender = SensorsCommandSender::new(sensors_command_executor.sender());
 
-    (sensors_command_executor, sensors_command_sender)
+    (
+        sensors_command_executor,
+        sensors_command_sender,
+        sensors_gateway,
+    )
 }
 
 pub fn create_em2rs(
@@ -100,53 +150,127 @@ pub fn create_em2rs(
     (em2rs_command_executor, em2rs_command_sender)
 }
 
-pub fn create_controller(config: &CooledSlitControllerConfig) -> CooledSlitController {
+// Axis names and their config sections, the position is the device index
+// the axis commands
+fn axis_configs(config: &CooledSlitControllerConfig) -> [(&'static str, &CooledSlitAxisConfig); 4] {
+    [
+        ("Y_Up", &config.upper_axis),
+        ("Y_Down", &config.lower_axis),
+        ("X_Left", &config.left_axis),
+        ("X_Right", &config.right_axis),
+    ]
+}
+
+// Second line of defence next to the host's own checks, on the drive each
+// axis commands
+fn apply_soft_limits(config: &CooledSlitControllerConfig, em2rs_cs: Em2rsCommandSender) {
+    let limits: Vec<(usize, &str, SoftLimitsConfig)> = axis_configs(config)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, (name, config))| {
+            config.soft_limits.map(|limits| (index, name, limits))
+        })
+        .collect();
+
+    if limits.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        for (index, name, limits) in limits {
+            let expected = SoftLimits {
+                negative: limits.negative,
+                positive: limits.positive,
+            };
+
+            let result = async {
+                let current = em2rs_cs.get_soft_limits(index).await?;
+                if current != expected {
+                    eprintln!(
+                        "Soft limits of {} differ from the configuration, {} -> {}",
+                        name, current, expected
+                    );
+                    em2rs_cs.set_soft_limits(index, expected).await?;
+                }
+
+                if !em2rs_cs.get_soft_limits_enabled(index).await? {
+                    eprintln!("Enabling soft limits of {}", name);
+                    em2rs_cs.set_soft_limits_enabled(index, true).await?;
+                }
+
+                Ok::<_, std::io::Error>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                eprintln!("Failed to set soft limits of {}: {}", name, e);
+            }
+        }
+    });
+}
+
+// Knife temperatures are channels 0 to 3 of the M-7015, in axis order
+fn apply_rtd_type(rtd_type: Option<RtdType>, sensors_cs: SensorsCommandSender) {
+    let Some(rtd_type) = rtd_type else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        for channel in 0..4 {
+            let result = async {
+                let current = sensors_cs.get_rtd_type(channel).await?;
+                if current != rtd_type {
+                    eprintln!(
+                        "RTD type of channel {} differs from the configuration, {:?} -> {:?}",
+                        channel, current, rtd_type
+                    );
+                    sensors_cs.set_rtd_type(channel, rtd_type).await?;
+                }
+
+                Ok::<_, std::io::Error>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                eprintln!("Failed to set RTD type of channel {}: {}", channel, e);
+            }
+        }
+    });
+}
+
+pub fn create_controller(
+    config: &CooledSlitControllerConfig,
+) -> Result<CooledSlitController, String> {
+    config
+        .lir_layout
+        .validate()
+        .map_err(|e| format!("Invalid lir_layout: {}", e))?;
+
     let (em2rs_command_executor, em2rs_command_sender) = create_em2rs(config);
-    let (sensors_command_executor, sensors_command_sender) = create_sensors(config);
-
-    let upper_axis = CooledSlitAxis::new(
-        "Y_Up".to_string(),
-        0,
-        sensors_command_sender.clone(),
-        em2rs_command_sender.clone(),
-        config.upper_axis.steps_per_mm,
-    );
-    let lower_axis = CooledSlitAxis::new(
-        "Y_Down".to_string(),
-        1,
-        sensors_command_sender.clone(),
-        em2rs_command_sender.clone(),
-        config.lower_axis.steps_per_mm,
-    );
-    let left_axis = CooledSlitAxis::new(
-        "X_Left".to_string(),
-        2,
-        sensors_command_sender.clone(),
-        em2rs_command_sender.clone(),
-        config.left_axis.steps_per_mm,
-    );
-    let right_axis = CooledSlitAxis::new(
-        "X_Right".to_string(),
-        3,
-        sensors_command_sender.clone(),
-        em2rs_command_sender.clone(),
-        config.right_axis.steps_per_mm,
-    );
+    let (sensors_command_executor, sensors_command_sender, sensors_gateway) =
+        create_sensors(config);
 
-    let mut controller = CooledSlitController::new(
-        // vec![
-        //     Arc::new(upper_axis),
-        //     Arc::new(lower_axis),
-        //     Arc::new(left_axis),
-        //     Arc::new(right_axis),
-        // ],
-        sensors_command_executor,
-        em2rs_command_executor,
-    );
-    controller.add_axis(Arc::new(upper_axis));
-    controller.add_axis(Arc::new(lower_axis));
-    controller.add_axis(Arc::new(left_axis));
-    controller.add_axis(Arc::new(right_axis));
+    apply_soft_limits(config, em2rs_command_sender.clone());
+    apply_rtd_type(config.rtd_type, sensors_command_sender.clone());
+
+    let mut controller =
+        CooledSlitController::new(sensors_command_executor, em2rs_command_executor);
+
+    for (index, (name, axis_config)) in axis_configs(config).into_iter().enumerate() {
+        let axis = CooledSlitAxis::new(
+            name.to_string(),
+            index,
+            sensors_command_sender.clone(),
+            em2rs_command_sender.clone(),
+            axis_config.steps_per_mm,
+        )
+        .with_inverted(axis_config.inverted)
+        .with_flow_channel(axis_config.flow_channel)
+        .with_settling(config.settling.clone())
+        .with_sensors_gateway(sensors_gateway.clone());
+
+        controller.add_axis(Arc::new(axis));
+    }
 
-    controller
+    Ok(controller)
 }
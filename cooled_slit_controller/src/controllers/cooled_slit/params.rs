@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use motarem::axis::movement_parameters::MovementParams;
+use utilities::params::{custom_finite, custom_seconds};
 
 #[derive(Debug)]
 pub struct MotorParameters {
@@ -9,6 +10,9 @@ pub struct MotorParameters {
     pub velocity: u16,
     pub position_window: f32,
     pub time_limit: Duration,
+    // Wait for the temperature to settle after the move, the configured
+    // default when unset
+    pub settle: Option<bool>,
 }
 
 impl Default for MotorParameters {
@@ -19,12 +23,15 @@ impl Default for MotorParameters {
             velocity: 1000,
             position_window: 0.001,
             time_limit: Duration::from_secs(60),
+            settle: None,
         }
     }
 }
 
-impl From<MovementParams> for MotorParameters {
-    fn from(value: MovementParams) -> Self {
+impl TryFrom<MovementParams> for MotorParameters {
+    type Error = String;
+
+    fn try_from(value: MovementParams) -> Result<Self, Self::Error> {
         let mut params = Self::default();
 
         if let Some(acceleration) = value.acceleration {
@@ -36,13 +43,16 @@ impl From<MovementParams> for MotorParameters {
         if let Some(velocity) = value.velocity {
             params.velocity = velocity as u16;
         }
-        if let Some(position_window) = value.custom.get("position_window") {
-            params.position_window = *position_window as f32;
+        if let Some(position_window) = custom_finite(&value, "position_window")? {
+            params.position_window = position_window as f32;
+        }
+        if let Some(time_limit) = custom_seconds(&value, "time_limit")? {
+            params.time_limit = time_limit;
         }
-        if let Some(time_limit) = value.custom.get("time_limit") {
-            params.time_limit = Duration::from_secs_f64(*time_limit);
+        if let Some(settle) = value.custom.get("settle") {
+            params.settle = Some(*settle != 0.0);
         }
 
-        params
+        Ok(params)
     }
 }
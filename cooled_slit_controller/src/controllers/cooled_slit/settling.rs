@@ -0,0 +1,105 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    command_executor::sensors::command_sender::SensorsCommandSender,
+    controllers::cooled_slit::config::SettlingConfig,
+};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+// Least squares slope of the window in degrees per minute
+fn slope(samples: &VecDeque<(Instant, f32)>) -> Option<f32> {
+    let (first, _) = samples.front()?;
+    let n = samples.len() as f64;
+    if n < 2.0 {
+        return None;
+    }
+
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|(time, temperature)| {
+            (
+                time.duration_since(*first).as_secs_f64() / 60.0,
+                *temperature as f64,
+            )
+        })
+        .collect();
+
+    let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let covariance: f64 = points
+        .iter()
+        .map(|(t, y)| (t - mean_t) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+
+    if variance == 0.0 {
+        return None;
+    }
+
+    Some((covariance / variance) as f32)
+}
+
+// Holds after a move until the knife temperature changes by less than
+// max_slope per minute over a whole window. Gives up after the timeout, or
+// as soon as `settling` is cleared by a stop.
+pub async fn settle(
+    axis: u8,
+    sensors_cs: &SensorsCommandSender,
+    config: &SettlingConfig,
+    settling: &Arc<AtomicBool>,
+    last_slope: &Arc<std::sync::Mutex<Option<f32>>>,
+) -> Result<(), String> {
+    let window = Duration::from_secs(config.window_s);
+    let timeout = Duration::from_secs(config.timeout_s);
+
+    let start = Instant::now();
+    let mut samples: VecDeque<(Instant, f32)> = VecDeque::new();
+
+    while settling.load(Ordering::Relaxed) {
+        if start.elapsed() > timeout {
+            return Err(format!(
+                "Thermal settling timed out after {} s",
+                config.timeout_s
+            ));
+        }
+
+        let now = Instant::now();
+        match sensors_cs.get_temperature(axis).await {
+            Ok(temperature) => samples.push_back((now, temperature)),
+            Err(e) => eprintln!("Failed to read temperature while settling: {}", e),
+        }
+
+        while samples
+            .front()
+            .is_some_and(|(time, _)| now.duration_since(*time) > window)
+        {
+            samples.pop_front();
+        }
+
+        let current = slope(&samples);
+        *last_slope.lock().unwrap() = current;
+
+        let covers_window = samples
+            .front()
+            .is_some_and(|(time, _)| now.duration_since(*time) + SAMPLE_INTERVAL > window);
+
+        if let Some(current) = current {
+            if covers_window && current.abs() <= config.max_slope {
+                return Ok(());
+            }
+        }
+
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+    }
+
+    Ok(())
+}
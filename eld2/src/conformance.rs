@@ -0,0 +1,58 @@
+use utilities::conformance::{Failure, argument, run_vectors, split_call};
+
+use crate::Em2rs;
+
+pub const VECTORS: &str = include_str!("../vectors/registers.txt");
+
+// Replays the register vectors against the driver, see
+// utilities::conformance for the format
+pub fn check(vectors: &str) -> Result<Vec<Failure>, String> {
+    let driver = Em2rs::new(1, 0, 1);
+
+    run_vectors(vectors, |call, port| {
+        let result = match split_call(call).0 {
+            "set_velocity" => driver
+                .set_velocity(port, argument(call)?)
+                .map(|_| String::new()),
+            "get_velocity" => driver.get_velocity(port).map(|v| v.to_string()),
+            "set_acceleration" => driver
+                .set_acceleration(port, argument(call)?)
+                .map(|_| String::new()),
+            "get_acceleration" => driver.get_acceleration(port).map(|v| v.to_string()),
+            "set_deceleration" => driver
+                .set_deceleration(port, argument(call)?)
+                .map(|_| String::new()),
+            "get_deceleration" => driver.get_deceleration(port).map(|v| v.to_string()),
+            "move_relative" => driver
+                .move_relative(port, argument(call)?)
+                .map(|_| String::new()),
+            "stop" => driver.stop(port).map(|_| String::new()),
+            "get_speed" => driver.get_speed(port).map(|v| v.to_string()),
+            "get_limit_switch_state" => driver
+                .get_limit_switch_state(port)
+                .map(|switch| format!("{:?}", switch)),
+            name => return Err(format!("Unknown call: {}", name)),
+        };
+
+        result.map_err(|e| e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_pass() {
+        let failures = check(VECTORS).unwrap();
+        assert!(
+            failures.is_empty(),
+            "{}",
+            failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
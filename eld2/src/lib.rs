@@ -4,6 +4,8 @@ use std::{
 };
 use utilities::modbus::{Modbus, ModbusError};
 
+pub mod conformance;
+
 const MOTION_CONTROL_REG: u16 = 0x6002;
 const SI_STATUS_REG: u16 = 0x0179;
 
@@ -5,11 +5,23 @@ use std::{
 };
 use utilities::modbus::{Modbus, ModbusError};
 
+pub mod conformance;
+
 const MOTION_CONTROL_REG: u16 = 0x6002;
 const MOTION_STATUS_REG: u16 = 0x1003;
 // const CONFIG_REG: u16 = 0x1801;
 // const SI_BASE_REG: u16 = 0x0145;
 const SI_STATUS_REG: u16 = 0x0179;
+// Diagnostic monitor registers
+const BUS_VOLTAGE_REG: u16 = 0x0187;
+const DRIVE_TEMPERATURE_REG: u16 = 0x0189;
+// Model code followed by the firmware version
+const IDENTITY_REG: u16 = 0x0180;
+// Software limits of the command position, positive then negative, each as
+// high and low word. Bit 1 of the PR control register switches them on.
+const PR_CONTROL_REG: u16 = 0x6000;
+const SOFT_LIMITS_REG: u16 = 0x6006;
+const SOFT_LIMIT_ENABLE: u16 = 0b10;
 
 bitflags!(
     #[derive(Debug, Clone, Copy)]
@@ -87,6 +99,47 @@ impl StateParams {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Identity {
+    pub model: u16,
+    pub firmware: u16,
+}
+
+impl std::fmt::Display for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "model {:#06x}, firmware {:#06x}",
+            self.model, self.firmware
+        )
+    }
+}
+
+// In steps of the drive's own position counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoftLimits {
+    pub negative: i32,
+    pub positive: i32,
+}
+
+impl std::fmt::Display for SoftLimits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.negative, self.positive)
+    }
+}
+
+fn words(value: i32) -> [u16; 2] {
+    let data = value.to_be_bytes();
+    [
+        u16::from_be_bytes([data[0], data[1]]),
+        u16::from_be_bytes([data[2], data[3]]),
+    ]
+}
+
+fn from_words(high: u16, low: u16) -> i32 {
+    ((high as u32) << 16 | low as u32) as i32
+}
+
 #[derive(Clone)]
 pub struct Em2rs {
     client: Modbus,
@@ -211,6 +264,91 @@ impl Em2rs {
         Ok(switch)
     }
 
+    // Drive temperature in degrees Celsius
+    pub fn get_drive_temperature(
+        &self,
+        client: &mut (impl Write + Read),
+    ) -> Result<f32, ModbusError> {
+        let raw = self
+            .client
+            .read_holding_register(client, DRIVE_TEMPERATURE_REG)?;
+        Ok(raw as i16 as f32)
+    }
+
+    // DC bus voltage in volts, reported in 0.1 V units
+    pub fn get_bus_voltage(&self, client: &mut (impl Write + Read)) -> Result<f32, ModbusError> {
+        let raw = self.client.read_holding_register(client, BUS_VOLTAGE_REG)?;
+        Ok(raw as f32 / 10.0)
+    }
+
+    pub fn identify(&self, client: &mut (impl Write + Read)) -> Result<Identity, ModbusError> {
+        let registers = self
+            .client
+            .read_holding_registers(client, IDENTITY_REG, 2)?;
+
+        Ok(Identity {
+            model: registers[0],
+            firmware: registers[1],
+        })
+    }
+
+    pub fn set_soft_limits(
+        &self,
+        client: &mut (impl Write + Read),
+        limits: SoftLimits,
+    ) -> Result<(), ModbusError> {
+        if limits.negative >= limits.positive {
+            return Err(ModbusError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Negative soft limit must be below the positive one",
+            )));
+        }
+
+        let [positive_high, positive_low] = words(limits.positive);
+        let [negative_high, negative_low] = words(limits.negative);
+
+        self.client.write_multiple_registers(
+            client,
+            SOFT_LIMITS_REG,
+            &[positive_high, positive_low, negative_high, negative_low],
+        )
+    }
+
+    pub fn get_soft_limits(
+        &self,
+        client: &mut (impl Write + Read),
+    ) -> Result<SoftLimits, ModbusError> {
+        let registers = self
+            .client
+            .read_holding_registers(client, SOFT_LIMITS_REG, 4)?;
+
+        Ok(SoftLimits {
+            positive: from_words(registers[0], registers[1]),
+            negative: from_words(registers[2], registers[3]),
+        })
+    }
+
+    // Masked write, the AND and OR masks keep the other bits of the control
+    // register
+    pub fn set_soft_limits_enabled(
+        &self,
+        client: &mut (impl Write + Read),
+        enabled: bool,
+    ) -> Result<(), ModbusError> {
+        let or_mask = if enabled { SOFT_LIMIT_ENABLE } else { 0 };
+
+        self.client
+            .mask_write_register(client, PR_CONTROL_REG, !SOFT_LIMIT_ENABLE, or_mask)
+    }
+
+    pub fn get_soft_limits_enabled(
+        &self,
+        client: &mut (impl Write + Read),
+    ) -> Result<bool, ModbusError> {
+        let control = self.client.read_holding_register(client, PR_CONTROL_REG)?;
+        Ok(control & SOFT_LIMIT_ENABLE != 0)
+    }
+
     pub fn get_state(&self, client: &mut (impl Write + Read)) -> Result<StateParams, ModbusError> {
         let motion_status = self.get_motion_status(client)?;
         let switches = self.get_limit_switch_state(client)?;
@@ -1,17 +1,20 @@
 use crate::command_executor::encoder::commands::EncoderCommand;
 use lir::LIR;
 use std::io;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, modbus::ModbusError};
+use utilities::{
+    command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, modbus::ModbusError,
+    transport::Transport,
+};
 pub mod command_sender;
 pub mod commands;
 
-pub struct EncoderHandler {
-    tcp_stream: LazyTcpStream,
+pub struct EncoderHandler<S = LazyTcpStream> {
+    tcp_stream: S,
     encoder: LIR,
 }
 
-impl EncoderHandler {
-    pub fn new(tcp_stream: LazyTcpStream, encoder: LIR) -> Self {
+impl<S: Transport> EncoderHandler<S> {
+    pub fn new(tcp_stream: S, encoder: LIR) -> Self {
         Self {
             tcp_stream,
             encoder,
@@ -20,7 +23,7 @@ impl EncoderHandler {
 
     fn get_position(&mut self) -> io::Result<f32> {
         self.encoder
-            .get_current_measurement(&mut self.tcp_stream, 3)
+            .get_current_measurement(&mut self.tcp_stream)
             .map_err(|e| match e {
                 ModbusError::IoError(error) => io::Error::from(error),
                 _ => io::Error::new(io::ErrorKind::Other, format!("{e}")),
@@ -96,4 +96,54 @@ impl Em2rsCommandSender {
             )),
         }
     }
+
+    pub async fn get_drive_temperature(&self) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetDriveTemperature)
+            .await?;
+
+        match response {
+            CommandResponse::Value(value) => Ok(value),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_bus_voltage(&self) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetBusVoltage)
+            .await?;
+
+        match response {
+            CommandResponse::Value(value) => Ok(value),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    // Velocity, acceleration and deceleration registers
+    pub async fn get_motion_parameters(&self) -> io::Result<(u16, u16, u16)> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetMotionParameters)
+            .await?;
+
+        match response {
+            CommandResponse::MotionParameters {
+                velocity,
+                acceleration,
+                deceleration,
+            } => Ok((velocity, acceleration, deceleration)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
@@ -12,10 +12,19 @@ pub enum MotorCommand {
     SetDeceleration { deceleration: u16 },
     Stop,
     Move { steps: i32 },
+    GetDriveTemperature,
+    GetBusVoltage,
+    GetMotionParameters,
 }
 
 pub enum CommandResponse {
     State(StateParams),
+    Value(f32),
+    MotionParameters {
+        velocity: u16,
+        acceleration: u16,
+        deceleration: u16,
+    },
     Ok,
 }
 
@@ -35,6 +44,9 @@ impl Command for MotorCommand {
             }
             MotorCommand::Stop => handler.stop(),
             MotorCommand::Move { steps } => handler.move_relative(steps),
+            MotorCommand::GetDriveTemperature => handler.get_drive_temperature(),
+            MotorCommand::GetBusVoltage => handler.get_bus_voltage(),
+            MotorCommand::GetMotionParameters => handler.get_motion_parameters(),
         }
     }
 }
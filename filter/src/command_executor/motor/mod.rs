@@ -1,12 +1,12 @@
 use crate::command_executor::motor::commands::{CommandResponse, MotorCommand};
 use em2rs::Em2rs;
 use std::io;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream};
+use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, transport::Transport};
 pub mod command_sender;
 pub mod commands;
 
-pub struct Em2rsHandler {
-    tcp_stream: LazyTcpStream,
+pub struct Em2rsHandler<S = LazyTcpStream> {
+    tcp_stream: S,
     em2rs: Em2rs,
 }
 
@@ -14,8 +14,8 @@ impl DeviceHandler for Em2rsHandler {
     type Command = MotorCommand;
 }
 
-impl Em2rsHandler {
-    pub fn new(tcp_stream: LazyTcpStream, em2rs: Em2rs) -> Self {
+impl<S: Transport> Em2rsHandler<S> {
+    pub fn new(tcp_stream: S, em2rs: Em2rs) -> Self {
         Self { tcp_stream, em2rs }
     }
 
@@ -54,4 +54,22 @@ impl Em2rsHandler {
             .set_deceleration(&mut self.tcp_stream, deceleration)?;
         Ok(CommandResponse::Ok)
     }
+
+    pub fn get_motion_parameters(&mut self) -> io::Result<CommandResponse> {
+        Ok(CommandResponse::MotionParameters {
+            velocity: self.em2rs.get_velocity(&mut self.tcp_stream)?,
+            acceleration: self.em2rs.get_acceleration(&mut self.tcp_stream)?,
+            deceleration: self.em2rs.get_deceleration(&mut self.tcp_stream)?,
+        })
+    }
+
+    pub fn get_drive_temperature(&mut self) -> io::Result<CommandResponse> {
+        let temperature = self.em2rs.get_drive_temperature(&mut self.tcp_stream)?;
+        Ok(CommandResponse::Value(temperature))
+    }
+
+    pub fn get_bus_voltage(&mut self) -> io::Result<CommandResponse> {
+        let voltage = self.em2rs.get_bus_voltage(&mut self.tcp_stream)?;
+        Ok(CommandResponse::Value(voltage))
+    }
 }
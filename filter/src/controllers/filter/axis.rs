@@ -9,9 +9,13 @@ use motarem::axis::{
     state_info::AxisStateInfo,
 };
 use tokio::{sync::Mutex, task::JoinHandle};
-use utilities::motor_controller::{Motor as _, MotorHolder};
+use utilities::{
+    motor_controller::{Motor as _, MotorHolder},
+    snapshot::ParameterSnapshot,
+    units::AxisUnits,
+};
 
-use super::params::MotorParameters;
+use super::{config::FilterControllerConfig, params::MotorParameters};
 use crate::{
     command_executor::{
         encoder::command_sender::EncoderCommandSender, motor::command_sender::Em2rsCommandSender,
@@ -29,6 +33,8 @@ pub struct FilterAxis {
     is_moving: Arc<AtomicBool>,
 
     steps_per_mm: i32,
+    inverted: bool,
+    units: AxisUnits,
 }
 
 impl FilterAxis {
@@ -37,6 +43,7 @@ impl FilterAxis {
         encoder_cs: EncoderCommandSender,
         motor_cs: Em2rsCommandSender,
         steps_per_mm: i32,
+        units: AxisUnits,
     ) -> Self {
         Self {
             name,
@@ -45,6 +52,54 @@ impl FilterAxis {
             move_thread: Arc::new(Mutex::new(None)),
             is_moving: Arc::new(AtomicBool::new(false)),
             steps_per_mm,
+            inverted: false,
+            units,
+        }
+    }
+
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    pub async fn read_parameters(
+        &self,
+        config: &FilterControllerConfig,
+        snapshot: &mut ParameterSnapshot,
+    ) {
+        let defaults = MotorParameters::default();
+        let device = format!("{} EM2RS", self.name);
+
+        match self.motor_cs.get_motion_parameters().await {
+            Ok((velocity, acceleration, deceleration)) => {
+                snapshot.record(
+                    &device,
+                    "velocity",
+                    Some(config.expected_velocity.unwrap_or(defaults.velocity) as f64),
+                    velocity as f64,
+                );
+                snapshot.record(
+                    &device,
+                    "acceleration",
+                    Some(
+                        config
+                            .expected_acceleration
+                            .unwrap_or(defaults.acceleration) as f64,
+                    ),
+                    acceleration as f64,
+                );
+                snapshot.record(
+                    &device,
+                    "deceleration",
+                    Some(
+                        config
+                            .expected_deceleration
+                            .unwrap_or(defaults.deceleration) as f64,
+                    ),
+                    deceleration as f64,
+                );
+            }
+            Err(e) => snapshot.record_error(&device, "motion_parameters", e),
         }
     }
 }
@@ -56,9 +111,13 @@ impl Axis for FilterAxis {
     }
 
     async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
-        let motor_params = parameters.unwrap_or_default().into();
+        let mut parameters = parameters.unwrap_or_default();
+        if let Some(window) = parameters.custom.get_mut("position_window") {
+            *window = self.units.to_native(*window);
+        }
+        let motor_params: MotorParameters = parameters.try_into().map_err(anyhow::Error::msg)?;
 
-        self.move_to(position as f32, motor_params)
+        self.move_to(self.units.to_native(position) as f32, motor_params)
             .await
             .map_err(|e| anyhow::Error::msg(format!("Failed to move motor: {}", e)))?;
 
@@ -84,10 +143,17 @@ impl Axis for FilterAxis {
             AxisState::On
         };
 
-        let limit_switches = match (
-            motor_state.low_limit_triggered(),
-            motor_state.high_limit_triggered(),
-        ) {
+        let (lower_switch, upper_switch) = match self.inverted {
+            false => (
+                motor_state.low_limit_triggered(),
+                motor_state.high_limit_triggered(),
+            ),
+            true => (
+                motor_state.high_limit_triggered(),
+                motor_state.low_limit_triggered(),
+            ),
+        };
+        let limit_switches = match (lower_switch, upper_switch) {
             (true, true) => LimitSwitches::Both,
             (true, false) => LimitSwitches::Lower,
             (false, true) => LimitSwitches::Upper,
@@ -109,14 +175,32 @@ impl Axis for FilterAxis {
         match name {
             "position" => MotorHolder::get_position(self)
                 .await
-                .map(|pos| pos as f64)
+                .map(|pos| self.units.from_native(pos as f64))
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get position: {}", err))),
+            "drive_temperature" => self
+                .motor_cs
+                .get_drive_temperature()
+                .await
+                .map(|temp| temp as f64)
+                .map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to get drive temperature: {}", err))
+                }),
+            "bus_voltage" => self
+                .motor_cs
+                .get_bus_voltage()
+                .await
+                .map(|voltage| voltage as f64)
+                .map_err(|err| anyhow::Error::msg(format!("Failed to get bus voltage: {}", err))),
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
 
     async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec!["position".to_string()])
+        Ok(vec![
+            "position".to_string(),
+            "drive_temperature".to_string(),
+            "bus_voltage".to_string(),
+        ])
     }
 
     async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
@@ -201,7 +285,8 @@ impl MotorHolder for FilterAxis {
             parameters.time_limit,
             self.is_moving.clone(),
             self.steps_per_mm,
-        );
+        )
+        .with_inverted(self.inverted);
 
         let handle = tokio::spawn(async move { move_thread.run().await });
 
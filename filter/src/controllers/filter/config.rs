@@ -1,4 +1,8 @@
+use lir::LirLayout;
 use serde::{Deserialize, Serialize};
+use utilities::{
+    archiver::ArchiverConfig, derived::DerivedAttributeConfig, groups::AxisGroupConfig, units::Unit,
+};
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct FilterControllerConfig {
@@ -10,11 +14,52 @@ pub struct FilterControllerConfig {
 
     pub lir_id: u8,
     pub lir_step: f32,
+    // Register layout of the LIR interface module, the commissioned one
+    // when unset
+    #[serde(default)]
+    pub lir_layout: LirLayout,
 
     pub em2rs_id: u8,
     pub em2rs_low_limit: u8,
     pub em2rs_high_limit: u8,
     pub steps_per_mm: i32,
+    // Motor mounted the other way round: steps are sent with the opposite
+    // sign and its limit switches swap places, steps_per_mm stays positive
+    #[serde(default)]
+    pub inverted: bool,
+
+    // The LIR step is in degrees per count, clients may ask for mdeg instead
+    #[serde(default = "default_unit")]
+    pub unit: Unit,
+
+    // EM2RS registers checked by the parameter snapshot, by default what
+    // every move writes
+    #[serde(default)]
+    pub expected_velocity: Option<u16>,
+    #[serde(default)]
+    pub expected_acceleration: Option<u16>,
+    #[serde(default)]
+    pub expected_deceleration: Option<u16>,
+
+    // Attributes computed from other attributes of the same controller
+    #[serde(default)]
+    pub derived_attributes: Vec<DerivedAttributeConfig>,
+
+    // Named sets of axes addressed like a single axis, e.g. `STOP vertical`
+    #[serde(default)]
+    pub axis_groups: Vec<AxisGroupConfig>,
+
+    // Read-only socket for status displays, motion commands are refused there
+    #[serde(default)]
+    pub observer_socket: Option<String>,
+
+    // Periodic axis samples sent to the facility archive
+    #[serde(default)]
+    pub archiver: Option<ArchiverConfig>,
+}
+
+fn default_unit() -> Unit {
+    Unit::Degree
 }
 
 impl Default for FilterControllerConfig {
@@ -28,11 +73,24 @@ impl Default for FilterControllerConfig {
 
             lir_id: 1,
             lir_step: 0.1,
+            lir_layout: LirLayout::default(),
 
             em2rs_id: 2,
             em2rs_low_limit: 0,
             em2rs_high_limit: 100,
             steps_per_mm: 100,
+            inverted: false,
+
+            unit: default_unit(),
+
+            expected_velocity: None,
+            expected_acceleration: None,
+            expected_deceleration: None,
+
+            derived_attributes: Vec::new(),
+            axis_groups: Vec::new(),
+            observer_socket: None,
+            archiver: None,
         }
     }
 }
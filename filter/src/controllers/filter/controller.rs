@@ -4,10 +4,13 @@ use motarem::{axis::Axis, motor_controller::MotorController};
 use tokio::{sync::Mutex, task::JoinHandle};
 use utilities::command_executor::CommandExecutor;
 
-use crate::command_executor::{encoder::EncoderHandler, motor::Em2rsHandler};
+use crate::{
+    command_executor::{encoder::EncoderHandler, motor::Em2rsHandler},
+    controllers::filter::axis::FilterAxis,
+};
 
 pub struct FilterController {
-    axis: Arc<dyn Axis>,
+    axis: Arc<FilterAxis>,
 
     sensors_join_handle: Arc<Mutex<JoinHandle<io::Result<()>>>>,
     em2rs_join_handle: Arc<Mutex<JoinHandle<io::Result<()>>>>,
@@ -15,7 +18,7 @@ pub struct FilterController {
 
 impl FilterController {
     pub fn new(
-        axis: Arc<dyn Axis>,
+        axis: Arc<FilterAxis>,
 
         mut sensors_command_executor: CommandExecutor<EncoderHandler>,
         mut em2rs_command_executor: CommandExecutor<Em2rsHandler>,
@@ -29,6 +32,10 @@ impl FilterController {
             em2rs_join_handle: Arc::new(Mutex::new(em2rs_handle)),
         }
     }
+
+    pub fn axis(&self) -> Arc<FilterAxis> {
+        self.axis.clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -38,7 +45,7 @@ impl MotorController for FilterController {
     }
 
     fn axes(&self) -> Vec<Arc<dyn Axis>> {
-        vec![self.axis.clone()]
+        vec![self.axis.clone() as Arc<dyn Axis>]
     }
 
     async fn shutdown(&self) -> anyhow::Result<()> {
@@ -9,7 +9,11 @@ use std::{net::SocketAddr, sync::Arc, time::Duration};
 use config::FilterControllerConfig;
 use em2rs::Em2rs;
 use lir::LIR;
-use utilities::{command_executor::CommandExecutor, lazy_tcp::LazyTcpStream};
+use utilities::{
+    command_executor::CommandExecutor,
+    lazy_tcp::LazyTcpStream,
+    units::{AxisUnits, Unit},
+};
 
 use crate::{
     command_executor::{
@@ -38,8 +42,10 @@ pub fn create_sensors(
         CONNECT_TIMEOUT,
     );
 
-    let sensors_handler =
-        EncoderHandler::new(sensors_tcp_stream, LIR::new(config.lir_id, config.lir_step));
+    let sensors_handler = EncoderHandler::new(
+        sensors_tcp_stream,
+        LIR::new(config.lir_id, config.lir_step).with_layout(config.lir_layout),
+    );
 
     let sensors_command_executor = CommandExecutor::new(sensors_handler);
     let sensors_command_sender = EncoderCommandSender::new(sensors_command_executor.sender());
@@ -74,7 +80,14 @@ pub fn create_em2rs(
     (em2rs_command_executor, em2rs_command_sender)
 }
 
-pub fn create_controller(config: &FilterControllerConfig) -> FilterController {
+pub fn create_controller(config: &FilterControllerConfig) -> Result<FilterController, String> {
+    config
+        .lir_layout
+        .validate()
+        .map_err(|e| format!("Invalid lir_layout: {}", e))?;
+    let units = AxisUnits::new(Unit::Degree, config.unit)
+        .map_err(|e| format!("Invalid unit of axis Rotational: {}", e))?;
+
     let (em2rs_command_executor, em2rs_command_sender) = create_em2rs(config);
     let (sensors_command_executor, sensors_command_sender) = create_sensors(config);
 
@@ -83,7 +96,9 @@ pub fn create_controller(config: &FilterControllerConfig) -> FilterController {
         sensors_command_sender.clone(),
         em2rs_command_sender.clone(),
         config.steps_per_mm,
-    );
+        units,
+    )
+    .with_inverted(config.inverted);
 
     let controller = FilterController::new(
         Arc::new(axis),
@@ -91,5 +106,5 @@ pub fn create_controller(config: &FilterControllerConfig) -> FilterController {
         em2rs_command_executor,
     );
 
-    controller
+    Ok(controller)
 }
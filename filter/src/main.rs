@@ -3,6 +3,8 @@ pub mod config;
 pub mod controllers;
 pub mod logging;
 
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
 use crate::{
     config::{create_default_config, init_config},
     controllers::filter::create_controller,
@@ -14,6 +16,11 @@ use motarem::{
     socket_server::{SocketServer, config::SocketServerConfig},
 };
 use std::{path::PathBuf, sync::Arc, time::Duration};
+use utilities::{
+    archiver::start_archiver, build_info, build_info::with_build_info,
+    derived::with_derived_attributes, groups::with_axis_groups, observer::start_observer_socket,
+    readiness::wait_until_ready, snapshot::ParameterSnapshot, systemd,
+};
 
 fn should_create_config() -> bool {
     std::env::var("CREATE_CONFIG")
@@ -21,10 +28,21 @@ fn should_create_config() -> bool {
         .unwrap_or(false)
 }
 
+fn should_take_snapshot() -> bool {
+    std::env::var("SNAPSHOT")
+        .map(|val| val == "1" || val.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     logging::init();
 
+    if build_info::version_requested() {
+        println!("{}", build_info!());
+        return Ok(());
+    }
+
     if should_create_config() {
         create_default_config(None::<PathBuf>)?;
     }
@@ -35,7 +53,26 @@ async fn main() -> anyhow::Result<()> {
         e
     })?;
 
-    let controller = create_controller(&config);
+    let build = build_info!()
+        .with_feature("observer", config.observer_socket.is_some())
+        .with_feature("archiver", config.archiver.is_some());
+    eprintln!("Starting {}", build);
+
+    let controller = create_controller(&config).map_err(anyhow::Error::msg)?;
+
+    if should_take_snapshot() {
+        let mut snapshot = ParameterSnapshot::new();
+        controller
+            .axis()
+            .read_parameters(&config, &mut snapshot)
+            .await;
+
+        print!("{}", snapshot);
+        println!(
+            "{} parameters differ from the configuration",
+            snapshot.mismatches().count()
+        );
+    }
 
     let manager_config = ManagerConfig {
         default_ttl: Duration::from_secs(1),
@@ -44,8 +81,14 @@ async fn main() -> anyhow::Result<()> {
 
     let manager = Arc::new(ControllerManager::new(manager_config));
 
+    let controller = with_derived_attributes(Arc::new(controller), &config.derived_attributes)
+        .map_err(anyhow::Error::msg)?;
+    let controller =
+        with_axis_groups(controller, &config.axis_groups).map_err(anyhow::Error::msg)?;
+    let controller = with_build_info(controller, &build).map_err(anyhow::Error::msg)?;
+
     manager
-        .register_controller(controller.name().to_string(), Arc::new(controller))
+        .register_controller(controller.name().to_string(), controller.clone())
         .await?;
 
     let socket_config = SocketServerConfig {
@@ -54,9 +97,28 @@ async fn main() -> anyhow::Result<()> {
         buffer_size: 8192,
     };
 
+    // Clients connect once every axis answered or the probe gave up, not while
+    // the executors are still reaching their devices
+    systemd::status("Waiting for axes");
+    wait_until_ready(&[controller.clone()], READY_TIMEOUT).await;
+
     let mut socket_server = SocketServer::new(socket_config, manager.clone());
     socket_server.start().await?;
 
+    let _archiver = match &config.archiver {
+        Some(archiver) => {
+            Some(start_archiver(archiver, &[controller.clone()]).map_err(anyhow::Error::msg)?)
+        }
+        None => None,
+    };
+
+    let _observer_server = match &config.observer_socket {
+        Some(socket_path) => Some(start_observer_socket(socket_path, &[controller]).await?),
+        None => None,
+    };
+
+    systemd::ready();
+
     loop {}
 
     // Ok(())
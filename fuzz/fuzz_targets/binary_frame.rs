@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use utilities::binary_frame::{Frame, decode_frame};
+
+// The client side of the attribute stream, fed whatever arrives on the
+// socket. Fields frames found on the way are used for the samples after them.
+fuzz_target!(|data: &[u8]| {
+    let mut fields = Vec::new();
+    let mut buffer = data;
+
+    while let Ok(Some((frame, used))) = decode_frame(buffer, &fields) {
+        assert!(used > 0 && used <= buffer.len());
+        buffer = &buffer[used..];
+
+        if let Frame::Fields(new_fields) = frame {
+            fields = new_fields;
+        }
+    }
+});
@@ -0,0 +1,40 @@
+#![no_main]
+
+use std::io::{self, Read, Write};
+
+use libfuzzer_sys::fuzz_target;
+use utilities::modbus::Modbus;
+
+// A slave answering with whatever it likes. The first byte picks how many
+// registers are asked for, the rest is the reply.
+struct Slave<'a> {
+    reply: &'a [u8],
+}
+
+impl Read for Slave<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reply.read(buf)
+    }
+}
+
+impl Write for Slave<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&count, reply)) = data.split_first() else {
+        return;
+    };
+    let count = u16::from(count % 125) + 1;
+
+    let mut slave = Slave { reply };
+    if let Ok(registers) = Modbus::new(1).read_holding_registers(&mut slave, 0, count) {
+        assert_eq!(registers.len(), count as usize);
+    }
+});
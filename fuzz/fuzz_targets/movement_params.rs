@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use motarem::axis::movement_parameters::MovementParams;
+use utilities::params::{custom_finite, custom_seconds};
+
+// Custom movement parameters as a client would type them, "name value" per
+// line. Whatever was sent must end in a value or an error.
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let mut params = MovementParams::default();
+    for line in text.lines() {
+        let Some((name, value)) = line.trim().split_once(' ') else {
+            continue;
+        };
+        if let Ok(value) = value.trim().parse::<f64>() {
+            params.custom.insert(name.to_string(), value);
+        }
+    }
+
+    for name in ["time_limit", "settle_time", "lease"] {
+        let _ = custom_seconds(&params, name);
+    }
+    if let Ok(Some(window)) = custom_finite(&params, "position_window") {
+        assert!(window.is_finite());
+    }
+});
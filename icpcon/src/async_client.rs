@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use utilities::modbus::{ModbusError, async_client::AsyncModbus, retry::RetryPolicy};
+
+use crate::{
+    ChannelReading, IcpconModule, M7015, READ_ATTEMPTS, RtdType, Scaling, TYPE_REGISTER,
+    channel_scaling, check_channel, codec, set_scaling,
+};
+
+const CHANNELS: u8 = <M7015 as IcpconModule>::CHANNELS;
+
+// Same module as `M7015` for tokio-native services, reads wait on the bus
+// instead of blocking a thread
+pub struct AsyncM7015 {
+    client: AsyncModbus,
+    scalings: Vec<Scaling>,
+}
+
+impl AsyncM7015 {
+    pub fn new(id: u8) -> Self {
+        Self {
+            client: AsyncModbus::new(id).with_retry_policy(RetryPolicy::new(READ_ATTEMPTS)),
+            scalings: Vec::new(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry);
+        self
+    }
+
+    pub fn with_scaling(mut self, channel: u8, scaling: Scaling) -> Self {
+        set_scaling(&mut self.scalings, channel, scaling);
+        self
+    }
+
+    pub async fn read_channels(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<Vec<ChannelReading>, ModbusError> {
+        let response = self
+            .client
+            .read_input_registers(client, 0x00, CHANNELS as u16)
+            .await?;
+        codec::channels(response, CHANNELS, |channel, raw| {
+            codec::reading(
+                raw,
+                codec::tenths(raw),
+                channel_scaling(&self.scalings, channel),
+            )
+        })
+    }
+
+    pub async fn get_current_measurement(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        channel: u8,
+    ) -> Result<ChannelReading, ModbusError> {
+        check_channel(channel, CHANNELS)?;
+        Ok(self.read_channels(client).await?[channel as usize])
+    }
+
+    pub async fn get_channel_type(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        channel: u8,
+    ) -> Result<RtdType, ModbusError> {
+        check_channel(channel, CHANNELS)?;
+
+        let response = self
+            .client
+            .read_holding_registers(client, TYPE_REGISTER + channel as u16, 1)
+            .await?;
+        codec::single(&response)
+    }
+
+    pub async fn set_channel_type(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        channel: u8,
+        rtd_type: RtdType,
+    ) -> Result<(), ModbusError> {
+        check_channel(channel, CHANNELS)?;
+        self.client
+            .write_single_register(client, TYPE_REGISTER + channel as u16, rtd_type.into())
+            .await
+    }
+}
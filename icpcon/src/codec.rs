@@ -0,0 +1,61 @@
+// Decoding of the module registers, shared by the blocking and the async
+// clients. Nothing in here touches IO.
+
+use utilities::modbus::ModbusError;
+
+use crate::{ChannelReading, Scaling};
+
+// Inputs outside of the range read as the end of the scale. A broken RTD or
+// thermocouple wire is an open circuit and reads as over range.
+const OVER_RANGE: u16 = 0x7FFF;
+const UNDER_RANGE: u16 = 0x8000;
+
+// RTD and cold junction readings, tenths of a degree
+pub fn tenths(raw: u16) -> f32 {
+    raw as i16 as f32 / 10.0
+}
+
+// Full scale readings in two's complement, +-32767 is the end of the range
+pub fn full_scale(raw: u16, range: f32) -> f32 {
+    raw as i16 as f32 / i16::MAX as f32 * range
+}
+
+// The engineering value of the register with the channel's scaling, flagged
+// when the module reports the input out of range
+pub fn reading(raw: u16, value: f32, scaling: Scaling) -> ChannelReading {
+    ChannelReading {
+        value: scaling.apply(value),
+        open_wire: raw == OVER_RANGE,
+        under_range: raw == UNDER_RANGE,
+    }
+}
+
+pub fn channels(
+    response: Vec<u16>,
+    channels: u8,
+    reading: impl Fn(u8, u16) -> ChannelReading,
+) -> Result<Vec<ChannelReading>, ModbusError> {
+    if response.len() != channels as usize {
+        return Err(ModbusError::InvalidResponseLength {
+            expected: channels as usize,
+            received: response.len(),
+        });
+    }
+
+    Ok(response
+        .into_iter()
+        .enumerate()
+        .map(|(channel, raw)| reading(channel as u8, raw))
+        .collect())
+}
+
+// The first register of a single register read
+pub fn single<T: From<u16>>(response: &[u16]) -> Result<T, ModbusError> {
+    match response.first() {
+        Some(raw) => Ok((*raw).into()),
+        None => Err(ModbusError::InvalidResponseLength {
+            expected: 1,
+            received: 0,
+        }),
+    }
+}
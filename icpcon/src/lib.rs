@@ -1,43 +1,588 @@
-use std::io::{Read, Write};
+use std::{
+    fmt,
+    io::{Read, Write},
+    time::Duration,
+};
 
-use utilities::modbus::{Modbus, ModbusError};
+use serde::{Deserialize, Serialize};
+use utilities::modbus::{Modbus, ModbusError, retry::RetryPolicy};
 
+pub mod async_client;
+pub mod codec;
+
+// Holding register with the type code of channel 0 (40427), the other
+// channels follow
+const TYPE_REGISTER: u16 = 426;
+
+// Host watchdog of the M-7000 modules: the timeout in tenths of a second
+// (40489), enable (00261), mode (00260, 1 lets any Modbus request feed the
+// watchdog) and the expired flag (00270, written 1 to clear)
+const WATCHDOG_TIMEOUT_REGISTER: u16 = 488;
+const WATCHDOG_MODE_COIL: u16 = 259;
+const WATCHDOG_ENABLE_COIL: u16 = 260;
+const WATCHDOG_EXPIRED_COIL: u16 = 269;
+const WATCHDOG_TICK: Duration = Duration::from_millis(100);
+
+// Input register of the cold junction temperature of thermocouple modules, in
+// tenths of a degree
+const CJC_REGISTER: u16 = 100;
+
+// Analog modules retry a read that timed out or came back garbled
+const READ_ATTEMPTS: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelReading {
+    pub value: f32,
+    pub open_wire: bool,
+    pub under_range: bool,
+}
+
+impl ChannelReading {
+    pub fn is_valid(&self) -> bool {
+        !self.open_wire && !self.under_range
+    }
+
+    // The value, unless the module flagged the input
+    pub fn checked(&self) -> Result<f32, String> {
+        match self.is_valid() {
+            true => Ok(self.value),
+            false => Err(self.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ChannelReading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.open_wire {
+            write!(f, "Open wire or over range")
+        } else if self.under_range {
+            write!(f, "Under range")
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+// Linear correction of a channel's engineering value, for sensors whose unit
+// differs from what the module reports
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Scaling {
+    pub factor: f32,
+    #[serde(default)]
+    pub offset: f32,
+}
+
+impl Default for Scaling {
+    fn default() -> Self {
+        Self {
+            factor: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl Scaling {
+    pub fn apply(&self, value: f32) -> f32 {
+        value * self.factor + self.offset
+    }
+}
+
+// Channels without a scaling of their own keep the module's value
+fn channel_scaling(scalings: &[Scaling], channel: u8) -> Scaling {
+    scalings.get(channel as usize).copied().unwrap_or_default()
+}
+
+fn set_scaling(scalings: &mut Vec<Scaling>, channel: u8, scaling: Scaling) {
+    if scalings.len() <= channel as usize {
+        scalings.resize(channel as usize + 1, Scaling::default());
+    }
+    scalings[channel as usize] = scaling;
+}
+
+fn check_channel(channel: u8, channels: u8) -> Result<(), ModbusError> {
+    if channel >= channels {
+        return Err(ModbusError::IoError(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Channel must be between 0 and {}", channels - 1),
+        )));
+    }
+
+    Ok(())
+}
+
+// Analog input modules, all of them keep one input register per channel from
+// address 0 and differ in channel count and in what a register means
+pub trait IcpconModule {
+    const CHANNELS: u8;
+
+    // What the type code register of a channel holds, input ranges and
+    // sensor types differ between modules
+    type ChannelType: Copy + From<u16> + Into<u16>;
+
+    fn client(&self) -> &Modbus;
+
+    // Engineering value of one input register
+    fn scale(&self, raw: u16) -> f32;
+
+    fn scaling(&self, channel: u8) -> Scaling;
+
+    fn reading(&self, channel: u8, raw: u16) -> ChannelReading {
+        codec::reading(raw, self.scale(raw), self.scaling(channel))
+    }
+
+    fn read_channels(
+        &self,
+        client: &mut (impl Write + Read),
+    ) -> Result<Vec<ChannelReading>, ModbusError> {
+        let response = self
+            .client()
+            .read_input_registers(client, 0x00, Self::CHANNELS as u16)?;
+        codec::channels(response, Self::CHANNELS, |channel, raw| {
+            self.reading(channel, raw)
+        })
+    }
+
+    fn get_current_measurement(
+        &self,
+        client: &mut (impl Write + Read),
+        channel: u8,
+    ) -> Result<ChannelReading, ModbusError> {
+        check_channel(channel, Self::CHANNELS)?;
+        Ok(self.read_channels(client)?[channel as usize])
+    }
+
+    fn get_channel_type(
+        &self,
+        client: &mut (impl Write + Read),
+        channel: u8,
+    ) -> Result<Self::ChannelType, ModbusError> {
+        check_channel(channel, Self::CHANNELS)?;
+
+        let response =
+            self.client()
+                .read_holding_registers(client, TYPE_REGISTER + channel as u16, 1)?;
+        codec::single(&response)
+    }
+
+    // Takes effect with the next conversion. The scale of a module assumes
+    // the type it was set up with, readings after a change of range are
+    // only right once the scale matches.
+    fn set_channel_type(
+        &self,
+        client: &mut (impl Write + Read),
+        channel: u8,
+        channel_type: Self::ChannelType,
+    ) -> Result<(), ModbusError> {
+        check_channel(channel, Self::CHANNELS)?;
+        self.client().write_single_register(
+            client,
+            TYPE_REGISTER + channel as u16,
+            channel_type.into(),
+        )
+    }
+}
+
+// A module whose host watchdog expired sets its outputs to their safe values
+// and keeps them there until the watchdog is reset, so outputs that hold an
+// interlock drop when the controller process dies or loses the bus
+pub trait HostWatchdog {
+    fn modbus(&self) -> &Modbus;
+
+    // From 0.1 to 25.5 s. Every request to the module feeds the watchdog.
+    fn enable_watchdog(
+        &self,
+        client: &mut (impl Write + Read),
+        timeout: Duration,
+    ) -> Result<(), ModbusError> {
+        let ticks = timeout.as_millis() / WATCHDOG_TICK.as_millis();
+        if !(1..=255).contains(&ticks) {
+            return Err(ModbusError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Watchdog timeout must be between 0.1 and 25.5 s",
+            )));
+        }
+
+        self.modbus()
+            .write_single_register(client, WATCHDOG_TIMEOUT_REGISTER, ticks as u16)?;
+        self.modbus()
+            .write_single_coil(client, WATCHDOG_MODE_COIL, true)?;
+        self.modbus()
+            .write_single_coil(client, WATCHDOG_ENABLE_COIL, true)
+    }
+
+    fn disable_watchdog(&self, client: &mut (impl Write + Read)) -> Result<(), ModbusError> {
+        self.modbus()
+            .write_single_coil(client, WATCHDOG_ENABLE_COIL, false)
+    }
+
+    fn watchdog_expired(&self, client: &mut (impl Write + Read)) -> Result<bool, ModbusError> {
+        self.modbus().read_coil(client, WATCHDOG_EXPIRED_COIL)
+    }
+
+    // Feeds the watchdog and clears it if it expired, after which the
+    // outputs take commands again. True when it had expired.
+    fn reset_watchdog(&self, client: &mut (impl Write + Read)) -> Result<bool, ModbusError> {
+        let expired = self.watchdog_expired(client)?;
+        if expired {
+            self.modbus()
+                .write_single_coil(client, WATCHDOG_EXPIRED_COIL, true)?;
+        }
+
+        Ok(expired)
+    }
+}
+
+// Type codes of the M-7015, alpha 0.00385 sensors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RtdType {
+    // Pt100 from -100 to 100 degrees
+    Pt100Minus100To100,
+    Pt100ZeroTo100,
+    Pt100ZeroTo200,
+    Pt100ZeroTo600,
+    // Pt1000 from -200 to 600 degrees
+    Pt1000Minus200To600,
+    // Any other code from the module's manual
+    Other(u16),
+}
+
+impl From<RtdType> for u16 {
+    fn from(rtd_type: RtdType) -> Self {
+        match rtd_type {
+            RtdType::Pt100Minus100To100 => 0x20,
+            RtdType::Pt100ZeroTo100 => 0x21,
+            RtdType::Pt100ZeroTo200 => 0x22,
+            RtdType::Pt100ZeroTo600 => 0x23,
+            RtdType::Pt1000Minus200To600 => 0x2A,
+            RtdType::Other(code) => code,
+        }
+    }
+}
+
+impl From<u16> for RtdType {
+    fn from(code: u16) -> Self {
+        match code {
+            0x20 => RtdType::Pt100Minus100To100,
+            0x21 => RtdType::Pt100ZeroTo100,
+            0x22 => RtdType::Pt100ZeroTo200,
+            0x23 => RtdType::Pt100ZeroTo600,
+            0x2A => RtdType::Pt1000Minus200To600,
+            code => RtdType::Other(code),
+        }
+    }
+}
+
+// 6 channel RTD input module, tenths of a degree
 pub struct M7015 {
     client: Modbus,
+    scalings: Vec<Scaling>,
 }
 
 impl M7015 {
+    pub fn new(id: u8) -> Self {
+        let modbus = Modbus::new(id).with_retry_policy(RetryPolicy::new(READ_ATTEMPTS));
+        Self {
+            client: modbus,
+            scalings: Vec::new(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry);
+        self
+    }
+
+    pub fn with_scaling(mut self, channel: u8, scaling: Scaling) -> Self {
+        set_scaling(&mut self.scalings, channel, scaling);
+        self
+    }
+}
+
+impl HostWatchdog for M7015 {
+    fn modbus(&self) -> &Modbus {
+        &self.client
+    }
+}
+
+impl IcpconModule for M7015 {
+    const CHANNELS: u8 = 6;
+    type ChannelType = RtdType;
+
+    fn client(&self) -> &Modbus {
+        &self.client
+    }
+
+    fn scale(&self, raw: u16) -> f32 {
+        codec::tenths(raw)
+    }
+
+    fn scaling(&self, channel: u8) -> Scaling {
+        channel_scaling(&self.scalings, channel)
+    }
+}
+
+// 8 channel analog input module. The range is the one the module is set to,
+// e.g. 10 for +-10 V or 20 for the 4-20 mA of the water-flow transmitters.
+pub struct M7017 {
+    client: Modbus,
+    range: f32,
+    scalings: Vec<Scaling>,
+}
+
+impl M7017 {
+    pub fn new(id: u8) -> Self {
+        let modbus = Modbus::new(id).with_retry_policy(RetryPolicy::new(READ_ATTEMPTS));
+        Self {
+            client: modbus,
+            range: 10.0,
+            scalings: Vec::new(),
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry);
+        self
+    }
+
+    pub fn with_scaling(mut self, channel: u8, scaling: Scaling) -> Self {
+        set_scaling(&mut self.scalings, channel, scaling);
+        self
+    }
+}
+
+impl HostWatchdog for M7017 {
+    fn modbus(&self) -> &Modbus {
+        &self.client
+    }
+}
+
+impl IcpconModule for M7017 {
+    const CHANNELS: u8 = 8;
+    type ChannelType = u16;
+
+    fn client(&self) -> &Modbus {
+        &self.client
+    }
+
+    fn scale(&self, raw: u16) -> f32 {
+        codec::full_scale(raw, self.range)
+    }
+
+    fn scaling(&self, channel: u8) -> Scaling {
+        channel_scaling(&self.scalings, channel)
+    }
+}
+
+// 8 channel thermocouple input module, the range is the top of the
+// thermocouple type in degrees, 1370 for type K
+pub struct M7018 {
+    client: Modbus,
+    range: f32,
+    scalings: Vec<Scaling>,
+}
+
+impl M7018 {
+    pub fn new(id: u8) -> Self {
+        let modbus = Modbus::new(id).with_retry_policy(RetryPolicy::new(READ_ATTEMPTS));
+        Self {
+            client: modbus,
+            range: 1370.0,
+            scalings: Vec::new(),
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry);
+        self
+    }
+
+    pub fn with_scaling(mut self, channel: u8, scaling: Scaling) -> Self {
+        set_scaling(&mut self.scalings, channel, scaling);
+        self
+    }
+
+    // Temperature of the terminals the thermocouple readings are
+    // compensated with
+    pub fn get_cjc_temperature(
+        &self,
+        client: &mut (impl Write + Read),
+    ) -> Result<f32, ModbusError> {
+        let response = self.client.read_input_registers(client, CJC_REGISTER, 1)?;
+        codec::single(&response).map(codec::tenths)
+    }
+}
+
+impl HostWatchdog for M7018 {
+    fn modbus(&self) -> &Modbus {
+        &self.client
+    }
+}
+
+impl IcpconModule for M7018 {
+    const CHANNELS: u8 = 8;
+    type ChannelType = u16;
+
+    fn client(&self) -> &Modbus {
+        &self.client
+    }
+
+    fn scale(&self, raw: u16) -> f32 {
+        codec::full_scale(raw, self.range)
+    }
+
+    fn scaling(&self, channel: u8) -> Scaling {
+        channel_scaling(&self.scalings, channel)
+    }
+}
+
+// 14 channel isolated digital input module
+pub struct M7041 {
+    client: Modbus,
+}
+
+impl M7041 {
+    pub const CHANNELS: u8 = 14;
+
     pub fn new(id: u8) -> Self {
         let modbus = Modbus::new(id);
         Self { client: modbus }
     }
 
-    pub fn get_current_measurement(
+    pub fn read_inputs(&self, client: &mut (impl Write + Read)) -> Result<Vec<bool>, ModbusError> {
+        self.client
+            .read_discrete_inputs(client, 0x00, Self::CHANNELS as u16)
+    }
+
+    pub fn read_input(
         &self,
         client: &mut (impl Write + Read),
         channel: u8,
-        retries: u8,
-    ) -> Result<f32, ModbusError> {
-        for t in 0..retries {
-            match self.client.read_input_registers(client, 0x00, 6) {
-                Ok(response) => {
-                    if response.len() != 6 {
-                        return Err(ModbusError::InvalidResponseLength {
-                            expected: 6,
-                            received: response.len(),
-                        });
-                    }
-
-                    return Ok(response[channel as usize] as f32 / 10.0);
-                }
-                Err(e) => {
-                    if t == retries - 1 {
-                        return Err(e);
-                    }
-                }
-            }
-        }
-
-        unreachable!()
+    ) -> Result<bool, ModbusError> {
+        check_channel(channel, Self::CHANNELS)?;
+        self.client.read_discrete_input(client, channel as u16)
+    }
+}
+
+impl HostWatchdog for M7041 {
+    fn modbus(&self) -> &Modbus {
+        &self.client
+    }
+}
+
+// 16 channel digital output module
+pub struct M7045 {
+    client: Modbus,
+}
+
+impl M7045 {
+    pub const CHANNELS: u8 = 16;
+
+    pub fn new(id: u8) -> Self {
+        let modbus = Modbus::new(id);
+        Self { client: modbus }
+    }
+
+    pub fn read_outputs(&self, client: &mut (impl Write + Read)) -> Result<Vec<bool>, ModbusError> {
+        self.client.read_coils(client, 0x00, Self::CHANNELS as u16)
+    }
+
+    pub fn read_output(
+        &self,
+        client: &mut (impl Write + Read),
+        channel: u8,
+    ) -> Result<bool, ModbusError> {
+        check_channel(channel, Self::CHANNELS)?;
+        self.client.read_coil(client, channel as u16)
+    }
+
+    pub fn write_output(
+        &self,
+        client: &mut (impl Write + Read),
+        channel: u8,
+        value: bool,
+    ) -> Result<(), ModbusError> {
+        check_channel(channel, Self::CHANNELS)?;
+        self.client.write_single_coil(client, channel as u16, value)
+    }
+
+    pub fn write_outputs(
+        &self,
+        client: &mut (impl Write + Read),
+        values: &[bool],
+    ) -> Result<(), ModbusError> {
+        if values.len() > Self::CHANNELS as usize {
+            return Err(ModbusError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Too many output values",
+            )));
+        }
+
+        self.client.write_multiple_coils(client, 0x00, values)
+    }
+}
+
+impl HostWatchdog for M7045 {
+    fn modbus(&self) -> &Modbus {
+        &self.client
+    }
+}
+
+// One digital output wired to an external interlock, a channel of an M-7045
+// or the alarm output of another module. The coil is only written when the
+// wanted state differs from the last one the module took.
+pub struct AlarmOutput {
+    client: Modbus,
+    coil: u16,
+    // Output that opens on alarm, so a cut cable also trips the interlock
+    active_low: bool,
+    asserted: Option<bool>,
+}
+
+impl AlarmOutput {
+    pub fn new(id: u8, coil: u16) -> Self {
+        Self {
+            client: Modbus::new(id),
+            coil,
+            active_low: false,
+            asserted: None,
+        }
+    }
+
+    pub fn with_active_low(mut self, active_low: bool) -> Self {
+        self.active_low = active_low;
+        self
+    }
+
+    // None until the first write went through
+    pub fn is_asserted(&self) -> Option<bool> {
+        self.asserted
+    }
+
+    pub fn set(
+        &mut self,
+        client: &mut (impl Write + Read),
+        asserted: bool,
+    ) -> Result<(), ModbusError> {
+        if self.asserted == Some(asserted) {
+            return Ok(());
+        }
+
+        self.asserted = None;
+        self.client
+            .write_single_coil(client, self.coil, asserted != self.active_low)?;
+        self.asserted = Some(asserted);
+
+        Ok(())
     }
 }
@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use utilities::modbus::{ModbusError, async_client::AsyncModbus, retry::RetryPolicy};
+
+use crate::{LirLayout, LirStatus, READ_ATTEMPTS, VELOCITY_INTERVAL};
+
+// Same encoder as `LIR` for tokio-native services, reads wait on the bus
+// instead of blocking a thread
+pub struct AsyncLIR {
+    client: AsyncModbus,
+    step: f32,
+    layout: LirLayout,
+}
+
+impl AsyncLIR {
+    pub fn new(id: u8, step: f32) -> Self {
+        Self {
+            client: AsyncModbus::new(id).with_retry_policy(RetryPolicy::new(READ_ATTEMPTS)),
+            step,
+            layout: LirLayout::default(),
+        }
+    }
+
+    pub fn with_layout(mut self, layout: LirLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry);
+        self
+    }
+
+    pub async fn get_current_measurement(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<f32, ModbusError> {
+        let response = self
+            .client
+            .read_input_registers(client, self.layout.start, self.layout.len)
+            .await?;
+        self.layout.measurement(&response, self.step)
+    }
+
+    pub async fn get_status(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<LirStatus, ModbusError> {
+        let response = self
+            .client
+            .read_input_registers(client, self.layout.start, self.layout.len)
+            .await?;
+        self.layout.status(&response)
+    }
+
+    async fn stamped_count(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<(f64, Instant), ModbusError> {
+        let response = self
+            .client
+            .read_input_registers(client, self.layout.start, self.layout.len)
+            .await?;
+        Ok((self.layout.valid_count(&response)?, Instant::now()))
+    }
+
+    pub async fn get_velocity(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<f32, ModbusError> {
+        if let Some(register) = self.layout.velocity_register {
+            let response = self
+                .client
+                .read_input_registers(client, register, 2)
+                .await?;
+            return self.layout.velocity(&response);
+        }
+
+        let (first, first_at) = self.stamped_count(client).await?;
+        tokio::time::sleep(VELOCITY_INTERVAL).await;
+        let (second, second_at) = self.stamped_count(client).await?;
+
+        Ok(((second - first) / (second_at - first_at).as_secs_f64()) as f32)
+    }
+
+    pub async fn set_preset(
+        &self,
+        client: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        value: f32,
+    ) -> Result<(), ModbusError> {
+        let words = self.layout.preset_words(value, self.step)?;
+        self.client
+            .write_multiple_registers(client, self.layout.preset_register, &words)
+            .await
+    }
+}
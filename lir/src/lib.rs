@@ -1,52 +1,377 @@
-use std::io::{Read, Write};
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
 
-use utilities::modbus::{Modbus, ModbusError};
+use utilities::modbus::{Modbus, ModbusError, retry::RetryPolicy};
+
+pub mod async_client;
+
+// Time between the two reads of a velocity estimate. Long enough for a
+// crawling blade to move a few counts, short enough to catch a stall early.
+pub const VELOCITY_INTERVAL: Duration = Duration::from_millis(50);
+
+// Reads a timeout or a garbled reply is retried within
+const READ_ATTEMPTS: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    LowFirst,
+    HighFirst,
+}
+
+// Where an interface module keeps the count. The default is the module the
+// slits were commissioned with: a block of 5 input registers from 0 with the
+// count as a signed 32 bit value, low word first, at 1. Register 0 is not
+// decoded unless status_word is set for a module known to report
+// LirStatus there. The preset holding registers share the layout of the
+// count.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LirLayout {
+    // First input register of the measurement block and its length
+    pub start: u16,
+    pub len: u16,
+    // Offsets within the block, the status offset is ignored for modules
+    // without a status word
+    pub count_offset: u16,
+    pub status_word: bool,
+    pub status_offset: u16,
+    pub word_order: WordOrder,
+    pub signed: bool,
+    pub preset_register: u16,
+    // Input register of a module that measures the speed itself, a signed 32
+    // bit count per second in the word order of the count
+    pub velocity_register: Option<u16>,
+}
+
+impl Default for LirLayout {
+    fn default() -> Self {
+        Self {
+            start: 0x00,
+            len: 5,
+            count_offset: 1,
+            status_word: false,
+            status_offset: 0,
+            word_order: WordOrder::LowFirst,
+            signed: true,
+            preset_register: 0x0001,
+            velocity_register: None,
+        }
+    }
+}
+
+impl LirLayout {
+    // The block has to hold the count and the status word
+    pub fn validate(&self) -> Result<(), String> {
+        if self.len == 0 || self.len > 125 {
+            return Err(format!("Invalid LIR block length {}", self.len));
+        }
+        if self.count_offset as u32 + 2 > self.len as u32 {
+            return Err(format!(
+                "LIR count at offset {} is outside of the {} register block",
+                self.count_offset, self.len
+            ));
+        }
+        if self.status_word && self.status_offset >= self.len {
+            return Err(format!(
+                "LIR status at offset {} is outside of the {} register block",
+                self.status_offset, self.len
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_len(&self, response: &[u16]) -> Result<(), ModbusError> {
+        if response.len() != self.len as usize {
+            return Err(ModbusError::InvalidResponseLength {
+                expected: self.len as usize,
+                received: response.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Modules without a status word always report a valid reading
+    fn status(&self, response: &[u16]) -> Result<LirStatus, ModbusError> {
+        self.check_len(response)?;
+
+        Ok(match self.status_word {
+            true => LirStatus::from_bits_truncate(response[self.status_offset as usize]),
+            false => LirStatus::empty(),
+        })
+    }
+
+    fn join_words(&self, first: u16, second: u16) -> u32 {
+        let (first, second) = (first as u32, second as u32);
+        match self.word_order {
+            WordOrder::LowFirst => second << 16 | first,
+            WordOrder::HighFirst => first << 16 | second,
+        }
+    }
+
+    fn count(&self, response: &[u16]) -> Result<f64, ModbusError> {
+        self.check_len(response)?;
+
+        let raw = self.join_words(
+            response[self.count_offset as usize],
+            response[self.count_offset as usize + 1],
+        );
+
+        Ok(match self.signed {
+            true => raw as i32 as f64,
+            false => raw as f64,
+        })
+    }
+
+    // Counts the encoder flags as invalid never reach the closed loop
+    fn valid_count(&self, response: &[u16]) -> Result<f64, ModbusError> {
+        let status = self.status(response)?;
+        if !status.is_valid() {
+            return Err(ModbusError::ProtocolError(format!(
+                "Encoder reading rejected: {}",
+                status
+            )));
+        }
+
+        self.count(response)
+    }
+
+    fn measurement(&self, response: &[u16], step: f32) -> Result<f32, ModbusError> {
+        Ok((self.valid_count(response)? * step as f64) as f32)
+    }
+
+    fn velocity(&self, response: &[u16]) -> Result<f32, ModbusError> {
+        if response.len() != 2 {
+            return Err(ModbusError::InvalidResponseLength {
+                expected: 2,
+                received: response.len(),
+            });
+        }
+
+        Ok(self.join_words(response[0], response[1]) as i32 as f32)
+    }
+
+    fn preset_words(&self, value: f32, step: f32) -> Result<[u16; 2], ModbusError> {
+        let count = (value as f64 / step as f64).round();
+        let (min, max) = match self.signed {
+            true => (i32::MIN as f64, i32::MAX as f64),
+            false => (0.0, u32::MAX as f64),
+        };
+        if !count.is_finite() || count < min || count > max {
+            return Err(ModbusError::ProtocolError(format!(
+                "Preset {} is out of the encoder range",
+                value
+            )));
+        }
+
+        let raw = match self.signed {
+            true => count as i32 as u32,
+            false => count as u32,
+        };
+        let (low, high) = (raw as u16, (raw >> 16) as u16);
+        Ok(match self.word_order {
+            WordOrder::LowFirst => [low, high],
+            WordOrder::HighFirst => [high, low],
+        })
+    }
+}
+
+bitflags!(
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct LirStatus: u16 {
+        const BATTERY_LOW = 0b001;
+        const COUNT_ERROR = 0b010;
+        const SIGNAL_LOSS = 0b100;
+    }
+);
+
+impl LirStatus {
+    // A low battery only threatens the count kept while powered off, the
+    // reading itself is still good
+    pub fn is_valid(&self) -> bool {
+        !self.intersects(LirStatus::COUNT_ERROR | LirStatus::SIGNAL_LOSS)
+    }
+}
+
+impl fmt::Display for LirStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags: Vec<&str> = [
+            (LirStatus::BATTERY_LOW, "battery low"),
+            (LirStatus::COUNT_ERROR, "count error"),
+            (LirStatus::SIGNAL_LOSS, "signal loss"),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| self.contains(*flag))
+        .map(|(_, name)| name)
+        .collect();
+
+        match flags.is_empty() {
+            true => write!(f, "ok"),
+            false => write!(f, "{}", flags.join(", ")),
+        }
+    }
+}
 
 pub struct LIR {
     client: Modbus,
     step: f32,
+    layout: LirLayout,
 }
 
 impl LIR {
     pub fn new(id: u8, step: f32) -> Self {
-        let modbus = Modbus::new(id);
+        let modbus = Modbus::new(id).with_retry_policy(RetryPolicy::new(READ_ATTEMPTS));
         Self {
             client: modbus,
             step,
+            layout: LirLayout::default(),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry);
+        self
+    }
+
+    pub fn with_layout(mut self, layout: LirLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn id(&self) -> u8 {
+        self.client.id()
+    }
+
     pub fn get_current_measurement(
         &self,
         client: &mut (impl Write + Read),
-        retries: u8,
     ) -> Result<f32, ModbusError> {
-        for t in 0..retries {
-            match self.client.read_input_registers(client, 0x00, 5) {
-                Ok(response) => {
-                    if response.len() != 5 {
-                        return Err(ModbusError::InvalidResponseLength {
-                            expected: 5,
-                            received: response.len(),
-                        });
-                    }
-
-                    let result = i32::from_le_bytes([
-                        (response[1] & 0xFF) as u8,
-                        (response[1] >> 8) as u8,
-                        (response[2] & 0xFF) as u8,
-                        (response[2] >> 8) as u8,
-                    ]);
-                    return Ok(result as f32 * self.step);
-                }
-                Err(e) => {
-                    if t == retries - 1 {
-                        return Err(e);
-                    }
-                }
+        let response =
+            self.client
+                .read_input_registers(client, self.layout.start, self.layout.len)?;
+        self.layout.measurement(&response, self.step)
+    }
+
+    pub fn get_status(&self, client: &mut (impl Write + Read)) -> Result<LirStatus, ModbusError> {
+        let response =
+            self.client
+                .read_input_registers(client, self.layout.start, self.layout.len)?;
+        self.layout.status(&response)
+    }
+
+    // Counts per second, from the module's own register where it has one,
+    // otherwise from two reads VELOCITY_INTERVAL apart. Each read is stamped
+    // when its reply is in, so a slow bus does not pass for a slow blade.
+    pub fn get_velocity(&self, client: &mut (impl Write + Read)) -> Result<f32, ModbusError> {
+        if let Some(register) = self.layout.velocity_register {
+            let response = self.client.read_input_registers(client, register, 2)?;
+            return self.layout.velocity(&response);
+        }
+
+        let mut read = || -> Result<(f64, Instant), ModbusError> {
+            let response =
+                self.client
+                    .read_input_registers(client, self.layout.start, self.layout.len)?;
+            Ok((self.layout.valid_count(&response)?, Instant::now()))
+        };
+
+        let (first, first_at) = read()?;
+        thread::sleep(VELOCITY_INTERVAL);
+        let (second, second_at) = read()?;
+
+        Ok(((second - first) / (second_at - first_at).as_secs_f64()) as f32)
+    }
+
+    // Makes the current position read as `value`, in the units of the
+    // measurement. After homing a blade, set_preset(client, 0.0) puts the
+    // encoder zero on the switch.
+    pub fn set_preset(
+        &self,
+        client: &mut (impl Write + Read),
+        value: f32,
+    ) -> Result<(), ModbusError> {
+        let words = self.layout.preset_words(value, self.step)?;
+        self.client
+            .write_multiple_registers(client, self.layout.preset_register, &words)
+    }
+}
+
+// Encoders sharing one gateway. They are separate slaves, so there is no
+// block read across them; read_all reads them back to back in one go and
+// retries only the ones that failed, a dead encoder does not hold up the rest.
+pub struct LirBus {
+    encoders: Vec<LIR>,
+    retries: u8,
+}
+
+impl LirBus {
+    pub fn new(encoders: Vec<LIR>) -> Self {
+        // The bus does the retrying, round by round
+        let encoders = encoders
+            .into_iter()
+            .map(|encoder| encoder.with_retry_policy(RetryPolicy::none()))
+            .collect();
+
+        Self {
+            encoders,
+            retries: READ_ATTEMPTS,
+        }
+    }
+
+    pub fn with_retries(mut self, retries: u8) -> Self {
+        self.retries = retries.max(1);
+        self
+    }
+
+    pub fn ids(&self) -> Vec<u8> {
+        self.encoders.iter().map(LIR::id).collect()
+    }
+
+    // One measurement per id, in the order given
+    pub fn read_all(
+        &self,
+        client: &mut (impl Write + Read),
+        ids: &[u8],
+    ) -> Vec<Result<f32, ModbusError>> {
+        let mut results: Vec<Result<f32, ModbusError>> = ids
+            .iter()
+            .map(|id| {
+                Err(ModbusError::ProtocolError(format!(
+                    "No encoder with id {}",
+                    id
+                )))
+            })
+            .collect();
+        let mut pending: Vec<(usize, &LIR)> = ids
+            .iter()
+            .enumerate()
+            .filter_map(|(index, id)| {
+                self.encoders
+                    .iter()
+                    .find(|encoder| encoder.id() == *id)
+                    .map(|encoder| (index, encoder))
+            })
+            .collect();
+
+        for _ in 0..self.retries {
+            pending.retain(|&(index, encoder)| {
+                results[index] = encoder.get_current_measurement(client);
+                results[index].is_err()
+            });
+
+            if pending.is_empty() {
+                break;
             }
         }
 
-        unreachable!()
+        results
     }
 }
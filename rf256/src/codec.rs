@@ -0,0 +1,95 @@
+// Pure RIFTEK wire coding, free of IO and std-only types so it can be reused
+// on `core` + `alloc` targets.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidFormat,
+    CounterMismatch,
+    InvalidChecksum { expected: u8, received: u8 },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidFormat => write!(f, "Invalid response format"),
+            DecodeError::CounterMismatch => write!(f, "Counters do not match"),
+            DecodeError::InvalidChecksum { expected, received } => write!(
+                f,
+                "Invalid checksum: expected {:#04x}, received {:#04x}",
+                expected, received
+            ),
+        }
+    }
+}
+
+pub fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |acc, &byte| acc.wrapping_add(byte))
+}
+
+// Every payload byte travels as two bytes carrying one nibble each, with the
+// top bit set to tell them apart from the address byte
+pub fn encode_request(device_id: u8, command: u8, payload: &[u8], checksummed: bool) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(2 + (payload.len() + 1) * 2);
+
+    packet.push(device_id);
+    packet.push(command | 0x80);
+
+    let mut push_byte = |byte: u8| {
+        packet.push(0x80 | (byte & 0x0F));
+        packet.push(0x80 | ((byte >> 4) & 0x0F));
+    };
+
+    for &byte in payload {
+        push_byte(byte);
+    }
+
+    if checksummed {
+        push_byte(command.wrapping_add(checksum(payload)));
+    }
+
+    packet
+}
+
+// Number of raw bytes on the wire for a response of `len` decoded bytes
+pub fn encoded_len(len: usize, checksummed: bool) -> usize {
+    (if checksummed { len + 1 } else { len }) * 2
+}
+
+pub fn decode_response(raw: &[u8], checksummed: bool) -> Result<Vec<u8>, DecodeError> {
+    if raw.len() % 2 != 0 {
+        return Err(DecodeError::InvalidFormat);
+    }
+
+    let mut decoded = Vec::with_capacity(raw.len() / 2);
+    let mut counter = None;
+
+    for chunk in raw.chunks(2) {
+        if chunk[0] & 0x80 == 0 || chunk[1] & 0x80 == 0 {
+            return Err(DecodeError::InvalidFormat);
+        }
+
+        // all counters must be the same
+        for byte_counter in [chunk[0] >> 4, chunk[1] >> 4] {
+            if *counter.get_or_insert(byte_counter) != byte_counter {
+                return Err(DecodeError::CounterMismatch);
+            }
+        }
+
+        decoded.push((chunk[0] & 0x0F) | ((chunk[1] & 0x0F) << 4));
+    }
+
+    if checksummed {
+        let received = decoded.pop().unwrap_or_default();
+        let expected = checksum(&decoded);
+
+        if received != expected {
+            return Err(DecodeError::InvalidChecksum { expected, received });
+        }
+    }
+
+    Ok(decoded)
+}
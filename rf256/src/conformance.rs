@@ -0,0 +1,70 @@
+use std::{io, time::Duration};
+
+use utilities::conformance::{argument, run_vectors, split_call, Failure, ReplayPort};
+
+use crate::{ReadTimeout, Rf256};
+
+pub const VECTORS: &str = include_str!("../vectors/packets.txt");
+pub const CHECKSUM_VECTORS: &str = include_str!("../vectors/checksum.txt");
+
+// Replies are queued up front, a read never waits
+impl ReadTimeout for ReplayPort {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        Ok(None)
+    }
+
+    fn set_read_timeout(&mut self, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Replays the packet vectors against a sensor on address 1, see
+// utilities::conformance for the format
+pub fn check(vectors: &str, checksummed: bool) -> Result<Vec<Failure>, String> {
+    let mut sensor = Rf256::new(1).with_checksum(checksummed);
+
+    run_vectors(vectors, |call, port| {
+        let result = match split_call(call).0 {
+            "read_data" => sensor.read_data(port).map(|v| v.to_string()),
+            "read_device_info" => sensor.read_device_info(port).map(|info| {
+                format!(
+                    "{}/{}/{}/{}/{}",
+                    info.device_type,
+                    info.firmware_revision,
+                    info.serial_number,
+                    info.base_distance,
+                    info.measurement_range
+                )
+            }),
+            "is_laser_on" => sensor.is_laser_on(port).map(|on| on.to_string()),
+            "laser_off" => sensor.laser_off(port).map(|_| String::new()),
+            "read_id" => sensor.read_id(port).map(|id| id.to_string()),
+            "set_id" => sensor.set_id(port, argument(call)?).map(|_| String::new()),
+            "read_baudrate" => sensor.read_baudrate(port).map(|v| v.to_string()),
+            "set_baudrate" => sensor
+                .set_baudrate(port, argument(call)?)
+                .map(|_| String::new()),
+            "read_sample_period" => sensor.read_sample_period(port).map(|v| v.to_string()),
+            "save_to_flash" => sensor.save_to_flash(port).map(|_| String::new()),
+            name => return Err(format!("Unknown call: {}", name)),
+        };
+
+        result.map_err(|e| e.to_string())
+    })
+}
+
+#[test]
+fn vectors_pass() {
+    for (vectors, checksummed) in [(VECTORS, false), (CHECKSUM_VECTORS, true)] {
+        let failures = check(vectors, checksummed).unwrap();
+        assert!(
+            failures.is_empty(),
+            "{}",
+            failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::{error::Error, fmt};
+
+use crate::codec::DecodeError;
+
+#[derive(Debug)]
+pub enum Rf256Error {
+    Io(std::io::Error),
+    FramingError,
+    CounterMismatch,
+    ChecksumMismatch { expected: u8, received: u8 },
+    IdMismatch { expected: u8, received: u8 },
+    InvalidResponse(String),
+    InvalidInput(&'static str),
+}
+
+impl Rf256Error {
+    // The line is out of step with the sensor (stale bytes, a reply meant for
+    // someone else). Clearing the receive buffer and asking again usually
+    // recovers, unlike an IO failure of the bridge itself.
+    pub fn is_desync(&self) -> bool {
+        matches!(
+            self,
+            Rf256Error::FramingError
+                | Rf256Error::CounterMismatch
+                | Rf256Error::ChecksumMismatch { .. }
+                | Rf256Error::IdMismatch { .. }
+        )
+    }
+}
+
+impl fmt::Display for Rf256Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rf256Error::Io(err) => write!(f, "IO error: {}", err),
+            Rf256Error::FramingError => write!(f, "Invalid response format"),
+            Rf256Error::CounterMismatch => write!(f, "Counters do not match"),
+            Rf256Error::ChecksumMismatch { expected, received } => write!(
+                f,
+                "Invalid checksum: expected {:#04x}, received {:#04x}",
+                expected, received
+            ),
+            Rf256Error::IdMismatch { expected, received } => write!(
+                f,
+                "Device ID mismatch: expected {}, got {}",
+                expected, received
+            ),
+            Rf256Error::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+            Rf256Error::InvalidInput(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for Rf256Error {}
+
+impl From<std::io::Error> for Rf256Error {
+    fn from(error: std::io::Error) -> Self {
+        Rf256Error::Io(error)
+    }
+}
+
+impl From<DecodeError> for Rf256Error {
+    fn from(error: DecodeError) -> Self {
+        match error {
+            DecodeError::InvalidFormat => Rf256Error::FramingError,
+            DecodeError::CounterMismatch => Rf256Error::CounterMismatch,
+            DecodeError::InvalidChecksum { expected, received } => {
+                Rf256Error::ChecksumMismatch { expected, received }
+            }
+        }
+    }
+}
+
+impl From<Rf256Error> for std::io::Error {
+    fn from(error: Rf256Error) -> Self {
+        match error {
+            Rf256Error::Io(error) => error,
+            Rf256Error::InvalidInput(_) => {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string())
+            }
+            _ => std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string()),
+        }
+    }
+}
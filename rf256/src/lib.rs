@@ -1,6 +1,23 @@
+extern crate alloc;
+
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
-use std::io::{Read, Write};
+use std::{
+    cell::Cell,
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+
+pub mod codec;
+// Test-only, the vector runner lives in utilities which the driver does not
+// depend on
+#[cfg(test)]
+mod conformance;
+mod error;
+pub mod mock;
+
+pub use error::Rf256Error;
 
 bitflags! {
     #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -12,14 +29,141 @@ bitflags! {
     }
 }
 
+// Transports that can bound a single read, so the response timeout holds
+// even where the transport would wait longer on its own. None waits for
+// ever.
+pub trait ReadTimeout {
+    fn read_timeout(&self) -> io::Result<Option<Duration>>;
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        TcpStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+// RIFTEK sensors report results normalized to 0x4000 over the measurement range
+const RAW_FULL_SCALE: f32 = 16384.0;
+const DEFAULT_DIVISOR: f32 = 10000.0;
+
 #[derive(Debug, Clone, Copy)]
+pub struct DeviceInfo {
+    pub device_type: u8,
+    pub firmware_revision: u8,
+    pub serial_number: u16,
+    pub base_distance: u16,
+    pub measurement_range: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fallback {
+    Error,
+    LastValue,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rf256Builder {
+    device_id: u8,
+    measurement_range: Option<u16>,
+    checksummed: bool,
+    timeout: Option<Duration>,
+    retries: u32,
+    fallback: Fallback,
+    zero_offset: f32,
+}
+
+impl Rf256Builder {
+    pub fn new(device_id: u8) -> Self {
+        Self {
+            device_id,
+            measurement_range: None,
+            checksummed: false,
+            timeout: None,
+            retries: 0,
+            fallback: Fallback::Error,
+            zero_offset: 0.0,
+        }
+    }
+
+    pub fn measurement_range(mut self, measurement_range: u16) -> Self {
+        self.measurement_range = Some(measurement_range);
+        self
+    }
+
+    pub fn checksum(mut self, checksummed: bool) -> Self {
+        self.checksummed = checksummed;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn fallback(mut self, fallback: Fallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn zero_offset(mut self, zero_offset: f32) -> Self {
+        self.zero_offset = zero_offset;
+        self
+    }
+
+    pub fn build(self) -> Rf256 {
+        Rf256 {
+            device_id: self.device_id,
+            measurement_range: self.measurement_range,
+            checksummed: self.checksummed,
+            timeout: self.timeout,
+            retries: self.retries,
+            fallback: self.fallback,
+            zero_offset: self.zero_offset,
+            last_value: Cell::new(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Rf256 {
     device_id: u8,
+    measurement_range: Option<u16>,
+    checksummed: bool,
+    timeout: Option<Duration>,
+    retries: u32,
+    fallback: Fallback,
+    // Subtracted from every reading, in mm, so positions can be referenced to
+    // the beam axis rather than the sensor face
+    zero_offset: f32,
+    last_value: Cell<Option<f32>>,
 }
 
 impl Rf256 {
     pub fn new(device_id: u8) -> Self {
-        Rf256 { device_id }
+        Rf256Builder::new(device_id).build()
+    }
+
+    pub fn builder(device_id: u8) -> Rf256Builder {
+        Rf256Builder::new(device_id)
+    }
+
+    pub fn with_checksum(mut self, checksummed: bool) -> Self {
+        self.checksummed = checksummed;
+        self
+    }
+
+    pub fn is_checksummed(&self) -> bool {
+        self.checksummed
     }
 
     pub fn get_device_id(&self) -> u8 {
@@ -30,6 +174,22 @@ impl Rf256 {
         self.device_id = device_id;
     }
 
+    pub fn measurement_range(&self) -> Option<u16> {
+        self.measurement_range
+    }
+
+    pub fn set_measurement_range(&mut self, measurement_range: Option<u16>) {
+        self.measurement_range = measurement_range;
+    }
+
+    pub fn zero_offset(&self) -> f32 {
+        self.zero_offset
+    }
+
+    pub fn set_zero_offset(&mut self, zero_offset: f32) {
+        self.zero_offset = zero_offset;
+    }
+
     fn convert_bytes_to_float(&self, data: &[u8]) -> f32 {
         if data.len() != 4 {
             panic!("Data must be exactly 4 bytes long");
@@ -37,7 +197,10 @@ impl Rf256 {
 
         let raw_value = i32::from_le_bytes(data.try_into().unwrap());
 
-        raw_value as f32 / 10000.0
+        match self.measurement_range {
+            Some(range) => raw_value as f32 * range as f32 / RAW_FULL_SCALE,
+            None => raw_value as f32 / DEFAULT_DIVISOR,
+        }
     }
 
     fn send_command(
@@ -45,18 +208,13 @@ impl Rf256 {
         sender: &mut impl Write,
         command: u8,
         msg: Option<&[u8]>,
-    ) -> std::io::Result<()> {
-        let mut packet = Vec::new();
-
-        packet.push(self.device_id);
-        packet.push(command | 0x80);
-
-        if let Some(msg) = msg {
-            for &byte in msg {
-                packet.push(0x80 | (byte & 0x0F));
-                packet.push(0x80 | ((byte >> 4) & 0x0F));
-            }
-        }
+    ) -> Result<(), Rf256Error> {
+        let packet = codec::encode_request(
+            self.device_id,
+            command,
+            msg.unwrap_or_default(),
+            self.checksummed,
+        );
 
         sender.write_all(&packet)?;
 
@@ -67,63 +225,100 @@ impl Rf256 {
         &self,
         sender: &mut impl Read,
         expected_len: usize,
-    ) -> std::io::Result<Vec<u8>> {
-        let mut raw = vec![0; expected_len * 2];
+    ) -> Result<Vec<u8>, Rf256Error> {
+        let mut raw = vec![0; codec::encoded_len(expected_len, self.checksummed)];
 
-        match sender.read_exact(&mut raw) {
-            Ok(_) => {}
-            Err(e) => return Err(e),
+        match self.timeout {
+            Some(timeout) => read_exact_within(sender, &mut raw, timeout)?,
+            None => sender.read_exact(&mut raw)?,
         }
 
-        let mut decoded = vec![];
-        let mut counters = vec![];
-
-        for chunk in raw.chunks(2) {
-            if chunk.len() != 2 || chunk[0] & 0x80 == 0 || chunk[1] & 0x80 == 0 {
+        codec::decode_response(&raw, self.checksummed).map_err(|e| {
+            // Framing is lost, drop whatever is left on the line
+            if !matches!(e, codec::DecodeError::InvalidChecksum { .. }) {
                 let mut buf = vec![0; 256];
                 let _ = sender.read_to_end(&mut buf);
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "Invalid response format",
-                ));
             }
 
-            let low = chunk[0] & 0x0F;
-            let high = chunk[1] & 0x0F;
+            Rf256Error::from(e)
+        })
+    }
 
-            let low_counter = chunk[0] >> 4;
-            let high_counter = chunk[1] >> 4;
+    fn read_data_once(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<f32, Rf256Error> {
+        self.send_command(sender, 0x06, None)?;
+        let response = self.read_response(sender, 4)?;
 
-            decoded.push(low | (high << 4));
-            counters.push(low_counter);
-            counters.push(high_counter);
-        }
+        Ok(self.convert_bytes_to_float(&response))
+    }
 
-        // all counters must be the same
-        if !counters.windows(2).all(|w| w[0] == w[1]) {
-            let mut buf = vec![0; 256];
-            let _ = sender.read_to_end(&mut buf);
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Counters do not match",
-            ));
-        }
+    pub fn read_data(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<f32, Rf256Error> {
+        self.read_sensor_value(sender)
+            .map(|value| value - self.zero_offset)
+    }
 
-        Ok(decoded)
+    // Cached values stay relative to the sensor face, so changing the offset
+    // also applies to a fallback reading
+    fn read_sensor_value(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<f32, Rf256Error> {
+        let mut attempt = 0;
+
+        loop {
+            match self.read_data_once(sender) {
+                Ok(value) => {
+                    self.last_value.set(Some(value));
+                    return Ok(value);
+                }
+                // A wrong reply is worth asking again once the stale bytes
+                // are gone, a dead bridge or a timeout is not
+                Err(e) if e.is_desync() && attempt < self.retries => {
+                    discard_pending(sender);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return match (self.fallback, self.last_value.get()) {
+                        (Fallback::LastValue, Some(value)) => Ok(value),
+                        _ => Err(e),
+                    };
+                }
+            }
+        }
     }
 
-    pub fn read_data(&self, sender: &mut (impl Write + Read)) -> std::io::Result<f32> {
-        self.send_command(sender, 0x06, None)?;
-        let response = self.read_response(sender, 4)?;
+    pub fn read_device_info(
+        &mut self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<DeviceInfo, Rf256Error> {
+        self.send_command(sender, 0x01, None)?;
+        let response = self.read_response(sender, 16)?;
+
+        let info = DeviceInfo {
+            device_type: response[0],
+            firmware_revision: response[1],
+            serial_number: u16::from_le_bytes([response[2], response[3]]),
+            base_distance: u16::from_le_bytes([response[4], response[5]]),
+            measurement_range: u16::from_le_bytes([response[6], response[7]]),
+        };
+
+        if info.measurement_range != 0 {
+            self.measurement_range = Some(info.measurement_range);
+        }
 
-        Ok(self.convert_bytes_to_float(&response))
+        Ok(info)
     }
 
     fn read_parameter(
         &self,
-        sender: &mut (impl Write + Read),
+        sender: &mut (impl Write + Read + ReadTimeout),
         parameter: u8,
-    ) -> std::io::Result<u8> {
+    ) -> Result<u8, Rf256Error> {
         self.send_command(sender, 0x02, Some(&[parameter]))?;
 
         let response = self.read_response(sender, 1)?;
@@ -133,54 +328,238 @@ impl Rf256 {
 
     fn write_parameter(
         &self,
-        sender: &mut (impl Write + Read),
+        sender: &mut (impl Write + Read + ReadTimeout),
         parameter: u8,
         value: u8,
-    ) -> std::io::Result<()> {
+    ) -> Result<(), Rf256Error> {
         self.send_command(sender, 0x03, Some(&[parameter, value]))?;
         Ok(())
     }
 
-    pub fn read_state(&self, sender: &mut (impl Write + Read)) -> std::io::Result<State> {
+    fn read_parameter_u16(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+        parameter: u8,
+    ) -> Result<u16, Rf256Error> {
+        let low = self.read_parameter(sender, parameter)?;
+        let high = self.read_parameter(sender, parameter + 1)?;
+
+        Ok(u16::from_le_bytes([low, high]))
+    }
+
+    fn write_parameter_u16(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+        parameter: u8,
+        value: u16,
+    ) -> Result<(), Rf256Error> {
+        let [low, high] = value.to_le_bytes();
+
+        self.write_parameter(sender, parameter, low)?;
+        self.write_parameter(sender, parameter + 1, high)
+    }
+
+    pub fn read_state(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<State, Rf256Error> {
         let value = self.read_parameter(sender, 0x00)?;
 
         bincode::deserialize::<State>(&[value])
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            .map_err(|e| Rf256Error::InvalidResponse(e.to_string()))
     }
 
-    pub fn read_id(&self, sender: &mut (impl Write + Read)) -> std::io::Result<u8> {
+    pub fn write_state(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+        state: State,
+    ) -> Result<(), Rf256Error> {
+        self.write_parameter(sender, 0x00, state.bits())
+    }
+
+    pub fn laser_on(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<(), Rf256Error> {
+        let state = self.read_state(sender)?;
+        self.write_state(sender, state | State::enabled)
+    }
+
+    pub fn laser_off(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<(), Rf256Error> {
+        let state = self.read_state(sender)?;
+        self.write_state(sender, state - State::enabled)
+    }
+
+    pub fn is_laser_on(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<bool, Rf256Error> {
+        self.read_state(sender)
+            .map(|state| state.contains(State::enabled))
+    }
+
+    pub fn read_id(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<u8, Rf256Error> {
         self.read_parameter(sender, 0x02)
     }
 
-    pub fn set_id(&self, sender: &mut (impl Write + Read), id: u8) -> std::io::Result<()> {
+    pub fn set_id(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+        id: u8,
+    ) -> Result<(), Rf256Error> {
         self.write_parameter(sender, 0x02, id)
     }
 
-    pub fn read_baudrate(&self, sender: &mut (impl Write + Read)) -> std::io::Result<u32> {
+    pub fn read_baudrate(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<u32, Rf256Error> {
         self.read_parameter(sender, 0x03).map(|v| v as u32 * 2400)
     }
 
     pub fn set_baudrate(
         &self,
-        sender: &mut (impl Write + Read),
+        sender: &mut (impl Write + Read + ReadTimeout),
         baudrate: u32,
-    ) -> std::io::Result<()> {
-        let value = (baudrate / 2400) as u8;
-        self.write_parameter(sender, 0x03, value)
+    ) -> Result<(), Rf256Error> {
+        // Stored in units of 2400 baud
+        if baudrate % 2400 != 0 || !(1..=u8::MAX as u32).contains(&(baudrate / 2400)) {
+            return Err(Rf256Error::InvalidInput(
+                "Baudrate must be a multiple of 2400 up to 612000",
+            ));
+        }
+
+        self.write_parameter(sender, 0x03, (baudrate / 2400) as u8)
+    }
+
+    // Analog output window, in raw sensor units
+    pub fn read_analog_window(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<(u16, u16), Rf256Error> {
+        let begin = self.read_parameter_u16(sender, 0x08)?;
+        let end = self.read_parameter_u16(sender, 0x0A)?;
+
+        Ok((begin, end))
+    }
+
+    pub fn set_analog_window(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+        begin: u16,
+        end: u16,
+    ) -> Result<(), Rf256Error> {
+        if begin >= end {
+            return Err(Rf256Error::InvalidInput(
+                "Analog window begin must be below its end",
+            ));
+        }
+
+        self.write_parameter_u16(sender, 0x08, begin)?;
+        self.write_parameter_u16(sender, 0x0A, end)
+    }
+
+    // Sample period is stored in 10 us units
+    pub fn read_sample_period(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<f32, Rf256Error> {
+        self.read_parameter_u16(sender, 0x0C)
+            .map(|period| period as f32 / 100.0)
+    }
+
+    pub fn set_sample_period(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+        period_ms: f32,
+    ) -> Result<(), Rf256Error> {
+        let period = (period_ms * 100.0).round();
+
+        if !(1.0..=u16::MAX as f32).contains(&period) {
+            return Err(Rf256Error::InvalidInput("Sample period out of range"));
+        }
+
+        self.write_parameter_u16(sender, 0x0C, period as u16)
     }
 
-    pub fn save_to_flash(&self, sender: &mut (impl Write + Read)) -> std::io::Result<()> {
+    pub fn save_to_flash(
+        &self,
+        sender: &mut (impl Write + Read + ReadTimeout),
+    ) -> Result<(), Rf256Error> {
         self.send_command(sender, 0x04, Some(&[0xAA]))?;
 
         let response = self.read_response(sender, 1)?;
 
         if response.is_empty() || response[0] != 0xAA {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Failed to save to flash",
+            return Err(Rf256Error::InvalidResponse(
+                "Failed to save to flash".to_string(),
             ));
         }
 
         Ok(())
     }
 }
+
+// Drops whatever already arrived, e.g. the rest of a reply that failed to
+// decode. Errors are left for the next request to report.
+fn discard_pending(sender: &mut impl Read) {
+    let mut buf = [0; 256];
+    let _ = sender.read(&mut buf);
+}
+
+// Like read_exact, but gives up once the whole response hasn't arrived within
+// `timeout`. Every read is bounded by what is left of it, the transport's
+// own read timeout is put back afterwards.
+fn read_exact_within(
+    sender: &mut (impl Read + ReadTimeout),
+    buf: &mut [u8],
+    timeout: Duration,
+) -> io::Result<()> {
+    let previous = sender.read_timeout()?;
+    let result = read_exact_until(sender, buf, Instant::now() + timeout);
+    let restored = sender.set_read_timeout(previous);
+
+    result.and(restored)
+}
+
+fn read_exact_until(
+    sender: &mut (impl Read + ReadTimeout),
+    buf: &mut [u8],
+    deadline: Instant,
+) -> io::Result<()> {
+    let mut filled = 0;
+
+    while filled < buf.len() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "Timed out waiting for response",
+            ));
+        }
+        sender.set_read_timeout(Some(remaining))?;
+
+        match sender.read(&mut buf[filled..]) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Connection closed while reading response",
+                ))
+            }
+            Ok(n) => filled += n,
+            Err(e)
+                if e.kind() == io::ErrorKind::WouldBlock
+                    || e.kind() == io::ErrorKind::TimedOut
+                    || e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+use crate::{codec::checksum, ReadTimeout, DEFAULT_DIVISOR};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    // Response is never sent, reads time out
+    Silent,
+    // Second half of the response carries a different packet counter
+    CounterMismatch,
+    // First byte of the response misses the 0x80 marker
+    MissingMarker,
+    // Last byte of the response is dropped
+    Truncated,
+    // Response checksum is off by one (only visible in checksummed mode)
+    BadChecksum,
+}
+
+// Simulated RF256 speaking the nibble-encoded wire format. Several sensors can
+// share one bus by chaining them with `MockBus`.
+#[derive(Debug, Clone)]
+pub struct MockSensor {
+    device_id: u8,
+    raw_position: i32,
+    parameters: [u8; 256],
+    device_info: [u8; 16],
+    checksummed: bool,
+
+    counter: u8,
+    faults: VecDeque<Fault>,
+    output: VecDeque<u8>,
+}
+
+impl MockSensor {
+    pub fn new(device_id: u8) -> Self {
+        let mut parameters = [0; 256];
+        parameters[0x00] = 0x01;
+        parameters[0x02] = device_id;
+        parameters[0x03] = 4;
+
+        Self {
+            device_id,
+            raw_position: 0,
+            parameters,
+            device_info: [0; 16],
+            checksummed: false,
+            counter: 0,
+            faults: VecDeque::new(),
+            output: VecDeque::new(),
+        }
+    }
+
+    pub fn with_checksum(mut self, checksummed: bool) -> Self {
+        self.checksummed = checksummed;
+        self
+    }
+
+    pub fn device_id(&self) -> u8 {
+        self.device_id
+    }
+
+    pub fn set_position(&mut self, position: f32) {
+        self.raw_position = (position * DEFAULT_DIVISOR).round() as i32;
+    }
+
+    pub fn set_raw_position(&mut self, raw_position: i32) {
+        self.raw_position = raw_position;
+    }
+
+    pub fn set_parameter(&mut self, parameter: u8, value: u8) {
+        self.parameters[parameter as usize] = value;
+    }
+
+    pub fn parameter(&self, parameter: u8) -> u8 {
+        self.parameters[parameter as usize]
+    }
+
+    pub fn set_device_info(
+        &mut self,
+        device_type: u8,
+        firmware_revision: u8,
+        serial_number: u16,
+        base_distance: u16,
+        measurement_range: u16,
+    ) {
+        self.device_info[0] = device_type;
+        self.device_info[1] = firmware_revision;
+        self.device_info[2..4].copy_from_slice(&serial_number.to_le_bytes());
+        self.device_info[4..6].copy_from_slice(&base_distance.to_le_bytes());
+        self.device_info[6..8].copy_from_slice(&measurement_range.to_le_bytes());
+    }
+
+    // Faults are consumed one per response, in order
+    pub fn inject_fault(&mut self, fault: Fault) {
+        self.faults.push_back(fault);
+    }
+
+    // Stale bytes left on the line, as after an interrupted exchange
+    pub fn inject_garbage(&mut self, bytes: &[u8]) {
+        self.output.extend(bytes);
+    }
+
+    pub fn pending(&self) -> usize {
+        self.output.len()
+    }
+
+    fn handle_packet(&mut self, packet: &[u8]) {
+        if packet.len() < 2 || packet[0] != self.device_id || packet[1] & 0x80 == 0 {
+            return;
+        }
+
+        let command = packet[1] & 0x7F;
+        let mut data: Vec<u8> = packet[2..]
+            .chunks(2)
+            .filter(|chunk| chunk.len() == 2)
+            .map(|chunk| (chunk[0] & 0x0F) | ((chunk[1] & 0x0F) << 4))
+            .collect();
+
+        if self.checksummed {
+            let Some(received) = data.pop() else {
+                return;
+            };
+
+            let mut checked = vec![command];
+            checked.extend_from_slice(&data);
+            if checksum(&checked) != received {
+                return;
+            }
+        }
+
+        let response = match (command, data.as_slice()) {
+            (0x01, _) => self.device_info.to_vec(),
+            (0x02, [parameter]) => vec![self.parameters[*parameter as usize]],
+            (0x03, [parameter, value]) => {
+                self.parameters[*parameter as usize] = *value;
+                if *parameter == 0x02 {
+                    self.device_id = *value;
+                }
+                return;
+            }
+            (0x04, [0xAA]) => vec![0xAA],
+            (0x06, _) => self.raw_position.to_le_bytes().to_vec(),
+            _ => return,
+        };
+
+        self.respond(response);
+    }
+
+    fn respond(&mut self, mut response: Vec<u8>) {
+        let fault = self.faults.pop_front();
+
+        if self.checksummed {
+            let mut sum = checksum(&response);
+            if fault == Some(Fault::BadChecksum) {
+                sum = sum.wrapping_add(1);
+            }
+            response.push(sum);
+        }
+
+        let counter = self.counter;
+        self.counter = (self.counter + 1) & 0x07;
+
+        let mut encoded = Vec::with_capacity(response.len() * 2);
+        for byte in response {
+            encoded.push(0x80 | (counter << 4) | (byte & 0x0F));
+            encoded.push(0x80 | (counter << 4) | (byte >> 4));
+        }
+
+        match fault {
+            Some(Fault::Silent) => return,
+            Some(Fault::CounterMismatch) => {
+                let last = encoded.len() - 1;
+                encoded[last] ^= 0x10;
+            }
+            Some(Fault::MissingMarker) => encoded[0] &= 0x7F,
+            Some(Fault::Truncated) => {
+                encoded.pop();
+            }
+            Some(Fault::BadChecksum) | None => {}
+        }
+
+        self.output.extend(encoded);
+    }
+}
+
+impl Write for MockSensor {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.handle_packet(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl ReadTimeout for MockSensor {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        Ok(None)
+    }
+
+    fn set_read_timeout(&mut self, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for MockSensor {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.output.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "No data from sensor",
+            ));
+        }
+
+        let n = buf.len().min(self.output.len());
+        for (slot, byte) in buf.iter_mut().zip(self.output.drain(..n)) {
+            *slot = byte;
+        }
+
+        Ok(n)
+    }
+}
+
+// Several sensors on one RS-485 line behind a single TCP bridge
+#[derive(Debug, Clone, Default)]
+pub struct MockBus {
+    sensors: Vec<MockSensor>,
+}
+
+impl MockBus {
+    pub fn new(sensors: Vec<MockSensor>) -> Self {
+        Self { sensors }
+    }
+
+    pub fn sensor_mut(&mut self, device_id: u8) -> Option<&mut MockSensor> {
+        self.sensors
+            .iter_mut()
+            .find(|sensor| sensor.device_id() == device_id)
+    }
+}
+
+impl Write for MockBus {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for sensor in &mut self.sensors {
+            sensor.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// Reads answer at once, there is nothing to bound
+impl ReadTimeout for MockBus {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        Ok(None)
+    }
+
+    fn set_read_timeout(&mut self, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for MockBus {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.sensors.iter_mut().find(|sensor| sensor.pending() > 0) {
+            Some(sensor) => sensor.read(buf),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "No data on bus",
+            )),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use utilities::command_executor::CommandSender;
 
-use crate::command_executor::encoder::commands::{EncoderCommand, EncoderResponse};
+use crate::command_executor::encoder::{
+    commands::{EncoderCommand, EncoderResponse},
+    SensorSettings,
+};
 
 #[derive(Clone)]
 pub struct EncoderCommandSender {
@@ -22,6 +25,69 @@ impl EncoderCommandSender {
                 axis: _axis,
                 position,
             } => Ok(position),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn is_laser_on(&self, axis: u8) -> std::io::Result<bool> {
+        let response = self
+            .sender
+            .send_command(EncoderCommand::GetLaser { axis })
+            .await?;
+        match response {
+            EncoderResponse::Laser { axis: _axis, on } => Ok(on),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_laser(&self, axis: u8, on: bool) -> std::io::Result<()> {
+        let response = self
+            .sender
+            .send_command(EncoderCommand::SetLaser { axis, on })
+            .await?;
+        match response {
+            EncoderResponse::Ok => Ok(()),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_settings(&self, axis: u8) -> std::io::Result<SensorSettings> {
+        let response = self
+            .sender
+            .send_command(EncoderCommand::GetSettings { axis })
+            .await?;
+        match response {
+            EncoderResponse::Settings {
+                axis: _axis,
+                settings,
+            } => Ok(settings),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn change_baudrate(&self, baudrate: u32) -> std::io::Result<()> {
+        let response = self
+            .sender
+            .send_command(EncoderCommand::ChangeBaudrate { baudrate })
+            .await?;
+        match response {
+            EncoderResponse::Ok => Ok(()),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
         }
     }
 }
@@ -1,16 +1,23 @@
 use utilities::command_executor::Command;
 
-use crate::command_executor::encoder::Rf256Handler;
+use crate::command_executor::encoder::{Rf256Handler, SensorSettings};
 
 const MAX_RETRIES: u8 = 5;
 
 #[derive(Clone)]
 pub enum EncoderCommand {
     GetPosition { axis: u8 },
+    GetLaser { axis: u8 },
+    SetLaser { axis: u8, on: bool },
+    GetSettings { axis: u8 },
+    ChangeBaudrate { baudrate: u32 },
 }
 
 pub enum EncoderResponse {
     Position { axis: u8, position: f32 },
+    Laser { axis: u8, on: bool },
+    Settings { axis: u8, settings: SensorSettings },
+    Ok,
 }
 
 impl Command for EncoderCommand {
@@ -24,15 +31,31 @@ impl Command for EncoderCommand {
                 loop {
                     match handler.get_position(axis) {
                         Ok(position) => return Ok(EncoderResponse::Position { axis, position }),
-                        Err(_) if attempts < MAX_RETRIES => {
+                        Err(e) if e.is_desync() && attempts < MAX_RETRIES => {
                             attempts += 1;
                         }
                         Err(e) => {
-                            return Err(e);
+                            return Err(e.into());
                         }
                     }
                 }
             }
+            EncoderCommand::GetLaser { axis } => handler
+                .is_laser_on(axis)
+                .map(|on| EncoderResponse::Laser { axis, on })
+                .map_err(Into::into),
+            EncoderCommand::SetLaser { axis, on } => handler
+                .set_laser(axis, on)
+                .map(|_| EncoderResponse::Ok)
+                .map_err(Into::into),
+            EncoderCommand::GetSettings { axis } => handler
+                .read_settings(axis)
+                .map(|settings| EncoderResponse::Settings { axis, settings })
+                .map_err(Into::into),
+            EncoderCommand::ChangeBaudrate { baudrate } => handler
+                .change_baudrate(baudrate)
+                .map(|_| EncoderResponse::Ok)
+                .map_err(Into::into),
         }
     }
 }
@@ -1,56 +1,309 @@
-use std::io::{self, ErrorKind, Read as _};
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    time::Duration,
+};
 
-use rf256::Rf256;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream};
+use rf256::{ReadTimeout, Rf256, Rf256Error};
+use utilities::{
+    command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, rfc2217, transport::Transport,
+};
 
 use crate::command_executor::encoder::commands::EncoderCommand;
 
 pub mod command_sender;
 pub mod commands;
 
-pub struct Rf256Handler {
-    tcp_stream: LazyTcpStream,
+// Persistent sensor settings, read back for the parameter snapshot
+#[derive(Debug, Clone, Copy)]
+pub struct SensorSettings {
+    pub id: u8,
+    pub baudrate: u32,
+    pub sample_period: f32,
+}
+
+const BRIDGE_CONTROL_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Transport side of a baudrate change: the bridge is switched to the new
+// rate, then a fresh connection drops whatever was buffered at the old one
+pub trait SerialLine: Transport {
+    fn can_reopen(&self) -> bool;
+    fn reopen(&mut self, baudrate: u32) -> io::Result<()>;
+}
+
+// The RF256 bridge, with the RFC 2217 port that sets its rate if it has one
+pub struct Rf256Line {
+    stream: LazyTcpStream,
+    control: Option<String>,
+}
+
+impl Rf256Line {
+    pub fn new(stream: LazyTcpStream, control: Option<String>) -> Self {
+        Self { stream, control }
+    }
+}
+
+impl Read for Rf256Line {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.read(buf)
+    }
+}
+
+impl Write for Rf256Line {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl ReadTimeout for Rf256Line {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        Ok(Some(self.stream.read_timeout()))
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        match timeout {
+            Some(timeout) => self.stream.set_read_timeout(timeout),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Transport for Rf256Line {
+    fn reconnect(&mut self) -> io::Result<()> {
+        self.stream.reconnect()
+    }
+}
+
+impl SerialLine for Rf256Line {
+    fn can_reopen(&self) -> bool {
+        self.control.is_some()
+    }
+
+    fn reopen(&mut self, baudrate: u32) -> io::Result<()> {
+        let control = self.control.as_deref().ok_or_else(|| {
+            io::Error::new(ErrorKind::Unsupported, "The bridge has no control port")
+        })?;
+
+        rfc2217::set_baudrate(control, baudrate, BRIDGE_CONTROL_TIMEOUT)?;
+        self.stream.reconnect()
+    }
+}
+
+// Generic over the transport so the recovery paths can run against
+// rf256::mock::MockBus instead of a real bridge
+pub struct Rf256Handler<S = Rf256Line> {
+    tcp_stream: S,
     rf256: [Rf256; 4],
+    // Sensors whose measurement range has not been asked for yet
+    info_pending: [bool; 4],
 }
 
-impl Rf256Handler {
-    pub fn new(tcp_stream: LazyTcpStream, rf256: [Rf256; 4]) -> Self {
-        Self { tcp_stream, rf256 }
+impl<S: Read + Write + ReadTimeout> Rf256Handler<S> {
+    pub fn new(tcp_stream: S, rf256: [Rf256; 4]) -> Self {
+        Self {
+            tcp_stream,
+            rf256,
+            info_pending: [true; 4],
+        }
+    }
+
+    // Once per sensor before its first reading, so readings are scaled by
+    // the range the sensor reports. Without it the default scaling stays.
+    fn read_device_info(&mut self, axis: u8) {
+        let index = axis as usize;
+        if !self.info_pending.get(index).copied().unwrap_or(false) {
+            return;
+        }
+        self.info_pending[index] = false;
+
+        let rf256 = &mut self.rf256[index];
+        match rf256.read_device_info(&mut self.tcp_stream) {
+            Ok(info) => eprintln!(
+                "RF256 {}: serial {}, firmware {}, range {} mm",
+                rf256.get_device_id(),
+                info.serial_number,
+                info.firmware_revision,
+                info.measurement_range
+            ),
+            Err(e) => eprintln!(
+                "RF256 {}: failed to read device info, using default scaling: {}",
+                rf256.get_device_id(),
+                e
+            ),
+        }
     }
 
-    fn get_position(&mut self, axis: u8) -> io::Result<f32> {
-        match self.verify_id(axis) {
-            Ok(_) => {}
-            Err(_) => {
+    fn read_position(&mut self, axis: u8) -> Result<f32, Rf256Error> {
+        self.verify_id(axis)?;
+        self.read_device_info(axis);
+        sensor(&self.rf256, axis)?.read_data(&mut self.tcp_stream)
+    }
+
+    // A desync is cleared once right away, hard IO failures are passed on
+    fn get_position(&mut self, axis: u8) -> Result<f32, Rf256Error> {
+        match self.read_position(axis) {
+            Err(e) if e.is_desync() => {
                 self.clear_buffer()?;
-                self.verify_id(axis)?;
+                self.read_position(axis)
             }
+            result => result,
         }
+    }
+
+    fn is_laser_on(&mut self, axis: u8) -> Result<bool, Rf256Error> {
+        sensor(&self.rf256, axis)?.is_laser_on(&mut self.tcp_stream)
+    }
 
-        self.rf256
-            .get(axis as usize)
-            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Invalid axis"))?
-            .read_data(&mut self.tcp_stream)
+    fn set_laser(&mut self, axis: u8, on: bool) -> Result<(), Rf256Error> {
+        let rf256 = sensor(&self.rf256, axis)?;
+
+        if on {
+            rf256.laser_on(&mut self.tcp_stream)
+        } else {
+            rf256.laser_off(&mut self.tcp_stream)
+        }
     }
 
-    fn verify_id(&mut self, axis: u8) -> io::Result<()> {
-        let id = self
-            .rf256
-            .get(axis as usize)
-            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Invalid axis"))?
-            .get_device_id();
+    fn read_settings(&mut self, axis: u8) -> Result<SensorSettings, Rf256Error> {
+        let rf256 = sensor(&self.rf256, axis)?;
 
-        let requested_id = self.rf256[axis as usize].read_id(&mut self.tcp_stream)?;
+        Ok(SensorSettings {
+            id: rf256.read_id(&mut self.tcp_stream)?,
+            baudrate: rf256.read_baudrate(&mut self.tcp_stream)?,
+            sample_period: rf256.read_sample_period(&mut self.tcp_stream)?,
+        })
+    }
 
-        if id != requested_id {
-            return Err(io::Error::new(
-                ErrorKind::InvalidData,
-                format!("Device ID mismatch: expected {}, got {}", id, requested_id),
-            ));
+    fn verify_id(&mut self, axis: u8) -> Result<(), Rf256Error> {
+        let rf256 = sensor(&self.rf256, axis)?;
+        let expected = rf256.get_device_id();
+        let received = rf256.read_id(&mut self.tcp_stream)?;
+
+        if expected != received {
+            return Err(Rf256Error::IdMismatch { expected, received });
         }
         Ok(())
     }
 
+    fn verify_all(&mut self) -> Result<(), Rf256Error> {
+        for axis in 0..self.rf256.len() as u8 {
+            self.verify_id(axis)?;
+        }
+        Ok(())
+    }
+
+    // Every sensor on the bus gets the new rate written and saved to flash,
+    // then the bridge is switched and each sensor has to answer. On any
+    // failure the sensors that were changed get the old rate back and the
+    // bridge returns to it, the bus counts as recovered only once every
+    // sensor answers there again.
+    fn change_baudrate(&mut self, baudrate: u32) -> Result<(), Rf256Error>
+    where
+        S: SerialLine,
+    {
+        if !self.tcp_stream.can_reopen() {
+            return Err(Rf256Error::InvalidInput(
+                "Changing the baudrate needs rf256_bridge_control",
+            ));
+        }
+
+        let previous = sensor(&self.rf256, 0)?.read_baudrate(&mut self.tcp_stream)?;
+        if previous == baudrate {
+            return Ok(());
+        }
+
+        let mut changed = Vec::new();
+        let mut reopened = false;
+        let Err(e) = self.switch_bus(baudrate, &mut changed, &mut reopened) else {
+            return Ok(());
+        };
+
+        let message = match self.restore_baudrate(previous, &changed, reopened) {
+            Ok(()) => format!(
+                "Failed to move the bus to {} baud ({}), line back at {}",
+                baudrate, e, previous
+            ),
+            Err(restore) => format!(
+                "Failed to move the bus to {} baud ({}), no way back to {} ({}), changed sensors: {:?}",
+                baudrate,
+                e,
+                previous,
+                restore,
+                changed
+                    .iter()
+                    .map(|&index| self.rf256[index].get_device_id())
+                    .collect::<Vec<_>>()
+            ),
+        };
+
+        Err(Rf256Error::InvalidResponse(message))
+    }
+
+    fn switch_bus(
+        &mut self,
+        baudrate: u32,
+        changed: &mut Vec<usize>,
+        reopened: &mut bool,
+    ) -> Result<(), Rf256Error>
+    where
+        S: SerialLine,
+    {
+        for (index, rf256) in self.rf256.iter().enumerate() {
+            rf256
+                .set_baudrate(&mut self.tcp_stream, baudrate)
+                .and_then(|_| rf256.save_to_flash(&mut self.tcp_stream))
+                .map_err(|e| {
+                    Rf256Error::InvalidResponse(format!(
+                        "sensor {} did not take it: {}",
+                        rf256.get_device_id(),
+                        e
+                    ))
+                })?;
+            changed.push(index);
+        }
+
+        *reopened = true;
+        self.tcp_stream.reopen(baudrate)?;
+        eprintln!("RF256 line reopened at {} baud", baudrate);
+
+        self.verify_all()
+    }
+
+    fn restore_baudrate(
+        &mut self,
+        previous: u32,
+        changed: &[usize],
+        reopened: bool,
+    ) -> Result<(), Rf256Error>
+    where
+        S: SerialLine,
+    {
+        for &index in changed {
+            let rf256 = &self.rf256[index];
+            let result = rf256
+                .set_baudrate(&mut self.tcp_stream, previous)
+                .and_then(|_| rf256.save_to_flash(&mut self.tcp_stream));
+
+            if let Err(e) = result {
+                eprintln!(
+                    "RF256 {}: failed to restore {} baud: {}",
+                    rf256.get_device_id(),
+                    previous,
+                    e
+                );
+            }
+        }
+
+        if reopened {
+            self.tcp_stream.reopen(previous)?;
+        }
+
+        self.verify_all()
+    }
+
     fn clear_buffer(&mut self) -> io::Result<()> {
         let mut buf = [0; 1024];
 
@@ -64,6 +317,14 @@ impl Rf256Handler {
     }
 }
 
+// Takes the array rather than the handler so the stream can still be
+// borrowed mutably next to the sensor
+fn sensor(rf256: &[Rf256], axis: u8) -> io::Result<&Rf256> {
+    rf256
+        .get(axis as usize)
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Invalid axis"))
+}
+
 impl DeviceHandler for Rf256Handler {
     type Command = EncoderCommand;
 }
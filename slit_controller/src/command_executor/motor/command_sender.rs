@@ -1,6 +1,19 @@
 use std::io;
 
-use standa::command::state::StateParams;
+use standa::{
+    command::{
+        analog::ChartData,
+        edges::EdgesSettings,
+        engine::EngineSettings,
+        info::Identity,
+        position::PositionParams,
+        power::PowerSettings,
+        r#move::{MOVEParameters, StopMode},
+        state::StateParams,
+        user_data::UserData,
+    },
+    counters::ErrorCounters,
+};
 use utilities::command_executor::CommandSender;
 
 use crate::command_executor::motor::commands::MotorResponse;
@@ -29,10 +42,91 @@ impl StandaCommandSender {
         }
     }
 
-    pub async fn set_velocity(&self, velocity: u32) -> io::Result<()> {
+    pub async fn get_move_parameters(&self) -> io::Result<MOVEParameters> {
         let response = self
             .sender
-            .send_command(MotorCommand::SetVelocity(velocity))
+            .send_command(MotorCommand::GetMoveParameters)
+            .await?;
+
+        match response {
+            MotorResponse::MoveParameters(parameters) => Ok(parameters),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_position(&self) -> io::Result<PositionParams> {
+        let response = self.sender.send_command(MotorCommand::GetPosition).await?;
+
+        match response {
+            MotorResponse::Position(position) => Ok(position),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_chart_data(&self) -> io::Result<ChartData> {
+        let response = self.sender.send_command(MotorCommand::GetChartData).await?;
+
+        match response {
+            MotorResponse::ChartData(data) => Ok(data),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn identify(&self) -> io::Result<Identity> {
+        let response = self.sender.send_command(MotorCommand::Identify).await?;
+
+        match response {
+            MotorResponse::Identity(identity) => Ok(identity),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_engine_settings(&self) -> io::Result<EngineSettings> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetEngineSettings)
+            .await?;
+
+        match response {
+            MotorResponse::EngineSettings(settings) => Ok(settings),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_power_settings(&self) -> io::Result<PowerSettings> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetPowerSettings)
+            .await?;
+
+        match response {
+            MotorResponse::PowerSettings(settings) => Ok(settings),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_power_settings(&self, settings: PowerSettings) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::SetPowerSettings(settings))
             .await?;
 
         match response {
@@ -44,10 +138,25 @@ impl StandaCommandSender {
         }
     }
 
-    pub async fn set_acceleration(&self, acceleration: u16) -> io::Result<()> {
+    pub async fn get_edges_settings(&self) -> io::Result<EdgesSettings> {
         let response = self
             .sender
-            .send_command(MotorCommand::SetAcceleration(acceleration))
+            .send_command(MotorCommand::GetEdgesSettings)
+            .await?;
+
+        match response {
+            MotorResponse::EdgesSettings(settings) => Ok(settings),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_edges_settings(&self, settings: EdgesSettings) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::SetEdgesSettings(settings))
             .await?;
 
         match response {
@@ -59,10 +168,22 @@ impl StandaCommandSender {
         }
     }
 
-    pub async fn set_deceleration(&self, deceleration: u16) -> io::Result<()> {
+    pub async fn get_user_data(&self) -> io::Result<UserData> {
+        let response = self.sender.send_command(MotorCommand::GetUserData).await?;
+
+        match response {
+            MotorResponse::UserData(data) => Ok(data),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_user_data(&self, data: UserData) -> io::Result<()> {
         let response = self
             .sender
-            .send_command(MotorCommand::SetDeceleration(deceleration))
+            .send_command(MotorCommand::SetUserData(data))
             .await?;
 
         match response {
@@ -74,8 +195,32 @@ impl StandaCommandSender {
         }
     }
 
-    pub async fn stop(&self) -> io::Result<()> {
-        let response = self.sender.send_command(MotorCommand::Stop).await?;
+    pub async fn set_move_parameters(
+        &self,
+        velocity: u32,
+        acceleration: u16,
+        deceleration: u16,
+    ) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::SetMoveParameters {
+                velocity,
+                acceleration,
+                deceleration,
+            })
+            .await?;
+
+        match response {
+            MotorResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn stop(&self, mode: StopMode) -> io::Result<()> {
+        let response = self.sender.send_command(MotorCommand::Stop(mode)).await?;
 
         match response {
             MotorResponse::Ok => Ok(()),
@@ -112,4 +257,85 @@ impl StandaCommandSender {
             )),
         }
     }
+
+    pub async fn get_error_counters(&self) -> io::Result<ErrorCounters> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetErrorCounters)
+            .await?;
+
+        match response {
+            MotorResponse::ErrorCounters(counters) => Ok(counters),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn reset_error_counters(&self) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::ResetErrorCounters)
+            .await?;
+
+        match response {
+            MotorResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn home(&self) -> io::Result<()> {
+        let response = self.sender.send_command(MotorCommand::Home).await?;
+
+        match response {
+            MotorResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn zero(&self) -> io::Result<()> {
+        let response = self.sender.send_command(MotorCommand::Zero).await?;
+
+        match response {
+            MotorResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn save_settings(&self) -> io::Result<()> {
+        let response = self.sender.send_command(MotorCommand::SaveSettings).await?;
+
+        match response {
+            MotorResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn restore_settings(&self) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::RestoreSettings)
+            .await?;
+
+        match response {
+            MotorResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
@@ -1,6 +1,19 @@
 use std::io;
 
-use standa::command::state::StateParams;
+use standa::{
+    command::{
+        analog::ChartData,
+        edges::EdgesSettings,
+        engine::EngineSettings,
+        info::Identity,
+        position::PositionParams,
+        power::PowerSettings,
+        r#move::{MOVEParameters, StopMode},
+        state::StateParams,
+        user_data::UserData,
+    },
+    counters::ErrorCounters,
+};
 use utilities::command_executor::Command;
 
 use crate::command_executor::motor::StandaHandler;
@@ -8,18 +21,49 @@ use crate::command_executor::motor::StandaHandler;
 #[derive(Clone)]
 pub enum MotorCommand {
     GetState,
-    SetVelocity(u32),
-    SetAcceleration(u16),
-    SetDeceleration(u16),
-    Stop,
-    Move { steps: i32, substeps: i16 },
+    GetMoveParameters,
+    GetPosition,
+    GetChartData,
+    Identify,
+    GetEngineSettings,
+    GetPowerSettings,
+    SetPowerSettings(PowerSettings),
+    GetEdgesSettings,
+    SetEdgesSettings(EdgesSettings),
+    GetUserData,
+    SetUserData(UserData),
+    SetMoveParameters {
+        velocity: u32,
+        acceleration: u16,
+        deceleration: u16,
+    },
+    Stop(StopMode),
+    Move {
+        steps: i32,
+        substeps: i16,
+    },
+    Home,
+    Zero,
+    SaveSettings,
+    RestoreSettings,
     Reconnect,
+    GetErrorCounters,
+    ResetErrorCounters,
 }
 
 #[derive(Debug)]
 pub enum MotorResponse {
     None,
     State(StateParams),
+    MoveParameters(MOVEParameters),
+    Position(PositionParams),
+    ChartData(ChartData),
+    Identity(Identity),
+    EngineSettings(EngineSettings),
+    PowerSettings(PowerSettings),
+    EdgesSettings(EdgesSettings),
+    UserData(UserData),
+    ErrorCounters(ErrorCounters),
     Ok,
 }
 
@@ -33,30 +77,93 @@ impl Command for MotorCommand {
                 let state = handler.get_state()?;
                 Ok(MotorResponse::State(state))
             }
-            MotorCommand::SetVelocity(velocity) => {
-                handler.set_velocity(velocity)?;
+            MotorCommand::GetMoveParameters => {
+                let parameters = handler.get_move_parameters()?;
+                Ok(MotorResponse::MoveParameters(parameters))
+            }
+            MotorCommand::GetPosition => {
+                let position = handler.get_position()?;
+                Ok(MotorResponse::Position(position))
+            }
+            MotorCommand::GetChartData => {
+                let data = handler.get_chart_data()?;
+                Ok(MotorResponse::ChartData(data))
+            }
+            MotorCommand::Identify => {
+                let identity = handler.identify()?;
+                Ok(MotorResponse::Identity(identity))
+            }
+            MotorCommand::GetEngineSettings => {
+                let settings = handler.get_engine_settings()?;
+                Ok(MotorResponse::EngineSettings(settings))
+            }
+            MotorCommand::GetPowerSettings => {
+                let settings = handler.get_power_settings()?;
+                Ok(MotorResponse::PowerSettings(settings))
+            }
+            MotorCommand::SetPowerSettings(settings) => {
+                handler.set_power_settings(&settings)?;
                 Ok(MotorResponse::Ok)
             }
-            MotorCommand::SetAcceleration(acceleration) => {
-                handler.set_acceleration(acceleration)?;
+            MotorCommand::GetEdgesSettings => {
+                let settings = handler.get_edges_settings()?;
+                Ok(MotorResponse::EdgesSettings(settings))
+            }
+            MotorCommand::SetEdgesSettings(settings) => {
+                handler.set_edges_settings(&settings)?;
                 Ok(MotorResponse::Ok)
             }
-            MotorCommand::SetDeceleration(deceleration) => {
-                handler.set_deceleration(deceleration)?;
+            MotorCommand::GetUserData => {
+                let data = handler.get_user_data()?;
+                Ok(MotorResponse::UserData(data))
+            }
+            MotorCommand::SetUserData(data) => {
+                handler.set_user_data(&data)?;
                 Ok(MotorResponse::Ok)
             }
-            MotorCommand::Stop => {
-                handler.stop()?;
+            MotorCommand::SetMoveParameters {
+                velocity,
+                acceleration,
+                deceleration,
+            } => {
+                handler.set_move_parameters(velocity, acceleration, deceleration)?;
+                Ok(MotorResponse::Ok)
+            }
+            MotorCommand::Stop(mode) => {
+                handler.stop(mode)?;
                 Ok(MotorResponse::Ok)
             }
             MotorCommand::Move { steps, substeps } => {
                 handler.move_relative(steps, substeps)?;
                 Ok(MotorResponse::Ok)
             }
+            MotorCommand::Home => {
+                handler.home()?;
+                Ok(MotorResponse::Ok)
+            }
+            MotorCommand::Zero => {
+                handler.zero()?;
+                Ok(MotorResponse::Ok)
+            }
+            MotorCommand::SaveSettings => {
+                handler.save_settings()?;
+                Ok(MotorResponse::Ok)
+            }
+            MotorCommand::RestoreSettings => {
+                handler.restore_settings()?;
+                Ok(MotorResponse::Ok)
+            }
             MotorCommand::Reconnect => {
                 handler.reconnect()?;
                 Ok(MotorResponse::Ok)
             }
+            MotorCommand::GetErrorCounters => {
+                Ok(MotorResponse::ErrorCounters(handler.error_counters()))
+            }
+            MotorCommand::ResetErrorCounters => {
+                handler.reset_error_counters();
+                Ok(MotorResponse::Ok)
+            }
         }
     }
 }
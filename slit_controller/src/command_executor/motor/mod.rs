@@ -1,15 +1,29 @@
 use std::io;
 
 use commands::MotorCommand;
-use standa::{command::state::StateParams, Standa};
+use standa::{
+    command::{
+        analog::ChartData,
+        edges::EdgesSettings,
+        engine::EngineSettings,
+        info::Identity,
+        position::PositionParams,
+        power::PowerSettings,
+        r#move::{MOVEParameters, StopMode},
+        state::StateParams,
+        user_data::UserData,
+    },
+    counters::ErrorCounters,
+    Standa, StandaError,
+};
 
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream};
+use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, transport::Transport};
 
 pub mod command_sender;
 pub mod commands;
 
-pub struct StandaHandler {
-    tcp_stream: LazyTcpStream,
+pub struct StandaHandler<S = LazyTcpStream> {
+    tcp_stream: S,
     standa: Standa,
 }
 
@@ -17,39 +31,144 @@ impl DeviceHandler for StandaHandler {
     type Command = MotorCommand;
 }
 
-impl StandaHandler {
-    pub fn new(standa: Standa, tcp_stream: LazyTcpStream) -> Self {
+impl<S: Transport> StandaHandler<S> {
+    pub fn new(standa: Standa, tcp_stream: S) -> Self {
         Self { tcp_stream, standa }
     }
 
-    pub fn stop(&mut self) -> io::Result<()> {
-        self.standa.stop(&mut self.tcp_stream)
+    // A dead link is replaced right away so the next request does not wait
+    // on it. The error is still returned, the request itself is not resent.
+    fn recover(&mut self, error: StandaError) -> io::Error {
+        if error.needs_reconnect() {
+            eprintln!("Standa link lost ({}), reconnecting", error);
+            if let Err(e) = self.tcp_stream.reconnect() {
+                eprintln!("Failed to reconnect to Standa: {}", e);
+            }
+        }
+
+        error.into()
+    }
+
+    // Anything that moves the motor or changes its settings goes out once,
+    // a timed out request may still have been executed
+    fn command<T>(
+        &mut self,
+        op: impl Fn(&Standa, &mut S) -> Result<T, StandaError>,
+    ) -> io::Result<T> {
+        op(&self.standa, &mut self.tcp_stream).map_err(|e| self.recover(e))
+    }
+
+    // Reads are safe to repeat, once, when the link is still in sync
+    fn query<T>(
+        &mut self,
+        op: impl Fn(&Standa, &mut S) -> Result<T, StandaError>,
+    ) -> io::Result<T> {
+        match op(&self.standa, &mut self.tcp_stream) {
+            Err(e) if e.is_retryable() => {
+                op(&self.standa, &mut self.tcp_stream).map_err(|e| self.recover(e))
+            }
+            result => result.map_err(|e| self.recover(e)),
+        }
+    }
+
+    pub fn stop(&mut self, mode: StopMode) -> io::Result<()> {
+        self.command(|standa, stream| standa.stop(stream, mode))
     }
 
     pub fn move_relative(&mut self, steps: i32, substeps: i16) -> io::Result<()> {
-        self.standa
-            .move_relative(&mut self.tcp_stream, steps, substeps)
+        self.command(|standa, stream| standa.move_relative(stream, steps, substeps))
+    }
+
+    pub fn get_position(&mut self) -> io::Result<PositionParams> {
+        self.query(|standa, stream| standa.get_position(stream))
+    }
+
+    pub fn home(&mut self) -> io::Result<()> {
+        self.command(|standa, stream| standa.home(stream))
+    }
+
+    pub fn zero(&mut self) -> io::Result<()> {
+        self.command(|standa, stream| standa.zero(stream))
+    }
+
+    pub fn save_settings(&mut self) -> io::Result<()> {
+        self.command(|standa, stream| standa.save_settings(stream))
+    }
+
+    pub fn restore_settings(&mut self) -> io::Result<()> {
+        self.command(|standa, stream| standa.restore_settings(stream))
     }
 
     pub fn get_state(&mut self) -> io::Result<StateParams> {
-        self.standa.get_state(&mut self.tcp_stream)
+        self.query(|standa, stream| standa.get_state(stream))
+    }
+
+    pub fn get_move_parameters(&mut self) -> io::Result<MOVEParameters> {
+        self.query(|standa, stream| standa.get_move_parameters(stream))
     }
 
-    pub fn set_velocity(&mut self, velocity: u32) -> io::Result<()> {
-        self.standa.set_velocity(&mut self.tcp_stream, velocity)
+    pub fn identify(&mut self) -> io::Result<Identity> {
+        self.query(|standa, stream| standa.identify(stream))
     }
 
-    pub fn set_acceleration(&mut self, acceleration: u16) -> io::Result<()> {
-        self.standa
-            .set_acceleration(&mut self.tcp_stream, acceleration)
+    pub fn get_chart_data(&mut self) -> io::Result<ChartData> {
+        self.query(|standa, stream| standa.get_chart_data(stream))
     }
 
-    pub fn set_deceleration(&mut self, deceleration: u16) -> io::Result<()> {
-        self.standa
-            .set_deceleration(&mut self.tcp_stream, deceleration)
+    pub fn get_engine_settings(&mut self) -> io::Result<EngineSettings> {
+        self.query(|standa, stream| standa.get_engine_settings(stream))
+    }
+
+    pub fn get_power_settings(&mut self) -> io::Result<PowerSettings> {
+        self.query(|standa, stream| standa.get_power_settings(stream))
+    }
+
+    pub fn set_power_settings(&mut self, settings: &PowerSettings) -> io::Result<()> {
+        self.command(|standa, stream| standa.set_power_settings(stream, settings))
+    }
+
+    pub fn get_edges_settings(&mut self) -> io::Result<EdgesSettings> {
+        self.query(|standa, stream| standa.get_edges_settings(stream))
+    }
+
+    pub fn set_edges_settings(&mut self, settings: &EdgesSettings) -> io::Result<()> {
+        self.command(|standa, stream| standa.set_edges_settings(stream, settings))
+    }
+
+    pub fn get_user_data(&mut self) -> io::Result<UserData> {
+        self.query(|standa, stream| standa.get_user_data(stream))
+    }
+
+    pub fn set_user_data(&mut self, data: &UserData) -> io::Result<()> {
+        self.command(|standa, stream| standa.set_user_data(stream, data))
+    }
+
+    // One read and one write for all three, the other move settings are
+    // written back as read
+    pub fn set_move_parameters(
+        &mut self,
+        velocity: u32,
+        acceleration: u16,
+        deceleration: u16,
+    ) -> io::Result<()> {
+        self.command(|standa, stream| {
+            standa.update_move_parameters(stream, |move_params| {
+                move_params.speed = velocity;
+                move_params.accel = acceleration;
+                move_params.decel = deceleration;
+            })
+        })
     }
 
     pub fn reconnect(&mut self) -> io::Result<()> {
         self.tcp_stream.reconnect()
     }
+
+    pub fn error_counters(&self) -> ErrorCounters {
+        self.standa.error_counters()
+    }
+
+    pub fn reset_error_counters(&mut self) {
+        self.standa.reset_error_counters()
+    }
 }
@@ -29,4 +29,34 @@ impl TridCommandSender {
             )),
         }
     }
+
+    pub async fn get_alarm_threshold(&self, axis: u8) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(TridCommand::GetAlarmThreshold { axis })
+            .await?;
+
+        match response {
+            TridResponse::AlarmThreshold(threshold) => Ok(threshold),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_alarm_threshold(&self, axis: u8, threshold: f32) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(TridCommand::SetAlarmThreshold { axis, threshold })
+            .await?;
+
+        match response {
+            TridResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
@@ -5,6 +5,8 @@ use utilities::command_executor::Command;
 #[derive(Clone)]
 pub enum TridCommand {
     GetTemperature { axis: u8 },
+    GetAlarmThreshold { axis: u8 },
+    SetAlarmThreshold { axis: u8, threshold: f32 },
 }
 
 impl Command for TridCommand {
@@ -16,6 +18,12 @@ impl Command for TridCommand {
             TridCommand::GetTemperature { axis } => handler
                 .get_temperature(axis)
                 .map(|temperature| TridResponse::Temperature(temperature)),
+            TridCommand::GetAlarmThreshold { axis } => handler
+                .get_alarm_threshold(axis)
+                .map(|threshold| TridResponse::AlarmThreshold(threshold)),
+            TridCommand::SetAlarmThreshold { axis, threshold } => handler
+                .set_alarm_threshold(axis, threshold)
+                .map(|_| TridResponse::Ok),
         }
     }
 }
@@ -23,5 +31,6 @@ impl Command for TridCommand {
 #[derive(Debug)]
 pub enum TridResponse {
     Temperature(f32),
+    AlarmThreshold(f32),
     Ok,
 }
@@ -2,32 +2,49 @@ pub mod command_sender;
 pub mod commands;
 use commands::TridCommand;
 use trid::Trid;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream};
+use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, transport::Transport};
 
-pub struct TridHandler {
-    tcp_stream: LazyTcpStream,
+pub struct TridHandler<S = LazyTcpStream> {
+    tcp_stream: S,
     // FIXME: There's only one TRID
     trid: [Trid; 4],
 }
 
+// Borrowed rather than copied, the decimal point setting is cached inside
+fn trid(trid: &mut [Trid], axis: u8) -> std::io::Result<&mut Trid> {
+    trid.get_mut(axis as usize).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid Trid ID: {}", axis),
+        )
+    })
+}
+
 impl DeviceHandler for TridHandler {
     type Command = TridCommand;
 }
 
-impl TridHandler {
-    pub fn new(tcp_stream: LazyTcpStream, trid: [Trid; 4]) -> Self {
+impl<S: Transport> TridHandler<S> {
+    pub fn new(tcp_stream: S, trid: [Trid; 4]) -> Self {
         Self { tcp_stream, trid }
     }
 
     fn get_temperature(&mut self, axis: u8) -> std::io::Result<f32> {
-        let trid = self.trid.get(axis as usize).ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                format!("Invalid Trid ID: {}", axis),
-            )
-        })?;
-
-        trid.read_data(&mut self.tcp_stream)
+        trid(&mut self.trid, axis)?
+            .read_data(&mut self.tcp_stream)
+            .map_err(Into::into)
+    }
+
+    fn get_alarm_threshold(&mut self, axis: u8) -> std::io::Result<f32> {
+        let trid = trid(&mut self.trid, axis)?;
+        trid.get_alarm_threshold(&mut self.tcp_stream, trid.get_axis())
+            .map_err(Into::into)
+    }
+
+    fn set_alarm_threshold(&mut self, axis: u8, threshold: f32) -> std::io::Result<()> {
+        let trid = trid(&mut self.trid, axis)?;
+        trid.set_alarm_threshold(&mut self.tcp_stream, trid.get_axis(), threshold)
+            .map_err(Into::into)
     }
 
     pub fn reconnect(&mut self) -> std::io::Result<()> {
@@ -1,24 +1,52 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI32, AtomicI8, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 use motarem::axis::{
     limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
     state_info::AxisStateInfo, Axis,
 };
-use standa::command::state::StateParams;
+use standa::command::{r#move::StopMode, state::StateParams};
 use tokio::{sync::Mutex, task::JoinHandle};
-use utilities::motor_controller::{Motor as _, MotorHolder};
+use trid::TridError;
+use utilities::{
+    cached_reading::CachedReading,
+    lease::Lease,
+    motor_controller::{Motor as _, MotorHolder},
+    move_log::{self, MoveLog},
+    params::{self, seconds},
+    progress::{self, ActionProgress, ActionStatus},
+    snapshot::ParameterSnapshot,
+    units::AxisUnits,
+};
 
 use crate::{
     command_executor::{
         encoder::command_sender::EncoderCommandSender, motor::command_sender::StandaCommandSender,
         temperature::command_sender::TridCommandSender,
     },
-    controllers::slit_controller::{motor::SlitMotor, params::MotorParameters},
+    controllers::slit_controller::{
+        calibration::{Calibration, CalibrationResult, StoredCalibration},
+        config::{CalibrationConfig, SlitAxisConfig},
+        gap::{GapLimit, KnifeSide},
+        motor::{SlitMotor, Verification},
+        params::MotorParameters,
+        recorder::MotionRecorder,
+        self_check::{SelfCheck, SelfCheckConfig, SelfCheckResult},
+        snapshot::read_axis_parameters,
+    },
 };
 
+// GET requests must answer within READ_DEADLINE even if a gateway is wedged
+const CACHE_MAX_AGE: Duration = Duration::from_millis(20);
+const READ_DEADLINE: Duration = Duration::from_millis(50);
+
+const SELF_CHECK: &str = "self-check";
+
 pub struct SlitAxis {
     pub name: String,
     axis: u8,
@@ -30,7 +58,32 @@ pub struct SlitAxis {
     move_thread: Arc<Mutex<Option<JoinHandle<Result<(), String>>>>>,
     is_moving: Arc<AtomicBool>,
 
-    steps_per_mm: i32,
+    steps_per_mm: AtomicI32,
+    backlash_steps: AtomicI32,
+    // Sign of the last steps sent to the drive, for the backlash
+    last_direction: Arc<AtomicI8>,
+    inverted: bool,
+    // Clients talk in the configured unit, the loop works in encoder mm
+    units: AxisUnits,
+    settle_time: Duration,
+    gap_limit: Option<Arc<GapLimit>>,
+    // Cleared at startup and by homing, absolute moves can be refused until
+    // homing finishes or the reference is set by hand
+    referenced: Arc<AtomicBool>,
+    reference_required: bool,
+    verification: Arc<std::sync::Mutex<Option<Verification>>>,
+    recorder: Arc<MotionRecorder>,
+
+    position: CachedReading,
+    temperature: CachedReading,
+    // Set by the last temperature read, reported in the axis state
+    sensor_missing: Arc<AtomicBool>,
+
+    lease: Lease,
+    // Homing and calibration, reported while they run
+    progress: ActionProgress,
+    // Outcome of recent moves by id, for clients that do not wait
+    moves: MoveLog,
 }
 
 impl SlitAxis {
@@ -41,6 +94,8 @@ impl SlitAxis {
         trid_cs: TridCommandSender,
         standa_cs: StandaCommandSender,
         steps_per_mm: i32,
+        units: AxisUnits,
+        settle_time: Duration,
     ) -> Self {
         Self {
             name,
@@ -50,8 +105,277 @@ impl SlitAxis {
             standa_cs,
             move_thread: Arc::new(Mutex::new(None)),
             is_moving: Arc::new(AtomicBool::new(false)),
-            steps_per_mm,
+            steps_per_mm: AtomicI32::new(steps_per_mm),
+            backlash_steps: AtomicI32::new(0),
+            last_direction: Arc::new(AtomicI8::new(0)),
+            inverted: false,
+            units,
+            settle_time,
+            gap_limit: None,
+            referenced: Arc::new(AtomicBool::new(false)),
+            reference_required: false,
+            verification: Arc::new(std::sync::Mutex::new(None)),
+            recorder: Arc::new(MotionRecorder::default()),
+            position: CachedReading::new(CACHE_MAX_AGE, READ_DEADLINE),
+            temperature: CachedReading::new(CACHE_MAX_AGE, READ_DEADLINE),
+            sensor_missing: Arc::new(AtomicBool::new(false)),
+            lease: Lease::new(),
+            progress: ActionProgress::default(),
+            moves: MoveLog::default(),
+        }
+    }
+
+    pub fn with_gap_limit(mut self, gap_limit: GapLimit) -> Self {
+        self.gap_limit = Some(Arc::new(gap_limit));
+        self
+    }
+
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    pub fn with_backlash_steps(self, backlash_steps: i32) -> Self {
+        self.set_backlash_steps(backlash_steps);
+        self
+    }
+
+    pub fn with_reference_required(mut self, reference_required: bool) -> Self {
+        self.reference_required = reference_required;
+        self
+    }
+
+    // The homing run reports itself as a move, the axis counts as referenced
+    // once it ends without an error. The first reversal of the drive is the
+    // switch found and the blade backing off it, the step counter is zeroed
+    // at the end.
+    fn watch_homing(&self) {
+        let standa_cs = self.standa_cs.clone();
+        let referenced = self.referenced.clone();
+        let progress = self.progress.clone();
+        let name = self.name.clone();
+
+        tokio::spawn(async move {
+            let mut direction = None;
+
+            loop {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+
+                // Stopped by a client
+                if !progress.is_running() {
+                    eprintln!("{}: homing aborted, axis is not referenced", name);
+                    break;
+                }
+
+                match standa_cs.get_state().await {
+                    Ok(state) if state.is_moving() => {
+                        let speed = state.cur_speed;
+                        if speed != 0 {
+                            match direction {
+                                None => direction = Some(speed > 0),
+                                Some(forward) if forward != (speed > 0) => {
+                                    progress.stage("backing off", None);
+                                }
+                                _ => {}
+                            }
+                        }
+                        continue;
+                    }
+                    Ok(state) if state.is_error() => {
+                        progress.fail(
+                            state
+                                .fault_message()
+                                .unwrap_or_else(|| "drive error".to_string()),
+                        );
+                        eprintln!("{}: homing failed, axis is not referenced", name);
+                    }
+                    Ok(_) => {
+                        progress.stage("setting zero", None);
+                        match standa_cs.zero().await {
+                            Ok(()) => {
+                                referenced.store(true, Ordering::Relaxed);
+                                progress.finish();
+                                eprintln!("{}: homing done, axis is referenced", name);
+                            }
+                            Err(e) => {
+                                progress.fail(format!("failed to set zero: {}", e));
+                                eprintln!("{}: homing failed to set zero: {}", name, e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        progress.fail(format!("lost track: {}", e));
+                        eprintln!("{}: lost track of homing: {}", name, e);
+                    }
+                }
+
+                break;
+            }
+        });
+    }
+
+    pub fn steps_per_mm(&self) -> i32 {
+        self.steps_per_mm.load(Ordering::Relaxed)
+    }
+
+    pub fn set_steps_per_mm(&self, steps_per_mm: i32) {
+        self.steps_per_mm.store(steps_per_mm, Ordering::Relaxed);
+    }
+
+    // After moves outside of the closed loop the next one takes up no slack
+    fn forget_direction(&self) {
+        self.last_direction.store(0, Ordering::Relaxed);
+    }
+
+    pub fn backlash_steps(&self) -> i32 {
+        self.backlash_steps.load(Ordering::Relaxed)
+    }
+
+    pub fn set_backlash_steps(&self, backlash_steps: i32) {
+        self.backlash_steps.store(backlash_steps, Ordering::Relaxed);
+    }
+
+    pub async fn calibrate(&self, config: CalibrationConfig) -> Result<CalibrationResult, String> {
+        if self.is_moving() {
+            return Err("Axis is already in motion".to_string());
+        }
+
+        let calibration = Calibration::new(
+            self.rf256_cs.clone(),
+            self.trid_cs.clone(),
+            self.standa_cs.clone(),
+            self.axis,
+            config,
+            self.steps_per_mm(),
+        )
+        .with_inverted(self.inverted);
+
+        self.progress.start("calibration", "starting");
+        self.set_moving(true);
+        let result = calibration.run(&self.progress).await;
+        self.set_moving(false);
+        self.forget_direction();
+
+        match &result {
+            Ok(_) => self.progress.finish(),
+            Err(e) => self.progress.fail(e),
+        }
+
+        result
+    }
+
+    // Not moving, no long action and no reservation, free for the self-check
+    pub fn is_idle(&self) -> bool {
+        !self.is_moving() && !self.progress.is_running() && self.lease.remaining().is_zero()
+    }
+
+    pub fn is_self_checking(&self) -> bool {
+        self.progress.report().is_some_and(|report| {
+            report.action == SELF_CHECK && report.status == ActionStatus::Running
+        })
+    }
+
+    // Knives with a gap limit open first, away from the other knife. The
+    // axis is claimed before anything is awaited, so a move or a second
+    // check cannot slip in, and the laser is left as it was found.
+    pub async fn self_check(&self, config: &SelfCheckConfig) -> Result<SelfCheckResult, String> {
+        if self.progress.is_running()
+            || !self.lease.remaining().is_zero()
+            || self
+                .is_moving
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed)
+                .is_err()
+        {
+            return Err("Axis is busy or reserved".to_string());
+        }
+        self.progress.start(SELF_CHECK, "starting");
+
+        let distance = match self.gap_limit.as_ref().map(|gap_limit| gap_limit.side()) {
+            Some(KnifeSide::Positive) => config.distance.abs(),
+            Some(KnifeSide::Negative) => -config.distance.abs(),
+            None => config.distance,
+        };
+
+        let laser_on = self
+            .rf256_cs
+            .is_laser_on(self.axis)
+            .await
+            .map_err(|e| format!("Failed to read laser state: {}", e));
+
+        let result = match laser_on {
+            Ok(laser_on) => {
+                let result = self.run_self_check(config, distance, laser_on).await;
+
+                if !laser_on {
+                    if let Err(e) = self.set_laser(false).await {
+                        eprintln!("{}: {} after the self-check", self.name, e);
+                    }
+                }
+
+                result
+            }
+            Err(e) => Err(e),
+        };
+
+        self.set_moving(false);
+        self.forget_direction();
+
+        match &result {
+            Ok(_) => self.progress.finish(),
+            Err(e) => self.progress.fail(e),
         }
+
+        result
+    }
+
+    async fn run_self_check(
+        &self,
+        config: &SelfCheckConfig,
+        distance: f32,
+        laser_on: bool,
+    ) -> Result<SelfCheckResult, String> {
+        if !laser_on {
+            self.set_laser(true).await?;
+        }
+
+        SelfCheck::new(
+            self.rf256_cs.clone(),
+            self.standa_cs.clone(),
+            self.axis,
+            config,
+            self.steps_per_mm(),
+        )
+        .with_inverted(self.inverted)
+        .with_distance(distance)
+        .run(&self.progress)
+        .await
+    }
+
+    pub async fn stored_calibration(&self) -> Result<Option<StoredCalibration>, String> {
+        self.standa_cs
+            .get_user_data()
+            .await
+            .map(|data| StoredCalibration::from_user_data(&data))
+            .map_err(|e| format!("Failed to read user data: {}", e))
+    }
+
+    pub async fn store_calibration(&self, calibration: StoredCalibration) -> Result<(), String> {
+        self.standa_cs
+            .set_user_data(calibration.to_user_data())
+            .await
+            .map_err(|e| format!("Failed to write user data: {}", e))
+    }
+
+    pub async fn read_parameters(&self, config: &SlitAxisConfig, snapshot: &mut ParameterSnapshot) {
+        read_axis_parameters(
+            &self.name,
+            self.axis,
+            &self.rf256_cs,
+            &self.standa_cs,
+            config,
+            snapshot,
+        )
+        .await
     }
 
     pub async fn get_temperature(&self) -> Result<f32, String> {
@@ -60,6 +384,46 @@ impl SlitAxis {
             .await
             .map_err(|e| format!("Failed to read temperature: {}", e))
     }
+
+    pub async fn set_laser(&self, on: bool) -> Result<(), String> {
+        self.rf256_cs
+            .set_laser(self.axis, on)
+            .await
+            .map_err(|e| format!("Failed to switch laser: {}", e))
+    }
+
+    async fn cached_position(&self) -> Result<f32, String> {
+        let rf256_cs = self.rf256_cs.clone();
+        let axis = self.axis;
+
+        self.position
+            .read(async move {
+                rf256_cs
+                    .get_position(axis)
+                    .await
+                    .map_err(|e| format!("Failed to get position: {}", e))
+            })
+            .await
+            .map(|reading| reading.value)
+    }
+
+    async fn cached_temperature(&self) -> Result<f32, String> {
+        let trid_cs = self.trid_cs.clone();
+        let sensor_missing = self.sensor_missing.clone();
+        let axis = self.axis;
+
+        self.temperature
+            .read(async move {
+                let result = trid_cs.read_temperature(axis).await;
+                sensor_missing.store(
+                    matches!(&result, Err(e) if TridError::is_sensor_missing(e)),
+                    Ordering::Relaxed,
+                );
+                result.map_err(|e| format!("Failed to read temperature: {}", e))
+            })
+            .await
+            .map(|reading| reading.value)
+    }
 }
 
 #[async_trait::async_trait]
@@ -69,9 +433,147 @@ impl Axis for SlitAxis {
     }
 
     async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
-        let motor_params = parameters.unwrap_or_default().into();
+        let mut parameters = parameters.unwrap_or_default();
+        // "token" identifies the client holding a lease on the axis
+        let token = params::custom_id(&parameters, "token").map_err(anyhow::Error::msg)?;
+
+        // Maintenance request only, the axis does not move
+        if parameters.custom.contains_key("reset_errors") {
+            self.lease.check(token).map_err(anyhow::Error::msg)?;
+
+            return self
+                .standa_cs
+                .reset_error_counters()
+                .await
+                .map_err(|e| anyhow::Error::msg(format!("Failed to reset error counters: {}", e)));
+        }
+
+        // Controller settings to and from flash, so commissioning changes
+        // survive a power cycle
+        if parameters
+            .custom
+            .get("save_settings")
+            .is_some_and(|save| *save != 0.0)
+        {
+            self.lease.check(token).map_err(anyhow::Error::msg)?;
+
+            return self
+                .standa_cs
+                .save_settings()
+                .await
+                .map_err(|e| anyhow::Error::msg(format!("Failed to save settings: {}", e)));
+        }
+
+        if parameters
+            .custom
+            .get("restore_settings")
+            .is_some_and(|restore| *restore != 0.0)
+        {
+            self.lease.check(token).map_err(anyhow::Error::msg)?;
+
+            return self
+                .standa_cs
+                .restore_settings()
+                .await
+                .map_err(|e| anyhow::Error::msg(format!("Failed to restore settings: {}", e)));
+        }
+
+        // Trace of the last recorded move for loop tuning, written next to
+        // the other temporary files
+        if parameters
+            .custom
+            .get("save_recording")
+            .is_some_and(|save| *save != 0.0)
+        {
+            self.lease.check(token).map_err(anyhow::Error::msg)?;
+
+            let path = std::env::temp_dir().join(format!("{}_motion.csv", self.name));
+
+            std::fs::write(&path, self.recorder.to_csv()).map_err(|e| {
+                anyhow::Error::msg(format!("Failed to write {}: {}", path.display(), e))
+            })?;
+            eprintln!(
+                "{}: {} motion samples written to {}",
+                self.name,
+                self.recorder.sample_count(),
+                path.display()
+            );
+
+            return Ok(());
+        }
+
+        // Reference run against the hardware home sensor, as set up in the
+        // controller's home settings. The position argument is ignored.
+        if parameters
+            .custom
+            .get("home")
+            .is_some_and(|home| *home != 0.0)
+        {
+            self.lease.check(token).map_err(anyhow::Error::msg)?;
+
+            self.referenced.store(false, Ordering::Relaxed);
+            self.forget_direction();
+            self.progress.start("homing", "seeking switch");
+            if let Err(e) = self.standa_cs.home().await {
+                let message = format!("Failed to start homing: {}", e);
+                self.progress.fail(&message);
+                return Err(anyhow::Error::msg(message));
+            }
+            self.watch_homing();
+
+            return Ok(());
+        }
+
+        // The operator vouches for the encoder mounting, e.g. after checking
+        // the blade against a reference mark. 0 clears the reference.
+        if let Some(reference) = parameters.custom.get("set_reference") {
+            self.lease.check(token).map_err(anyhow::Error::msg)?;
+
+            let referenced = *reference != 0.0;
+            self.referenced.store(referenced, Ordering::Relaxed);
+            eprintln!(
+                "{}: reference {} by request",
+                self.name,
+                if referenced { "set" } else { "cleared" }
+            );
+
+            return Ok(());
+        }
+
+        // Reservations ride along with motion: "lease" reserves the axis for
+        // that many seconds (0 releases it)
+        match (token, parameters.custom.get("lease")) {
+            (Some(token), Some(lease)) if *lease > 0.0 => self
+                .lease
+                .reserve(token, seconds("lease", *lease).map_err(anyhow::Error::msg)?)
+                .map_err(anyhow::Error::msg)?,
+            (Some(token), Some(_)) => self.lease.release(token).map_err(anyhow::Error::msg)?,
+            _ => self.lease.check(token).map_err(anyhow::Error::msg)?,
+        }
+
+        if self.reference_required && !self.referenced.load(Ordering::Relaxed) {
+            return Err(anyhow::Error::msg(format!(
+                "{} is not referenced, home it or set the reference first",
+                self.name
+            )));
+        }
+
+        if let Some(window) = parameters.custom.get_mut("position_window") {
+            *window = self.units.to_native(*window);
+        }
+        let motor_params: MotorParameters = parameters.try_into().map_err(anyhow::Error::msg)?;
+
+        // The closed loop needs the encoder, so wake it up if it was idled
+        let laser_on = self
+            .rf256_cs
+            .is_laser_on(self.axis)
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("Failed to read laser state: {}", e)))?;
+        if !laser_on {
+            self.set_laser(true).await.map_err(anyhow::Error::msg)?;
+        }
 
-        self.move_to(position as f32, motor_params)
+        self.move_to(self.units.to_native(position) as f32, motor_params)
             .await
             .map_err(|e| anyhow::Error::msg(format!("Failed to move motor: {}", e)))?;
 
@@ -79,6 +581,8 @@ impl Axis for SlitAxis {
     }
 
     async fn stop(&self) -> anyhow::Result<()> {
+        self.progress.fail("stopped");
+
         MotorHolder::stop(self)
             .await
             .map_err(|e| anyhow::Error::msg(format!("Failed to stop motor: {}", e)))
@@ -89,7 +593,7 @@ impl Axis for SlitAxis {
             .await
             .map_err(|e| anyhow::Error::msg(format!("Failed to get motor state: {}", e)))?;
 
-        let is_moving = self.is_moving.load(Ordering::Relaxed);
+        let is_moving = self.is_moving.load(Ordering::Relaxed) || self.progress.is_running();
 
         let state = if is_moving {
             AxisState::Moving
@@ -97,17 +601,28 @@ impl Axis for SlitAxis {
             AxisState::On
         };
 
-        let limit_switches = match (motor_state.left_switch(), motor_state.right_switch()) {
+        let (lower_switch, upper_switch) = match self.inverted {
+            false => (motor_state.left_switch(), motor_state.right_switch()),
+            true => (motor_state.right_switch(), motor_state.left_switch()),
+        };
+        let limit_switches = match (lower_switch, upper_switch) {
             (true, true) => LimitSwitches::Both,
             (true, false) => LimitSwitches::Lower,
             (false, true) => LimitSwitches::Upper,
             (false, false) => LimitSwitches::None,
         };
 
-        let message = match (motor_state.is_moving(), is_moving) {
-            (true, false) => Some("Motor is moving, but axis is not".to_string()),
-            _ => None,
-        };
+        // Drive faults first, e.g. "alarm: border crossed", then long actions
+        let message = motor_state
+            .fault_message()
+            .or_else(|| self.progress.message())
+            .or_else(|| match (motor_state.is_moving(), is_moving) {
+                (true, false) => Some("Motor is moving, but axis is not".to_string()),
+                _ if self.sensor_missing.load(Ordering::Relaxed) => {
+                    Some("Temperature sensor missing".to_string())
+                }
+                _ => None,
+            });
 
         Ok(AxisStateInfo {
             state,
@@ -118,21 +633,151 @@ impl Axis for SlitAxis {
 
     async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
         match name {
-            "position" => MotorHolder::get_position(self)
+            "position" => self
+                .cached_position()
                 .await
-                .map(|pos| pos as f64)
+                .map(|pos| self.units.from_native(pos as f64))
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get position: {}", err))),
             "temperature" => self
-                .get_temperature()
+                .cached_temperature()
                 .await
                 .map(|temp| temp as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get temperature: {}", err))),
+            "laser" => self
+                .rf256_cs
+                .is_laser_on(self.axis)
+                .await
+                .map(|on| on as u8 as f64)
+                .map_err(|err| anyhow::Error::msg(format!("Failed to get laser state: {}", err))),
+            "lease" => Ok(self.lease.remaining().as_secs_f64()),
+            "recorded_samples" => Ok(self.recorder.sample_count() as f64),
+            "referenced" => Ok(self.referenced.load(Ordering::Relaxed) as u8 as f64),
+            "alarm_threshold" => self
+                .trid_cs
+                .get_alarm_threshold(self.axis)
+                .await
+                .map(|threshold| threshold as f64)
+                .map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to get alarm threshold: {}", err))
+                }),
+            "verified_position" | "residual_error" => {
+                let verification = self
+                    .verification
+                    .lock()
+                    .unwrap()
+                    .ok_or_else(|| anyhow::Error::msg("No verified move yet"))?;
+
+                Ok(self.units.from_native(match name {
+                    "verified_position" => verification.position,
+                    _ => verification.residual,
+                } as f64))
+            }
+            "standa_resyncs"
+            | "standa_unreachable"
+            | "standa_data_errors"
+            | "standa_io_errors"
+            | "standa_link_errors"
+            | "standa_command_errors"
+            | "standa_integrity_errors"
+            | "standa_value_errors" => {
+                let counters = self.standa_cs.get_error_counters().await.map_err(|e| {
+                    anyhow::Error::msg(format!("Failed to get error counters: {}", e))
+                })?;
+
+                Ok(match name {
+                    "standa_resyncs" => counters.resyncs,
+                    "standa_unreachable" => counters.unreachable,
+                    "standa_data_errors" => counters.data_errors,
+                    "standa_io_errors" => counters.io_errors,
+                    "standa_link_errors" => counters.link_errors(),
+                    "standa_command_errors" => counters.command_errors,
+                    "standa_integrity_errors" => counters.integrity_errors,
+                    _ => counters.value_errors,
+                } as f64)
+            }
+            // The controller's own counters, to cross-check the RF256
+            "motor_steps" | "motor_encoder" => {
+                let position = self.standa_cs.get_position().await.map_err(|e| {
+                    anyhow::Error::msg(format!("Failed to get motor position: {}", e))
+                })?;
+
+                let value = match name {
+                    "motor_steps" => position.position as f64,
+                    _ => position.enc_position as f64,
+                };
+                Ok(if self.inverted { -value } else { value })
+            }
+            // Winding diagnostics, currents in A and voltages in V
+            "winding_current_a" | "winding_current_b" | "winding_voltage_a"
+            | "winding_voltage_b" | "duty_cycle" => {
+                let data = self.standa_cs.get_chart_data().await.map_err(|e| {
+                    anyhow::Error::msg(format!("Failed to get analog readings: {}", e))
+                })?;
+
+                Ok(match name {
+                    "winding_current_a" => data.winding_current_a as f64 / 1000.0,
+                    "winding_current_b" => data.winding_current_b as f64 / 1000.0,
+                    "winding_voltage_a" => data.winding_voltage_a as f64 / 100.0,
+                    "winding_voltage_b" => data.winding_voltage_b as f64 / 100.0,
+                    _ => data.duty_cycle as f64,
+                })
+            }
+            "action_status" | "action_progress" | "action_elapsed" => self
+                .progress
+                .attribute(name)
+                .ok_or_else(|| anyhow::Error::msg(format!("Unknown attribute: {}", name))),
+            name if name == move_log::LAST_MOVE_ID
+                || name.starts_with(move_log::MOVE_STATUS_PREFIX) =>
+            {
+                self.moves
+                    .attribute(name)
+                    .ok_or_else(|| anyhow::Error::msg(format!("Unknown attribute: {}", name)))
+            }
+            "position_stale" => Ok(self.position.is_stale() as u8 as f64),
+            "temperature_stale" => Ok(self.temperature.is_stale() as u8 as f64),
+            // When the device read behind the last position or temperature
+            // completed, in ms on the process clock
+            "position_timestamp" => Ok(self.position.timestamp()),
+            "temperature_timestamp" => Ok(self.temperature.timestamp()),
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
 
     async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec!["position".to_string(), "temperature".to_string()])
+        let mut params = vec![
+            "position".to_string(),
+            "temperature".to_string(),
+            "position_stale".to_string(),
+            "temperature_stale".to_string(),
+            "position_timestamp".to_string(),
+            "temperature_timestamp".to_string(),
+            "laser".to_string(),
+            "lease".to_string(),
+            "verified_position".to_string(),
+            "residual_error".to_string(),
+            "recorded_samples".to_string(),
+            "referenced".to_string(),
+            "alarm_threshold".to_string(),
+            "motor_steps".to_string(),
+            "motor_encoder".to_string(),
+            "winding_current_a".to_string(),
+            "winding_current_b".to_string(),
+            "winding_voltage_a".to_string(),
+            "winding_voltage_b".to_string(),
+            "duty_cycle".to_string(),
+            "standa_resyncs".to_string(),
+            "standa_unreachable".to_string(),
+            "standa_data_errors".to_string(),
+            "standa_io_errors".to_string(),
+            "standa_link_errors".to_string(),
+            "standa_command_errors".to_string(),
+            "standa_integrity_errors".to_string(),
+            "standa_value_errors".to_string(),
+            move_log::LAST_MOVE_ID.to_string(),
+        ];
+        params.extend(progress::ATTRIBUTES.iter().map(|name| name.to_string()));
+
+        Ok(params)
     }
 
     async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
@@ -142,6 +787,17 @@ impl Axis for SlitAxis {
             "deceleration".to_string(),
             "position_window".to_string(),
             "time_limit".to_string(),
+            "settle_time".to_string(),
+            "token".to_string(),
+            "lease".to_string(),
+            "reset_errors".to_string(),
+            "home".to_string(),
+            "set_reference".to_string(),
+            "save_settings".to_string(),
+            "restore_settings".to_string(),
+            "record".to_string(),
+            "save_recording".to_string(),
+            "move_id".to_string(),
         ])
     }
 }
@@ -155,7 +811,7 @@ impl MotorHolder for SlitAxis {
             self.is_moving.store(false, Ordering::Relaxed);
 
             self.standa_cs
-                .stop()
+                .stop(StopMode::Immediate)
                 .await
                 .map_err(|e| format!("Failed to stop motor: {}", e))?;
 
@@ -170,18 +826,13 @@ impl MotorHolder for SlitAxis {
 
     async fn update_parameters(&self, parameters: &Self::MovementParameters) -> Result<(), String> {
         self.standa_cs
-            .set_acceleration(parameters.acceleration)
+            .set_move_parameters(
+                parameters.velocity,
+                parameters.acceleration,
+                parameters.deceleration,
+            )
             .await
-            .map_err(|e| format!("Failed to set acceleration: {}", e))?;
-        self.standa_cs
-            .set_deceleration(parameters.deceleration)
-            .await
-            .map_err(|e| format!("Failed to set deceleration: {}", e))?;
-        self.standa_cs
-            .set_velocity(parameters.velocity)
-            .await
-            .map_err(|e| format!("Failed to set velocity: {}", e))?;
-        Ok(())
+            .map_err(|e| format!("Failed to set move parameters: {}", e))
     }
 
     async fn get_state(&self) -> Result<Self::MotorState, String> {
@@ -225,10 +876,51 @@ impl MotorHolder for SlitAxis {
             parameters.position_window,
             parameters.time_limit,
             self.is_moving.clone(),
-            self.steps_per_mm,
-        );
+            self.steps_per_mm(),
+        )
+        .with_inverted(self.inverted)
+        .with_backlash(self.backlash_steps(), self.last_direction.clone())
+        .with_gap_limit(self.gap_limit.clone())
+        .with_recorder(self.recorder.clone());
+
+        if parameters.record {
+            self.recorder.start();
+        }
 
-        let handle = tokio::spawn(async move { move_thread.run().await });
+        let settle_time = parameters.settle_time.unwrap_or(self.settle_time);
+        let is_moving = self.is_moving.clone();
+        let verification = self.verification.clone();
+        *verification.lock().unwrap() = None;
+        let recorder = self.recorder.clone();
+        let moves = self.moves.clone();
+        let move_id = moves.start(parameters.move_id);
+
+        // The axis keeps reporting Moving until the verification read is in
+        let handle = tokio::spawn(async move {
+            let mut timed_out = false;
+            let result = async {
+                move_thread.run().await?;
+                timed_out = move_thread.is_time_limit_exceeded();
+
+                if is_moving.load(Ordering::Relaxed) {
+                    *verification.lock().unwrap() = Some(move_thread.verify(settle_time).await?);
+                }
+
+                Ok(())
+            }
+            .await;
+
+            recorder.finish();
+            moves.finish(
+                move_id,
+                &match &result {
+                    Ok(()) if !is_moving.load(Ordering::Relaxed) => Err("stopped".to_string()),
+                    Ok(()) if timed_out => Err("time limit exceeded".to_string()),
+                    _ => result.clone(),
+                },
+            );
+            result
+        });
 
         let mut move_thread = self.move_thread.lock().await;
         *move_thread = Some(handle);
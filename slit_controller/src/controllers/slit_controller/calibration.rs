@@ -0,0 +1,294 @@
+use std::time::{Duration, Instant};
+
+use standa::{codec::crc16, command::user_data::UserData};
+use utilities::progress::ActionProgress;
+
+use crate::{
+    command_executor::{
+        encoder::command_sender::EncoderCommandSender, motor::command_sender::StandaCommandSender,
+        temperature::command_sender::TridCommandSender,
+    },
+    controllers::slit_controller::config::CalibrationConfig,
+};
+
+const MOVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy)]
+pub struct CalibrationSample {
+    pub commanded_steps: i64,
+    pub displacement: f32,
+    pub temperature: f32,
+    pub forward: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CalibrationResult {
+    pub steps_per_mm: i32,
+    pub backlash_steps: i32,
+    pub residual_rms: f32,
+    pub reference_temperature: f32,
+    pub samples: Vec<CalibrationSample>,
+}
+
+// Calibration kept in the Standa's user data, so it travels with the
+// controller and any host can pick it up. Layout, little endian:
+//
+//   "SLIT", u8 version, i32 steps_per_mm, i32 backlash_steps,
+//   f32 zero_offset, u16 CRC of everything before it
+const STORED_MAGIC: &[u8; 4] = b"SLIT";
+const STORED_VERSION: u8 = 1;
+const STORED_LEN: usize = 17;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StoredCalibration {
+    pub steps_per_mm: i32,
+    pub backlash_steps: i32,
+    pub zero_offset: f32,
+}
+
+impl StoredCalibration {
+    pub fn to_user_data(&self) -> UserData {
+        let mut bytes = Vec::with_capacity(STORED_LEN + 2);
+        bytes.extend_from_slice(STORED_MAGIC);
+        bytes.push(STORED_VERSION);
+        bytes.extend_from_slice(&self.steps_per_mm.to_le_bytes());
+        bytes.extend_from_slice(&self.backlash_steps.to_le_bytes());
+        bytes.extend_from_slice(&self.zero_offset.to_le_bytes());
+
+        let crc = crc16(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+
+        UserData::from_bytes(&bytes)
+    }
+
+    // None for blank flash, other contents or a damaged record
+    pub fn from_user_data(data: &UserData) -> Option<Self> {
+        let bytes = data.bytes();
+
+        if &bytes[..4] != STORED_MAGIC || bytes[4] != STORED_VERSION {
+            return None;
+        }
+
+        let crc = u16::from_le_bytes([bytes[STORED_LEN], bytes[STORED_LEN + 1]]);
+        if crc16(&bytes[..STORED_LEN]) != crc {
+            return None;
+        }
+
+        let field = |offset: usize| -> [u8; 4] { bytes[offset..offset + 4].try_into().unwrap() };
+
+        Some(StoredCalibration {
+            steps_per_mm: i32::from_le_bytes(field(5)),
+            backlash_steps: i32::from_le_bytes(field(9)),
+            zero_offset: f32::from_le_bytes(field(13)),
+        })
+    }
+}
+
+pub struct Calibration {
+    rf256_cs: EncoderCommandSender,
+    trid_cs: TridCommandSender,
+    standa_cs: StandaCommandSender,
+    axis: u8,
+
+    config: CalibrationConfig,
+    steps_per_mm: i32,
+    inverted: bool,
+}
+
+impl Calibration {
+    pub fn new(
+        rf256_cs: EncoderCommandSender,
+        trid_cs: TridCommandSender,
+        standa_cs: StandaCommandSender,
+        axis: u8,
+        config: CalibrationConfig,
+        steps_per_mm: i32,
+    ) -> Self {
+        Self {
+            rf256_cs,
+            trid_cs,
+            standa_cs,
+            axis,
+            config,
+            steps_per_mm,
+            inverted: false,
+        }
+    }
+
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    pub async fn run(&self, progress: &ActionProgress) -> Result<CalibrationResult, String> {
+        if self.config.pattern.is_empty() {
+            return Err("Calibration pattern is empty".to_string());
+        }
+
+        progress.stage("reading reference", Some(0.0));
+        let start_position = self.read_position().await?;
+        let reference_temperature = self.read_temperature().await?;
+
+        let mut samples = Vec::with_capacity(self.config.pattern.len());
+        let mut commanded_steps: i64 = 0;
+
+        let points = self.config.pattern.len();
+        for (index, &distance) in self.config.pattern.iter().enumerate() {
+            progress.stage(
+                &format!("point {} of {}", index + 1, points),
+                Some(index as f64 / points as f64),
+            );
+
+            let steps = (distance * self.steps_per_mm as f32) as i32;
+
+            self.standa_cs
+                .send_steps(if self.inverted { -steps } else { steps }, 0)
+                .await
+                .map_err(|e| format!("Failed to send steps: {}", e))?;
+            self.wait_for_stop().await?;
+
+            tokio::time::sleep(Duration::from_millis(self.config.settle_time_ms)).await;
+
+            commanded_steps += steps as i64;
+
+            let position = self.read_position().await?;
+            let temperature = self.read_temperature().await?;
+
+            let thermal_drift =
+                self.config.thermal_coefficient * (temperature - reference_temperature);
+
+            samples.push(CalibrationSample {
+                commanded_steps,
+                displacement: position - start_position - thermal_drift,
+                temperature,
+                forward: distance > 0.0,
+            });
+        }
+
+        progress.stage("fitting", Some(1.0));
+        let mut result = fit(&samples)?;
+        result.reference_temperature = reference_temperature;
+        result.samples = samples;
+
+        Ok(result)
+    }
+
+    async fn read_position(&self) -> Result<f32, String> {
+        let count = self.config.samples_per_point.max(1);
+        let mut sum = 0.0;
+
+        for _ in 0..count {
+            sum += self
+                .rf256_cs
+                .get_position(self.axis)
+                .await
+                .map_err(|e| format!("Failed to read position: {}", e))?;
+        }
+
+        Ok(sum / count as f32)
+    }
+
+    async fn read_temperature(&self) -> Result<f32, String> {
+        self.trid_cs
+            .read_temperature(self.axis)
+            .await
+            .map_err(|e| format!("Failed to read temperature: {}", e))
+    }
+
+    async fn wait_for_stop(&self) -> Result<(), String> {
+        wait_for_stop(&self.standa_cs).await
+    }
+}
+
+// Open loop moves, the Standa reports when its step count is done
+pub async fn wait_for_stop(standa_cs: &StandaCommandSender) -> Result<(), String> {
+    let start = Instant::now();
+
+    loop {
+        let state = standa_cs
+            .get_state()
+            .await
+            .map_err(|e| format!("Failed to get state: {}", e))?;
+
+        if !state.is_moving() {
+            return Ok(());
+        }
+
+        if start.elapsed() > MOVE_TIMEOUT {
+            return Err("Timed out waiting for motor to stop".to_string());
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+// Least squares line through (steps, displacement), returns (slope, intercept)
+fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+
+    if sxx == 0.0 {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    Some((slope, mean_y - slope * mean_x))
+}
+
+fn fit(samples: &[CalibrationSample]) -> Result<CalibrationResult, String> {
+    let points = |forward: bool| -> Vec<(f64, f64)> {
+        samples
+            .iter()
+            .filter(|s| s.forward == forward)
+            .map(|s| (s.commanded_steps as f64, s.displacement as f64))
+            .collect()
+    };
+
+    let forward_points = points(true);
+    let reverse_points = points(false);
+
+    let (forward_slope, forward_intercept) = linear_fit(&forward_points)
+        .ok_or_else(|| "Not enough forward samples to fit".to_string())?;
+    let (reverse_slope, reverse_intercept) = linear_fit(&reverse_points)
+        .ok_or_else(|| "Not enough reverse samples to fit".to_string())?;
+
+    let slope = (forward_slope + reverse_slope) / 2.0;
+    if slope == 0.0 {
+        return Err("Encoder did not register any displacement".to_string());
+    }
+
+    let steps_per_mm = 1.0 / slope;
+    // Lost motion on reversal shows up as an offset between the two passes
+    let backlash_mm = (forward_intercept - reverse_intercept).abs();
+
+    let residuals = forward_points
+        .iter()
+        .map(|(x, y)| y - (forward_slope * x + forward_intercept))
+        .chain(
+            reverse_points
+                .iter()
+                .map(|(x, y)| y - (reverse_slope * x + reverse_intercept)),
+        )
+        .map(|r| r * r)
+        .collect::<Vec<_>>();
+    let residual_rms = (residuals.iter().sum::<f64>() / residuals.len() as f64).sqrt();
+
+    Ok(CalibrationResult {
+        steps_per_mm: steps_per_mm.round() as i32,
+        backlash_steps: (backlash_mm * steps_per_mm.abs()).round() as i32,
+        residual_rms: residual_rms as f32,
+        reference_temperature: 0.0,
+        samples: Vec::new(),
+    })
+}
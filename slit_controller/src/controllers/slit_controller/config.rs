@@ -1,6 +1,18 @@
 use serde::{Deserialize, Serialize};
+use utilities::{
+    archiver::ArchiverConfig,
+    derived::DerivedAttributeConfig,
+    groups::AxisGroupConfig,
+    precision::{default_precision, Precision},
+    presets::PresetConfig,
+    units::Unit,
+};
 
-#[derive(Deserialize, Debug, Serialize)]
+use crate::controllers::slit_controller::{
+    parking::ParkingConfig, self_check::SelfCheckConfig, sequence::SequenceConfig,
+};
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
 pub struct SlitAxisConfig {
     pub rf256_id: u8,
     pub trid_id: u16,
@@ -9,21 +21,206 @@ pub struct SlitAxisConfig {
     pub standa_port: u16,
 
     pub steps_per_mm: i32,
+    // Motor mounted the other way round: steps are sent with the opposite
+    // sign and its switches and counters are swapped, steps_per_mm stays
+    // positive
+    #[serde(default)]
+    pub inverted: bool,
+    #[serde(default)]
+    pub backlash_steps: i32,
+    // Encoder reading in mm at which the blade sits on the beam axis
+    #[serde(default)]
+    pub zero_offset: f32,
+    // Over-temperature alarm written to the TRID on startup
+    #[serde(default)]
+    pub alarm_threshold: Option<f32>,
+    // Readings outside of [min, max] are treated as a missing sensor
+    #[serde(default)]
+    pub temperature_range: Option<[f32; 2]>,
+    // Unit of positions as seen by clients, mm or um
+    #[serde(default)]
+    pub unit: Unit,
+    // Hardware settings checked by the parameter snapshot
+    #[serde(default)]
+    pub expected: ExpectedParameters,
+    // Holding current reduction, written to the Standa on startup
+    #[serde(default)]
+    pub power: Option<PowerConfig>,
+    // Limit switch polarity and software borders, checked against the Standa
+    // on startup and written when they differ
+    #[serde(default)]
+    pub edges: Option<EdgesConfig>,
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct PowerConfig {
+    // Percent of the nominal current kept while standing still
+    pub hold_current: u8,
+    pub reduction_delay_ms: u16,
+    // The windings are powered off this long after a stop, never when unset
+    #[serde(default)]
+    pub power_off_delay_s: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct EdgesConfig {
+    #[serde(default)]
+    pub swap_switches: bool,
+    #[serde(default)]
+    pub switch1_active_low: bool,
+    #[serde(default)]
+    pub switch2_active_low: bool,
+    // In steps, the drive stops at a border that is set
+    #[serde(default)]
+    pub left_border: Option<i32>,
+    #[serde(default)]
+    pub right_border: Option<i32>,
+}
+
+// Standa motion parameters default to what every move writes
+#[derive(Deserialize, Debug, Serialize, Clone, Default)]
+pub struct ExpectedParameters {
+    pub velocity: Option<u32>,
+    pub acceleration: Option<u16>,
+    pub deceleration: Option<u16>,
+
+    // Travel in mm per motor revolution, with it the engine's steps per
+    // revolution are checked against steps_per_mm
+    pub mm_per_rev: Option<f32>,
+    pub microstep_mode: Option<u8>,
+
+    pub rf256_baudrate: Option<u32>,
+    pub rf256_sample_period: Option<f32>,
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
+pub struct CalibrationConfig {
+    // Relative moves in mm, forward pass followed by the reverse pass
+    pub pattern: Vec<f32>,
+    pub settle_time_ms: u64,
+    pub samples_per_point: usize,
+    // Thermal expansion of the blade mount in mm per degree
+    pub thermal_coefficient: f32,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        Self {
+            pattern: vec![0.5, 0.5, 0.5, 0.5, -0.5, -0.5, -0.5, -0.5],
+            settle_time_ms: 500,
+            samples_per_point: 10,
+            thermal_coefficient: 0.0,
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, Serialize)]
+fn default_verify_settle_ms() -> u64 {
+    100
+}
+
+#[derive(Deserialize, Debug, Serialize, Clone)]
 pub struct SlitControllerConfig {
     pub rf256_ip: String,
     pub rf256_port: u16,
+    // Sensors set to checksummed packets, a summed byte ends every request
+    // and response
+    #[serde(default)]
+    pub rf256_checksum: bool,
+    // "host:port" of the bridge's RFC 2217 port, without it the bus
+    // baudrate cannot be changed at runtime
+    #[serde(default)]
+    pub rf256_bridge_control: Option<String>,
 
     pub trid_ip: String,
     pub trid_port: u16,
     pub trid_device_id: u8,
+    // First alarm setpoint register, only needed with alarm thresholds
+    #[serde(default)]
+    pub trid_alarm_register: Option<u16>,
+    // First decimal point register, without it one decimal place is assumed
+    #[serde(default)]
+    pub trid_decimal_point_register: Option<u16>,
 
     pub upper_axis: SlitAxisConfig,
     pub lower_axis: SlitAxisConfig,
     pub left_axis: SlitAxisConfig,
     pub right_axis: SlitAxisConfig,
+
+    #[serde(default)]
+    pub calibration: CalibrationConfig,
+
+    // Delay before the post-move verification read
+    #[serde(default = "default_verify_settle_ms")]
+    pub verify_settle_ms: u64,
+
+    // Smallest allowed distance in mm between Y_Up and Y_Down and between
+    // X_Right and X_Left. Positions are along the pair's common axis, so the
+    // gap is Y_Up - Y_Down and X_Right - X_Left.
+    #[serde(default)]
+    pub min_gap: Option<f32>,
+
+    // Refuse moves on axes that were not homed or referenced by hand since
+    // startup
+    #[serde(default)]
+    pub require_reference: bool,
+
+    #[serde(default)]
+    pub sequences: Vec<SequenceConfig>,
+
+    // Automatic parking at set times or after a period of inactivity
+    #[serde(default)]
+    pub parking: Option<ParkingConfig>,
+
+    // Short verification moves at a set time, alarms on worn mechanics
+    #[serde(default)]
+    pub self_check: Option<SelfCheckConfig>,
+
+    // Attributes computed from other attributes of the same controller
+    #[serde(default)]
+    pub derived_attributes: Vec<DerivedAttributeConfig>,
+
+    // Named sets of axes addressed like a single axis, e.g. `STOP vertical`
+    #[serde(default)]
+    pub axis_groups: Vec<AxisGroupConfig>,
+
+    // Named target sets for all blades, e.g. beam sizes, served as the
+    // "preset" axis
+    #[serde(default)]
+    pub presets: Vec<PresetConfig>,
+
+    // Decimals attributes are reported with, by attribute name
+    #[serde(default = "default_precision")]
+    pub precision: Precision,
+
+    // Read-only socket for status displays, motion commands are refused there
+    #[serde(default)]
+    pub observer_socket: Option<String>,
+
+    // Periodic axis samples sent to the facility archive
+    #[serde(default)]
+    pub archiver: Option<ArchiverConfig>,
+}
+
+impl SlitControllerConfig {
+    pub fn axis(&self, name: &str) -> Option<&SlitAxisConfig> {
+        match name {
+            "Y_Up" => Some(&self.upper_axis),
+            "Y_Down" => Some(&self.lower_axis),
+            "X_Left" => Some(&self.left_axis),
+            "X_Right" => Some(&self.right_axis),
+            _ => None,
+        }
+    }
+
+    pub fn axis_mut(&mut self, name: &str) -> Option<&mut SlitAxisConfig> {
+        match name {
+            "Y_Up" => Some(&mut self.upper_axis),
+            "Y_Down" => Some(&mut self.lower_axis),
+            "X_Left" => Some(&mut self.left_axis),
+            "X_Right" => Some(&mut self.right_axis),
+            _ => None,
+        }
+    }
 }
 
 impl Default for SlitControllerConfig {
@@ -31,10 +228,14 @@ impl Default for SlitControllerConfig {
         Self {
             rf256_ip: String::from("192.168.1.1"),
             rf256_port: 502,
+            rf256_checksum: false,
+            rf256_bridge_control: None,
 
             trid_ip: String::from("192.168.1.2"),
             trid_port: 502,
             trid_device_id: 1,
+            trid_alarm_register: None,
+            trid_decimal_point_register: None,
 
             upper_axis: SlitAxisConfig {
                 rf256_id: 1,
@@ -42,6 +243,15 @@ impl Default for SlitControllerConfig {
                 standa_ip: String::from("192.168.1.3"),
                 standa_port: 502,
                 steps_per_mm: 800,
+                inverted: false,
+                backlash_steps: 0,
+                zero_offset: 0.0,
+                alarm_threshold: None,
+                temperature_range: None,
+                unit: Unit::Millimeter,
+                expected: ExpectedParameters::default(),
+                power: None,
+                edges: None,
             },
             lower_axis: SlitAxisConfig {
                 rf256_id: 2,
@@ -49,6 +259,15 @@ impl Default for SlitControllerConfig {
                 standa_ip: String::from("192.168.1.4"),
                 standa_port: 502,
                 steps_per_mm: 800,
+                inverted: false,
+                backlash_steps: 0,
+                zero_offset: 0.0,
+                alarm_threshold: None,
+                temperature_range: None,
+                unit: Unit::Millimeter,
+                expected: ExpectedParameters::default(),
+                power: None,
+                edges: None,
             },
             left_axis: SlitAxisConfig {
                 rf256_id: 3,
@@ -56,6 +275,15 @@ impl Default for SlitControllerConfig {
                 standa_ip: String::from("192.168.1.5"),
                 standa_port: 502,
                 steps_per_mm: 800,
+                inverted: false,
+                backlash_steps: 0,
+                zero_offset: 0.0,
+                alarm_threshold: None,
+                temperature_range: None,
+                unit: Unit::Millimeter,
+                expected: ExpectedParameters::default(),
+                power: None,
+                edges: None,
             },
             right_axis: SlitAxisConfig {
                 rf256_id: 4,
@@ -63,7 +291,30 @@ impl Default for SlitControllerConfig {
                 standa_ip: String::from("192.168.1.6"),
                 standa_port: 502,
                 steps_per_mm: 800,
+                inverted: false,
+                backlash_steps: 0,
+                zero_offset: 0.0,
+                alarm_threshold: None,
+                temperature_range: None,
+                unit: Unit::Millimeter,
+                expected: ExpectedParameters::default(),
+                power: None,
+                edges: None,
             },
+
+            calibration: CalibrationConfig::default(),
+            verify_settle_ms: default_verify_settle_ms(),
+            min_gap: None,
+            require_reference: false,
+            sequences: Vec::new(),
+            parking: None,
+            self_check: None,
+            derived_attributes: Vec::new(),
+            axis_groups: Vec::new(),
+            presets: Vec::new(),
+            precision: default_precision(),
+            observer_socket: None,
+            archiver: None,
         }
     }
 }
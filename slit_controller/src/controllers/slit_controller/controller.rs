@@ -1,45 +1,58 @@
-use std::{io, sync::Arc};
+use std::sync::Arc;
 
 use motarem::{axis::Axis, motor_controller::MotorController};
-use tokio::{sync::Mutex, task::JoinHandle};
-use utilities::command_executor::CommandExecutor;
+use utilities::command_executor::SupervisedExecutor;
 
-use crate::command_executor::{
-    encoder::Rf256Handler, motor::StandaHandler, temperature::TridHandler,
+use crate::controllers::slit_controller::{
+    axis::SlitAxis, endpoints::EndpointAxis, parking::ParkingAxis, self_check::SelfCheckAxis,
+    sequence::SequenceAxis,
 };
 
 pub struct SlitController {
-    axes: Vec<Arc<dyn Axis>>,
+    axes: Vec<Arc<SlitAxis>>,
+    sequence_axis: Option<Arc<SequenceAxis>>,
+    parking_axis: Option<Arc<ParkingAxis>>,
+    self_check_axis: Option<Arc<SelfCheckAxis>>,
+    endpoint_axis: Option<Arc<EndpointAxis>>,
 
-    rf256_join_handle: Arc<Mutex<JoinHandle<io::Result<()>>>>,
-    trid_join_handle: Arc<Mutex<JoinHandle<io::Result<()>>>>,
-    standas_join_handlers: Arc<Mutex<Vec<JoinHandle<io::Result<()>>>>>,
+    executors: Vec<SupervisedExecutor>,
 }
 
 impl SlitController {
-    pub fn new(
-        mut rf256_command_executor: CommandExecutor<Rf256Handler>,
-        mut trid_command_executor: CommandExecutor<TridHandler>,
-        standa_command_executors: Vec<CommandExecutor<StandaHandler>>,
-    ) -> Self {
-        let rf256_handle = tokio::task::spawn_blocking(move || rf256_command_executor.run());
-        let trid_handle = tokio::task::spawn_blocking(move || trid_command_executor.run());
-        let standas_handles = standa_command_executors
-            .into_iter()
-            .map(|mut executor| tokio::task::spawn_blocking(move || executor.run()))
-            .collect();
-
+    pub fn new(executors: Vec<SupervisedExecutor>) -> Self {
         Self {
             axes: Vec::new(),
-            rf256_join_handle: Arc::new(Mutex::new(rf256_handle)),
-            trid_join_handle: Arc::new(Mutex::new(trid_handle)),
-            standas_join_handlers: Arc::new(Mutex::new(standas_handles)),
+            sequence_axis: None,
+            parking_axis: None,
+            self_check_axis: None,
+            endpoint_axis: None,
+            executors,
         }
     }
 
-    pub fn add_axis(&mut self, axis: Arc<dyn Axis>) {
+    pub fn add_axis(&mut self, axis: Arc<SlitAxis>) {
         self.axes.push(axis);
     }
+
+    pub fn set_sequence_axis(&mut self, axis: Arc<SequenceAxis>) {
+        self.sequence_axis = Some(axis);
+    }
+
+    pub fn set_parking_axis(&mut self, axis: Arc<ParkingAxis>) {
+        self.parking_axis = Some(axis);
+    }
+
+    pub fn set_self_check_axis(&mut self, axis: Arc<SelfCheckAxis>) {
+        self.self_check_axis = Some(axis);
+    }
+
+    pub fn set_endpoint_axis(&mut self, axis: Arc<EndpointAxis>) {
+        self.endpoint_axis = Some(axis);
+    }
+
+    pub fn axis_by_name(&self, name: &str) -> Option<Arc<SlitAxis>> {
+        self.axes.iter().find(|axis| axis.name == name).cloned()
+    }
 }
 
 #[async_trait::async_trait]
@@ -49,22 +62,59 @@ impl MotorController for SlitController {
     }
 
     fn axes(&self) -> Vec<Arc<dyn Axis>> {
-        self.axes.clone()
+        self.axes
+            .iter()
+            .map(|axis| axis.clone() as Arc<dyn Axis>)
+            .chain(
+                self.sequence_axis
+                    .iter()
+                    .map(|axis| axis.clone() as Arc<dyn Axis>),
+            )
+            .chain(
+                self.parking_axis
+                    .iter()
+                    .map(|axis| axis.clone() as Arc<dyn Axis>),
+            )
+            .chain(
+                self.self_check_axis
+                    .iter()
+                    .map(|axis| axis.clone() as Arc<dyn Axis>),
+            )
+            .chain(
+                self.endpoint_axis
+                    .iter()
+                    .map(|axis| axis.clone() as Arc<dyn Axis>),
+            )
+            .collect()
     }
 
     async fn shutdown(&self) -> anyhow::Result<()> {
-        for axis in self.axes() {
-            axis.stop().await?;
+        if let Some(parking_axis) = &self.parking_axis {
+            parking_axis.shutdown();
+        }
+
+        if let Some(self_check_axis) = &self.self_check_axis {
+            self_check_axis.shutdown();
+        }
+
+        if let Some(sequence_axis) = &self.sequence_axis {
+            sequence_axis.stop().await?;
+        }
+
+        for axis in &self.axes {
+            Axis::stop(axis.as_ref()).await?;
         }
 
         // FIXME: Is it really ok?
-        let rf256_handle = self.rf256_join_handle.lock().await;
-        rf256_handle.abort();
-        let trid_handle = self.trid_join_handle.lock().await;
-        trid_handle.abort();
-        let standas_handles = self.standas_join_handlers.lock().await;
-        for handle in &*standas_handles {
-            handle.abort();
+        for executor in &self.executors {
+            if executor.restarts() > 0 {
+                eprintln!(
+                    "{} executor was restarted {} times",
+                    executor.name(),
+                    executor.restarts()
+                );
+            }
+            executor.abort();
         }
 
         Ok(())
@@ -0,0 +1,224 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use motarem::axis::{
+    limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+    state_info::AxisStateInfo, Axis,
+};
+use utilities::lazy_tcp::Endpoint;
+
+use crate::{
+    command_executor::encoder::command_sender::EncoderCommandSender,
+    controllers::slit_controller::config::SlitControllerConfig,
+};
+
+fn endpoint(ip: &str, port: u16) -> Endpoint {
+    Endpoint::new(SocketAddr::new(ip.parse().unwrap(), port))
+}
+
+// Addresses of the bridges, shared with the TCP streams of the executors so
+// a swapped gateway can be pointed to without a restart
+#[derive(Clone)]
+pub struct Endpoints {
+    pub rf256: Endpoint,
+    pub trid: Endpoint,
+    // Same order as create_standas
+    pub standas: [Endpoint; 4],
+}
+
+impl Endpoints {
+    pub fn new(config: &SlitControllerConfig) -> Self {
+        Self {
+            rf256: endpoint(&config.rf256_ip, config.rf256_port),
+            trid: endpoint(&config.trid_ip, config.trid_port),
+            standas: [
+                &config.upper_axis,
+                &config.lower_axis,
+                &config.right_axis,
+                &config.left_axis,
+            ]
+            .map(|axis| endpoint(&axis.standa_ip, axis.standa_port)),
+        }
+    }
+
+    fn devices(&self) -> [(&'static str, &Endpoint); 6] {
+        [
+            ("rf256", &self.rf256),
+            ("trid", &self.trid),
+            ("Y_Up", &self.standas[0]),
+            ("Y_Down", &self.standas[1]),
+            ("X_Right", &self.standas[2]),
+            ("X_Left", &self.standas[3]),
+        ]
+    }
+}
+
+// Admin interface for swapping gateways. The "<device>_ip" custom parameter
+// takes an IPv4 address packed into an integer (192.168.0.51 is 3232235571),
+// "<device>_port" the port, either one leaves the other as it is. Only the
+// executor of that device reconnects, before its next request. The change is
+// not written back to the configuration file.
+//
+// "rf256_baudrate" moves the whole RF256 bus to another rate, see
+// Rf256Handler::change_baudrate. The bridge is switched over its
+// rf256_bridge_control port.
+pub struct EndpointAxis {
+    pub name: String,
+
+    endpoints: Endpoints,
+    swaps: AtomicU32,
+
+    rf256_cs: EncoderCommandSender,
+}
+
+impl EndpointAxis {
+    pub fn new(name: String, endpoints: Endpoints, rf256_cs: EncoderCommandSender) -> Self {
+        Self {
+            name,
+            endpoints,
+            swaps: AtomicU32::new(0),
+            rf256_cs,
+        }
+    }
+
+    async fn change_rf256_baudrate(&self, baudrate: f64) -> Result<(), String> {
+        if !(1.0..=u32::MAX as f64).contains(&baudrate) || baudrate.fract() != 0.0 {
+            return Err(format!("Invalid rf256_baudrate: {}", baudrate));
+        }
+
+        eprintln!("Changing RF256 baudrate to {}", baudrate);
+        self.rf256_cs
+            .change_baudrate(baudrate as u32)
+            .await
+            .map_err(|e| format!("Failed to change RF256 baudrate: {}", e))
+    }
+
+    fn swap(&self, parameters: &MovementParams) -> Result<(), String> {
+        let mut changes = Vec::new();
+
+        for (device, endpoint) in self.endpoints.devices() {
+            let ip = parameters.custom.get(&format!("{}_ip", device));
+            let port = parameters.custom.get(&format!("{}_port", device));
+            if ip.is_none() && port.is_none() {
+                continue;
+            }
+
+            let mut addr = endpoint.get();
+            if let Some(ip) = ip {
+                if !(0.0..=u32::MAX as f64).contains(ip) || ip.fract() != 0.0 {
+                    return Err(format!("Invalid {}_ip: {}", device, ip));
+                }
+                addr.set_ip(IpAddr::V4(Ipv4Addr::from(*ip as u32)));
+            }
+            if let Some(port) = port {
+                if !(1.0..=u16::MAX as f64).contains(port) || port.fract() != 0.0 {
+                    return Err(format!("Invalid {}_port: {}", device, port));
+                }
+                addr.set_port(*port as u16);
+            }
+
+            changes.push((device, endpoint, addr));
+        }
+
+        if changes.is_empty() {
+            return Err("No endpoint given".to_string());
+        }
+
+        // Everything is checked before anything changes
+        for (device, endpoint, addr) in changes {
+            eprintln!("{} endpoint {} -> {}", device, endpoint.get(), addr);
+            endpoint.set(addr);
+            self.swaps.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for EndpointAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(
+        &self,
+        _position: f64,
+        parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        let parameters = parameters.ok_or_else(|| anyhow::Error::msg("No endpoint given"))?;
+
+        if let Some(baudrate) = parameters.custom.get("rf256_baudrate") {
+            return self
+                .change_rf256_baudrate(*baudrate)
+                .await
+                .map_err(anyhow::Error::msg);
+        }
+
+        self.swap(&parameters).map_err(anyhow::Error::msg)
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        Ok(AxisStateInfo {
+            state: AxisState::On,
+            message: None,
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        if name == "position" || name == "swaps" {
+            return Ok(self.swaps.load(Ordering::Relaxed) as f64);
+        }
+        if name == "rf256_baudrate" {
+            return self
+                .rf256_cs
+                .get_settings(0)
+                .await
+                .map(|settings| settings.baudrate as f64)
+                .map_err(|e| anyhow::Error::msg(format!("Failed to read RF256 baudrate: {}", e)));
+        }
+
+        for (device, endpoint) in self.endpoints.devices() {
+            let addr = endpoint.get();
+
+            if name == format!("{}_ip", device) {
+                return match addr.ip() {
+                    IpAddr::V4(ip) => Ok(u32::from(ip) as f64),
+                    IpAddr::V6(_) => Err(anyhow::Error::msg(format!(
+                        "{} is not an IPv4 address",
+                        addr.ip()
+                    ))),
+                };
+            }
+            if name == format!("{}_port", device) {
+                return Ok(addr.port() as f64);
+            }
+        }
+
+        Err(anyhow::Error::msg(format!("Unknown attribute: {}", name)))
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params = vec!["position".to_string(), "swaps".to_string()];
+        params.extend(self.get_supported_movement_params().await?);
+
+        Ok(params)
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .endpoints
+            .devices()
+            .iter()
+            .flat_map(|(device, _)| [format!("{}_ip", device), format!("{}_port", device)])
+            .chain(["rf256_baudrate".to_string()])
+            .collect())
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::command_executor::encoder::command_sender::EncoderCommandSender;
+
+// Knife positions are taken along the common axis of the pair, upper and
+// right knives sit on the positive side of the gap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnifeSide {
+    Positive,
+    Negative,
+}
+
+impl KnifeSide {
+    fn index(self) -> usize {
+        match self {
+            KnifeSide::Positive => 0,
+            KnifeSide::Negative => 1,
+        }
+    }
+
+    fn opposite(self) -> Self {
+        match self {
+            KnifeSide::Positive => KnifeSide::Negative,
+            KnifeSide::Negative => KnifeSide::Positive,
+        }
+    }
+}
+
+// Shared by the two knives of a pair. Each knife claims the innermost
+// position it may reach during its current move, and corrections are checked
+// against the other knife's encoder and claim under one lock, so two knives
+// correcting at the same time can't both close in on the gap. A claim lasts
+// until the move ends, the encoder covers everything that moves a knife
+// outside of closed loop moves.
+pub struct GapGuard {
+    min_gap: f32,
+    correcting: Mutex<()>,
+    claims: std::sync::Mutex<[Option<f32>; 2]>,
+}
+
+impl GapGuard {
+    pub fn new(min_gap: f32) -> Self {
+        Self {
+            min_gap,
+            correcting: Mutex::new(()),
+            claims: std::sync::Mutex::new([None, None]),
+        }
+    }
+
+    pub fn min_gap(&self) -> f32 {
+        self.min_gap
+    }
+}
+
+pub struct GapLimit {
+    guard: Arc<GapGuard>,
+    side: KnifeSide,
+    // Encoder of the opposing knife
+    opposing_axis: u8,
+}
+
+impl GapLimit {
+    pub fn new(guard: Arc<GapGuard>, side: KnifeSide, opposing_axis: u8) -> Self {
+        Self {
+            guard,
+            side,
+            opposing_axis,
+        }
+    }
+
+    pub fn min_gap(&self) -> f32 {
+        self.guard.min_gap()
+    }
+
+    pub fn side(&self) -> KnifeSide {
+        self.side
+    }
+
+    // Returns the displacement, cut short where it would close the gap below
+    // the minimum. A knife already inside the minimum is not moved further in.
+    pub async fn limit(
+        &self,
+        current: f32,
+        displacement: f32,
+        rf256_cs: &EncoderCommandSender,
+    ) -> Result<f32, String> {
+        let _correcting = self.guard.correcting.lock().await;
+
+        let position = rf256_cs
+            .get_position(self.opposing_axis)
+            .await
+            .map_err(|e| format!("Failed to read opposing knife: {}", e))?;
+
+        // Whichever of the encoder and the claim is further in
+        let claim = self.guard.claims.lock().unwrap()[self.side.opposite().index()];
+        let opposing = match (self.side, claim) {
+            (KnifeSide::Positive, Some(claim)) => position.max(claim),
+            (KnifeSide::Negative, Some(claim)) => position.min(claim),
+            (_, None) => position,
+        };
+
+        let (displacement, innermost) = match self.side {
+            KnifeSide::Positive => {
+                let bound = opposing + self.guard.min_gap - current;
+                let displacement = displacement.max(bound.min(0.0));
+                (displacement, current.min(current + displacement))
+            }
+            KnifeSide::Negative => {
+                let bound = opposing - self.guard.min_gap - current;
+                let displacement = displacement.min(bound.max(0.0));
+                (displacement, current.max(current + displacement))
+            }
+        };
+
+        let mut claims = self.guard.claims.lock().unwrap();
+        let claim = &mut claims[self.side.index()];
+        // Innermost over the whole move, not just this correction
+        *claim = Some(match (self.side, *claim) {
+            (KnifeSide::Positive, Some(claim)) => claim.min(innermost),
+            (KnifeSide::Negative, Some(claim)) => claim.max(innermost),
+            (_, None) => innermost,
+        });
+
+        Ok(displacement)
+    }
+
+    // Called once the move of this knife is over
+    pub fn release(&self) {
+        self.guard.claims.lock().unwrap()[self.side.index()] = None;
+    }
+}
@@ -1,67 +1,108 @@
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{net::SocketAddr, ops::RangeInclusive, sync::Arc, time::Duration};
 
+use motarem::motor_controller::MotorController as _;
 use rf256::Rf256;
-use standa::Standa;
+use standa::{
+    command::{
+        edges::{BorderFlags, EdgesSettings, EnderFlags},
+        power::PowerFlags,
+    },
+    Standa,
+};
 use trid::Trid;
-use utilities::{command_executor::CommandExecutor, lazy_tcp::LazyTcpStream};
+use utilities::{
+    command_executor::CommandExecutor,
+    lazy_tcp::{Endpoint, LazyTcpStream},
+    units::{AxisUnits, Unit},
+};
 
 use crate::{
     command_executor::{
-        encoder::{command_sender::EncoderCommandSender, Rf256Handler},
+        encoder::{command_sender::EncoderCommandSender, Rf256Handler, Rf256Line},
         motor::{command_sender::StandaCommandSender, StandaHandler},
         temperature::{command_sender::TridCommandSender, TridHandler},
     },
     controllers::slit_controller::{
-        axis::SlitAxis, config::SlitControllerConfig, controller::SlitController,
+        axis::SlitAxis,
+        config::{EdgesConfig, PowerConfig, SlitAxisConfig, SlitControllerConfig},
+        controller::SlitController,
+        endpoints::{EndpointAxis, Endpoints},
+        gap::{GapGuard, GapLimit, KnifeSide},
+        parking::ParkingAxis,
+        self_check::SelfCheckAxis,
+        sequence::SequenceAxis,
     },
 };
 
 pub mod axis;
+pub mod calibration;
 pub mod config;
 pub mod controller;
+pub mod endpoints;
+pub mod gap;
 pub mod motor;
 pub mod params;
+pub mod parking;
+pub mod recorder;
+pub mod self_check;
+pub mod sequence;
+pub mod snapshot;
 
 const READ_TIMEOUT: Duration = Duration::from_millis(100);
 const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
 const CONNECT_TIMEOUT: Duration = Duration::from_millis(100);
 const MAX_RETRIES: u32 = 3;
 
-pub fn create_encoder(
-    config: &SlitControllerConfig,
-) -> (CommandExecutor<Rf256Handler>, EncoderCommandSender) {
-    let rf256_scoket_addr = SocketAddr::new(config.rf256_ip.parse().unwrap(), config.rf256_port);
-
+fn create_rf256_handler(config: &SlitControllerConfig, endpoint: &Endpoint) -> Rf256Handler {
     let rf256_tcp_stream = LazyTcpStream::new(
-        rf256_scoket_addr,
+        endpoint.get(),
         MAX_RETRIES,
         READ_TIMEOUT,
         WRITE_TIMEOUT,
         CONNECT_TIMEOUT,
-    );
+    )
+    .follow(endpoint.clone());
 
-    let rf256_handler = Rf256Handler::new(
-        rf256_tcp_stream,
+    Rf256Handler::new(
+        Rf256Line::new(rf256_tcp_stream, config.rf256_bridge_control.clone()),
         [
-            Rf256::new(config.upper_axis.rf256_id),
-            Rf256::new(config.lower_axis.rf256_id),
-            Rf256::new(config.right_axis.rf256_id),
-            Rf256::new(config.left_axis.rf256_id),
+            Rf256::builder(config.upper_axis.rf256_id)
+                .zero_offset(config.upper_axis.zero_offset)
+                .checksum(config.rf256_checksum)
+                .build(),
+            Rf256::builder(config.lower_axis.rf256_id)
+                .zero_offset(config.lower_axis.zero_offset)
+                .checksum(config.rf256_checksum)
+                .build(),
+            Rf256::builder(config.right_axis.rf256_id)
+                .zero_offset(config.right_axis.zero_offset)
+                .checksum(config.rf256_checksum)
+                .build(),
+            Rf256::builder(config.left_axis.rf256_id)
+                .zero_offset(config.left_axis.zero_offset)
+                .checksum(config.rf256_checksum)
+                .build(),
         ],
-    );
+    )
+}
 
-    let rf256_command_executor = CommandExecutor::new(rf256_handler);
+pub fn create_encoder(
+    config: &SlitControllerConfig,
+    endpoint: &Endpoint,
+) -> (CommandExecutor<Rf256Handler>, EncoderCommandSender) {
+    let rf256_command_executor = CommandExecutor::new(create_rf256_handler(config, endpoint));
     let rf256_command_sender = EncoderCommandSender::new(rf256_command_executor.sender());
 
     (rf256_command_executor, rf256_command_sender)
 }
 
-pub fn create_trid(
-    config: &SlitControllerConfig,
-) -> (CommandExecutor<TridHandler>, TridCommandSender) {
-    let trid_socket_addr = SocketAddr::new(config.trid_ip.parse().unwrap(), config.trid_port);
+// Modbus RTU slave addresses
+const TRID_SCAN_RANGE: RangeInclusive<u8> = 1..=247;
 
-    let trid_tcp_stream = LazyTcpStream::new(
+// Field diagnostic, lists the TRID units answering on the configured bridge
+pub fn scan_trid_bus(config: &SlitControllerConfig) -> Vec<u8> {
+    let trid_socket_addr = SocketAddr::new(config.trid_ip.parse().unwrap(), config.trid_port);
+    let mut trid_tcp_stream = LazyTcpStream::new(
         trid_socket_addr,
         MAX_RETRIES,
         READ_TIMEOUT,
@@ -69,51 +110,259 @@ pub fn create_trid(
         CONNECT_TIMEOUT,
     );
 
-    let trid_handler = TridHandler::new(
+    Trid::scan_bus(&mut trid_tcp_stream, TRID_SCAN_RANGE)
+}
+
+fn create_trid_handler(config: &SlitControllerConfig, endpoint: &Endpoint) -> TridHandler {
+    let trid_tcp_stream = LazyTcpStream::new(
+        endpoint.get(),
+        MAX_RETRIES,
+        READ_TIMEOUT,
+        WRITE_TIMEOUT,
+        CONNECT_TIMEOUT,
+    )
+    .follow(endpoint.clone());
+
+    let trid = |axis: &SlitAxisConfig| {
+        let mut trid = Trid::new(config.trid_device_id, axis.trid_id);
+
+        if let Some(register) = config.trid_alarm_register {
+            trid = trid.with_alarm_register(register);
+        }
+        if let Some(register) = config.trid_decimal_point_register {
+            trid = trid.with_decimal_point_register(register);
+        }
+        if let Some([min, max]) = axis.temperature_range {
+            trid = trid.with_valid_range(min, max);
+        }
+
+        trid
+    };
+
+    TridHandler::new(
         trid_tcp_stream,
         [
-            Trid::new(config.trid_device_id, config.upper_axis.trid_id),
-            Trid::new(config.trid_device_id, config.lower_axis.trid_id),
-            Trid::new(config.trid_device_id, config.right_axis.trid_id),
-            Trid::new(config.trid_device_id, config.left_axis.trid_id),
+            trid(&config.upper_axis),
+            trid(&config.lower_axis),
+            trid(&config.right_axis),
+            trid(&config.left_axis),
         ],
-    );
+    )
+}
+
+// Same order as the TRIDs in create_trid_handler
+fn apply_alarm_thresholds(config: &SlitControllerConfig, trid_cs: TridCommandSender) {
+    let thresholds: Vec<(u8, f32)> = [
+        &config.upper_axis,
+        &config.lower_axis,
+        &config.right_axis,
+        &config.left_axis,
+    ]
+    .into_iter()
+    .enumerate()
+    .filter_map(|(axis, config)| config.alarm_threshold.map(|t| (axis as u8, t)))
+    .collect();
+
+    if thresholds.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        for (axis, threshold) in thresholds {
+            if let Err(e) = trid_cs.set_alarm_threshold(axis, threshold).await {
+                eprintln!("Failed to set alarm threshold of TRID {}: {}", axis, e);
+            }
+        }
+    });
+}
+
+// Inventory of the Standa units, so a swapped cable shows up in the log
+fn log_standa_identities(standa_cs: Vec<StandaCommandSender>) {
+    tokio::spawn(async move {
+        for (name, standa_cs) in ["Y_Up", "Y_Down", "X_Right", "X_Left"]
+            .into_iter()
+            .zip(standa_cs)
+        {
+            match standa_cs.identify().await {
+                Ok(identity) => eprintln!("Standa {}: {}", name, identity),
+                Err(e) => eprintln!("Failed to identify Standa {}: {}", name, e),
+            }
+        }
+    });
+}
+
+// Lets idle knives drop to a holding current instead of heating the mount
+fn apply_power_settings(config: &SlitControllerConfig, standa_cs: Vec<StandaCommandSender>) {
+    let settings: Vec<(&str, PowerConfig, StandaCommandSender)> = [
+        ("Y_Up", &config.upper_axis),
+        ("Y_Down", &config.lower_axis),
+        ("X_Right", &config.right_axis),
+        ("X_Left", &config.left_axis),
+    ]
+    .into_iter()
+    .zip(standa_cs)
+    .filter_map(|((name, config), standa_cs)| {
+        config.power.clone().map(|power| (name, power, standa_cs))
+    })
+    .collect();
+
+    if settings.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        for (name, power, standa_cs) in settings {
+            let result = async {
+                // Read first, the current set time is left as it is
+                let mut settings = standa_cs.get_power_settings().await?;
+                let mut flags = settings.power_flags | PowerFlags::REDUCT_ENABLED;
+
+                settings.hold_current = power.hold_current;
+                settings.curr_reduct_delay = power.reduction_delay_ms;
+                match power.power_off_delay_s {
+                    Some(delay) => {
+                        settings.power_off_delay = delay;
+                        flags |= PowerFlags::OFF_ENABLED;
+                    }
+                    None => flags.remove(PowerFlags::OFF_ENABLED),
+                }
+                settings.power_flags = flags;
 
-    let trid_command_executor = CommandExecutor::new(trid_handler);
+                standa_cs.set_power_settings(settings).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                eprintln!("Failed to set power settings of {}: {}", name, e);
+            }
+        }
+    });
+}
+
+// Borders are kept in steps, misset detection is left as it is
+fn edges_settings(current: &EdgesSettings, edges: &EdgesConfig) -> EdgesSettings {
+    let mut settings = *current;
+
+    let mut ender_flags = EnderFlags::empty();
+    ender_flags.set(EnderFlags::SWAP, edges.swap_switches);
+    ender_flags.set(EnderFlags::SW1_ACTIVE_LOW, edges.switch1_active_low);
+    ender_flags.set(EnderFlags::SW2_ACTIVE_LOW, edges.switch2_active_low);
+    settings.ender_flags = ender_flags;
+
+    let mut border_flags = current.border_flags;
+    border_flags.remove(BorderFlags::IS_ENCODER);
+    border_flags.set(BorderFlags::STOP_LEFT, edges.left_border.is_some());
+    border_flags.set(BorderFlags::STOP_RIGHT, edges.right_border.is_some());
+    settings.border_flags = border_flags;
+
+    if let Some(border) = edges.left_border {
+        settings.left_border = border;
+        settings.u_left_border = 0;
+    }
+    if let Some(border) = edges.right_border {
+        settings.right_border = border;
+        settings.u_right_border = 0;
+    }
+
+    settings
+}
+
+// Fields are copied out, the struct is packed
+fn same_edges(a: &EdgesSettings, b: &EdgesSettings) -> bool {
+    let fields = |settings: &EdgesSettings| {
+        (
+            settings.border_flags,
+            settings.ender_flags,
+            settings.left_border,
+            settings.u_left_border,
+            settings.right_border,
+            settings.u_right_border,
+        )
+    };
+
+    fields(a) == fields(b)
+}
+
+// A wrong switch polarity leaves a knife driving into its end stop, so the
+// deployed values are checked and corrected on every start
+fn apply_edges_settings(config: &SlitControllerConfig, standa_cs: Vec<StandaCommandSender>) {
+    let settings: Vec<(&str, EdgesConfig, StandaCommandSender)> = [
+        ("Y_Up", &config.upper_axis),
+        ("Y_Down", &config.lower_axis),
+        ("X_Right", &config.right_axis),
+        ("X_Left", &config.left_axis),
+    ]
+    .into_iter()
+    .zip(standa_cs)
+    .filter_map(|((name, config), standa_cs)| {
+        config.edges.clone().map(|edges| (name, edges, standa_cs))
+    })
+    .collect();
+
+    if settings.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        for (name, edges, standa_cs) in settings {
+            let result = async {
+                let current = standa_cs.get_edges_settings().await?;
+                let expected = edges_settings(&current, &edges);
+
+                if same_edges(&current, &expected) {
+                    return Ok(());
+                }
+
+                eprintln!(
+                    "Edge settings of {} differ from the configuration, {:?} -> {:?}",
+                    name, current, expected
+                );
+                standa_cs.set_edges_settings(expected).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                eprintln!("Failed to set edge settings of {}: {}", name, e);
+            }
+        }
+    });
+}
+
+pub fn create_trid(
+    config: &SlitControllerConfig,
+    endpoint: &Endpoint,
+) -> (CommandExecutor<TridHandler>, TridCommandSender) {
+    let trid_command_executor = CommandExecutor::new(create_trid_handler(config, endpoint));
     let trid_command_sender = TridCommandSender::new(trid_command_executor.sender());
 
     (trid_command_executor, trid_command_sender)
 }
 
-fn create_standa_command_executor(
-    standa_ip: &str,
-    standa_port: u16,
-) -> CommandExecutor<StandaHandler> {
+fn create_standa_handler(endpoint: &Endpoint) -> StandaHandler {
     let tcp_stream = LazyTcpStream::new(
-        SocketAddr::new(standa_ip.parse().unwrap(), standa_port),
+        endpoint.get(),
         1,
         READ_TIMEOUT,
         WRITE_TIMEOUT,
         CONNECT_TIMEOUT,
-    );
+    )
+    .follow(endpoint.clone());
 
     let standa = Standa::new();
-    let handler = StandaHandler::new(standa, tcp_stream);
+    StandaHandler::new(standa, tcp_stream)
+}
 
-    CommandExecutor::new(handler)
+fn create_standa_command_executor(endpoint: &Endpoint) -> CommandExecutor<StandaHandler> {
+    CommandExecutor::new(create_standa_handler(endpoint))
 }
 
 pub fn create_standas(
-    config: &SlitControllerConfig,
+    endpoints: &Endpoints,
 ) -> Vec<(CommandExecutor<StandaHandler>, StandaCommandSender)> {
-    let upper_standa_executor =
-        create_standa_command_executor(&config.upper_axis.standa_ip, config.upper_axis.standa_port);
-    let lower_standa_executor =
-        create_standa_command_executor(&config.lower_axis.standa_ip, config.lower_axis.standa_port);
-    let right_standa_executor =
-        create_standa_command_executor(&config.right_axis.standa_ip, config.right_axis.standa_port);
-    let left_standa_executor =
-        create_standa_command_executor(&config.left_axis.standa_ip, config.left_axis.standa_port);
+    let upper_standa_executor = create_standa_command_executor(&endpoints.standas[0]);
+    let lower_standa_executor = create_standa_command_executor(&endpoints.standas[1]);
+    let right_standa_executor = create_standa_command_executor(&endpoints.standas[2]);
+    let left_standa_executor = create_standa_command_executor(&endpoints.standas[3]);
 
     let upper_standa_command_sender = StandaCommandSender::new(upper_standa_executor.sender());
     let lower_standa_command_sender = StandaCommandSender::new(lower_standa_executor.sender());
@@ -128,10 +377,27 @@ pub fn create_standas(
     ]
 }
 
-pub fn create_controller(config: &SlitControllerConfig) -> SlitController {
-    let (rf256_command_executor, rf256_command_sender) = create_encoder(config);
-    let (trid_command_executor, trid_command_sender) = create_trid(config);
-    let standas = create_standas(config);
+// Encoder readings and the closed loop are in mm
+fn axis_units(name: &str, config: &SlitAxisConfig) -> Result<AxisUnits, String> {
+    AxisUnits::new(Unit::Millimeter, config.unit)
+        .map_err(|e| format!("Invalid unit of axis {}: {}", name, e))
+}
+
+pub fn create_controller(config: &SlitControllerConfig) -> Result<SlitController, String> {
+    let endpoints = Endpoints::new(config);
+    let (rf256_command_executor, rf256_command_sender) = create_encoder(config, &endpoints.rf256);
+    let (trid_command_executor, trid_command_sender) = create_trid(config, &endpoints.trid);
+    apply_alarm_thresholds(config, trid_command_sender.clone());
+    let standas = create_standas(&endpoints);
+    log_standa_identities(standas.iter().map(|(_, sender)| sender.clone()).collect());
+    apply_power_settings(
+        config,
+        standas.iter().map(|(_, sender)| sender.clone()).collect(),
+    );
+    apply_edges_settings(
+        config,
+        standas.iter().map(|(_, sender)| sender.clone()).collect(),
+    );
 
     let upper_axis = SlitAxis::new(
         "Y_Up".to_string(),
@@ -140,7 +406,12 @@ pub fn create_controller(config: &SlitControllerConfig) -> SlitController {
         trid_command_sender.clone(),
         standas[0].1.clone(),
         config.upper_axis.steps_per_mm,
-    );
+        axis_units("Y_Up", &config.upper_axis)?,
+        Duration::from_millis(config.verify_settle_ms),
+    )
+    .with_reference_required(config.require_reference)
+    .with_inverted(config.upper_axis.inverted)
+    .with_backlash_steps(config.upper_axis.backlash_steps);
     let lower_axis = SlitAxis::new(
         "Y_Down".to_string(),
         1,
@@ -148,7 +419,12 @@ pub fn create_controller(config: &SlitControllerConfig) -> SlitController {
         trid_command_sender.clone(),
         standas[1].1.clone(),
         config.lower_axis.steps_per_mm,
-    );
+        axis_units("Y_Down", &config.lower_axis)?,
+        Duration::from_millis(config.verify_settle_ms),
+    )
+    .with_reference_required(config.require_reference)
+    .with_inverted(config.lower_axis.inverted)
+    .with_backlash_steps(config.lower_axis.backlash_steps);
     let left_axis = SlitAxis::new(
         "X_Left".to_string(),
         2,
@@ -156,7 +432,12 @@ pub fn create_controller(config: &SlitControllerConfig) -> SlitController {
         trid_command_sender.clone(),
         standas[2].1.clone(),
         config.left_axis.steps_per_mm,
-    );
+        axis_units("X_Left", &config.left_axis)?,
+        Duration::from_millis(config.verify_settle_ms),
+    )
+    .with_reference_required(config.require_reference)
+    .with_inverted(config.left_axis.inverted)
+    .with_backlash_steps(config.left_axis.backlash_steps);
     let right_axis = SlitAxis::new(
         "X_Right".to_string(),
         3,
@@ -164,21 +445,95 @@ pub fn create_controller(config: &SlitControllerConfig) -> SlitController {
         trid_command_sender.clone(),
         standas[3].1.clone(),
         config.right_axis.steps_per_mm,
-    );
+        axis_units("X_Right", &config.right_axis)?,
+        Duration::from_millis(config.verify_settle_ms),
+    )
+    .with_reference_required(config.require_reference)
+    .with_inverted(config.right_axis.inverted)
+    .with_backlash_steps(config.right_axis.backlash_steps);
 
-    let mut controller = SlitController::new(
-        rf256_command_executor,
-        trid_command_executor,
-        standas
-            .into_iter()
-            .map(|(executor, _sender)| executor)
-            .collect(),
-    );
+    // The knives of a pair share a guard, the last argument is the encoder
+    // of the opposing knife
+    let (upper_axis, lower_axis, left_axis, right_axis) = match config.min_gap {
+        Some(min_gap) => {
+            let vertical = Arc::new(GapGuard::new(min_gap));
+            let horizontal = Arc::new(GapGuard::new(min_gap));
+            (
+                upper_axis.with_gap_limit(GapLimit::new(vertical.clone(), KnifeSide::Positive, 1)),
+                lower_axis.with_gap_limit(GapLimit::new(vertical, KnifeSide::Negative, 0)),
+                left_axis.with_gap_limit(GapLimit::new(horizontal.clone(), KnifeSide::Negative, 3)),
+                right_axis.with_gap_limit(GapLimit::new(horizontal, KnifeSide::Positive, 2)),
+            )
+        }
+        None => (upper_axis, lower_axis, left_axis, right_axis),
+    };
+
+    // Executors are rebuilt from the configuration if they ever die, at the
+    // current endpoint so a swapped gateway stays swapped
+    let mut executors = vec![
+        {
+            let config = config.clone();
+            let endpoint = endpoints.rf256.clone();
+            rf256_command_executor
+                .supervise("RF256", move || create_rf256_handler(&config, &endpoint))
+        },
+        {
+            let config = config.clone();
+            let endpoint = endpoints.trid.clone();
+            trid_command_executor.supervise("TRID", move || create_trid_handler(&config, &endpoint))
+        },
+    ];
+
+    let names = ["Y_Up", "Y_Down", "X_Right", "X_Left"];
+    for (((executor, _sender), name), endpoint) in standas
+        .into_iter()
+        .zip(names)
+        .zip(endpoints.standas.clone())
+    {
+        executors.push(executor.supervise(format!("Standa {}", name), move || {
+            create_standa_handler(&endpoint)
+        }));
+    }
+
+    let mut controller = SlitController::new(executors);
+
+    let blades = vec![
+        Arc::new(upper_axis),
+        Arc::new(lower_axis),
+        Arc::new(left_axis),
+        Arc::new(right_axis),
+    ];
+    for axis in &blades {
+        controller.add_axis(axis.clone());
+    }
+
+    if !config.sequences.is_empty() {
+        controller.set_sequence_axis(Arc::new(SequenceAxis::new(
+            "Sequence".to_string(),
+            controller.axes(),
+            &config.sequences,
+        )?));
+    }
+
+    controller.set_endpoint_axis(Arc::new(EndpointAxis::new(
+        "Endpoints".to_string(),
+        endpoints,
+        rf256_command_sender.clone(),
+    )));
+
+    if let Some(parking) = &config.parking {
+        controller.set_parking_axis(Arc::new(
+            ParkingAxis::new("Parking".to_string(), controller.axes(), parking)
+                .map_err(|e| format!("Invalid parking configuration: {}", e))?,
+        ));
+    }
 
-    controller.add_axis(Arc::new(upper_axis));
-    controller.add_axis(Arc::new(lower_axis));
-    controller.add_axis(Arc::new(left_axis));
-    controller.add_axis(Arc::new(right_axis));
+    if let Some(self_check) = &config.self_check {
+        controller.set_self_check_axis(Arc::new(
+            SelfCheckAxis::new("SelfCheck".to_string(), blades, self_check)
+                .map_err(|e| format!("Invalid self_check configuration: {}", e))?,
+        ));
+    }
 
-    controller
+    Ok(controller)
 }
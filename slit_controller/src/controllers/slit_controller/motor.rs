@@ -1,22 +1,31 @@
 use std::{
     io::{self},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicI8, Ordering},
         Arc,
     },
     time::{Duration, Instant},
 };
 
-use standa::command::state::StateParams;
+use standa::command::{r#move::StopMode, state::StateParams};
 use utilities::{
     motor_controller::{Motor, MotorState},
     moving_average::MovingAverage,
 };
 
-use crate::command_executor::{
-    encoder::command_sender::EncoderCommandSender, motor::command_sender::StandaCommandSender,
+use crate::{
+    command_executor::{
+        encoder::command_sender::EncoderCommandSender, motor::command_sender::StandaCommandSender,
+    },
+    controllers::slit_controller::{gap::GapLimit, recorder::MotionRecorder},
 };
 
+#[derive(Debug, Clone, Copy)]
+pub struct Verification {
+    pub position: f32,
+    pub residual: f32,
+}
+
 pub struct SlitMotor {
     rf256_cs: EncoderCommandSender,
     rf256_axis: u8,
@@ -31,11 +40,23 @@ pub struct SlitMotor {
     is_moving: Arc<AtomicBool>,
     start_time: Instant,
     steps_per_mm: i32,
+    inverted: bool,
+    // Extra steps taken up when the drive reverses, and the sign of the
+    // last steps sent, kept by the axis across moves (0 before the first)
+    backlash_steps: i32,
+    last_direction: Arc<AtomicI8>,
+
+    gap_limit: Option<Arc<GapLimit>>,
+    recorder: Option<Arc<MotionRecorder>>,
 }
 
 impl Drop for SlitMotor {
     fn drop(&mut self) {
         self.is_moving.store(false, Ordering::Relaxed);
+
+        if let Some(gap_limit) = &self.gap_limit {
+            gap_limit.release();
+        }
     }
 }
 
@@ -67,31 +88,118 @@ impl SlitMotor {
             is_moving,
             start_time,
             steps_per_mm,
+            inverted: false,
+            backlash_steps: 0,
+            last_direction: Arc::new(AtomicI8::new(0)),
+
+            gap_limit: None,
+            recorder: None,
+        }
+    }
+
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    pub fn with_backlash(mut self, backlash_steps: i32, last_direction: Arc<AtomicI8>) -> Self {
+        self.backlash_steps = backlash_steps;
+        self.last_direction = last_direction;
+        self
+    }
+
+    pub fn with_gap_limit(mut self, gap_limit: Option<Arc<GapLimit>>) -> Self {
+        self.gap_limit = gap_limit;
+        self
+    }
+
+    pub fn with_recorder(mut self, recorder: Arc<MotionRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    // The RMS window can close early on a noisy encoder, so the final answer
+    // comes from a fresh read once the mechanics had time to settle
+    pub async fn verify(&self, settle_time: Duration) -> Result<Verification, String> {
+        tokio::time::sleep(settle_time).await;
+
+        let position = self.position().await?;
+
+        Ok(Verification {
+            position,
+            residual: position - self.target_position,
+        })
+    }
+
+    // The slack of the drive train is taken up first when the direction
+    // changes, so the blade moves by the steps asked for
+    fn take_up_backlash(&self, steps: i32, sub_steps: i16) -> i32 {
+        let direction = match (steps, sub_steps) {
+            (0, 0) => return steps,
+            (0, sub_steps) => sub_steps.signum() as i8,
+            (steps, _) => steps.signum() as i8,
+        };
+
+        let last = self.last_direction.swap(direction, Ordering::Relaxed);
+        if last != 0 && last != direction {
+            steps + self.backlash_steps.abs() * direction as i32
+        } else {
+            steps
         }
     }
 
     async fn send_steps(&self, steps: i32, sub_steps: i16) -> io::Result<()> {
-        self.standa_cs.send_steps(steps, sub_steps).await
+        let steps = self.take_up_backlash(steps, sub_steps);
+
+        if self.inverted {
+            self.standa_cs.send_steps(-steps, -sub_steps).await
+        } else {
+            self.standa_cs.send_steps(steps, sub_steps).await
+        }
     }
 }
 
 impl Motor for SlitMotor {
     async fn position(&self) -> Result<f32, String> {
-        self.rf256_cs
+        let position = self
+            .rf256_cs
             .get_position(self.rf256_axis)
             .await
-            .map_err(|e| format!("Failed to read position: {}", e))
+            .map_err(|e| format!("Failed to read position: {}", e))?;
+
+        if let Some(recorder) = &self.recorder {
+            recorder.encoder(self.target_position, position);
+        }
+
+        Ok(position)
     }
 
     async fn state(&self) -> Result<impl MotorState, String> {
-        self.standa_cs
+        let state = self
+            .standa_cs
             .get_state()
             .await
-            .map(StandaState)
-            .map_err(|e| format!("Failed to get Standa state: {}", e))
+            .map_err(|e| format!("Failed to get Standa state: {}", e))?;
+
+        if let Some(recorder) = &self.recorder {
+            recorder.drive(self.target_position, &state);
+        }
+
+        Ok(StandaState(state, self.inverted))
     }
 
     async fn move_relative(&mut self, error: f32) -> Result<(), String> {
+        // The opposing knife may be correcting as well, so every step is
+        // checked, not just the target. A correction of -error mm is needed.
+        let (error, limited) = match &self.gap_limit {
+            Some(gap_limit) => {
+                let current = self.target_position + error;
+                let displacement = gap_limit.limit(current, -error, &self.rf256_cs).await?;
+                (-displacement, displacement != -error)
+            }
+            None => (error, false),
+        };
+
         let (steps, sub_steps) = if error.abs() == 0.0 {
             (0, 0)
         } else if error.abs() < 0.001 {
@@ -100,6 +208,10 @@ impl Motor for SlitMotor {
             ((error * self.steps_per_mm as f32) as i32, 0)
         };
 
+        if let Some(recorder) = &self.recorder {
+            recorder.correction(self.target_position, error, steps, sub_steps);
+        }
+
         let _result = self
             .send_steps(steps, sub_steps)
             .await
@@ -110,6 +222,22 @@ impl Motor for SlitMotor {
             tokio::time::sleep(Duration::from_millis(10)).await;
         }
 
+        // Out of time halfway through a correction, the blade is ramped down
+        // rather than halted
+        if self.is_moving() && self.is_time_limit_exceeded() && self.state().await?.is_moving() {
+            self.standa_cs
+                .stop(StopMode::Soft)
+                .await
+                .map_err(|e| format!("Failed to stop motor: {}", e))?;
+        }
+
+        if let (true, Some(gap_limit)) = (limited, &self.gap_limit) {
+            return Err(format!(
+                "Stopped at the minimum gap of {} mm",
+                gap_limit.min_gap()
+            ));
+        }
+
         Ok(())
     }
 
@@ -146,15 +274,23 @@ impl Motor for SlitMotor {
     }
 }
 
-struct StandaState(StateParams);
+// The flag is set for an inverted motor, whose right switch is at the start
+// of the axis
+struct StandaState(StateParams, bool);
 
 impl MotorState for StandaState {
     fn start_switch(&self) -> bool {
-        self.0.left_switch()
+        match self.1 {
+            false => self.0.left_switch(),
+            true => self.0.right_switch(),
+        }
     }
 
     fn end_switch(&self) -> bool {
-        self.0.right_switch()
+        match self.1 {
+            false => self.0.right_switch(),
+            true => self.0.left_switch(),
+        }
     }
 
     fn is_moving(&self) -> bool {
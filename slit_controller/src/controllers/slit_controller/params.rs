@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use motarem::axis::movement_parameters::MovementParams;
+use utilities::params::{custom_finite, custom_id, custom_seconds};
 
 #[derive(Debug)]
 pub struct MotorParameters {
@@ -9,6 +10,11 @@ pub struct MotorParameters {
     pub velocity: u32,
     pub position_window: f32,
     pub time_limit: Duration,
+    pub settle_time: Option<Duration>,
+    // Trace the closed loop of this move
+    pub record: bool,
+    // Id to report the outcome under, assigned by the axis if not given
+    pub move_id: Option<u64>,
 }
 
 impl Default for MotorParameters {
@@ -19,12 +25,17 @@ impl Default for MotorParameters {
             velocity: 1000,
             position_window: 0.001,
             time_limit: Duration::from_secs(60),
+            settle_time: None,
+            record: false,
+            move_id: None,
         }
     }
 }
 
-impl From<MovementParams> for MotorParameters {
-    fn from(value: MovementParams) -> Self {
+impl TryFrom<MovementParams> for MotorParameters {
+    type Error = String;
+
+    fn try_from(value: MovementParams) -> Result<Self, Self::Error> {
         let mut params = Self::default();
 
         if let Some(acceleration) = value.acceleration {
@@ -36,13 +47,21 @@ impl From<MovementParams> for MotorParameters {
         if let Some(velocity) = value.velocity {
             params.velocity = velocity as u32;
         }
-        if let Some(position_window) = value.custom.get("position_window") {
-            params.position_window = *position_window as f32;
+        if let Some(position_window) = custom_finite(&value, "position_window")? {
+            params.position_window = position_window as f32;
+        }
+        if let Some(time_limit) = custom_seconds(&value, "time_limit")? {
+            params.time_limit = time_limit;
         }
-        if let Some(time_limit) = value.custom.get("time_limit") {
-            params.time_limit = Duration::from_secs_f64(*time_limit);
+        if let Some(settle_time) = custom_seconds(&value, "settle_time")? {
+            params.settle_time = Some(settle_time);
         }
+        if let Some(record) = value.custom.get("record") {
+            params.record = *record != 0.0;
+        }
+
+        params.move_id = custom_id(&value, "move_id")?;
 
-        params
+        Ok(params)
     }
 }
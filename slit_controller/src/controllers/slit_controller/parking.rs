@@ -0,0 +1,380 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use motarem::axis::{
+    limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+    state_info::AxisStateInfo, Axis,
+};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const PARK_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ParkingConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Park once no axis has moved for this long
+    #[serde(default)]
+    pub idle_minutes: Option<u64>,
+    // Times of day in UTC, "HH:MM"
+    #[serde(default)]
+    pub times: Vec<String>,
+    // Axis name to park position, in the axis' own units
+    pub positions: HashMap<String, f64>,
+}
+
+// Minute of the day
+pub fn parse_time(time: &str) -> Result<u64, String> {
+    let (hours, minutes) = time
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid time \"{}\", expected HH:MM", time))?;
+
+    match (hours.trim().parse::<u64>(), minutes.trim().parse::<u64>()) {
+        (Ok(hours), Ok(minutes)) if hours < 24 && minutes < 60 => Ok(hours * 60 + minutes),
+        _ => Err(format!("Invalid time \"{}\", expected HH:MM", time)),
+    }
+}
+
+// Minutes since the epoch, UTC
+pub fn current_minute() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 60
+}
+
+// Every enable, disable and park ends up here, so the log shows who moved the
+// slits when nobody was at the beamline
+fn audit(message: &str) {
+    eprintln!("[parking] {}", message);
+}
+
+struct State {
+    last_activity: Instant,
+    // Set after parking and cleared by the next move, so an idle controller
+    // is parked once rather than every idle period
+    parked: bool,
+    last_scheduled: Option<u64>,
+    last_message: Option<String>,
+}
+
+struct Scheduler {
+    axes: Vec<(Arc<dyn Axis>, f64)>,
+    idle: Option<Duration>,
+    times: Vec<u64>,
+
+    enabled: Arc<AtomicBool>,
+    parking: Arc<AtomicBool>,
+    state: Arc<std::sync::Mutex<State>>,
+}
+
+impl Scheduler {
+    async fn any_moving(&self) -> bool {
+        for (axis, _) in &self.axes {
+            if let Ok(state) = axis.get_state().await {
+                if matches!(state.state, AxisState::Moving) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    // The reason for parking now, if any
+    fn due(&self) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        let minute = current_minute();
+
+        if self.times.contains(&(minute % (24 * 60))) && state.last_scheduled != Some(minute) {
+            state.last_scheduled = Some(minute);
+            return Some(format!(
+                "scheduled at {:02}:{:02} UTC",
+                minute % (24 * 60) / 60,
+                minute % 60
+            ));
+        }
+
+        match self.idle {
+            Some(idle) if !state.parked && state.last_activity.elapsed() >= idle => {
+                Some(format!("idle for {} minutes", idle.as_secs() / 60))
+            }
+            _ => None,
+        }
+    }
+
+    async fn wait_parked(&self) -> Result<(), String> {
+        let start = Instant::now();
+
+        while self.any_moving().await {
+            if start.elapsed() > PARK_TIMEOUT {
+                return Err("Timed out waiting for the axes to settle".to_string());
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    async fn park(&self, reason: &str) {
+        self.parking.store(true, Ordering::Relaxed);
+        audit(&format!("Parking ({})", reason));
+
+        let mut errors = Vec::new();
+        for (axis, position) in &self.axes {
+            match axis.start(*position, None).await {
+                Ok(()) => audit(&format!("{} -> {}", axis.name(), position)),
+                Err(e) => {
+                    audit(&format!("Failed to park {}: {}", axis.name(), e));
+                    errors.push(format!("{}: {}", axis.name(), e));
+                }
+            }
+        }
+
+        if let Err(e) = self.wait_parked().await {
+            audit(&e);
+            errors.push(e);
+        }
+
+        let message = if errors.is_empty() {
+            audit("Parked");
+            format!("Parked, {}", reason)
+        } else {
+            format!("Parking failed, {}", errors.join(", "))
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.parked = true;
+        state.last_activity = Instant::now();
+        state.last_message = Some(message);
+        self.parking.store(false, Ordering::Relaxed);
+    }
+
+    async fn run(self) {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            if self.any_moving().await {
+                let mut state = self.state.lock().unwrap();
+                state.last_activity = Instant::now();
+                state.parked = false;
+                continue;
+            }
+
+            if !self.enabled.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            if let Some(reason) = self.due() {
+                self.park(&reason).await;
+            }
+        }
+    }
+}
+
+// Moves the configured axes to their park positions at set times of day or
+// after a period of inactivity. Exposed as an axis for the admin interface:
+// the "enabled" custom parameter switches the scheduler on and off, any other
+// move parks right away.
+pub struct ParkingAxis {
+    pub name: String,
+
+    axes: Vec<(Arc<dyn Axis>, f64)>,
+
+    enabled: Arc<AtomicBool>,
+    parking: Arc<AtomicBool>,
+    state: Arc<std::sync::Mutex<State>>,
+    scheduler: std::sync::Mutex<Option<JoinHandle<()>>>,
+    manual: std::sync::Mutex<Option<JoinHandle<()>>>,
+    idle: Option<Duration>,
+    times: Vec<u64>,
+}
+
+impl ParkingAxis {
+    pub fn new(
+        name: String,
+        axes: Vec<Arc<dyn Axis>>,
+        config: &ParkingConfig,
+    ) -> Result<Self, String> {
+        let times = config
+            .times
+            .iter()
+            .map(|time| parse_time(time))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let axes = config
+            .positions
+            .iter()
+            .map(|(name, position)| {
+                axes.iter()
+                    .find(|axis| axis.name() == name)
+                    .map(|axis| (axis.clone(), *position))
+                    .ok_or_else(|| format!("Unknown parking axis: {}", name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let axis = Self {
+            name,
+            axes,
+            enabled: Arc::new(AtomicBool::new(config.enabled)),
+            parking: Arc::new(AtomicBool::new(false)),
+            state: Arc::new(std::sync::Mutex::new(State {
+                last_activity: Instant::now(),
+                parked: false,
+                last_scheduled: None,
+                last_message: None,
+            })),
+            scheduler: std::sync::Mutex::new(None),
+            manual: std::sync::Mutex::new(None),
+            idle: config
+                .idle_minutes
+                .map(|minutes| Duration::from_secs(minutes * 60)),
+            times,
+        };
+
+        *axis.scheduler.lock().unwrap() = Some(tokio::spawn(axis.scheduler().run()));
+        audit(&format!(
+            "Scheduler started, {}",
+            if config.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        ));
+
+        Ok(axis)
+    }
+
+    fn scheduler(&self) -> Scheduler {
+        Scheduler {
+            axes: self.axes.clone(),
+            idle: self.idle,
+            times: self.times.clone(),
+            enabled: self.enabled.clone(),
+            parking: self.parking.clone(),
+            state: self.state.clone(),
+        }
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            audit(if enabled {
+                "Scheduler enabled"
+            } else {
+                "Scheduler disabled"
+            });
+        }
+
+        // Counting idle time from now keeps a re-enabled scheduler from
+        // parking right away
+        self.state.lock().unwrap().last_activity = Instant::now();
+    }
+
+    pub fn shutdown(&self) {
+        for handle in [&self.scheduler, &self.manual] {
+            if let Some(handle) = handle.lock().unwrap().take() {
+                handle.abort();
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for ParkingAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(
+        &self,
+        _position: f64,
+        parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        if let Some(enabled) = parameters
+            .as_ref()
+            .and_then(|parameters| parameters.custom.get("enabled"))
+        {
+            self.set_enabled(*enabled != 0.0);
+            return Ok(());
+        }
+
+        if self.parking.load(Ordering::Relaxed) {
+            return Err(anyhow::Error::msg("Parking is already in progress"));
+        }
+
+        let scheduler = self.scheduler();
+        *self.manual.lock().unwrap() = Some(tokio::spawn(async move {
+            scheduler.park("requested").await;
+        }));
+
+        Ok(())
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        if let Some(handle) = self.manual.lock().unwrap().take() {
+            handle.abort();
+        }
+
+        if self.parking.swap(false, Ordering::Relaxed) {
+            audit("Parking aborted");
+        }
+
+        for (axis, _) in &self.axes {
+            axis.stop().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        let state = if self.parking.load(Ordering::Relaxed) {
+            AxisState::Moving
+        } else {
+            AxisState::On
+        };
+
+        Ok(AxisStateInfo {
+            state,
+            message: self.state.lock().unwrap().last_message.clone(),
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        let state = self.state.lock().unwrap();
+
+        match name {
+            "position" | "parked" => Ok(state.parked as u8 as f64),
+            "enabled" => Ok(self.enabled.load(Ordering::Relaxed) as u8 as f64),
+            "idle" => Ok(state.last_activity.elapsed().as_secs_f64()),
+            _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec![
+            "position".to_string(),
+            "parked".to_string(),
+            "enabled".to_string(),
+            "idle".to_string(),
+        ])
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec!["enabled".to_string()])
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use standa::command::state::StateParams;
+
+// Rows kept per axis, about a minute of a busy closed loop
+const CAPACITY: usize = 20_000;
+
+enum Event {
+    Encoder {
+        position: f32,
+    },
+    Correction {
+        error: f32,
+        steps: i32,
+        sub_steps: i16,
+    },
+    Drive {
+        position: i32,
+        sub_position: i16,
+        speed: i32,
+        running: bool,
+    },
+}
+
+struct Sample {
+    // Seconds since the recording started
+    time: f64,
+    target: f32,
+    event: Event,
+}
+
+// Closed-loop trace of the last recorded move: encoder reads, the
+// corrections sent and the drive status while they run. Only moves started
+// with the "record" parameter are traced, the previous trace is dropped then.
+pub struct MotionRecorder {
+    recording: AtomicBool,
+    start: Mutex<Instant>,
+    samples: Mutex<VecDeque<Sample>>,
+}
+
+impl Default for MotionRecorder {
+    fn default() -> Self {
+        Self {
+            recording: AtomicBool::new(false),
+            start: Mutex::new(Instant::now()),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl MotionRecorder {
+    pub fn start(&self) {
+        self.samples.lock().unwrap().clear();
+        *self.start.lock().unwrap() = Instant::now();
+        self.recording.store(true, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self) {
+        self.recording.store(false, Ordering::Relaxed);
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples.lock().unwrap().len()
+    }
+
+    fn record(&self, target: f32, event: Event) {
+        if !self.recording.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let time = self.start.lock().unwrap().elapsed().as_secs_f64();
+
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() >= CAPACITY {
+            samples.pop_front();
+        }
+        samples.push_back(Sample {
+            time,
+            target,
+            event,
+        });
+    }
+
+    pub fn encoder(&self, target: f32, position: f32) {
+        self.record(target, Event::Encoder { position });
+    }
+
+    pub fn correction(&self, target: f32, error: f32, steps: i32, sub_steps: i16) {
+        self.record(
+            target,
+            Event::Correction {
+                error,
+                steps,
+                sub_steps,
+            },
+        );
+    }
+
+    pub fn drive(&self, target: f32, state: &StateParams) {
+        self.record(
+            target,
+            Event::Drive {
+                position: state.cur_position,
+                sub_position: state.u_cur_position,
+                speed: state.cur_speed,
+                running: state.is_moving(),
+            },
+        );
+    }
+
+    // One row per event, columns that do not apply to it stay empty
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "time_s,event,target_mm,encoder_mm,error_mm,steps,sub_steps,drive_steps,drive_sub_steps,drive_speed,running\n",
+        );
+
+        for sample in self.samples.lock().unwrap().iter() {
+            let _ = write!(csv, "{:.6},", sample.time);
+            let _ = match &sample.event {
+                Event::Encoder { position } => {
+                    writeln!(csv, "encoder,{},{},,,,,,,", sample.target, position)
+                }
+                Event::Correction {
+                    error,
+                    steps,
+                    sub_steps,
+                } => writeln!(
+                    csv,
+                    "correction,{},,{},{},{},,,,",
+                    sample.target, error, steps, sub_steps
+                ),
+                Event::Drive {
+                    position,
+                    sub_position,
+                    speed,
+                    running,
+                } => writeln!(
+                    csv,
+                    "drive,{},,,,,{},{},{},{}",
+                    sample.target, position, sub_position, speed, *running as u8
+                ),
+            };
+        }
+
+        csv
+    }
+}
@@ -0,0 +1,636 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use motarem::axis::{
+    limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+    state_info::AxisStateInfo, Axis,
+};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use utilities::progress::ActionProgress;
+
+use crate::{
+    command_executor::{
+        encoder::command_sender::EncoderCommandSender, motor::command_sender::StandaCommandSender,
+    },
+    controllers::slit_controller::{
+        axis::SlitAxis,
+        calibration::wait_for_stop,
+        parking::{current_minute, parse_time},
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_distance() -> f32 {
+    0.1
+}
+
+fn default_settle_time_ms() -> u64 {
+    200
+}
+
+fn default_samples() -> usize {
+    5
+}
+
+fn default_response_tolerance() -> f32 {
+    0.1
+}
+
+fn default_max_return_error() -> f32 {
+    0.005
+}
+
+fn default_window_minutes() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SelfCheckConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Time of day in UTC, "HH:MM"
+    pub time: String,
+    // Axes busy or reserved at that time are checked once they are free,
+    // for this long
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u64,
+    // All blades when empty
+    #[serde(default)]
+    pub axes: Vec<String>,
+    // Open loop move in mm out and back. Blades with a gap limit open first
+    // whatever the sign.
+    #[serde(default = "default_distance")]
+    pub distance: f32,
+    #[serde(default = "default_settle_time_ms")]
+    pub settle_time_ms: u64,
+    // Encoder reads averaged per point
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+    // Encoder travel over commanded travel may be off 1 by this much
+    #[serde(default = "default_response_tolerance")]
+    pub response_tolerance: f32,
+    // Distance in mm the blade may end up from where it started
+    #[serde(default = "default_max_return_error")]
+    pub max_return_error: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfCheckResult {
+    // Encoder travel over commanded travel, each way
+    pub forward_response: f32,
+    pub reverse_response: f32,
+    // Encoder mm between the start and the end of the check
+    pub return_error: f32,
+}
+
+impl SelfCheckResult {
+    // Why the mechanics look worse than they should, if they do
+    fn degradation(&self, config: &SelfCheckConfig) -> Option<String> {
+        let mut problems = Vec::new();
+
+        for (direction, response) in [
+            ("forward", self.forward_response),
+            ("reverse", self.reverse_response),
+        ] {
+            if (response - 1.0).abs() > config.response_tolerance {
+                problems.push(format!("{} response {:.3}", direction, response));
+            }
+        }
+
+        if self.return_error.abs() > config.max_return_error {
+            problems.push(format!("return error {:.4} mm", self.return_error));
+        }
+
+        (!problems.is_empty()).then(|| problems.join(", "))
+    }
+}
+
+// Moves a blade a short way by steps alone and back, and compares what the
+// encoder saw. A slipping coupling, a sticking guide or a failing motor
+// show up as a response below 1 or a blade that does not come back.
+pub struct SelfCheck {
+    rf256_cs: EncoderCommandSender,
+    standa_cs: StandaCommandSender,
+    axis: u8,
+
+    distance: f32,
+    settle_time: Duration,
+    samples: usize,
+    steps_per_mm: i32,
+    inverted: bool,
+}
+
+impl SelfCheck {
+    pub fn new(
+        rf256_cs: EncoderCommandSender,
+        standa_cs: StandaCommandSender,
+        axis: u8,
+        config: &SelfCheckConfig,
+        steps_per_mm: i32,
+    ) -> Self {
+        Self {
+            rf256_cs,
+            standa_cs,
+            axis,
+            distance: config.distance,
+            settle_time: Duration::from_millis(config.settle_time_ms),
+            samples: config.samples,
+            steps_per_mm,
+            inverted: false,
+        }
+    }
+
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    // Encoder mm, positive towards the positive side of the gap
+    pub fn with_distance(mut self, distance: f32) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    pub async fn run(&self, progress: &ActionProgress) -> Result<SelfCheckResult, String> {
+        let steps = (self.distance * self.steps_per_mm as f32) as i32;
+        if steps == 0 {
+            return Err("Self-check distance is below one step".to_string());
+        }
+        let commanded = steps as f32 / self.steps_per_mm as f32;
+
+        progress.stage("reading start", Some(0.0));
+        let start = self.read_position().await?;
+
+        progress.stage("moving out", Some(0.25));
+        self.move_steps(steps, progress).await?;
+        let out = self.read_position().await?;
+
+        progress.stage("moving back", Some(0.6));
+        self.move_steps(-steps, progress).await?;
+        let back = self.read_position().await?;
+
+        Ok(SelfCheckResult {
+            forward_response: (out - start) / commanded,
+            reverse_response: (out - back) / commanded,
+            return_error: back - start,
+        })
+    }
+
+    async fn move_steps(&self, steps: i32, progress: &ActionProgress) -> Result<(), String> {
+        self.standa_cs
+            .send_steps(if self.inverted { -steps } else { steps }, 0)
+            .await
+            .map_err(|e| format!("Failed to send steps: {}", e))?;
+        wait_for_stop(&self.standa_cs).await?;
+
+        // A stop by a client fails the action
+        if !progress.is_running() {
+            return Err("Stopped".to_string());
+        }
+
+        tokio::time::sleep(self.settle_time).await;
+
+        Ok(())
+    }
+
+    async fn read_position(&self) -> Result<f32, String> {
+        let count = self.samples.max(1);
+        let mut sum = 0.0;
+
+        for _ in 0..count {
+            sum += self
+                .rf256_cs
+                .get_position(self.axis)
+                .await
+                .map_err(|e| format!("Failed to read position: {}", e))?;
+        }
+
+        Ok(sum / count as f32)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Outcome {
+    Passed(SelfCheckResult),
+    Degraded(SelfCheckResult, String),
+    Failed(String),
+    Skipped(String),
+}
+
+impl Outcome {
+    fn is_alarm(&self) -> bool {
+        matches!(self, Outcome::Degraded(..) | Outcome::Failed(_))
+    }
+
+    fn result(&self) -> Option<&SelfCheckResult> {
+        match self {
+            Outcome::Passed(result) | Outcome::Degraded(result, _) => Some(result),
+            _ => None,
+        }
+    }
+}
+
+fn audit(message: &str) {
+    eprintln!("[self-check] {}", message);
+}
+
+#[derive(Default)]
+struct State {
+    last_scheduled: Option<u64>,
+    // Axes still to check in the current run, and until which minute
+    pending: Vec<Arc<SlitAxis>>,
+    deadline: u64,
+    // Seconds since the epoch
+    last_run: Option<u64>,
+    outcomes: Vec<(String, Outcome)>,
+}
+
+impl State {
+    fn record(&mut self, name: &str, outcome: Outcome) {
+        self.outcomes.retain(|(axis, _)| axis != name);
+        self.outcomes.push((name.to_string(), outcome));
+    }
+
+    fn alarms(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| outcome.is_alarm())
+            .count()
+    }
+}
+
+struct Scheduler {
+    axes: Vec<Arc<SlitAxis>>,
+    config: SelfCheckConfig,
+    time: u64,
+
+    enabled: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    state: Arc<std::sync::Mutex<State>>,
+}
+
+impl Scheduler {
+    fn begin(&self, reason: &str) {
+        audit(&format!("Starting ({})", reason));
+
+        let mut state = self.state.lock().unwrap();
+        state.pending = self.axes.clone();
+        state.deadline = current_minute() + self.config.window_minutes;
+        state.outcomes.clear();
+        state.last_run = Some(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        );
+    }
+
+    fn due(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let minute = current_minute();
+
+        if minute % (24 * 60) == self.time && state.last_scheduled != Some(minute) {
+            state.last_scheduled = Some(minute);
+            return true;
+        }
+
+        false
+    }
+
+    async fn check(&self, axis: &SlitAxis) -> Outcome {
+        match axis.self_check(&self.config).await {
+            Ok(result) => match result.degradation(&self.config) {
+                Some(problems) => {
+                    audit(&format!("{}: ALARM, {}", axis.name, problems));
+                    Outcome::Degraded(result, problems)
+                }
+                None => {
+                    audit(&format!(
+                        "{}: passed, response {:.3}/{:.3}, return error {:.4} mm",
+                        axis.name,
+                        result.forward_response,
+                        result.reverse_response,
+                        result.return_error
+                    ));
+                    Outcome::Passed(result)
+                }
+            },
+            // Not the mechanics' fault
+            Err(e) if !axis.is_self_checking() && e == "Stopped" => {
+                audit(&format!("{}: stopped", axis.name));
+                Outcome::Skipped("stopped".to_string())
+            }
+            Err(e) => {
+                audit(&format!("{}: ALARM, check failed: {}", axis.name, e));
+                Outcome::Failed(e)
+            }
+        }
+    }
+
+    // Checks the pending axes that are free now, one at a time. Taking them
+    // off the list one by one lets a stop drop the rest of the run.
+    async fn check_pending(&self) {
+        loop {
+            let axis = {
+                let mut state = self.state.lock().unwrap();
+                if state.pending.is_empty() {
+                    return;
+                }
+
+                if current_minute() >= state.deadline {
+                    for axis in std::mem::take(&mut state.pending) {
+                        audit(&format!("{}: skipped, busy or reserved", axis.name));
+                        state.record(&axis.name, Outcome::Skipped("busy or reserved".to_string()));
+                    }
+                    audit(&format!("Done, {} alarms", state.alarms()));
+                    return;
+                }
+
+                match state.pending.iter().position(|axis| axis.is_idle()) {
+                    Some(index) => {
+                        self.running.store(true, Ordering::Relaxed);
+                        state.pending.remove(index)
+                    }
+                    None => return,
+                }
+            };
+
+            let outcome = self.check(&axis).await;
+
+            let mut state = self.state.lock().unwrap();
+            state.record(&axis.name, outcome);
+            self.running.store(false, Ordering::Relaxed);
+            if state.pending.is_empty() {
+                audit(&format!("Done, {} alarms", state.alarms()));
+            }
+        }
+    }
+
+    async fn run(self) {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            if self.enabled.load(Ordering::Relaxed) && self.due() {
+                self.begin(&format!("scheduled at {}", self.config.time));
+            }
+
+            self.check_pending().await;
+        }
+    }
+}
+
+// Nightly check of the blade mechanics, so slow failures are caught before
+// beamtime. Exposed as an axis: any move starts a check right away, the
+// "enabled" custom parameter switches the schedule on and off. The state
+// message lists the alarms of the last run, "alarms" counts them and
+// "<axis>.alarm", "<axis>.forward_response", "<axis>.reverse_response" and
+// "<axis>.return_error" give the details.
+pub struct SelfCheckAxis {
+    pub name: String,
+
+    axes: Vec<Arc<SlitAxis>>,
+    config: SelfCheckConfig,
+    time: u64,
+
+    enabled: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    state: Arc<std::sync::Mutex<State>>,
+    scheduler: std::sync::Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SelfCheckAxis {
+    pub fn new(
+        name: String,
+        axes: Vec<Arc<SlitAxis>>,
+        config: &SelfCheckConfig,
+    ) -> Result<Self, String> {
+        let time = parse_time(&config.time)?;
+
+        let axes = if config.axes.is_empty() {
+            axes
+        } else {
+            config
+                .axes
+                .iter()
+                .map(|name| {
+                    axes.iter()
+                        .find(|axis| axis.name == *name)
+                        .cloned()
+                        .ok_or_else(|| format!("Unknown self-check axis: {}", name))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let axis = Self {
+            name,
+            axes,
+            config: config.clone(),
+            time,
+            enabled: Arc::new(AtomicBool::new(config.enabled)),
+            running: Arc::new(AtomicBool::new(false)),
+            state: Arc::new(std::sync::Mutex::new(State::default())),
+            scheduler: std::sync::Mutex::new(None),
+        };
+
+        *axis.scheduler.lock().unwrap() = Some(tokio::spawn(axis.scheduler().run()));
+
+        Ok(axis)
+    }
+
+    fn scheduler(&self) -> Scheduler {
+        Scheduler {
+            axes: self.axes.clone(),
+            config: self.config.clone(),
+            time: self.time,
+            enabled: self.enabled.clone(),
+            running: self.running.clone(),
+            state: self.state.clone(),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.running.load(Ordering::Relaxed) || !self.state.lock().unwrap().pending.is_empty()
+    }
+
+    pub fn shutdown(&self) {
+        if let Some(handle) = self.scheduler.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for SelfCheckAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(
+        &self,
+        _position: f64,
+        parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        if let Some(enabled) = parameters
+            .as_ref()
+            .and_then(|parameters| parameters.custom.get("enabled"))
+        {
+            let enabled = *enabled != 0.0;
+            if self.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+                audit(if enabled {
+                    "Schedule enabled"
+                } else {
+                    "Schedule disabled"
+                });
+            }
+            return Ok(());
+        }
+
+        if self.is_active() {
+            return Err(anyhow::Error::msg("Self-check is already in progress"));
+        }
+
+        // Picked up by the scheduler on its next poll
+        self.scheduler().begin("requested");
+
+        Ok(())
+    }
+
+    // Drops the rest of the run and stops the blade being checked
+    async fn stop(&self) -> anyhow::Result<()> {
+        let pending = std::mem::take(&mut self.state.lock().unwrap().pending);
+        if !pending.is_empty() || self.running.load(Ordering::Relaxed) {
+            audit("Aborted");
+        }
+
+        for axis in &self.axes {
+            if axis.is_self_checking() {
+                Axis::stop(axis.as_ref()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        let state = if self.is_active() {
+            AxisState::Moving
+        } else {
+            AxisState::On
+        };
+
+        let check = self.state.lock().unwrap();
+        let outcomes = &check.outcomes;
+        let alarms: Vec<String> = outcomes
+            .iter()
+            .filter_map(|(axis, outcome)| match outcome {
+                Outcome::Degraded(_, problems) => Some(format!("{}: {}", axis, problems)),
+                Outcome::Failed(e) => Some(format!("{}: check failed, {}", axis, e)),
+                _ => None,
+            })
+            .collect();
+        let skipped: Vec<String> = outcomes
+            .iter()
+            .filter_map(|(axis, outcome)| match outcome {
+                Outcome::Skipped(reason) => Some(format!("{} skipped, {}", axis, reason)),
+                _ => None,
+            })
+            .collect();
+
+        let message = match (alarms.is_empty(), skipped.is_empty()) {
+            (true, true) => None,
+            (true, false) => Some(skipped.join("; ")),
+            (false, _) => Some(format!(
+                "Degraded: {}",
+                alarms
+                    .into_iter()
+                    .chain(skipped)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )),
+        };
+
+        Ok(AxisStateInfo {
+            state,
+            message,
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        let state = self.state.lock().unwrap();
+
+        match name {
+            "position" | "alarms" => Ok(state.alarms() as f64),
+            "enabled" => Ok(self.enabled.load(Ordering::Relaxed) as u8 as f64),
+            "running" => Ok(
+                (self.running.load(Ordering::Relaxed) || !state.pending.is_empty()) as u8 as f64,
+            ),
+            "last_run" => state
+                .last_run
+                .map(|time| time as f64)
+                .ok_or_else(|| anyhow::Error::msg("No self-check yet")),
+            _ => {
+                let (axis, attribute) = name
+                    .split_once('.')
+                    .ok_or_else(|| anyhow::Error::msg(format!("Unknown attribute: {}", name)))?;
+                let outcome = state
+                    .outcomes
+                    .iter()
+                    .find(|(name, _)| name == axis)
+                    .map(|(_, outcome)| outcome)
+                    .ok_or_else(|| anyhow::Error::msg(format!("No self-check of {} yet", axis)))?;
+
+                let result = || {
+                    outcome.result().ok_or_else(|| {
+                        anyhow::Error::msg(format!("Last self-check of {} did not finish", axis))
+                    })
+                };
+
+                match attribute {
+                    "alarm" => Ok(outcome.is_alarm() as u8 as f64),
+                    "forward_response" => Ok(result()?.forward_response as f64),
+                    "reverse_response" => Ok(result()?.reverse_response as f64),
+                    "return_error" => Ok(result()?.return_error as f64),
+                    _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
+                }
+            }
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params = vec![
+            "position".to_string(),
+            "alarms".to_string(),
+            "enabled".to_string(),
+            "running".to_string(),
+            "last_run".to_string(),
+        ];
+        for axis in &self.axes {
+            for attribute in [
+                "alarm",
+                "forward_response",
+                "reverse_response",
+                "return_error",
+            ] {
+                params.push(format!("{}.{}", axis.name, attribute));
+            }
+        }
+
+        Ok(params)
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec!["enabled".to_string()])
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use motarem::axis::{
+    limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+    state_info::AxisStateInfo, Axis,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::Mutex, task::JoinHandle};
+use utilities::expression::Expression;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SequenceStep {
+    Move {
+        axis: String,
+        position: f64,
+    },
+    WaitInPosition {
+        axis: String,
+        timeout_ms: u64,
+    },
+    Wait {
+        duration_ms: u64,
+    },
+    // e.g. "axis0.in_position && axis1.temperature < 35"
+    WaitUntil {
+        condition: String,
+        timeout_ms: u64,
+    },
+    SetParam {
+        axis: String,
+        name: String,
+        value: f64,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SequenceConfig {
+    pub name: String,
+    pub steps: Vec<SequenceStep>,
+}
+
+// A configured sequence with its axes looked up and its conditions parsed
+enum Step {
+    Move {
+        axis: Arc<dyn Axis>,
+        position: f64,
+    },
+    WaitInPosition {
+        axis: Arc<dyn Axis>,
+        timeout: Duration,
+    },
+    Wait {
+        duration: Duration,
+    },
+    WaitUntil {
+        condition: Expression,
+        text: String,
+        timeout: Duration,
+    },
+    SetParam {
+        axis: Arc<dyn Axis>,
+        name: String,
+        value: f64,
+    },
+}
+
+struct Sequence {
+    name: String,
+    steps: Vec<Step>,
+}
+
+fn find_axis(axes: &[Arc<dyn Axis>], name: &str) -> Result<Arc<dyn Axis>, String> {
+    axes.iter()
+        .find(|axis| axis.name() == name)
+        .cloned()
+        .ok_or_else(|| format!("Unknown axis: {}", name))
+}
+
+impl Step {
+    fn compile(step: &SequenceStep, axes: &[Arc<dyn Axis>]) -> Result<Self, String> {
+        Ok(match step {
+            SequenceStep::Move { axis, position } => Step::Move {
+                axis: find_axis(axes, axis)?,
+                position: *position,
+            },
+            SequenceStep::WaitInPosition { axis, timeout_ms } => Step::WaitInPosition {
+                axis: find_axis(axes, axis)?,
+                timeout: Duration::from_millis(*timeout_ms),
+            },
+            SequenceStep::Wait { duration_ms } => Step::Wait {
+                duration: Duration::from_millis(*duration_ms),
+            },
+            SequenceStep::WaitUntil {
+                condition,
+                timeout_ms,
+            } => {
+                let parsed = Expression::parse(condition)
+                    .and_then(|parsed| parsed.check_axes(axes).map(|_| parsed))
+                    .map_err(|e| format!("Invalid condition \"{}\": {}", condition, e))?;
+
+                Step::WaitUntil {
+                    condition: parsed,
+                    text: condition.clone(),
+                    timeout: Duration::from_millis(*timeout_ms),
+                }
+            }
+            SequenceStep::SetParam { axis, name, value } => Step::SetParam {
+                axis: find_axis(axes, axis)?,
+                name: name.clone(),
+                value: *value,
+            },
+        })
+    }
+}
+
+impl Sequence {
+    fn compile(config: &SequenceConfig, axes: &[Arc<dyn Axis>]) -> Result<Arc<Self>, String> {
+        let steps = config
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                Step::compile(step, axes)
+                    .map_err(|e| format!("Sequence {}, step {}: {}", config.name, index, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Arc::new(Self {
+            name: config.name.clone(),
+            steps,
+        }))
+    }
+}
+
+struct SequenceRunner {
+    axes: Vec<Arc<dyn Axis>>,
+    abort: Arc<AtomicBool>,
+    current_step: Arc<AtomicUsize>,
+}
+
+impl SequenceRunner {
+    fn check_abort(&self) -> Result<(), String> {
+        if self.abort.load(Ordering::Relaxed) {
+            Err("Sequence aborted".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn sleep(&self, duration: Duration) -> Result<(), String> {
+        let start = Instant::now();
+
+        while start.elapsed() < duration {
+            self.check_abort()?;
+            tokio::time::sleep(POLL_INTERVAL.min(duration - start.elapsed())).await;
+        }
+
+        self.check_abort()
+    }
+
+    async fn wait_in_position(
+        &self,
+        axis: &Arc<dyn Axis>,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let start = Instant::now();
+
+        loop {
+            self.check_abort()?;
+
+            let state = axis
+                .get_state()
+                .await
+                .map_err(|e| format!("Failed to get state of {}: {}", axis.name(), e))?;
+
+            if !matches!(state.state, AxisState::Moving) {
+                return Ok(());
+            }
+
+            if start.elapsed() > timeout {
+                return Err(format!("Timed out waiting for {} to settle", axis.name()));
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn wait_until(
+        &self,
+        condition: &Expression,
+        text: &str,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let start = Instant::now();
+
+        loop {
+            self.check_abort()?;
+
+            if condition.check(&self.axes).await? {
+                return Ok(());
+            }
+
+            if start.elapsed() > timeout {
+                return Err(format!("Timed out waiting for {}", text));
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    fn movement_params(params: Option<&HashMap<String, f64>>) -> MovementParams {
+        let mut movement_params = MovementParams::default();
+
+        for (name, value) in params.into_iter().flatten() {
+            match name.as_str() {
+                "velocity" => movement_params.velocity = Some(*value),
+                "acceleration" => movement_params.acceleration = Some(*value),
+                "deceleration" => movement_params.deceleration = Some(*value),
+                _ => {
+                    movement_params.custom.insert(name.clone(), *value);
+                }
+            }
+        }
+
+        movement_params
+    }
+
+    async fn run(&self, steps: &[Step]) -> Result<(), String> {
+        let mut params: HashMap<String, HashMap<String, f64>> = HashMap::new();
+
+        for (index, step) in steps.iter().enumerate() {
+            self.check_abort()?;
+            self.current_step.store(index, Ordering::Relaxed);
+
+            match step {
+                Step::Move { axis, position } => {
+                    let movement_params = Self::movement_params(params.get(axis.name()));
+
+                    axis.start(*position, Some(movement_params))
+                        .await
+                        .map_err(|e| format!("Failed to move {}: {}", axis.name(), e))?;
+                }
+                Step::WaitInPosition { axis, timeout } => {
+                    self.wait_in_position(axis, *timeout).await?;
+                }
+                Step::Wait { duration } => {
+                    self.sleep(*duration).await?;
+                }
+                Step::WaitUntil {
+                    condition,
+                    text,
+                    timeout,
+                } => {
+                    self.wait_until(condition, text, *timeout).await?;
+                }
+                Step::SetParam { axis, name, value } => {
+                    params
+                        .entry(axis.name().to_string())
+                        .or_default()
+                        .insert(name.clone(), *value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn stop_all(&self) {
+        for axis in &self.axes {
+            if let Err(e) = axis.stop().await {
+                eprintln!("Failed to stop {}: {}", axis.name(), e);
+            }
+        }
+    }
+}
+
+// Exposes server-side sequences through the regular axis interface: moving
+// this axis to N runs the N-th configured sequence, stopping it aborts the
+// sequence and stops every axis it drives.
+pub struct SequenceAxis {
+    pub name: String,
+
+    axes: Vec<Arc<dyn Axis>>,
+    sequences: Vec<Arc<Sequence>>,
+
+    run_thread: Arc<Mutex<Option<JoinHandle<Result<(), String>>>>>,
+    abort: Arc<AtomicBool>,
+    current_sequence: Arc<AtomicUsize>,
+    current_step: Arc<AtomicUsize>,
+    last_error: Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl SequenceAxis {
+    // Fails on unknown axes and conditions that do not parse, before any
+    // sequence can run
+    pub fn new(
+        name: String,
+        axes: Vec<Arc<dyn Axis>>,
+        sequences: &[SequenceConfig],
+    ) -> Result<Self, String> {
+        let sequences = sequences
+            .iter()
+            .map(|sequence| Sequence::compile(sequence, &axes))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            name,
+            axes,
+            sequences,
+            run_thread: Arc::new(Mutex::new(None)),
+            abort: Arc::new(AtomicBool::new(false)),
+            current_sequence: Arc::new(AtomicUsize::new(0)),
+            current_step: Arc::new(AtomicUsize::new(0)),
+            last_error: Arc::new(std::sync::Mutex::new(None)),
+        })
+    }
+
+    async fn is_running(&self) -> bool {
+        self.run_thread
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for SequenceAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(
+        &self,
+        position: f64,
+        _parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        let mut run_thread = self.run_thread.lock().await;
+
+        if run_thread
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return Err(anyhow::Error::msg("A sequence is already running"));
+        }
+
+        let (index, sequence) = (position >= 0.0 && position.fract() == 0.0)
+            .then(|| position as usize)
+            .and_then(|index| Some((index, self.sequences.get(index)?.clone())))
+            .ok_or_else(|| anyhow::Error::msg(format!("Unknown sequence: {}", position)))?;
+
+        self.abort.store(false, Ordering::Relaxed);
+        self.current_sequence.store(index, Ordering::Relaxed);
+        self.current_step.store(0, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = None;
+
+        let runner = SequenceRunner {
+            axes: self.axes.clone(),
+            abort: self.abort.clone(),
+            current_step: self.current_step.clone(),
+        };
+        let last_error = self.last_error.clone();
+
+        *run_thread = Some(tokio::spawn(async move {
+            let result = runner.run(&sequence.steps).await;
+
+            if let Err(e) = &result {
+                runner.stop_all().await;
+                *last_error.lock().unwrap() = Some(format!("{}: {}", sequence.name, e));
+            }
+
+            result
+        }));
+
+        Ok(())
+    }
+
+    // Axes moved by clients are left alone when no sequence runs
+    async fn stop(&self) -> anyhow::Result<()> {
+        let mut run_thread = self.run_thread.lock().await;
+        let Some(handle) = run_thread.take() else {
+            return Ok(());
+        };
+
+        if handle.is_finished() {
+            return Ok(());
+        }
+
+        self.abort.store(true, Ordering::Relaxed);
+
+        let mut errors = Vec::new();
+        for axis in &self.axes {
+            if let Err(e) = axis.stop().await {
+                errors.push(format!("{}: {}", axis.name(), e));
+            }
+        }
+
+        let _ = handle.await;
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(anyhow::Error::msg(format!(
+                "Failed to stop {}",
+                errors.join(", ")
+            ))),
+        }
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        let state = if self.is_running().await {
+            AxisState::Moving
+        } else {
+            AxisState::On
+        };
+
+        Ok(AxisStateInfo {
+            state,
+            message: self.last_error.lock().unwrap().clone(),
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        match name {
+            "position" | "sequence" => Ok(self.current_sequence.load(Ordering::Relaxed) as f64),
+            "step" => Ok(self.current_step.load(Ordering::Relaxed) as f64),
+            "count" => Ok(self.sequences.len() as f64),
+            _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec![
+            "position".to_string(),
+            "sequence".to_string(),
+            "step".to_string(),
+            "count".to_string(),
+        ])
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,106 @@
+use utilities::snapshot::ParameterSnapshot;
+
+use crate::{
+    command_executor::{
+        encoder::command_sender::EncoderCommandSender, motor::command_sender::StandaCommandSender,
+    },
+    controllers::slit_controller::{config::SlitAxisConfig, params::MotorParameters},
+};
+
+pub async fn read_axis_parameters(
+    name: &str,
+    axis: u8,
+    rf256_cs: &EncoderCommandSender,
+    standa_cs: &StandaCommandSender,
+    config: &SlitAxisConfig,
+    snapshot: &mut ParameterSnapshot,
+) {
+    let expected = &config.expected;
+    let defaults = MotorParameters::default();
+    let device = format!("{} Standa", name);
+
+    match standa_cs.get_move_parameters().await {
+        Ok(parameters) => {
+            // Copied out, the struct is packed
+            let (speed, accel, decel) = (parameters.speed, parameters.accel, parameters.decel);
+
+            snapshot.record(
+                &device,
+                "velocity",
+                Some(expected.velocity.unwrap_or(defaults.velocity) as f64),
+                speed as f64,
+            );
+            snapshot.record(
+                &device,
+                "acceleration",
+                Some(expected.acceleration.unwrap_or(defaults.acceleration) as f64),
+                accel as f64,
+            );
+            snapshot.record(
+                &device,
+                "deceleration",
+                Some(expected.deceleration.unwrap_or(defaults.deceleration) as f64),
+                decel as f64,
+            );
+        }
+        Err(e) => snapshot.record_error(&device, "move_parameters", e),
+    }
+
+    match standa_cs.get_engine_settings().await {
+        Ok(settings) => {
+            let (steps_per_rev, microstep_mode) = (settings.steps_per_rev, settings.microstep_mode);
+
+            snapshot.record(
+                &device,
+                "microstep_mode",
+                expected.microstep_mode.map(|mode| mode as f64),
+                microstep_mode as f64,
+            );
+            snapshot.record(
+                &device,
+                "steps_per_rev",
+                expected
+                    .mm_per_rev
+                    .map(|mm_per_rev| config.steps_per_mm as f64 * mm_per_rev as f64),
+                steps_per_rev as f64,
+            );
+
+            // What the motor makes of the configured scale
+            if let Some(mm_per_rev) = expected.mm_per_rev {
+                snapshot.record(
+                    &device,
+                    "steps_per_mm",
+                    Some(config.steps_per_mm as f64),
+                    steps_per_rev as f64 / mm_per_rev as f64,
+                );
+            }
+        }
+        Err(e) => snapshot.record_error(&device, "engine_settings", e),
+    }
+
+    let device = format!("{} RF256", name);
+
+    match rf256_cs.get_settings(axis).await {
+        Ok(settings) => {
+            snapshot.record(
+                &device,
+                "id",
+                Some(config.rf256_id as f64),
+                settings.id as f64,
+            );
+            snapshot.record(
+                &device,
+                "baudrate",
+                expected.rf256_baudrate.map(|baudrate| baudrate as f64),
+                settings.baudrate as f64,
+            );
+            snapshot.record(
+                &device,
+                "sample_period_ms",
+                expected.rf256_sample_period.map(|period| period as f64),
+                settings.sample_period as f64,
+            );
+        }
+        Err(e) => snapshot.record_error(&device, "settings", e),
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
-    config::{create_default_config, init_config},
-    controllers::slit_controller::create_controller,
+    config::{create_default_config, init_config, ConfigManager},
+    controllers::slit_controller::{
+        calibration::StoredCalibration, config::SlitControllerConfig, controller::SlitController,
+        create_controller, scan_trid_bus,
+    },
 };
 
 use motarem::{
@@ -8,34 +11,269 @@ use motarem::{
     motor_controller::MotorController,
     socket_server::{config::SocketServerConfig, SocketServer},
 };
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{io::Write as _, path::PathBuf, sync::Arc, time::Duration};
+use tokio::io::{AsyncBufReadExt as _, BufReader};
+use utilities::{
+    archiver::start_archiver, build_info, build_info::with_build_info,
+    derived::with_derived_attributes, groups::with_axis_groups, observer::start_observer_socket,
+    precision::with_precision, presets::with_presets, readiness::wait_until_ready,
+    snapshot::ParameterSnapshot, systemd,
+};
 
 pub mod command_executor;
 pub mod config;
 pub mod controllers;
 pub mod logging;
 
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
 fn should_create_config() -> bool {
     std::env::var("CREATE_CONFIG")
         .map(|val| val == "1" || val.to_lowercase() == "true")
         .unwrap_or(false)
 }
 
+fn calibration_axis() -> Option<String> {
+    std::env::var("CALIBRATE")
+        .ok()
+        .filter(|axis| !axis.is_empty())
+}
+
+fn should_take_snapshot() -> bool {
+    std::env::var("SNAPSHOT")
+        .map(|val| val == "1" || val.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
+fn should_restore_calibration() -> bool {
+    std::env::var("RESTORE_CALIBRATION")
+        .map(|val| val == "1" || val.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
+fn should_scan_trid_bus() -> bool {
+    std::env::var("SCAN_TRID")
+        .map(|val| val == "1" || val.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
+fn print_trid_scan(config: &SlitControllerConfig) {
+    println!(
+        "Scanning the TRID bus at {}:{}...",
+        config.trid_ip, config.trid_port
+    );
+
+    let ids = scan_trid_bus(config);
+    if ids.is_empty() {
+        println!("No TRID units answered");
+    } else {
+        println!("TRID units answering: {:?}", ids);
+    }
+
+    if !ids.contains(&config.trid_device_id) {
+        println!(
+            "The configured device ID {} did not answer",
+            config.trid_device_id
+        );
+    }
+}
+
+async fn print_parameter_snapshot(controller: &SlitController, config: &SlitControllerConfig) {
+    let mut snapshot = ParameterSnapshot::new();
+
+    for name in ["Y_Up", "Y_Down", "X_Left", "X_Right"] {
+        if let (Some(axis), Some(axis_config)) = (controller.axis_by_name(name), config.axis(name))
+        {
+            axis.read_parameters(axis_config, &mut snapshot).await;
+        }
+    }
+
+    print!("{}", snapshot);
+
+    let mismatches = snapshot.mismatches().count();
+    if mismatches == 0 {
+        println!("All parameters match the configuration");
+    } else {
+        println!("{} parameters differ from the configuration", mismatches);
+    }
+}
+
+async fn run_guided_calibration(
+    controller: &SlitController,
+    config_manager: &ConfigManager,
+    config: &mut SlitControllerConfig,
+    axis_name: &str,
+) -> anyhow::Result<()> {
+    let axis = controller
+        .axis_by_name(axis_name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown axis: {}", axis_name))?;
+
+    println!("Calibrating {}...", axis_name);
+
+    let result = axis
+        .calibrate(config.calibration.clone())
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    for sample in &result.samples {
+        println!(
+            "  steps {:>8}  displacement {:>9.4} mm  temperature {:.1}",
+            sample.commanded_steps, sample.displacement, sample.temperature
+        );
+    }
+    println!(
+        "steps_per_mm: {} (current {})",
+        result.steps_per_mm,
+        axis.steps_per_mm()
+    );
+    println!("backlash_steps: {}", result.backlash_steps);
+    println!("residual rms: {:.4} mm", result.residual_rms);
+
+    print!("Apply calibration? [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    BufReader::new(tokio::io::stdin())
+        .read_line(&mut answer)
+        .await?;
+
+    if !answer.trim().eq_ignore_ascii_case("y") {
+        println!("Calibration discarded");
+        return Ok(());
+    }
+
+    axis.set_steps_per_mm(result.steps_per_mm);
+    axis.set_backlash_steps(result.backlash_steps);
+
+    let axis_config = config
+        .axis_mut(axis_name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown axis: {}", axis_name))?;
+    axis_config.steps_per_mm = result.steps_per_mm;
+    axis_config.backlash_steps = result.backlash_steps;
+
+    let stored = StoredCalibration {
+        steps_per_mm: axis_config.steps_per_mm,
+        backlash_steps: axis_config.backlash_steps,
+        zero_offset: axis_config.zero_offset,
+    };
+    config_manager.save(config)?;
+
+    println!("Calibration saved");
+
+    // The configuration stays authoritative, the copy on the controller is
+    // for replacing the host
+    match axis.store_calibration(stored).await {
+        Ok(()) => println!("Calibration stored on the controller"),
+        Err(e) => println!("Calibration not stored on the controller: {}", e),
+    }
+
+    Ok(())
+}
+
+// Takes over the calibration stored on the controllers, e.g. on a new host.
+// A changed zero offset is used after a restart.
+async fn restore_calibration(
+    controller: &SlitController,
+    config_manager: &ConfigManager,
+    config: &mut SlitControllerConfig,
+) -> anyhow::Result<()> {
+    let mut restored = 0;
+
+    for name in ["Y_Up", "Y_Down", "X_Left", "X_Right"] {
+        let (Some(axis), Some(axis_config)) =
+            (controller.axis_by_name(name), config.axis_mut(name))
+        else {
+            continue;
+        };
+
+        let stored = match axis.stored_calibration().await {
+            Ok(Some(stored)) => stored,
+            Ok(None) => {
+                println!("{}: no calibration stored on the controller", name);
+                continue;
+            }
+            Err(e) => {
+                println!("{}: {}", name, e);
+                continue;
+            }
+        };
+
+        println!(
+            "{}: steps_per_mm {} (was {}), backlash_steps {} (was {}), zero_offset {} (was {})",
+            name,
+            stored.steps_per_mm,
+            axis_config.steps_per_mm,
+            stored.backlash_steps,
+            axis_config.backlash_steps,
+            stored.zero_offset,
+            axis_config.zero_offset
+        );
+
+        axis.set_steps_per_mm(stored.steps_per_mm);
+        axis.set_backlash_steps(stored.backlash_steps);
+        axis_config.steps_per_mm = stored.steps_per_mm;
+        axis_config.backlash_steps = stored.backlash_steps;
+        axis_config.zero_offset = stored.zero_offset;
+        restored += 1;
+    }
+
+    if restored > 0 {
+        config_manager.save(config)?;
+        println!("Calibration of {} axes restored", restored);
+    }
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     logging::init();
 
+    if build_info::version_requested() {
+        println!("{}", build_info!());
+        return Ok(());
+    }
+
     if should_create_config() {
         create_default_config(None::<PathBuf>)?;
     }
 
-    let (_config_manager, config) = init_config().map_err(|e| {
+    let (config_manager, mut config) = init_config().map_err(|e| {
         eprintln!("Failed to load configuration: {}", e);
         eprintln!("Run with CREATE_CONFIG=1 to create a default configuration file.");
         e
     })?;
 
-    let controller = create_controller(&config);
+    if should_scan_trid_bus() {
+        print_trid_scan(&config);
+    }
+
+    let build = build_info!()
+        .with_feature("observer", config.observer_socket.is_some())
+        .with_feature("archiver", config.archiver.is_some());
+    eprintln!("Starting {}", build);
+
+    let controller = create_controller(&config).map_err(anyhow::Error::msg)?;
+
+    if should_take_snapshot() {
+        print_parameter_snapshot(&controller, &config).await;
+    }
+
+    // A failed calibration leaves the values in use as they were, the
+    // server comes up either way
+    if should_restore_calibration() {
+        if let Err(e) = restore_calibration(&controller, &config_manager, &mut config).await {
+            eprintln!("Failed to restore calibration: {}", e);
+        }
+    }
+
+    if let Some(axis_name) = calibration_axis() {
+        if let Err(e) =
+            run_guided_calibration(&controller, &config_manager, &mut config, &axis_name).await
+        {
+            eprintln!("Calibration of {} failed: {}", axis_name, e);
+        }
+    }
 
     let manager_config = ManagerConfig {
         default_ttl: Duration::from_secs(1),
@@ -44,8 +282,16 @@ async fn main() -> anyhow::Result<()> {
 
     let manager = Arc::new(ControllerManager::new(manager_config));
 
+    let controller = with_derived_attributes(Arc::new(controller), &config.derived_attributes)
+        .map_err(anyhow::Error::msg)?;
+    let controller =
+        with_axis_groups(controller, &config.axis_groups).map_err(anyhow::Error::msg)?;
+    let controller = with_presets(controller, &config.presets).map_err(anyhow::Error::msg)?;
+    let controller = with_precision(controller, &config.precision);
+    let controller = with_build_info(controller, &build).map_err(anyhow::Error::msg)?;
+
     manager
-        .register_controller(controller.name().to_string(), Arc::new(controller))
+        .register_controller(controller.name().to_string(), controller.clone())
         .await?;
 
     let socket_config = SocketServerConfig {
@@ -54,9 +300,28 @@ async fn main() -> anyhow::Result<()> {
         buffer_size: 8192,
     };
 
+    // Clients connect once every axis answered or the probe gave up, not while
+    // the executors are still reaching their devices
+    systemd::status("Waiting for axes");
+    wait_until_ready(&[controller.clone()], READY_TIMEOUT).await;
+
     let mut socket_server = SocketServer::new(socket_config, manager.clone());
     socket_server.start().await?;
 
+    let _archiver = match &config.archiver {
+        Some(archiver) => {
+            Some(start_archiver(archiver, &[controller.clone()]).map_err(anyhow::Error::msg)?)
+        }
+        None => None,
+    };
+
+    let _observer_server = match &config.observer_socket {
+        Some(socket_path) => Some(start_observer_socket(socket_path, &[controller]).await?),
+        None => None,
+    };
+
+    systemd::ready();
+
     loop {}
 
     // Ok(())
@@ -0,0 +1,334 @@
+use std::{
+    mem::size_of,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::de::DeserializeOwned;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _},
+    time::timeout,
+};
+
+use crate::{
+    codec::{self, CMD_NAME_LEN, CRC_LEN},
+    command::{
+        position::PositionParams,
+        r#move::{MOVEParameters, StopMode, MOVR, SSTP, STOP},
+        state::StateParams,
+        StandaCommand, StandaGetSetCommand,
+    },
+    counters::ErrorCounters,
+    error::StandaError,
+};
+
+type Result<T> = std::result::Result<T, StandaError>;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Same protocol as `Standa` for senders driven by tokio. The transport has no
+// timeouts of its own here, so every read is bounded by the client's.
+pub struct AsyncStanda {
+    timeout: Duration,
+    counters: Mutex<ErrorCounters>,
+    // Held from reading the move parameters to writing them back, so
+    // concurrent setters do not overwrite each other's fields
+    move_params: tokio::sync::Mutex<()>,
+}
+
+impl Default for AsyncStanda {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            counters: Mutex::new(ErrorCounters::default()),
+            move_params: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+impl AsyncStanda {
+    pub fn new() -> Self {
+        AsyncStanda::default()
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn track<T>(&self, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result {
+            self.counters.lock().unwrap().record_error(e);
+        }
+
+        result
+    }
+
+    pub fn error_counters(&self) -> ErrorCounters {
+        *self.counters.lock().unwrap()
+    }
+
+    pub fn reset_error_counters(&self) {
+        *self.counters.lock().unwrap() = ErrorCounters::default();
+    }
+
+    // Writes the request and returns the echoed command name, leading zeros
+    // left over from a synchronization are skipped
+    async fn request(
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        request: &[u8],
+    ) -> Result<[u8; CMD_NAME_LEN]> {
+        sender.write_all(request).await?;
+
+        let mut echo = [0; CMD_NAME_LEN];
+        while echo[0] == 0 {
+            sender.read_exact(&mut echo[..1]).await?;
+        }
+        sender.read_exact(&mut echo[1..]).await?;
+
+        Ok(echo)
+    }
+
+    async fn exchange(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        request: &[u8],
+        payload_size: usize,
+    ) -> Result<Vec<u8>> {
+        let echo = match timeout(self.timeout, Self::request(sender, request)).await {
+            Ok(echo) => echo?,
+            Err(_) => return Err(self.synchronization(sender, StandaError::Timeout).await),
+        };
+
+        if codec::check_command_echo(request, &echo).is_err() {
+            return Err(self.synchronization(sender, StandaError::CommandEcho).await);
+        }
+
+        if payload_size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut payload = vec![0; payload_size + CRC_LEN];
+        match timeout(self.timeout, sender.read_exact(&mut payload)).await {
+            Ok(read) => {
+                read?;
+            }
+            Err(_) => return Err(self.synchronization(sender, StandaError::Timeout).await),
+        }
+
+        Ok(codec::parse_payload(&payload, payload_size)?.to_vec())
+    }
+
+    // Zeros until the controller echoes one back, see StandaCommand. Returns
+    // `cause` once the device is back in sync.
+    async fn synchronization(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        cause: StandaError,
+    ) -> StandaError {
+        for _ in 0..3 {
+            if let Err(e) = sender.write_all(&[0; 64]).await {
+                return e.into();
+            }
+
+            for _ in 0..64 {
+                let mut byte = [0; 1];
+                match timeout(self.timeout, sender.read_exact(&mut byte)).await {
+                    Ok(Ok(_)) if byte[0] == 0 => return cause,
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => return e.into(),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        StandaError::Resync
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        cmd_name: &str,
+        size: usize,
+    ) -> Result<T> {
+        let payload = self.exchange(sender, cmd_name.as_bytes(), size).await?;
+
+        bincode::deserialize::<T>(&payload[..size_of::<T>()])
+            .map_err(|_e| StandaError::Protocol("failed to parse response from serial port."))
+    }
+
+    async fn send(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        request: &[u8],
+    ) -> Result<()> {
+        self.exchange(sender, request, 0).await.map(|_| ())
+    }
+
+    pub async fn get_move_parameters(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<MOVEParameters> {
+        self.track(
+            self.get(sender, MOVEParameters::GET_CMD_NAME, MOVEParameters::SIZE)
+                .await,
+        )
+    }
+
+    async fn set_move_parameters(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        move_params: &MOVEParameters,
+    ) -> Result<()> {
+        let request = move_params.as_bytes(MOVEParameters::SET_CMD_NAME);
+
+        self.track(self.send(sender, &request).await)
+    }
+
+    pub async fn update_move_parameters(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        update: impl FnOnce(&mut MOVEParameters),
+    ) -> Result<()> {
+        let _guard = self.move_params.lock().await;
+
+        let mut move_params = self.get_move_parameters(sender).await?;
+        update(&mut move_params);
+        self.set_move_parameters(sender, &move_params).await
+    }
+
+    pub async fn get_velocity(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<u32> {
+        Ok(self.get_move_parameters(sender).await?.speed)
+    }
+
+    pub async fn set_velocity(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        velocity: u32,
+    ) -> Result<()> {
+        self.update_move_parameters(sender, |move_params| move_params.speed = velocity)
+            .await
+    }
+
+    pub async fn get_acceleration(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<u16> {
+        Ok(self.get_move_parameters(sender).await?.accel)
+    }
+
+    pub async fn set_acceleration(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        acceleration: u16,
+    ) -> Result<()> {
+        self.update_move_parameters(sender, |move_params| move_params.accel = acceleration)
+            .await
+    }
+
+    pub async fn get_deceleration(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<u16> {
+        Ok(self.get_move_parameters(sender).await?.decel)
+    }
+
+    pub async fn set_deceleration(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        deceleration: u16,
+    ) -> Result<()> {
+        self.update_move_parameters(sender, |move_params| move_params.decel = deceleration)
+            .await
+    }
+
+    pub async fn get_state(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<StateParams> {
+        let state: StateParams = self.track(
+            self.get(sender, StateParams::GET_CMD_NAME, StateParams::SIZE)
+                .await,
+        )?;
+
+        self.counters.lock().unwrap().record_state(&state);
+
+        Ok(state)
+    }
+
+    pub async fn get_position(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<PositionParams> {
+        self.track(
+            self.get(sender, PositionParams::GET_CMD_NAME, PositionParams::SIZE)
+                .await,
+        )
+    }
+
+    // Same as Standa::get_state_and_position, the sender is held for both
+    // reads
+    pub async fn get_state_and_position(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    ) -> Result<(StateParams, PositionParams)> {
+        let state = self.get_state(sender).await?;
+        let position = self.get_position(sender).await?;
+
+        Ok((state, position))
+    }
+
+    // Same as Standa::wait_for_stop
+    pub async fn wait_for_stop(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<StateParams> {
+        let start = Instant::now();
+
+        loop {
+            let state = self.get_state(sender).await?;
+            if !state.is_moving() {
+                return Ok(state);
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(StandaError::MotionTimeout(timeout));
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    pub async fn move_relative(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        steps: i32,
+        sub_steps: i16,
+    ) -> Result<()> {
+        let request = MOVR {
+            position: steps,
+            u_position: sub_steps,
+        }
+        .as_bytes(MOVR::CMD_NAME);
+
+        self.track(self.send(sender, &request).await)
+    }
+
+    pub async fn stop(
+        &self,
+        sender: &mut (impl AsyncRead + AsyncWrite + Unpin),
+        mode: StopMode,
+    ) -> Result<()> {
+        let request = match mode {
+            StopMode::Immediate => STOP.as_bytes(STOP::CMD_NAME),
+            StopMode::Soft => SSTP.as_bytes(SSTP::CMD_NAME),
+        };
+
+        self.track(self.send(sender, &request).await)
+    }
+}
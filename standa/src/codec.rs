@@ -0,0 +1,93 @@
+// Pure Standa packet framing, free of IO and std-only types so it can be
+// reused on `core` + `alloc` targets.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+pub const CMD_NAME_LEN: usize = 4;
+pub const CRC_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    UnexpectedCommand,
+    InvalidLength { expected: usize, received: usize },
+    CrcMismatch { expected: u16, received: u16 },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::UnexpectedCommand => write!(f, "Unexpected command in response"),
+            FrameError::InvalidLength { expected, received } => write!(
+                f,
+                "Invalid response length: expected {}, received {}",
+                expected, received
+            ),
+            FrameError::CrcMismatch { .. } => write!(f, "CRC mismatch"),
+        }
+    }
+}
+
+pub fn crc16(pbuf: &[u8]) -> u16 {
+    let mut crc: u16 = 0xffff;
+    for &byte in pbuf {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            let a = crc;
+            let carry_flag = a & 0x0001;
+            crc >>= 1;
+            if carry_flag == 1 {
+                crc ^= 0xa001;
+            }
+        }
+    }
+    crc
+}
+
+// Command name, serialized body, reserved zero padding and, for commands that
+// carry data, a CRC over everything after the name
+pub fn encode_request(cmd_name: &[u8], body: &[u8], reserved: usize, crc: bool) -> Vec<u8> {
+    let mut buffer =
+        Vec::with_capacity(cmd_name.len() + body.len() + reserved + if crc { CRC_LEN } else { 0 });
+
+    buffer.extend_from_slice(cmd_name);
+    buffer.extend_from_slice(body);
+    buffer.resize(buffer.len() + reserved, 0);
+
+    if crc {
+        let crc = crc16(&buffer[cmd_name.len()..]);
+        buffer.extend_from_slice(&crc.to_le_bytes());
+    }
+
+    buffer
+}
+
+// The controller answers with the name of the command it executed
+pub fn check_command_echo(request: &[u8], echo: &[u8]) -> Result<(), FrameError> {
+    if request.len() < CMD_NAME_LEN || echo != &request[..CMD_NAME_LEN] {
+        return Err(FrameError::UnexpectedCommand);
+    }
+
+    Ok(())
+}
+
+// Splits `payload_size` bytes of data followed by their CRC and validates it
+pub fn parse_payload(frame: &[u8], payload_size: usize) -> Result<&[u8], FrameError> {
+    if frame.len() != payload_size + CRC_LEN {
+        return Err(FrameError::InvalidLength {
+            expected: payload_size + CRC_LEN,
+            received: frame.len(),
+        });
+    }
+
+    let (payload, crc) = frame.split_at(payload_size);
+    let expected = crc16(payload);
+    let received = u16::from_le_bytes([crc[0], crc[1]]);
+
+    if expected != received {
+        return Err(FrameError::CrcMismatch { expected, received });
+    }
+
+    Ok(payload)
+}
@@ -0,0 +1,16 @@
+// Winding voltages in tens of mV and currents in mA, phase C is only used
+// by brushless motors
+standa_command! {
+    pub struct ChartData: get "getc", reserved 14, size 38 {
+        pub winding_voltage_a: i16,
+        pub winding_voltage_b: i16,
+        pub winding_voltage_c: i16,
+        pub winding_current_a: i16,
+        pub winding_current_b: i16,
+        pub winding_current_c: i16,
+        pub pot: u16,
+        pub joy: u16,
+        // PWM duty cycle
+        pub duty_cycle: i16,
+    }
+}
@@ -0,0 +1,46 @@
+// Scale and offset of the winding current sensors and the full current they
+// correspond to. Factory values, only rewritten after a board repair.
+standa_command! {
+    pub struct CalibrationSettings: get "gcal", set "scal", reserved 24, size 54 {
+        pub css1_a: f32,
+        pub css1_b: f32,
+        pub css2_a: f32,
+        pub css2_b: f32,
+        pub full_current_a: f32,
+        pub full_current_b: f32,
+    }
+}
+
+// The controller only counts steps and microsteps, user units are a host side
+// scale the same way the vendor library does it: units per full step and the
+// microstep mode the fractional part is counted in.
+#[derive(Debug, Clone, Copy)]
+pub struct UserUnits {
+    pub units_per_step: f64,
+    pub microsteps_per_step: u16,
+}
+
+impl UserUnits {
+    pub fn new(units_per_step: f64, microsteps_per_step: u16) -> Self {
+        Self {
+            units_per_step,
+            microsteps_per_step: microsteps_per_step.max(1),
+        }
+    }
+
+    pub fn to_units(&self, steps: i32, microsteps: i16) -> f64 {
+        (steps as f64 + microsteps as f64 / self.microsteps_per_step as f64) * self.units_per_step
+    }
+
+    // Rounded to the nearest microstep, the fraction keeps the sign of the
+    // whole like the controller expects
+    pub fn to_steps(&self, value: f64) -> (i32, i16) {
+        let microsteps_per_step = self.microsteps_per_step as i64;
+        let microsteps = (value / self.units_per_step * microsteps_per_step as f64).round() as i64;
+
+        (
+            (microsteps / microsteps_per_step) as i32,
+            (microsteps % microsteps_per_step) as i16,
+        )
+    }
+}
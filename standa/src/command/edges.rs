@@ -0,0 +1,45 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+// 0x1 - BORDER_IS_ENCODER
+// 0x2 - BORDER_STOP_LEFT
+// 0x4 - BORDER_STOP_RIGHT
+// 0x8 - BORDERS_SWAP_MISSET_DETECTION
+
+bitflags! {
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(transparent)]
+    pub struct BorderFlags: u8 {
+        const IS_ENCODER = 0x1;
+        const STOP_LEFT = 0x2;
+        const STOP_RIGHT = 0x4;
+        const SWAP_MISSET_DETECTION = 0x8;
+    }
+}
+
+// 0x1 - ENDER_SWAP
+// 0x2 - ENDER_SW1_ACTIVE_LOW
+// 0x4 - ENDER_SW2_ACTIVE_LOW
+
+bitflags! {
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    #[serde(transparent)]
+    pub struct EnderFlags: u8 {
+        const SWAP = 0x1;
+        const SW1_ACTIVE_LOW = 0x2;
+        const SW2_ACTIVE_LOW = 0x4;
+    }
+}
+
+// Limit switch polarity and the software borders. Without IS_ENCODER the
+// borders are in steps and microsteps.
+standa_command! {
+    pub struct EdgesSettings: get "geds", set "seds", reserved 6, size 26 {
+        pub border_flags: BorderFlags,
+        pub ender_flags: EnderFlags,
+        pub left_border: i32,
+        pub u_left_border: i16,
+        pub right_border: i32,
+        pub u_right_border: i16,
+    }
+}
@@ -0,0 +1,55 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+// 0x1 - ENGINE_REVERSE
+// 0x2 - ENGINE_CURRENT_AS_RMS
+// 0x4 - ENGINE_MAX_SPEED
+// 0x8 - ENGINE_ANTIPLAY
+// 0x10 - ENGINE_ACCEL_ON
+// 0x20 - ENGINE_LIMIT_VOLT
+// 0x40 - ENGINE_LIMIT_CURR
+// 0x80 - ENGINE_LIMIT_RPM
+
+bitflags! {
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+    #[serde(transparent)]
+    pub struct EngineFlags: u16 {
+        const REVERSE = 0x1;
+        const CURRENT_AS_RMS = 0x2;
+        const MAX_SPEED = 0x4;
+        const ANTIPLAY = 0x8;
+        const ACCEL_ON = 0x10;
+        const LIMIT_VOLT = 0x20;
+        const LIMIT_CURR = 0x40;
+        const LIMIT_RPM = 0x80;
+    }
+}
+
+// 1 - MICROSTEP_MODE_FULL
+// 2 - MICROSTEP_MODE_FRAC_2
+// ...
+// 9 - MICROSTEP_MODE_FRAC_256
+
+standa_command! {
+    pub struct EngineSettings: get "geng", set "seng", reserved 12, size 34 {
+        pub nom_voltage: u16,
+        pub nom_current: u16,
+        pub nom_speed: u32,
+        pub u_nom_speed: u8,
+        pub engine_flags: EngineFlags,
+        pub antiplay: i16,
+        pub microstep_mode: u8,
+        // Full steps per revolution
+        pub steps_per_rev: u16,
+    }
+}
+
+impl EngineSettings {
+    // Microsteps in one full step, None for an unknown mode
+    pub fn microsteps_per_step(&self) -> Option<u16> {
+        match self.microstep_mode {
+            mode @ 1..=9 => Some(1 << (mode - 1)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+// Writes the current settings to flash, they are loaded again on power up
+standa_command! {
+    pub struct SAVE: "save";
+}
+
+// Replaces the current settings with the ones stored in flash
+standa_command! {
+    pub struct READ: "read";
+}
@@ -1,8 +1,6 @@
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
-use super::{StandaCommand, StandaGetSetCommand};
-
 // 0x1 - HOME_DIR_FIRST
 // 0x2 - HOME_DIR_SECOND
 // 0x4 - HOME_MV_SEC_EN
@@ -35,30 +33,28 @@ bitflags! {
     }
 }
 
-#[repr(C, packed)]
-#[derive(Serialize, Deserialize, Debug)]
-pub struct HomeParameters {
-    pub fast_home: u32,
-    pub u_fast_home: u8,
-    pub slow_home: u32,
-    pub u_slow_home: u8,
-    pub home_delta: i32,
-    pub u_home_delta: i16,
-    pub home_flags: HomeFlags,
+standa_command! {
+    pub struct HomeParameters: get "ghom", set "shom", reserved 9, size 33 {
+        pub fast_home: u32,
+        pub u_fast_home: u8,
+        pub slow_home: u32,
+        pub u_slow_home: u8,
+        pub home_delta: i32,
+        pub u_home_delta: i16,
+        pub home_flags: HomeFlags,
+    }
 }
 
-impl<'a> StandaCommand<'a, 9> for HomeParameters {}
-impl<'a> StandaGetSetCommand<'a, 9> for HomeParameters {
-    const GET_CMD_NAME: &'static str = "ghom";
-    const SET_CMD_NAME: &'static str = "shom";
+// Runs the homing sequence described by the home settings
+standa_command! {
+    pub struct HOME: "home";
 }
 
-#[repr(C, packed)]
-#[derive(Serialize, Deserialize, Debug)]
-pub struct HOME {}
-impl<'a> StandaCommand<'a, 0, false> for HOME {}
+standa_command! {
+    pub struct HOMR: "homr";
+}
 
-#[repr(C, packed)]
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ZERO {}
-impl<'a> StandaCommand<'a, 0, false> for ZERO {}
+// Sets the step counter and the encoder to zero without moving
+standa_command! {
+    pub struct ZERO: "zero";
+}
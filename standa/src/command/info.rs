@@ -0,0 +1,81 @@
+use std::fmt;
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string()
+}
+
+// Strings are NUL padded ASCII, the version is the hardware revision
+standa_command! {
+    pub struct DeviceInformation: get "geti", reserved 12, size 36 {
+        pub manufacturer: [u8; 4],
+        pub manufacturer_id: [u8; 2],
+        pub product_description: [u8; 8],
+        pub major: u8,
+        pub minor: u8,
+        pub release: u16,
+    }
+}
+
+impl DeviceInformation {
+    pub fn manufacturer(&self) -> String {
+        text(&self.manufacturer)
+    }
+
+    pub fn product_description(&self) -> String {
+        text(&self.product_description)
+    }
+}
+
+standa_command! {
+    pub struct SerialNumber: get "gser", reserved 0, size 10 {
+        pub serial_number: u32,
+    }
+}
+
+standa_command! {
+    pub struct FirmwareVersion: get "gfwv", reserved 0, size 10 {
+        pub major: u8,
+        pub minor: u8,
+        pub release: u16,
+    }
+}
+
+// Everything needed to tell the physical units apart
+#[derive(Debug, Clone, Copy)]
+pub struct Identity {
+    pub information: DeviceInformation,
+    pub serial_number: u32,
+    pub firmware: FirmwareVersion,
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (hardware_major, hardware_minor, hardware_release) = (
+            self.information.major,
+            self.information.minor,
+            self.information.release,
+        );
+        let (major, minor, release) = (
+            self.firmware.major,
+            self.firmware.minor,
+            self.firmware.release,
+        );
+
+        write!(
+            f,
+            "{} {}, serial {}, hardware {}.{}.{}, firmware {}.{}.{}",
+            self.information.manufacturer(),
+            self.information.product_description(),
+            self.serial_number,
+            hardware_major,
+            hardware_minor,
+            hardware_release,
+            major,
+            minor,
+            release
+        )
+    }
+}
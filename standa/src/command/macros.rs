@@ -0,0 +1,111 @@
+// Declares a command struct with its framing, e.g.
+//
+//   standa_command! {
+//       pub struct EdgesSettings: get "geds", set "seds", reserved 6, size 26 {
+//           pub border_flags: BorderFlags,
+//           ...
+//       }
+//   }
+//
+// `size` is the frame length from the manual: command name, payload,
+// reserved bytes and CRC. It is checked against the packed layout at compile
+// time, so a wrong field type or reserved count does not build. The forms:
+//
+//   struct X: get "gxxx", set "sxxx", reserved R, size N { .. }  settings pair
+//   struct X: get "gxxx", reserved R, size N { .. }              read only
+//   struct X: "xxxx", reserved R, size N { .. }                  request
+//   struct X: "xxxx";                                            bare command
+macro_rules! standa_command {
+    (@size $name:ident, $reserved:literal, $size:literal) => {
+        const _: () = assert!(
+            $crate::codec::CMD_NAME_LEN
+                + ::std::mem::size_of::<$name>()
+                + $reserved
+                + $crate::codec::CRC_LEN
+                == $size,
+            concat!("frame size of ", stringify!($name), " does not match")
+        );
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: get $get:literal, set $set:literal, reserved $reserved:literal, size $size:literal {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(C, packed)]
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl<'a> $crate::command::StandaCommand<'a, $reserved> for $name {}
+        impl<'a> $crate::command::StandaGetSetCommand<'a, $reserved> for $name {
+            const GET_CMD_NAME: &'static str = $get;
+            const SET_CMD_NAME: &'static str = $set;
+        }
+
+        standa_command!(@size $name, $reserved, $size);
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: get $get:literal, reserved $reserved:literal, size $size:literal {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(C, packed)]
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl<'a> $crate::command::StandaCommand<'a, $reserved> for $name {}
+        impl<'a> $crate::command::StandaGetSetCommand<'a, $reserved> for $name {
+            const GET_CMD_NAME: &'static str = $get;
+            const SET_CMD_NAME: &'static str = "";
+
+            fn set(
+                &self,
+                _: &mut (impl ::std::io::Write + ::std::io::Read),
+            ) -> Result<(), $crate::StandaError> {
+                Ok(())
+            }
+        }
+
+        standa_command!(@size $name, $reserved, $size);
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: $cmd:literal, reserved $reserved:literal, size $size:literal {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(C, packed)]
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl<'a> $crate::command::StandaCommand<'a, $reserved> for $name {
+            const CMD_NAME: &'static str = $cmd;
+        }
+
+        standa_command!(@size $name, $reserved, $size);
+    };
+
+    ($(#[$meta:meta])* $vis:vis struct $name:ident: $cmd:literal;) => {
+        $(#[$meta])*
+        #[repr(C, packed)]
+        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+        $vis struct $name;
+
+        impl<'a> $crate::command::StandaCommand<'a, 0, false> for $name {
+            const CMD_NAME: &'static str = $cmd;
+        }
+    };
+}
@@ -1,30 +1,28 @@
+#[macro_use]
+mod macros;
+
+pub mod analog;
+pub mod calibration;
+pub mod edges;
+pub mod engine;
+pub mod flash;
 pub mod home;
+pub mod info;
 pub mod r#move;
+pub mod position;
+pub mod power;
 pub mod state;
+pub mod user_data;
 
 use std::{
-    io::{self, Error, ErrorKind, Read, Write},
+    io::{ErrorKind, Read, Write},
     mem::size_of,
 };
 
 use bincode::deserialize;
 use serde::{Deserialize, Serialize};
 
-fn crc16(pbuf: &[u8]) -> u16 {
-    let mut crc: u16 = 0xffff;
-    for &byte in pbuf {
-        crc ^= byte as u16;
-        for _ in 0..8 {
-            let a = crc;
-            let carry_flag = a & 0x0001;
-            crc >>= 1;
-            if carry_flag == 1 {
-                crc ^= 0xa001;
-            }
-        }
-    }
-    crc
-}
+use crate::{codec, error::StandaError};
 
 #[repr(C, packed)]
 #[derive(Deserialize, Debug)]
@@ -42,25 +40,12 @@ pub trait StandaCommand<'a, const RESERVED: usize = 0, const CRC: bool = true>:
     const CMD_NAME: &'static str = "";
 
     fn as_bytes(&self, cmd_name: &'a str) -> Vec<u8> {
-        let command = cmd_name.as_bytes();
-
         let bytes = bincode::serialize(self).expect("failed to serialize struct.");
 
-        let mut buffer = Vec::with_capacity(4 + Self::SIZE + if CRC { 2 } else { 0 });
-
-        buffer.extend_from_slice(command);
-        buffer.extend_from_slice(&bytes);
-        buffer.extend_from_slice(&[0; RESERVED]);
-
-        if CRC {
-            let crc = crc16(&buffer[4..]);
-            buffer.extend_from_slice(&[crc as u8, (crc >> 8) as u8]);
-        }
-
-        buffer
+        codec::encode_request(cmd_name.as_bytes(), &bytes, RESERVED, CRC)
     }
 
-    fn send(&self, sender: &mut (impl Write + Read)) -> io::Result<()> {
+    fn send(&self, sender: &mut (impl Write + Read)) -> Result<(), StandaError> {
         let bytes = self.as_bytes(Self::CMD_NAME);
 
         Self::send_raw(sender, &bytes, 0).map(|_| ())
@@ -70,7 +55,7 @@ pub trait StandaCommand<'a, const RESERVED: usize = 0, const CRC: bool = true>:
         sender: &mut (impl Write + Read),
         bytes: &[u8],
         payload_size: usize,
-    ) -> io::Result<Vec<u8>> {
+    ) -> Result<Vec<u8>, StandaError> {
         sender.write_all(bytes)?;
 
         // Eat all zeros
@@ -81,10 +66,10 @@ pub trait StandaCommand<'a, const RESERVED: usize = 0, const CRC: bool = true>:
                 Ok(_) => {}
                 // Sync on timeout
                 Err(e) if e.kind() == ErrorKind::TimedOut => {
-                    return Err(Self::synchronization(sender).unwrap_err());
+                    return Err(Self::synchronization(sender, StandaError::Timeout));
                 }
                 Err(e) => {
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         }
@@ -93,14 +78,14 @@ pub trait StandaCommand<'a, const RESERVED: usize = 0, const CRC: bool = true>:
         match sender.read_exact(&mut cmd_name_buffer[1..]) {
             Ok(_) => {}
             Err(e) if e.kind() == ErrorKind::TimedOut => {
-                return Err(Self::synchronization(sender).unwrap_err())
+                return Err(Self::synchronization(sender, StandaError::Timeout))
             }
-            Err(e) => return Err(e),
+            Err(e) => return Err(e.into()),
         }
 
         // Check command name
-        if cmd_name_buffer != bytes[0..4] {
-            return Err(Self::synchronization(sender).unwrap_err());
+        if codec::check_command_echo(bytes, &cmd_name_buffer).is_err() {
+            return Err(Self::synchronization(sender, StandaError::CommandEcho));
         }
 
         if payload_size == 0 {
@@ -112,47 +97,43 @@ pub trait StandaCommand<'a, const RESERVED: usize = 0, const CRC: bool = true>:
         match sender.read_exact(&mut payload) {
             Ok(_) => {}
             Err(e) if e.kind() == ErrorKind::TimedOut => {
-                return Err(Self::synchronization(sender).unwrap_err())
+                return Err(Self::synchronization(sender, StandaError::Timeout))
             }
-            Err(e) => return Err(e),
+            Err(e) => return Err(e.into()),
         }
 
         // Check CRC
-        let (payload, crc) = payload.split_at(payload_size);
-        let calculated_crc = crc16(payload);
-        let received_crc = u16::from_le_bytes([crc[0], crc[1]]);
-
-        if calculated_crc != received_crc {
-            return Err(Error::new(ErrorKind::InvalidData, "CRC mismatch"));
-        }
+        let payload = codec::parse_payload(&payload, payload_size)?;
 
         Ok(payload.to_vec())
     }
 
-    fn synchronization(sender: &mut (impl Write + Read)) -> io::Result<()> {
+    // Returns `cause` once the device is back in sync
+    fn synchronization(sender: &mut (impl Write + Read), cause: StandaError) -> StandaError {
         'outer: for _ in 0..3 {
-            sender.flush()?;
+            if let Err(e) = sender.flush() {
+                return e.into();
+            }
 
-            sender.write_all(&[0; 64])?;
+            if let Err(e) = sender.write_all(&[0; 64]) {
+                return e.into();
+            }
 
             for _ in 0..64 {
                 let mut buf = [0; 1];
                 match sender.read_exact(&mut buf) {
                     Ok(_) => {
                         if buf[0] == 0 {
-                            return Err(Error::other("Synchronized with device"));
+                            return cause;
                         }
                     }
                     Err(e) if e.kind() == ErrorKind::TimedOut => continue 'outer,
-                    Err(e) => return Err(e),
+                    Err(e) => return e.into(),
                 }
             }
         }
 
-        Err(Error::new(
-            ErrorKind::HostUnreachable,
-            "Device is unreachable or not responding",
-        ))
+        StandaError::Resync
     }
 }
 
@@ -164,7 +145,7 @@ where
     const GET_CMD_NAME: &'static str;
     const SET_CMD_NAME: &'static str;
 
-    fn get(sender: &mut (impl Write + Read)) -> io::Result<Self>
+    fn get(sender: &mut (impl Write + Read)) -> Result<Self, StandaError>
     where
         Self: for<'de> Deserialize<'de>,
     {
@@ -174,17 +155,13 @@ where
 
         let (data, _) = payload.split_at(size_of::<Self>());
 
-        let response = deserialize::<Self>(data).map_err(|_e| {
-            Error::new(
-                ErrorKind::InvalidData,
-                "failed to parse response from serial port.",
-            )
-        })?;
+        let response = deserialize::<Self>(data)
+            .map_err(|_e| StandaError::Protocol("failed to parse response from serial port."))?;
 
         Ok(response)
     }
 
-    fn set(&self, sender: &mut (impl Write + Read)) -> io::Result<()> {
+    fn set(&self, sender: &mut (impl Write + Read)) -> Result<(), StandaError> {
         let bytes = self.as_bytes(Self::SET_CMD_NAME);
 
         Self::send_raw(sender, &bytes, 0)?;
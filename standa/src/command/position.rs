@@ -0,0 +1,30 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+bitflags! {
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+    #[serde(transparent)]
+    pub struct PositionFlags: u8 {
+        const IGNORE_POSITION = 0x1;
+        const IGNORE_ENCODER = 0x2;
+    }
+}
+
+// Step counter and encoder value as kept by the controller itself
+standa_command! {
+    pub struct PositionParams: get "gpos", reserved 6, size 26 {
+        pub position: i32,
+        pub u_position: i16,
+        pub enc_position: i64,
+    }
+}
+
+// Overwrites the counters without moving, the flags pick which ones
+standa_command! {
+    pub struct SPOS: "spos", reserved 5, size 26 {
+        pub position: i32,
+        pub u_position: i16,
+        pub enc_position: i64,
+        pub pos_flags: PositionFlags,
+    }
+}
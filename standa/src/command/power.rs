@@ -0,0 +1,30 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+// 0x1 - POWER_REDUCT_ENABLED
+// 0x2 - POWER_OFF_ENABLED
+// 0x4 - POWER_SMOOTH_CURRENT
+
+bitflags! {
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+    #[serde(transparent)]
+    pub struct PowerFlags: u8 {
+        const REDUCT_ENABLED = 0x1;
+        const OFF_ENABLED = 0x2;
+        const SMOOTH_CURRENT = 0x4;
+    }
+}
+
+standa_command! {
+    pub struct PowerSettings: get "gpwr", set "spwr", reserved 6, size 20 {
+        // Percent of the nominal current kept while standing still
+        pub hold_current: u8,
+        // ms after a stop before the current is reduced
+        pub curr_reduct_delay: u16,
+        // s after a stop before the windings are powered off
+        pub power_off_delay: u16,
+        // ms for a smooth current change
+        pub current_set_time: u16,
+        pub power_flags: PowerFlags,
+    }
+}
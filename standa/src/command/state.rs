@@ -1,10 +1,8 @@
-use std::io;
+use std::fmt;
 
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
-use super::{StandaCommand, StandaGetSetCommand};
-
 bitflags! {
     // #[repr(C, packed)]
     #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -126,48 +124,241 @@ bitflags! {
     }
 }
 
-#[repr(C, packed)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StateParams {
-    pub move_sts: MoveState,
-    pub mv_cmd_sts: MvCmdSts,
-    pub pwr_sts: PowerState,
-    pub enc_sts: EncoderState,
-    pub wind_sts: WindState,
+standa_command! {
+    pub struct StateParams: get "gets", reserved 4, size 54 {
+        pub move_sts: MoveState,
+        pub mv_cmd_sts: MvCmdSts,
+        pub pwr_sts: PowerState,
+        pub enc_sts: EncoderState,
+        pub wind_sts: WindState,
+
+        pub cur_position: i32,
+        pub u_cur_position: i16,
 
-    pub cur_position: i32,
-    pub u_cur_position: i16,
+        pub enc_position: i64,
 
-    pub enc_position: i64,
+        pub cur_speed: i32,
+        pub u_cur_speed: i16,
 
-    pub cur_speed: i32,
-    pub u_cur_speed: i16,
+        pub i_pwr: i16,
+        pub u_pwr: i16,
+        pub i_usb: i16,
+        pub u_usb: i16,
 
-    pub i_pwr: i16,
-    pub u_pwr: i16,
-    pub i_usb: i16,
-    pub u_usb: i16,
+        pub cur_t: i16,
 
-    pub cur_t: i16,
+        pub state: State,
+        pub gpio_flags: GpioFlags,
+
+        pub cmd_buf_free_space: u8,
+    }
+}
 
-    pub state: State,
-    pub gpio_flags: GpioFlags,
+// MOVE_STATE flags, as one value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveStatus {
+    Stopped,
+    Accelerating,
+    AtSpeed,
+    // Backlash compensation at the end of a move
+    Antiplay,
+}
 
-    pub cmd_buf_free_space: u8,
+// Low six bits of MVCMD_STS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveCommand {
+    Unknown,
+    Move,
+    Movr,
+    Left,
+    Right,
+    Stop,
+    Home,
+    Loft,
+    Sstp,
 }
 
-impl<'a> StandaCommand<'a, 4> for StateParams {}
+// High two bits of MVCMD_STS, for the command above
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandStatus {
+    Running,
+    Done,
+    Error,
+}
 
-impl<'a> StandaGetSetCommand<'a, 4> for StateParams {
-    const GET_CMD_NAME: &'static str = "gets";
-    const SET_CMD_NAME: &'static str = "";
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerStatus {
+    Unknown,
+    Off,
+    Normal,
+    Reduced,
+    Max,
+}
 
-    fn set(&self, _: &mut (impl io::Write + io::Read)) -> io::Result<()> {
-        Ok(())
+// One set bit of the state flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    CommandError,
+    DataError,
+    ValueError,
+    Alarm,
+    CtpError,
+    PowerOverheat,
+    ControllerOverheat,
+    OverloadPowerVoltage,
+    OverloadPowerCurrent,
+    OverloadUsbVoltage,
+    LowUsbVoltage,
+    OverloadUsbCurrent,
+    BordersSwapMisset,
+    LowPowerVoltage,
+    HBridgeFault,
+    WindingResistanceMismatch,
+    EncoderFault,
+    EngineResponseError,
+    ExtioAlarm,
+}
+
+const FAULTS: [(State, Fault); 19] = [
+    (State::ERRC, Fault::CommandError),
+    (State::ERRD, Fault::DataError),
+    (State::ERRV, Fault::ValueError),
+    (State::ALARM, Fault::Alarm),
+    (State::CTP_ERROR, Fault::CtpError),
+    (State::POWER_OVERHEAT, Fault::PowerOverheat),
+    (State::CONTROLLER_OVERHEAT, Fault::ControllerOverheat),
+    (State::OVERLOAD_POWER_VOLTAGE, Fault::OverloadPowerVoltage),
+    (State::OVERLOAD_POWER_CURRENT, Fault::OverloadPowerCurrent),
+    (State::OVERLOAD_USB_VOLTAGE, Fault::OverloadUsbVoltage),
+    (State::LOW_USB_VOLTAGE, Fault::LowUsbVoltage),
+    (State::OVERLOAD_USB_CURRENT, Fault::OverloadUsbCurrent),
+    (State::BORDERS_SWAP_MISSET, Fault::BordersSwapMisset),
+    (State::LOW_POWER_VOLTAGE, Fault::LowPowerVoltage),
+    (State::H_BRIDGE_FAULT, Fault::HBridgeFault),
+    (
+        State::WINDING_RES_MISMATCH,
+        Fault::WindingResistanceMismatch,
+    ),
+    (State::ENCODER_FAULT, Fault::EncoderFault),
+    (State::ENGINE_RESPONSE_ERROR, Fault::EngineResponseError),
+    (State::EXTIO_ALARM, Fault::ExtioAlarm),
+];
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Fault::CommandError => "command error",
+            Fault::DataError => "data integrity error",
+            Fault::ValueError => "value error",
+            Fault::Alarm => "alarm",
+            Fault::CtpError => "revolution sensor error",
+            Fault::PowerOverheat => "power driver overheat",
+            Fault::ControllerOverheat => "controller overheat",
+            Fault::OverloadPowerVoltage => "power voltage too high",
+            Fault::OverloadPowerCurrent => "power current too high",
+            Fault::OverloadUsbVoltage => "USB voltage too high",
+            Fault::LowUsbVoltage => "USB voltage too low",
+            Fault::OverloadUsbCurrent => "USB current too high",
+            Fault::BordersSwapMisset => "borders swap misset",
+            Fault::LowPowerVoltage => "power voltage too low",
+            Fault::HBridgeFault => "H-bridge fault",
+            Fault::WindingResistanceMismatch => "winding resistance mismatch",
+            Fault::EncoderFault => "encoder fault",
+            Fault::EngineResponseError => "engine response error",
+            Fault::ExtioAlarm => "external alarm input",
+        };
+
+        write!(f, "{}", text)
     }
 }
 
 impl StateParams {
+    pub fn move_status(&self) -> MoveStatus {
+        let move_sts = self.move_sts;
+
+        if move_sts.contains(MoveState::ANTIPLAY) {
+            MoveStatus::Antiplay
+        } else if move_sts.contains(MoveState::TARGET_SPEED) {
+            MoveStatus::AtSpeed
+        } else if move_sts.contains(MoveState::MOVING) {
+            MoveStatus::Accelerating
+        } else {
+            MoveStatus::Stopped
+        }
+    }
+
+    pub fn move_command(&self) -> MoveCommand {
+        match self.mv_cmd_sts.bits() & 0x3f {
+            0x1 => MoveCommand::Move,
+            0x2 => MoveCommand::Movr,
+            0x3 => MoveCommand::Left,
+            0x4 => MoveCommand::Right,
+            0x5 => MoveCommand::Stop,
+            0x6 => MoveCommand::Home,
+            0x7 => MoveCommand::Loft,
+            0x8 => MoveCommand::Sstp,
+            _ => MoveCommand::Unknown,
+        }
+    }
+
+    pub fn command_status(&self) -> CommandStatus {
+        if self.is_moving() {
+            CommandStatus::Running
+        } else if self.is_error() {
+            CommandStatus::Error
+        } else {
+            CommandStatus::Done
+        }
+    }
+
+    pub fn power_status(&self) -> PowerStatus {
+        match self.pwr_sts.bits() {
+            0x1 => PowerStatus::Off,
+            0x3 => PowerStatus::Normal,
+            0x4 => PowerStatus::Reduced,
+            0x5 => PowerStatus::Max,
+            _ => PowerStatus::Unknown,
+        }
+    }
+
+    pub fn faults(&self) -> Vec<Fault> {
+        let state = self.state;
+
+        FAULTS
+            .iter()
+            .filter(|(flag, _)| state.contains(*flag))
+            .map(|(_, fault)| *fault)
+            .collect()
+    }
+
+    // Operator facing summary, None while nothing is wrong. An alarm with
+    // nothing else to show for it and a limit switch hit is a crossed border.
+    pub fn fault_message(&self) -> Option<String> {
+        let faults = self.faults();
+        let alarm = faults.contains(&Fault::Alarm);
+
+        let mut reasons: Vec<String> = faults
+            .iter()
+            .filter(|fault| **fault != Fault::Alarm)
+            .map(|fault| fault.to_string())
+            .collect();
+
+        if alarm && reasons.is_empty() && (self.left_switch() || self.right_switch()) {
+            reasons.push("border crossed".to_string());
+        }
+
+        if self.command_status() == CommandStatus::Error {
+            reasons.push(format!("{:?} failed", self.move_command()).to_lowercase());
+        }
+
+        match (alarm, reasons.is_empty()) {
+            (true, true) => Some("alarm".to_string()),
+            (true, false) => Some(format!("alarm: {}", reasons.join(", "))),
+            (false, false) => Some(format!("error: {}", reasons.join(", "))),
+            (false, true) => None,
+        }
+    }
+
     pub fn is_moving(&self) -> bool {
         self.mv_cmd_sts.contains(MvCmdSts::RUNNING)
     }
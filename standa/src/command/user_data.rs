@@ -0,0 +1,38 @@
+pub const USER_DATA_LEN: usize = 56;
+
+// Free area in the controller's flash, kept across power cycles and left
+// alone by the firmware. Split in two because serde stops at 32 byte arrays.
+standa_command! {
+    pub struct UserData: get "gusr", set "susr", reserved 2, size 64 {
+        head: [u8; 32],
+        tail: [u8; USER_DATA_LEN - 32],
+    }
+}
+
+impl UserData {
+    // Shorter input is padded with zeros, longer input is cut
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut data = [0; USER_DATA_LEN];
+        let len = bytes.len().min(USER_DATA_LEN);
+        data[..len].copy_from_slice(&bytes[..len]);
+
+        let mut user_data = UserData {
+            head: [0; 32],
+            tail: [0; USER_DATA_LEN - 32],
+        };
+        user_data.head.copy_from_slice(&data[..32]);
+        user_data.tail.copy_from_slice(&data[32..]);
+
+        user_data
+    }
+
+    pub fn bytes(&self) -> [u8; USER_DATA_LEN] {
+        let (head, tail) = (self.head, self.tail);
+
+        let mut data = [0; USER_DATA_LEN];
+        data[..32].copy_from_slice(&head);
+        data[32..].copy_from_slice(&tail);
+
+        data
+    }
+}
@@ -0,0 +1,53 @@
+use utilities::conformance::{argument, run_vectors, split_call, Failure};
+
+use crate::{command::r#move::StopMode, Standa};
+
+pub const VECTORS: &str = include_str!("../vectors/frames.txt");
+
+// Replays the frame vectors against the driver, see utilities::conformance
+// for the format
+pub fn check(vectors: &str) -> Result<Vec<Failure>, String> {
+    let standa = Standa::new();
+
+    run_vectors(vectors, |call, port| {
+        let result = match split_call(call).0 {
+            "get_velocity" => standa.get_velocity(port).map(|v| v.to_string()),
+            "set_velocity" => standa
+                .set_velocity(port, argument(call)?)
+                .map(|_| String::new()),
+            "get_acceleration" => standa.get_acceleration(port).map(|v| v.to_string()),
+            "move_relative" => standa
+                .move_relative(port, argument(call)?, 0)
+                .map(|_| String::new()),
+            "stop" => standa
+                .stop(port, StopMode::Immediate)
+                .map(|_| String::new()),
+            "soft_stop" => standa.stop(port, StopMode::Soft).map(|_| String::new()),
+            "get_position" => standa.get_position(port).map(|position| {
+                let (steps, sub_steps, encoder) = (
+                    position.position,
+                    position.u_position,
+                    position.enc_position,
+                );
+                format!("{}/{}/{}", steps, sub_steps, encoder)
+            }),
+            name => return Err(format!("Unknown call: {}", name)),
+        };
+
+        result.map_err(|e| e.to_string())
+    })
+}
+
+#[test]
+fn vectors_pass() {
+    let failures = check(VECTORS).unwrap();
+    assert!(
+        failures.is_empty(),
+        "{}",
+        failures
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
@@ -0,0 +1,61 @@
+use crate::{
+    command::state::{State, StateParams},
+    error::StandaError,
+};
+
+// Link and controller error statistics. The controller itself only reports
+// sticky flags, so occurrences are counted on this side of the bridge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ErrorCounters {
+    // Replies lost or garbled badly enough to need a resynchronization
+    pub resyncs: u32,
+    // Resynchronization failed, the device never answered
+    pub unreachable: u32,
+    // Replies that arrived complete but failed the CRC or could not be parsed
+    pub data_errors: u32,
+    // Any other transport failure, usually the TCP link to the bridge
+    pub io_errors: u32,
+
+    // ERRC, ERRD and ERRV as seen in state reports
+    pub command_errors: u32,
+    pub integrity_errors: u32,
+    pub value_errors: u32,
+}
+
+impl ErrorCounters {
+    pub fn record_error(&mut self, error: &StandaError) {
+        let counter = match error {
+            StandaError::Timeout | StandaError::CommandEcho => &mut self.resyncs,
+            StandaError::Resync => &mut self.unreachable,
+            StandaError::CrcMismatch { .. }
+            | StandaError::InvalidLength { .. }
+            | StandaError::Protocol(_) => &mut self.data_errors,
+            StandaError::Io(_) => &mut self.io_errors,
+            // Not a communication problem
+            StandaError::MotionTimeout(_) => return,
+        };
+
+        *counter = counter.saturating_add(1);
+    }
+
+    pub fn record_state(&mut self, state: &StateParams) {
+        let flags = state.state;
+
+        for (flag, counter) in [
+            (State::ERRC, &mut self.command_errors),
+            (State::ERRD, &mut self.integrity_errors),
+            (State::ERRV, &mut self.value_errors),
+        ] {
+            if flags.contains(flag) {
+                *counter = counter.saturating_add(1);
+            }
+        }
+    }
+
+    pub fn link_errors(&self) -> u32 {
+        self.resyncs
+            .saturating_add(self.unreachable)
+            .saturating_add(self.data_errors)
+            .saturating_add(self.io_errors)
+    }
+}
@@ -0,0 +1,119 @@
+use std::{error::Error, fmt, io, time::Duration};
+
+use crate::codec::FrameError;
+
+#[derive(Debug)]
+pub enum StandaError {
+    // Transport failure, usually the TCP link to the bridge
+    Io(io::Error),
+    // No reply in time, the link was resynchronized afterwards
+    Timeout,
+    // The reply belonged to another command, resynchronized as well
+    CommandEcho,
+    // Resynchronization failed, the device never answered
+    Resync,
+    CrcMismatch { expected: u16, received: u16 },
+    InvalidLength { expected: usize, received: usize },
+    // A complete reply that does not make sense, or a request that can not
+    // be made
+    Protocol(&'static str),
+    // The drive was still running when wait_for_stop gave up
+    MotionTimeout(Duration),
+}
+
+impl StandaError {
+    // The link itself is gone, a new connection is the only way forward
+    pub fn needs_reconnect(&self) -> bool {
+        match self {
+            StandaError::Io(error) => !matches!(
+                error.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+            ),
+            StandaError::Resync => true,
+            _ => false,
+        }
+    }
+
+    // The link is fine and in sync, sending the request again may work
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            StandaError::Timeout
+                | StandaError::CommandEcho
+                | StandaError::CrcMismatch { .. }
+                | StandaError::InvalidLength { .. }
+        )
+    }
+
+    // Handlers pass errors on as io::Error, this looks through that
+    pub fn from_io(error: &io::Error) -> Option<&StandaError> {
+        error
+            .get_ref()
+            .and_then(|error| error.downcast_ref::<StandaError>())
+    }
+}
+
+impl fmt::Display for StandaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StandaError::Io(error) => write!(f, "{}", error),
+            StandaError::Timeout => write!(f, "No response, synchronized with device"),
+            StandaError::CommandEcho => {
+                write!(
+                    f,
+                    "Unexpected command in response, synchronized with device"
+                )
+            }
+            StandaError::Resync => write!(f, "Device is unreachable or not responding"),
+            StandaError::CrcMismatch { .. } => write!(f, "CRC mismatch"),
+            StandaError::InvalidLength { expected, received } => write!(
+                f,
+                "Invalid response length: expected {}, received {}",
+                expected, received
+            ),
+            StandaError::Protocol(message) => write!(f, "Protocol error: {}", message),
+            StandaError::MotionTimeout(timeout) => {
+                write!(f, "Motion did not complete within {:?}", timeout)
+            }
+        }
+    }
+}
+
+impl Error for StandaError {}
+
+impl From<io::Error> for StandaError {
+    fn from(error: io::Error) -> Self {
+        StandaError::Io(error)
+    }
+}
+
+impl From<FrameError> for StandaError {
+    fn from(error: FrameError) -> Self {
+        match error {
+            FrameError::UnexpectedCommand => StandaError::CommandEcho,
+            FrameError::InvalidLength { expected, received } => {
+                StandaError::InvalidLength { expected, received }
+            }
+            FrameError::CrcMismatch { expected, received } => {
+                StandaError::CrcMismatch { expected, received }
+            }
+        }
+    }
+}
+
+// The StandaError is kept inside so from_io still finds it
+impl From<StandaError> for io::Error {
+    fn from(error: StandaError) -> Self {
+        let kind = match error {
+            StandaError::Io(error) => return error,
+            StandaError::Timeout | StandaError::MotionTimeout(_) => io::ErrorKind::TimedOut,
+            StandaError::CommandEcho => io::ErrorKind::Other,
+            StandaError::Resync => io::ErrorKind::HostUnreachable,
+            StandaError::CrcMismatch { .. }
+            | StandaError::InvalidLength { .. }
+            | StandaError::Protocol(_) => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
@@ -1,31 +1,106 @@
+extern crate alloc;
+
 use command::{
-    r#move::{MOVEParameters, MOVR, STOP},
+    analog::ChartData,
+    calibration::{CalibrationSettings, UserUnits},
+    edges::EdgesSettings,
+    engine::EngineSettings,
+    flash::{READ, SAVE},
+    home::{HomeParameters, HOME, ZERO},
+    info::{DeviceInformation, FirmwareVersion, Identity, SerialNumber},
+    position::{PositionFlags, PositionParams, SPOS},
+    power::PowerSettings,
+    r#move::{MOVEParameters, StopMode, MOVR, SSTP, STOP},
     state::StateParams,
+    user_data::UserData,
     StandaCommand, StandaGetSetCommand,
 };
-use std::io::{Read, Result, Write};
+use counters::ErrorCounters;
+use std::{
+    cell::Cell,
+    io::{Read, Write},
+    time::{Duration, Instant},
+};
+pub mod async_client;
+pub mod codec;
+// Test-only, the vector runner lives in utilities which the driver does not
+// depend on
 pub mod command;
+#[cfg(test)]
+mod conformance;
+pub mod counters;
+mod error;
+pub use error::StandaError;
+
+type Result<T> = std::result::Result<T, StandaError>;
 
 #[derive(Default)]
-pub struct Standa;
+pub struct Standa {
+    counters: Cell<ErrorCounters>,
+    user_units: Option<UserUnits>,
+}
 
 impl Standa {
     pub fn new() -> Self {
-        Standa {}
+        Standa::default()
+    }
+
+    pub fn with_user_units(mut self, user_units: UserUnits) -> Self {
+        self.user_units = Some(user_units);
+        self
+    }
+
+    fn user_units(&self) -> Result<UserUnits> {
+        self.user_units
+            .ok_or(StandaError::Protocol("User units are not configured"))
+    }
+
+    fn track<T>(&self, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result {
+            let mut counters = self.counters.get();
+            counters.record_error(e);
+            self.counters.set(counters);
+        }
+
+        result
+    }
+
+    pub fn error_counters(&self) -> ErrorCounters {
+        self.counters.get()
+    }
+
+    pub fn reset_error_counters(&self) {
+        self.counters.set(ErrorCounters::default());
+    }
+
+    // Speed, acceleration and deceleration in one round trip
+    pub fn get_move_parameters(&self, sender: &mut (impl Write + Read)) -> Result<MOVEParameters> {
+        self.track(MOVEParameters::get(sender))
     }
 
     pub fn get_velocity(&self, sender: &mut (impl Write + Read)) -> Result<u32> {
-        Ok(MOVEParameters::get(sender)?.speed)
+        Ok(self.track(MOVEParameters::get(sender))?.speed)
+    }
+
+    // Speed, acceleration and deceleration share one settings block, so
+    // every change reads the block and writes it back in a single call.
+    // Changes made as separate calls may interleave with other callers.
+    pub fn update_move_parameters(
+        &self,
+        sender: &mut (impl Write + Read),
+        update: impl FnOnce(&mut MOVEParameters),
+    ) -> Result<()> {
+        let mut move_params = self.track(MOVEParameters::get(sender))?;
+        update(&mut move_params);
+        self.track(move_params.set(sender))
     }
 
     pub fn set_velocity(&self, sender: &mut (impl Write + Read), velocity: u32) -> Result<()> {
-        let mut move_params = MOVEParameters::get(sender)?;
-        move_params.speed = velocity;
-        move_params.set(sender)
+        self.update_move_parameters(sender, |move_params| move_params.speed = velocity)
     }
 
     pub fn get_acceleration(&self, sender: &mut (impl Write + Read)) -> Result<u16> {
-        Ok(MOVEParameters::get(sender)?.accel)
+        Ok(self.track(MOVEParameters::get(sender))?.accel)
     }
 
     pub fn set_acceleration(
@@ -33,13 +108,11 @@ impl Standa {
         sender: &mut (impl Write + Read),
         acceleration: u16,
     ) -> Result<()> {
-        let mut move_params = MOVEParameters::get(sender)?;
-        move_params.accel = acceleration;
-        move_params.set(sender)
+        self.update_move_parameters(sender, |move_params| move_params.accel = acceleration)
     }
 
     pub fn get_deceleration(&self, sender: &mut (impl Write + Read)) -> Result<u16> {
-        Ok(MOVEParameters::get(sender)?.decel)
+        Ok(self.track(MOVEParameters::get(sender))?.decel)
     }
 
     pub fn set_deceleration(
@@ -47,13 +120,41 @@ impl Standa {
         sender: &mut (impl Write + Read),
         deceleration: u16,
     ) -> Result<()> {
-        let mut move_params = MOVEParameters::get(sender)?;
-        move_params.decel = deceleration;
-        move_params.set(sender)
+        self.update_move_parameters(sender, |move_params| move_params.decel = deceleration)
     }
 
     pub fn get_state(&self, sender: &mut (impl Write + Read)) -> Result<StateParams> {
-        StateParams::get(sender)
+        let state = self.track(StateParams::get(sender))?;
+
+        let mut counters = self.counters.get();
+        counters.record_state(&state);
+        self.counters.set(counters);
+
+        Ok(state)
+    }
+
+    // Polls the state until the drive reports the move done and returns the
+    // final state
+    pub fn wait_for_stop(
+        &self,
+        sender: &mut (impl Write + Read),
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<StateParams> {
+        let start = Instant::now();
+
+        loop {
+            let state = self.get_state(sender)?;
+            if !state.is_moving() {
+                return Ok(state);
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(StandaError::MotionTimeout(timeout));
+            }
+
+            std::thread::sleep(poll_interval);
+        }
     }
 
     pub fn move_relative(
@@ -62,14 +163,198 @@ impl Standa {
         steps: i32,
         sub_steps: i16,
     ) -> Result<()> {
-        MOVR {
-            position: steps,
-            u_position: sub_steps,
+        self.track(
+            MOVR {
+                position: steps,
+                u_position: sub_steps,
+            }
+            .send(sender),
+        )
+    }
+
+    pub fn stop(&self, sender: &mut (impl Write + Read), mode: StopMode) -> Result<()> {
+        match mode {
+            StopMode::Immediate => self.track(STOP.send(sender)),
+            StopMode::Soft => self.track(SSTP.send(sender)),
         }
-        .send(sender)
     }
 
-    pub fn stop(&self, sender: &mut (impl Write + Read)) -> Result<()> {
-        STOP.send(sender)
+    pub fn get_position(&self, sender: &mut (impl Write + Read)) -> Result<PositionParams> {
+        self.track(PositionParams::get(sender))
+    }
+
+    // GETS and GPOS back to back on the one connection, with nothing else in
+    // between, so the state and the counters belong to the same moment
+    pub fn get_state_and_position(
+        &self,
+        sender: &mut (impl Write + Read),
+    ) -> Result<(StateParams, PositionParams)> {
+        let state = self.get_state(sender)?;
+        let position = self.get_position(sender)?;
+
+        Ok((state, position))
+    }
+
+    pub fn get_position_in_units(&self, sender: &mut (impl Write + Read)) -> Result<f64> {
+        let user_units = self.user_units()?;
+        let position = self.get_position(sender)?;
+
+        Ok(user_units.to_units(position.position, position.u_position))
+    }
+
+    pub fn move_relative_in_units(
+        &self,
+        sender: &mut (impl Write + Read),
+        distance: f64,
+    ) -> Result<()> {
+        let (steps, sub_steps) = self.user_units()?.to_steps(distance);
+
+        self.move_relative(sender, steps, sub_steps)
+    }
+
+    pub fn get_calibration_settings(
+        &self,
+        sender: &mut (impl Write + Read),
+    ) -> Result<CalibrationSettings> {
+        self.track(CalibrationSettings::get(sender))
+    }
+
+    pub fn set_calibration_settings(
+        &self,
+        sender: &mut (impl Write + Read),
+        settings: &CalibrationSettings,
+    ) -> Result<()> {
+        self.track(settings.set(sender))
+    }
+
+    // Without an encoder value only the step counter is overwritten
+    pub fn set_position(
+        &self,
+        sender: &mut (impl Write + Read),
+        position: i32,
+        u_position: i16,
+        enc_position: Option<i64>,
+    ) -> Result<()> {
+        let pos_flags = match enc_position {
+            Some(_) => PositionFlags::empty(),
+            None => PositionFlags::IGNORE_ENCODER,
+        };
+
+        self.track(
+            SPOS {
+                position,
+                u_position,
+                enc_position: enc_position.unwrap_or_default(),
+                pos_flags,
+            }
+            .send(sender),
+        )
+    }
+
+    pub fn get_device_information(
+        &self,
+        sender: &mut (impl Write + Read),
+    ) -> Result<DeviceInformation> {
+        self.track(DeviceInformation::get(sender))
+    }
+
+    pub fn get_serial_number(&self, sender: &mut (impl Write + Read)) -> Result<u32> {
+        Ok(self.track(SerialNumber::get(sender))?.serial_number)
+    }
+
+    pub fn get_firmware_version(
+        &self,
+        sender: &mut (impl Write + Read),
+    ) -> Result<FirmwareVersion> {
+        self.track(FirmwareVersion::get(sender))
+    }
+
+    pub fn identify(&self, sender: &mut (impl Write + Read)) -> Result<Identity> {
+        Ok(Identity {
+            information: self.get_device_information(sender)?,
+            serial_number: self.get_serial_number(sender)?,
+            firmware: self.get_firmware_version(sender)?,
+        })
+    }
+
+    // Winding currents and voltages as the driver measures them
+    pub fn get_chart_data(&self, sender: &mut (impl Write + Read)) -> Result<ChartData> {
+        self.track(ChartData::get(sender))
+    }
+
+    pub fn get_engine_settings(&self, sender: &mut (impl Write + Read)) -> Result<EngineSettings> {
+        self.track(EngineSettings::get(sender))
+    }
+
+    pub fn set_engine_settings(
+        &self,
+        sender: &mut (impl Write + Read),
+        settings: &EngineSettings,
+    ) -> Result<()> {
+        self.track(settings.set(sender))
+    }
+
+    pub fn get_power_settings(&self, sender: &mut (impl Write + Read)) -> Result<PowerSettings> {
+        self.track(PowerSettings::get(sender))
+    }
+
+    pub fn set_power_settings(
+        &self,
+        sender: &mut (impl Write + Read),
+        settings: &PowerSettings,
+    ) -> Result<()> {
+        self.track(settings.set(sender))
+    }
+
+    pub fn get_edges_settings(&self, sender: &mut (impl Write + Read)) -> Result<EdgesSettings> {
+        self.track(EdgesSettings::get(sender))
+    }
+
+    pub fn set_edges_settings(
+        &self,
+        sender: &mut (impl Write + Read),
+        settings: &EdgesSettings,
+    ) -> Result<()> {
+        self.track(settings.set(sender))
+    }
+
+    pub fn get_user_data(&self, sender: &mut (impl Write + Read)) -> Result<UserData> {
+        self.track(UserData::get(sender))
+    }
+
+    // Goes straight to flash, no SAVE needed
+    pub fn set_user_data(&self, sender: &mut (impl Write + Read), data: &UserData) -> Result<()> {
+        self.track(data.set(sender))
+    }
+
+    pub fn get_home_settings(&self, sender: &mut (impl Write + Read)) -> Result<HomeParameters> {
+        self.track(HomeParameters::get(sender))
+    }
+
+    pub fn set_home_settings(
+        &self,
+        sender: &mut (impl Write + Read),
+        settings: &HomeParameters,
+    ) -> Result<()> {
+        self.track(settings.set(sender))
+    }
+
+    // Starts the homing run and returns right away, the controller reports
+    // it as a move in the state
+    pub fn home(&self, sender: &mut (impl Write + Read)) -> Result<()> {
+        self.track(HOME.send(sender))
+    }
+
+    // The current position becomes zero, e.g. after mechanical alignment
+    pub fn zero(&self, sender: &mut (impl Write + Read)) -> Result<()> {
+        self.track(ZERO.send(sender))
+    }
+
+    pub fn save_settings(&self, sender: &mut (impl Write + Read)) -> Result<()> {
+        self.track(SAVE.send(sender))
+    }
+
+    pub fn restore_settings(&self, sender: &mut (impl Write + Read)) -> Result<()> {
+        self.track(READ.send(sender))
     }
 }
@@ -0,0 +1,53 @@
+use utilities::conformance::{Failure, argument, run_vectors, split_call};
+
+use crate::Trid;
+
+pub const VECTORS: &str = include_str!("../vectors/registers.txt");
+
+// Replays the register vectors against the driver, see
+// utilities::conformance for the format
+pub fn check(vectors: &str) -> Result<Vec<Failure>, String> {
+    let mut trid = Trid::new(1, 2)
+        .with_setpoint_register(0x0100)
+        .with_control_register(0x0101)
+        .with_relay_coil(0x0010);
+
+    run_vectors(vectors, |call, port| {
+        let result = match split_call(call).0 {
+            "read_data" => trid.read_data(port).map(|v| v.to_string()),
+            "read_setpoint" => trid.read_setpoint(port).map(|v| v.to_string()),
+            "write_setpoint" => trid
+                .write_setpoint(port, argument(call)?)
+                .map(|_| String::new()),
+            "set_control_enabled" => trid
+                .set_control_enabled(port, argument::<u8>(call)? != 0)
+                .map(|_| String::new()),
+            "is_relay_on" => trid.is_relay_on(port).map(|on| on.to_string()),
+            "set_relay" => trid
+                .set_relay(port, argument::<u8>(call)? != 0)
+                .map(|_| String::new()),
+            name => return Err(format!("Unknown call: {}", name)),
+        };
+
+        result.map_err(|e| e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_pass() {
+        let failures = check(VECTORS).unwrap();
+        assert!(
+            failures.is_empty(),
+            "{}",
+            failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::{error::Error, fmt, io};
+
+use utilities::{error_code::ErrorCode, modbus::ModbusError};
+
+#[derive(Debug)]
+pub enum TridError {
+    Modbus(ModbusError),
+    // The reading is outside of the valid range, the probe is disconnected
+    SensorMissing,
+    OutOfRange,
+    InvalidResponse(&'static str),
+    NotConfigured(&'static str),
+}
+
+impl TridError {
+    // Handlers pass errors on as io::Error, this looks through that
+    pub fn is_sensor_missing(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .and_then(|error| error.downcast_ref::<TridError>())
+            .is_some_and(|error| matches!(error, TridError::SensorMissing))
+    }
+}
+
+impl fmt::Display for TridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TridError::Modbus(err) => write!(f, "{}", err),
+            TridError::SensorMissing => write!(f, "Temp sensors are missing"),
+            TridError::OutOfRange => write!(f, "Value out of range"),
+            TridError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+            TridError::NotConfigured(register) => {
+                write!(f, "{} address is not configured", register)
+            }
+        }
+    }
+}
+
+impl Error for TridError {}
+
+impl From<ModbusError> for TridError {
+    fn from(error: ModbusError) -> Self {
+        TridError::Modbus(error)
+    }
+}
+
+// The TridError is kept inside so is_sensor_missing still works
+impl From<TridError> for io::Error {
+    fn from(error: TridError) -> Self {
+        let kind = match error {
+            TridError::Modbus(ModbusError::IoError(error)) => return error,
+            TridError::OutOfRange => io::ErrorKind::InvalidInput,
+            TridError::NotConfigured(_) => io::ErrorKind::Unsupported,
+            _ => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
+
+impl From<&TridError> for ErrorCode {
+    fn from(error: &TridError) -> Self {
+        match error {
+            TridError::Modbus(error) => error.into(),
+            TridError::SensorMissing => ErrorCode::SensorMissing,
+            TridError::OutOfRange => ErrorCode::OutOfRange,
+            TridError::InvalidResponse(_) => ErrorCode::InvalidResponse,
+            TridError::NotConfigured(_) => ErrorCode::NotConfigured,
+        }
+    }
+}
@@ -1,114 +1,278 @@
-use std::io::{Read, Write};
+use std::{
+    io::{Read, Write},
+    ops::RangeInclusive,
+};
 
-#[derive(Debug, Clone, Copy)]
+use utilities::modbus::{Modbus, ModbusError};
+
+pub mod conformance;
+mod error;
+pub use error::TridError;
+
+// Factory setting, one decimal place and a range that catches open sensors
+const DEFAULT_DECIMAL_PLACES: u8 = 1;
+const DEFAULT_VALID_RANGE: (f32, f32) = (0.0, 200.0);
+
+#[derive(Clone)]
 pub struct Trid {
-    device_id: u8,
+    client: Modbus,
     axis: u16,
+    // Only regulator models have these, and their location depends on the
+    // model, so they come from the configuration
+    setpoint_register: Option<u16>,
+    control_register: Option<u16>,
+    // First alarm setpoint, channel N uses the register N places further
+    alarm_register: Option<u16>,
+    // Decimal point setting of the first channel, laid out like the alarms.
+    // Read once and kept, without it the factory setting is assumed.
+    decimal_point_register: Option<u16>,
+    decimal_places: Option<u8>,
+    // Readings outside of it mean a missing or broken sensor
+    valid_range: (f32, f32),
+    // Output relay wired to this channel's interlock
+    relay_coil: Option<u16>,
 }
 
 impl Trid {
     pub fn new(device_id: u8, axis: u16) -> Self {
-        Trid { device_id, axis }
+        Trid {
+            client: Modbus::new(device_id),
+            axis,
+            setpoint_register: None,
+            control_register: None,
+            alarm_register: None,
+            decimal_point_register: None,
+            decimal_places: None,
+            valid_range: DEFAULT_VALID_RANGE,
+            relay_coil: None,
+        }
+    }
+
+    pub fn with_setpoint_register(mut self, register_address: u16) -> Self {
+        self.setpoint_register = Some(register_address);
+        self
+    }
+
+    pub fn with_control_register(mut self, register_address: u16) -> Self {
+        self.control_register = Some(register_address);
+        self
+    }
+
+    pub fn with_alarm_register(mut self, register_address: u16) -> Self {
+        self.alarm_register = Some(register_address);
+        self
+    }
+
+    pub fn with_decimal_point_register(mut self, register_address: u16) -> Self {
+        self.decimal_point_register = Some(register_address);
+        self
+    }
+
+    pub fn with_valid_range(mut self, min: f32, max: f32) -> Self {
+        self.valid_range = (min, max);
+        self
+    }
+
+    pub fn with_relay_coil(mut self, coil_address: u16) -> Self {
+        self.relay_coil = Some(coil_address);
+        self
+    }
+
+    // Probes every ID in the range by reading the first channel and returns
+    // the ones that answered. An exception reply still means a unit listens
+    // at that ID, silence and garbage do not.
+    pub fn scan_bus(sender: &mut (impl Write + Read), id_range: RangeInclusive<u8>) -> Vec<u8> {
+        id_range
+            .filter(|&id| {
+                matches!(
+                    Modbus::new(id).read_holding_register(sender, 0),
+                    Ok(_) | Err(ModbusError::ExceptionResponse { .. })
+                )
+            })
+            .collect()
+    }
+
+    pub fn get_axis(&self) -> u16 {
+        self.axis
     }
 
     pub fn get_device_id(&self) -> u8 {
-        self.device_id
+        self.client.id()
     }
 
     pub fn set_device_id(&mut self, device_id: u8) {
-        self.device_id = device_id;
+        self.client.set_id(device_id);
     }
 
     pub fn read_holding_register(
         &self,
         sender: &mut (impl Write + Read),
         register_address: u16,
-    ) -> std::io::Result<Vec<u8>> {
-        let register_count = 1;
-
-        let mut request = vec![
-            self.device_id,
-            0x03,
-            (register_address >> 8) as u8,
-            (register_address & 0xFF) as u8,
-            (register_count >> 8) as u8,
-            (register_count & 0xFF) as u8,
-        ];
-
-        let crc = self.calculate_crc(&request);
-        request.push((crc & 0xFF) as u8);
-        request.push((crc >> 8) as u8);
-
-        sender.write_all(&request)?;
-
-        let mut response_header = vec![0; 3];
-        sender.read_exact(&mut response_header)?;
-
-        if response_header[0] != self.device_id || response_header[1] != 0x03 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid response header",
-            ));
-        }
+    ) -> Result<u16, TridError> {
+        Ok(self
+            .client
+            .read_holding_register(sender, register_address)?)
+    }
 
-        let byte_count = response_header[2] as usize;
-        let mut response_data = vec![0; byte_count + 2];
-        sender.read_exact(&mut response_data)?;
+    pub fn write_holding_register(
+        &self,
+        sender: &mut (impl Write + Read),
+        register_address: u16,
+        value: u16,
+    ) -> Result<(), TridError> {
+        Ok(self
+            .client
+            .write_single_register(sender, register_address, value)?)
+    }
 
-        let mut full_response = response_header.clone();
-        full_response.extend_from_slice(&response_data);
+    pub fn read_coil(
+        &self,
+        sender: &mut (impl Write + Read),
+        coil_address: u16,
+    ) -> Result<bool, TridError> {
+        Ok(self.client.read_coil(sender, coil_address)?)
+    }
 
-        let received_crc =
-            ((response_data[byte_count + 1] as u16) << 8) | (response_data[byte_count] as u16);
-        let calculated_crc = self.calculate_crc(&full_response[0..full_response.len() - 2]);
+    pub fn write_coil(
+        &self,
+        sender: &mut (impl Write + Read),
+        coil_address: u16,
+        on: bool,
+    ) -> Result<(), TridError> {
+        Ok(self.client.write_single_coil(sender, coil_address, on)?)
+    }
 
-        if received_crc != calculated_crc {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "CRC check failed",
-            ));
+    pub fn decimal_places(&mut self, sender: &mut (impl Write + Read)) -> Result<u8, TridError> {
+        if let Some(decimal_places) = self.decimal_places {
+            return Ok(decimal_places);
         }
 
-        Ok(response_data[0..byte_count].to_vec())
+        let Some(base) = self.decimal_point_register else {
+            return Ok(DEFAULT_DECIMAL_PLACES);
+        };
+        let register_address = base.checked_add(self.axis).ok_or(TridError::OutOfRange)?;
+
+        let decimal_places = match self.read_holding_register(sender, register_address)? {
+            value @ 0..=3 => value as u8,
+            _ => return Err(TridError::InvalidResponse("invalid decimal point setting")),
+        };
+
+        self.decimal_places = Some(decimal_places);
+        Ok(decimal_places)
     }
 
-    fn calculate_crc(&self, data: &[u8]) -> u16 {
-        let mut crc = 0xFFFF;
+    fn scale(&mut self, sender: &mut (impl Write + Read)) -> Result<f32, TridError> {
+        Ok(10f32.powi(self.decimal_places(sender)? as i32))
+    }
 
-        for byte in data {
-            crc ^= *byte as u16;
+    pub fn read_data(&mut self, sender: &mut (impl Write + Read)) -> Result<f32, TridError> {
+        let value = self.read_temperature_register(sender, self.axis)?;
 
-            for _ in 0..8 {
-                if (crc & 0x0001) != 0 {
-                    crc >>= 1;
-                    crc ^= 0xA001;
-                } else {
-                    crc >>= 1;
-                }
-            }
+        let (min, max) = self.valid_range;
+        if value < min || value > max {
+            return Err(TridError::SensorMissing);
         }
 
-        crc
+        Ok(value)
     }
 
-    pub fn read_data(&self, sender: &mut (impl Write + Read)) -> std::io::Result<f32> {
-        let result = self.read_holding_register(sender, self.axis)?;
-        if result.len() < 2 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Response too short",
-            ));
-        }
+    fn setpoint_register(&self) -> Result<u16, TridError> {
+        self.setpoint_register
+            .ok_or(TridError::NotConfigured("Setpoint"))
+    }
 
-        let value = (((result[0] as u16) << 8) | (result[1] as u16)) as f32 / 10.0;
+    fn control_register(&self) -> Result<u16, TridError> {
+        self.control_register
+            .ok_or(TridError::NotConfigured("Control"))
+    }
+
+    fn relay_coil(&self) -> Result<u16, TridError> {
+        self.relay_coil.ok_or(TridError::NotConfigured("Relay"))
+    }
+
+    fn alarm_register(&self, channel: u16) -> Result<u16, TridError> {
+        let base = self
+            .alarm_register
+            .ok_or(TridError::NotConfigured("Alarm"))?;
+
+        base.checked_add(channel).ok_or(TridError::OutOfRange)
+    }
+
+    // Setpoints and alarms use the same resolution as measurements
+    fn read_temperature_register(
+        &mut self,
+        sender: &mut (impl Write + Read),
+        register_address: u16,
+    ) -> Result<f32, TridError> {
+        let scale = self.scale(sender)?;
+
+        let value = self.read_holding_register(sender, register_address)?;
 
-        if value < 0.0 || value > 200.0 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Temp sensors are missing",
-            ));
+        // Signed, so that sub-zero readings survive
+        Ok(value as i16 as f32 / scale)
+    }
+
+    fn write_temperature_register(
+        &mut self,
+        sender: &mut (impl Write + Read),
+        register_address: u16,
+        temperature: f32,
+    ) -> Result<(), TridError> {
+        let value = (temperature * self.scale(sender)?).round();
+
+        if !(i16::MIN as f32..=i16::MAX as f32).contains(&value) {
+            return Err(TridError::OutOfRange);
         }
 
-        Ok(value)
+        self.write_holding_register(sender, register_address, value as i16 as u16)
+    }
+
+    pub fn read_setpoint(&mut self, sender: &mut (impl Write + Read)) -> Result<f32, TridError> {
+        self.read_temperature_register(sender, self.setpoint_register()?)
+    }
+
+    pub fn write_setpoint(
+        &mut self,
+        sender: &mut (impl Write + Read),
+        setpoint: f32,
+    ) -> Result<(), TridError> {
+        self.write_temperature_register(sender, self.setpoint_register()?, setpoint)
+    }
+
+    pub fn get_alarm_threshold(
+        &mut self,
+        sender: &mut (impl Write + Read),
+        channel: u16,
+    ) -> Result<f32, TridError> {
+        self.read_temperature_register(sender, self.alarm_register(channel)?)
+    }
+
+    pub fn set_alarm_threshold(
+        &mut self,
+        sender: &mut (impl Write + Read),
+        channel: u16,
+        temperature: f32,
+    ) -> Result<(), TridError> {
+        self.write_temperature_register(sender, self.alarm_register(channel)?, temperature)
+    }
+
+    pub fn is_control_enabled(&self, sender: &mut (impl Write + Read)) -> Result<bool, TridError> {
+        Ok(self.read_holding_register(sender, self.control_register()?)? != 0)
+    }
+
+    pub fn set_control_enabled(
+        &self,
+        sender: &mut (impl Write + Read),
+        enabled: bool,
+    ) -> Result<(), TridError> {
+        self.write_holding_register(sender, self.control_register()?, enabled as u16)
+    }
+
+    pub fn is_relay_on(&self, sender: &mut (impl Write + Read)) -> Result<bool, TridError> {
+        self.read_coil(sender, self.relay_coil()?)
+    }
+
+    pub fn set_relay(&self, sender: &mut (impl Write + Read), on: bool) -> Result<(), TridError> {
+        self.write_coil(sender, self.relay_coil()?, on)
     }
 }
@@ -0,0 +1,314 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use motarem::{axis::state::AxisState, motor_controller::MotorController};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::TcpStream,
+    task::JoinHandle,
+};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_BASE: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(300);
+
+fn default_measurement() -> String {
+    "axis".to_string()
+}
+
+fn default_interval_ms() -> u64 {
+    10_000
+}
+
+fn default_batch_size() -> usize {
+    500
+}
+
+fn default_max_buffered() -> usize {
+    100_000
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ArchiverConfig {
+    // InfluxDB write endpoint with the query string, e.g.
+    // "http://influx:8086/api/v2/write?org=beamline&bucket=slits&precision=ns"
+    pub url: String,
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default = "default_measurement")]
+    pub measurement: String,
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    // Points per write request
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    // Points kept while the archive is unreachable, the oldest go first
+    #[serde(default = "default_max_buffered")]
+    pub max_buffered: usize,
+    // Attributes to sample, every available one when empty
+    #[serde(default)]
+    pub attributes: Vec<String>,
+}
+
+pub struct Sample {
+    pub controller: String,
+    pub axis: String,
+    // None when the state could not be read
+    pub moving: Option<bool>,
+    pub fields: Vec<(String, f64)>,
+    // Nanoseconds since the epoch
+    pub timestamp: u128,
+}
+
+// Where samples end up. Only InfluxDB for now, anything else that takes
+// batches of samples can be plugged into the archiver the same way.
+#[async_trait::async_trait]
+pub trait ArchiveSink: Send + Sync {
+    async fn write(&self, samples: &[Sample]) -> Result<(), String>;
+}
+
+fn escape(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+// InfluxDB line protocol over plain HTTP
+pub struct InfluxSink {
+    host: String,
+    path: String,
+    token: Option<String>,
+    measurement: String,
+}
+
+impl InfluxSink {
+    pub fn new(config: &ArchiverConfig) -> Result<Self, String> {
+        let rest = config
+            .url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("Only http:// archive URLs are supported: {}", config.url))?;
+
+        let (host, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let host = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+
+        Ok(Self {
+            host,
+            path: path.to_string(),
+            token: config.token.clone(),
+            measurement: config.measurement.clone(),
+        })
+    }
+
+    pub fn line(&self, sample: &Sample) -> String {
+        let mut line = format!(
+            "{},controller={},axis={} ",
+            escape(&self.measurement, &[',', ' ']),
+            escape(&sample.controller, &[',', '=', ' ']),
+            escape(&sample.axis, &[',', '=', ' ']),
+        );
+
+        // A point needs at least one field, reachability always goes in
+        let _ = write!(line, "online={}", sample.moving.is_some());
+        if let Some(moving) = sample.moving {
+            let _ = write!(line, ",moving={}", moving);
+        }
+        for (name, value) in &sample.fields {
+            if value.is_finite() {
+                let _ = write!(line, ",{}={}", escape(name, &[',', '=', ' ']), value);
+            }
+        }
+
+        let _ = write!(line, " {}", sample.timestamp);
+        line
+    }
+
+    async fn post(&self, body: &str) -> Result<(), String> {
+        let mut stream = TcpStream::connect(&self.host)
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", self.host, e))?;
+
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.path,
+            self.host,
+            body.len()
+        );
+        if let Some(token) = &self.token {
+            let _ = write!(request, "Authorization: Token {}\r\n", token);
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to send to {}: {}", self.host, e))?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .await
+            .map_err(|e| format!("Failed to read from {}: {}", self.host, e))?;
+
+        let response = String::from_utf8_lossy(&response);
+        let status = response.lines().next().unwrap_or_default();
+
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(format!("Archive refused the write: {}", status)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ArchiveSink for InfluxSink {
+    async fn write(&self, samples: &[Sample]) -> Result<(), String> {
+        let body = samples
+            .iter()
+            .map(|sample| self.line(sample))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        tokio::time::timeout(HTTP_TIMEOUT, self.post(&body))
+            .await
+            .map_err(|_| format!("Timed out writing to {}", self.host))?
+    }
+}
+
+fn now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+async fn sample_controller(
+    controller: &Arc<dyn MotorController>,
+    attributes: &[String],
+    samples: &mut VecDeque<Sample>,
+) {
+    for axis in controller.axes() {
+        let moving = axis
+            .get_state()
+            .await
+            .ok()
+            .map(|state| matches!(state.state, AxisState::Moving));
+
+        let names = if attributes.is_empty() {
+            axis.get_available_params().await.unwrap_or_default()
+        } else {
+            attributes.to_vec()
+        };
+
+        let mut fields = Vec::new();
+        for name in names {
+            // Missing attributes are left out of the point rather than
+            // failing the whole sample
+            if let Ok(value) = axis.get_attribute(&name).await {
+                fields.push((name, value));
+            }
+        }
+
+        samples.push_back(Sample {
+            controller: controller.name().to_string(),
+            axis: axis.name().to_string(),
+            moving,
+            fields,
+            timestamp: now(),
+        });
+    }
+}
+
+// Samples every axis of the controllers each interval and writes them out in
+// batches. Failed writes stay buffered and are retried with a growing delay,
+// so an archive outage leaves a gap in the timing, not in the data.
+pub fn spawn_archiver(
+    config: &ArchiverConfig,
+    controllers: &[Arc<dyn MotorController>],
+    sink: Arc<dyn ArchiveSink>,
+) -> JoinHandle<()> {
+    let config = config.clone();
+    let controllers = controllers.to_vec();
+
+    tokio::spawn(async move {
+        let interval = Duration::from_millis(config.interval_ms);
+        let mut samples = VecDeque::new();
+        let mut retry_delay = Duration::ZERO;
+        let mut retry_at = tokio::time::Instant::now();
+        let mut dropped = 0usize;
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            for controller in &controllers {
+                sample_controller(controller, &config.attributes, &mut samples).await;
+            }
+
+            while samples.len() > config.max_buffered {
+                samples.pop_front();
+                dropped += 1;
+            }
+
+            if tokio::time::Instant::now() < retry_at {
+                continue;
+            }
+
+            while !samples.is_empty() {
+                let len = samples.len().min(config.batch_size.max(1));
+                let batch = &samples.make_contiguous()[..len];
+
+                match sink.write(batch).await {
+                    Ok(()) => {
+                        samples.drain(..len);
+                        retry_delay = Duration::ZERO;
+                    }
+                    Err(e) => {
+                        retry_delay = (retry_delay * 2).clamp(RETRY_BASE, RETRY_MAX);
+                        retry_at = tokio::time::Instant::now() + retry_delay;
+                        eprintln!(
+                            "Archiver: {}, {} samples buffered, retrying in {:?}",
+                            e,
+                            samples.len(),
+                            retry_delay
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if dropped > 0 {
+                eprintln!("Archiver: dropped {} samples, buffer full", dropped);
+                dropped = 0;
+            }
+        }
+    })
+}
+
+// The configured sink for the controllers, InfluxDB being the only one
+pub fn start_archiver(
+    config: &ArchiverConfig,
+    controllers: &[Arc<dyn MotorController>],
+) -> Result<JoinHandle<()>, String> {
+    let sink = InfluxSink::new(config)?;
+
+    Ok(spawn_archiver(config, controllers, Arc::new(sink)))
+}
@@ -0,0 +1,192 @@
+use std::sync::{Arc, Mutex};
+
+use motarem::axis::{
+    Axis, limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+    state_info::AxisStateInfo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::ClockInstant;
+
+// Travel of a simulated axis per second of simulation time, in its units
+const SIMULATED_SPEED: f64 = 1.0;
+
+// How a configured axis is served. Simulated and disabled axes never reach
+// their devices, so hardware that is out for repairs does not flood the log
+// with errors while the rest of the setup runs on real hardware.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisMode {
+    #[default]
+    Hardware,
+    // Moves run at a fixed speed on the simulation clock, see utilities::clock
+    Simulated,
+    // Listed, but refuses moves and has no attributes
+    Disabled,
+}
+
+pub fn with_mode(axis: Arc<dyn Axis>, mode: AxisMode) -> Arc<dyn Axis> {
+    match mode {
+        AxisMode::Hardware => axis,
+        AxisMode::Simulated => Arc::new(SimulatedAxis::new(axis.name().to_string())),
+        AxisMode::Disabled => Arc::new(DisabledAxis {
+            name: axis.name().to_string(),
+        }),
+    }
+}
+
+// Straight line from one position to the target, started at `started`
+struct Motion {
+    from: f64,
+    to: f64,
+    started: ClockInstant,
+}
+
+impl Motion {
+    fn position(&self) -> f64 {
+        let travelled = self.started.elapsed().as_secs_f64() * SIMULATED_SPEED;
+        let distance = self.to - self.from;
+
+        if travelled >= distance.abs() {
+            self.to
+        } else {
+            self.from + travelled.copysign(distance)
+        }
+    }
+
+    fn is_moving(&self) -> bool {
+        self.position() != self.to
+    }
+}
+
+pub struct SimulatedAxis {
+    name: String,
+    motion: Mutex<Motion>,
+}
+
+impl SimulatedAxis {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            motion: Mutex::new(Motion {
+                from: 0.0,
+                to: 0.0,
+                started: ClockInstant::now(),
+            }),
+        }
+    }
+
+    // Starts over from wherever the axis is at the moment
+    fn move_to(&self, target: impl FnOnce(f64) -> f64) {
+        let mut motion = self.motion.lock().unwrap();
+        let position = motion.position();
+
+        *motion = Motion {
+            from: position,
+            to: target(position),
+            started: ClockInstant::now(),
+        };
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for SimulatedAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(
+        &self,
+        position: f64,
+        _parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        self.move_to(|_| position);
+        Ok(())
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.move_to(|current| current);
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        let state = if self.motion.lock().unwrap().is_moving() {
+            AxisState::Moving
+        } else {
+            AxisState::On
+        };
+
+        Ok(AxisStateInfo {
+            state,
+            message: Some("Simulated".to_string()),
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        match name {
+            "position" => Ok(self.motion.lock().unwrap().position()),
+            "simulated" => Ok(1.0),
+            _ => Err(anyhow::anyhow!("Unknown attribute: {}", name)),
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(vec!["position".to_string(), "simulated".to_string()])
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+pub struct DisabledAxis {
+    name: String,
+}
+
+#[async_trait::async_trait]
+impl Axis for DisabledAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(
+        &self,
+        _position: f64,
+        _parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "{} is disabled in the configuration",
+            self.name
+        ))
+    }
+
+    // Nothing is moving, a controller-wide stop must not fail on this axis
+    async fn stop(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        Ok(AxisStateInfo {
+            state: AxisState::On,
+            message: Some("Disabled in the configuration".to_string()),
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        Err(anyhow::anyhow!(
+            "{} is disabled in the configuration, no attribute {}",
+            self.name,
+            name
+        ))
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,227 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Compact framing for high-rate attribute streams. Every frame is
+//
+//   u32 length of what follows, u8 kind, body
+//
+// little endian throughout. A fields frame (kind 1) names the streamed
+// attributes once per connection:
+//
+//   u16 count, then per field: u16 index, u8 decimals, u8 name length, name
+//
+// and sample frames (kind 2) carry only numbers:
+//
+//   u64 microseconds since the epoch, u16 count,
+//   then per value: u16 field index, i32 value * 10^decimals
+//
+// A value that could not be read, or does not fit, is sent as i32::MIN.
+
+pub const KIND_FIELDS: u8 = 1;
+pub const KIND_SAMPLE: u8 = 2;
+
+pub const MISSING: i32 = i32::MIN;
+
+const LENGTH_LEN: usize = 4;
+const MAX_DECIMALS: u8 = 9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSpec {
+    // Usually "controller/axis/attribute"
+    pub name: String,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Fields(Vec<(u16, FieldSpec)>),
+    Sample {
+        timestamp_us: u64,
+        values: Vec<(u16, Option<f64>)>,
+    },
+}
+
+fn frame(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(LENGTH_LEN + 1 + body.len());
+
+    frame.extend_from_slice(&(body.len() as u32 + 1).to_le_bytes());
+    frame.push(kind);
+    frame.extend_from_slice(body);
+
+    frame
+}
+
+fn scale(decimals: u8) -> f64 {
+    10f64.powi(decimals as i32)
+}
+
+pub fn now_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+pub struct FrameEncoder {
+    fields: Vec<FieldSpec>,
+}
+
+impl FrameEncoder {
+    pub fn new(fields: Vec<FieldSpec>) -> Result<Self, String> {
+        if fields.len() > u16::MAX as usize {
+            return Err(format!("Too many fields: {}", fields.len()));
+        }
+
+        for field in &fields {
+            if field.name.len() > u8::MAX as usize {
+                return Err(format!("Field name too long: {}", field.name));
+            }
+            if field.decimals > MAX_DECIMALS {
+                return Err(format!(
+                    "{} decimals of {} exceed {}",
+                    field.decimals, field.name, MAX_DECIMALS
+                ));
+            }
+        }
+
+        Ok(Self { fields })
+    }
+
+    // Sent once before the first sample
+    pub fn fields_frame(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&(self.fields.len() as u16).to_le_bytes());
+
+        for (index, field) in self.fields.iter().enumerate() {
+            body.extend_from_slice(&(index as u16).to_le_bytes());
+            body.push(field.decimals);
+            body.push(field.name.len() as u8);
+            body.extend_from_slice(field.name.as_bytes());
+        }
+
+        frame(KIND_FIELDS, &body)
+    }
+
+    // Values in field order, None for the ones that could not be read
+    pub fn sample_frame(&self, timestamp_us: u64, values: &[Option<f64>]) -> Vec<u8> {
+        let count = values.len().min(self.fields.len());
+
+        let mut body = Vec::with_capacity(8 + 2 + count * 6);
+        body.extend_from_slice(&timestamp_us.to_le_bytes());
+        body.extend_from_slice(&(count as u16).to_le_bytes());
+
+        for (index, (field, value)) in self.fields.iter().zip(values).enumerate() {
+            let fixed = value
+                .map(|value| (value * scale(field.decimals)).round())
+                .filter(|value| *value > MISSING as f64 && *value <= i32::MAX as f64)
+                .map(|value| value as i32)
+                .unwrap_or(MISSING);
+
+            body.extend_from_slice(&(index as u16).to_le_bytes());
+            body.extend_from_slice(&fixed.to_le_bytes());
+        }
+
+        frame(KIND_SAMPLE, &body)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < len {
+            return Err("Truncated frame".to_string());
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+// Client side. Returns the frame and the bytes it used, or None until the
+// buffer holds a whole frame. Sample values are scaled back with the
+// decimals of the fields frame.
+pub fn decode_frame(
+    buffer: &[u8],
+    fields: &[(u16, FieldSpec)],
+) -> Result<Option<(Frame, usize)>, String> {
+    if buffer.len() < LENGTH_LEN {
+        return Ok(None);
+    }
+
+    let length = u32::from_le_bytes(buffer[..LENGTH_LEN].try_into().unwrap()) as usize;
+    if length == 0 {
+        return Err("Empty frame".to_string());
+    }
+    if buffer.len() < LENGTH_LEN + length {
+        return Ok(None);
+    }
+
+    let mut reader = Reader {
+        bytes: &buffer[LENGTH_LEN..LENGTH_LEN + length],
+    };
+
+    let frame = match reader.u8()? {
+        KIND_FIELDS => {
+            let count = reader.u16()?;
+            let mut fields = Vec::with_capacity(count as usize);
+
+            for _ in 0..count {
+                let index = reader.u16()?;
+                let decimals = reader.u8()?;
+                let len = reader.u8()? as usize;
+                let name = String::from_utf8(reader.take(len)?.to_vec())
+                    .map_err(|_| "Field name is not UTF-8".to_string())?;
+
+                fields.push((index, FieldSpec { name, decimals }));
+            }
+
+            Frame::Fields(fields)
+        }
+        KIND_SAMPLE => {
+            let timestamp_us = reader.u64()?;
+            let count = reader.u16()?;
+            let mut values = Vec::with_capacity(count as usize);
+
+            for _ in 0..count {
+                let index = reader.u16()?;
+                let fixed = reader.i32()?;
+
+                let decimals = fields
+                    .iter()
+                    .find(|(field, _)| *field == index)
+                    .map(|(_, spec)| spec.decimals)
+                    .ok_or_else(|| format!("Unknown field {}", index))?;
+
+                let value = (fixed != MISSING).then(|| fixed as f64 / scale(decimals));
+                values.push((index, value));
+            }
+
+            Frame::Sample {
+                timestamp_us,
+                values,
+            }
+        }
+        kind => return Err(format!("Unknown frame kind {}", kind)),
+    };
+
+    Ok(Some((frame, LENGTH_LEN + length)))
+}
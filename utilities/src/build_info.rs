@@ -0,0 +1,237 @@
+use std::{fmt, sync::Arc};
+
+use motarem::{
+    axis::{
+        Axis, limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+        state_info::AxisStateInfo,
+    },
+    motor_controller::MotorController,
+};
+
+// Set by build.rs
+const GIT_COMMIT: &str = env!("BUILD_GIT_COMMIT");
+const GIT_DIRTY: &str = env!("BUILD_GIT_DIRTY");
+const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+// Axis the build is reported under, `STATE build` gives the whole text and
+// `GET build ALL` the numbers
+pub const BUILD_AXIS: &str = "build";
+
+// Which build runs on a host. The package and version are the binary's, so
+// it is created through build_info!().
+#[derive(Debug, Clone)]
+pub struct BuildInfo {
+    pub package: &'static str,
+    pub version: &'static str,
+    pub commit: &'static str,
+    // Built with uncommitted changes
+    pub dirty: bool,
+    // Seconds since the epoch
+    pub built_at: u64,
+    pub profile: &'static str,
+    // Optional parts this instance runs with
+    pub features: Vec<&'static str>,
+}
+
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::build_info::BuildInfo::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    };
+}
+
+pub fn version_requested() -> bool {
+    std::env::args()
+        .skip(1)
+        .any(|arg| arg == "--version" || arg == "-V")
+}
+
+impl BuildInfo {
+    pub fn new(package: &'static str, version: &'static str) -> Self {
+        Self {
+            package,
+            version,
+            commit: GIT_COMMIT,
+            dirty: GIT_DIRTY == "true",
+            built_at: BUILD_TIMESTAMP.parse().unwrap_or(0),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            },
+            features: Vec::new(),
+        }
+    }
+
+    pub fn with_feature(mut self, feature: &'static str, enabled: bool) -> Self {
+        if enabled {
+            self.features.push(feature);
+        }
+        self
+    }
+
+    // major * 10000 + minor * 100 + patch
+    fn version_number(&self) -> f64 {
+        self.version
+            .split(['.', '-', '+'])
+            .take(3)
+            .map(|part| part.parse::<f64>().unwrap_or(0.0))
+            .fold(0.0, |number, part| number * 100.0 + part)
+    }
+
+    // The 12 digit abbreviation fits an f64 exactly, NaN without git
+    fn commit_number(&self) -> f64 {
+        u64::from_str_radix(self.commit, 16).map_or(f64::NAN, |commit| commit as f64)
+    }
+}
+
+// Days since the epoch to a UTC date, from Howard Hinnant's civil_from_days
+fn utc(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ({}{}, built {}, {})",
+            self.package,
+            self.version,
+            self.commit,
+            if self.dirty { "-dirty" } else { "" },
+            utc(self.built_at),
+            self.profile
+        )?;
+
+        if !self.features.is_empty() {
+            write!(f, " with {}", self.features.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct BuildAxis {
+    info: BuildInfo,
+}
+
+#[async_trait::async_trait]
+impl Axis for BuildAxis {
+    fn name(&self) -> &str {
+        BUILD_AXIS
+    }
+
+    async fn start(
+        &self,
+        _position: f64,
+        _parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("{} does not move", BUILD_AXIS))
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        Ok(AxisStateInfo {
+            state: AxisState::On,
+            message: Some(self.info.to_string()),
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        match name {
+            "version" => Ok(self.info.version_number()),
+            "commit" => Ok(self.info.commit_number()),
+            "dirty" => Ok(self.info.dirty as u8 as f64),
+            "built_at" => Ok(self.info.built_at as f64),
+            _ => match name.strip_prefix("feature_") {
+                Some(feature) => Ok(self.info.features.contains(&feature) as u8 as f64),
+                None => Err(anyhow::anyhow!("Unknown attribute: {}", name)),
+            },
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params: Vec<String> = ["version", "commit", "dirty", "built_at"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        params.extend(
+            self.info
+                .features
+                .iter()
+                .map(|feature| format!("feature_{}", feature)),
+        );
+
+        Ok(params)
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+pub struct BuildInfoController {
+    inner: Arc<dyn MotorController>,
+    axes: Vec<Arc<dyn Axis>>,
+}
+
+#[async_trait::async_trait]
+impl MotorController for BuildInfoController {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn axes(&self) -> Vec<Arc<dyn Axis>> {
+        self.axes.clone()
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+pub fn with_build_info(
+    controller: Arc<dyn MotorController>,
+    info: &BuildInfo,
+) -> Result<Arc<dyn MotorController>, String> {
+    let axes = controller.axes();
+    if axes.iter().any(|axis| axis.name() == BUILD_AXIS) {
+        return Err(format!(
+            "{} already has an axis named {}",
+            controller.name(),
+            BUILD_AXIS
+        ));
+    }
+
+    let build = Arc::new(BuildAxis { info: info.clone() }) as Arc<dyn Axis>;
+
+    Ok(Arc::new(BuildInfoController {
+        inner: controller,
+        axes: axes.into_iter().chain(std::iter::once(build)).collect(),
+    }))
+}
@@ -0,0 +1,155 @@
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::watch;
+
+use crate::clock;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Reading {
+    pub value: f32,
+    pub stale: bool,
+    // When the device read behind the value completed
+    pub at: Instant,
+}
+
+#[derive(Debug, Clone)]
+struct Sample {
+    value: Result<f32, String>,
+    last_good: Option<(f32, Instant)>,
+    at: Instant,
+}
+
+#[derive(Clone)]
+pub struct CachedReading {
+    sample: watch::Sender<Option<Sample>>,
+    in_flight: Arc<AtomicBool>,
+    served_stale: Arc<AtomicBool>,
+    served_at: Arc<std::sync::Mutex<Option<Instant>>>,
+    max_age: Duration,
+    deadline: Duration,
+}
+
+impl CachedReading {
+    pub fn new(max_age: Duration, deadline: Duration) -> Self {
+        // Every sample is then later than the epoch
+        clock::epoch();
+
+        Self {
+            sample: watch::Sender::new(None),
+            in_flight: Arc::new(AtomicBool::new(false)),
+            served_stale: Arc::new(AtomicBool::new(false)),
+            served_at: Arc::new(std::sync::Mutex::new(None)),
+            max_age,
+            deadline,
+        }
+    }
+
+    // Serves a fresh cached value, otherwise waits at most `deadline` for the
+    // device. On timeout the last good value is returned marked as stale while
+    // the read keeps running in the background to refresh the cache.
+    pub async fn read<F>(&self, read: F) -> Result<Reading, String>
+    where
+        F: Future<Output = Result<f32, String>> + Send + 'static,
+    {
+        let mut receiver = self.sample.subscribe();
+
+        if let Some(Sample {
+            value: Ok(value),
+            at,
+            ..
+        }) = *receiver.borrow_and_update()
+        {
+            if at.elapsed() < self.max_age {
+                self.served_stale.store(false, Ordering::Relaxed);
+                *self.served_at.lock().unwrap() = Some(at);
+                return Ok(Reading {
+                    value,
+                    stale: false,
+                    at,
+                });
+            }
+        }
+
+        // Only one device read per value at a time, so a wedged gateway
+        // doesn't pile up requests in the executor queue
+        if !self.in_flight.swap(true, Ordering::AcqRel) {
+            let sample = self.sample.clone();
+            let in_flight = self.in_flight.clone();
+
+            tokio::spawn(async move {
+                let value = read.await;
+
+                let at = Instant::now();
+                sample.send_modify(|current| {
+                    let last_good = match (&value, current.as_ref()) {
+                        (Ok(value), _) => Some((*value, at)),
+                        (Err(_), Some(previous)) => previous.last_good,
+                        (Err(_), None) => None,
+                    };
+
+                    *current = Some(Sample {
+                        value,
+                        last_good,
+                        at,
+                    });
+                });
+                in_flight.store(false, Ordering::Release);
+            });
+        }
+
+        let result = match tokio::time::timeout(self.deadline, receiver.changed()).await {
+            Ok(Ok(())) => match receiver.borrow().as_ref() {
+                Some(Sample {
+                    value: Ok(value),
+                    at,
+                    ..
+                }) => Ok(Reading {
+                    value: *value,
+                    stale: false,
+                    at: *at,
+                }),
+                Some(Sample { value: Err(e), .. }) => Err(e.clone()),
+                None => Err("No reading available".to_string()),
+            },
+            _ => match self.sample.borrow().as_ref().and_then(|s| s.last_good) {
+                Some((value, at)) => Ok(Reading {
+                    value,
+                    stale: true,
+                    at,
+                }),
+                None => Err(format!(
+                    "Device did not respond within {} ms",
+                    self.deadline.as_millis()
+                )),
+            },
+        };
+
+        if let Ok(reading) = &result {
+            self.served_stale.store(reading.stale, Ordering::Relaxed);
+            *self.served_at.lock().unwrap() = Some(reading.at);
+        }
+
+        result
+    }
+
+    // Whether the last served value came from the cache after a timeout
+    pub fn is_stale(&self) -> bool {
+        self.served_stale.load(Ordering::Relaxed)
+    }
+
+    // Milliseconds on the process clock at which the device read behind the
+    // last served value completed, NaN before the first one
+    pub fn timestamp(&self) -> f64 {
+        self.served_at
+            .lock()
+            .unwrap()
+            .map_or(f64::NAN, clock::monotonic_ms)
+    }
+}
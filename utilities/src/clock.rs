@@ -0,0 +1,54 @@
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+// Clock of simulated axes. It runs at real time unless SIMULATION_TIME_SCALE
+// speeds it up, then simulated moves finish that many times sooner. Axes
+// that drive hardware never read it, their time limits stay real.
+static TIME_SCALE: OnceLock<f64> = OnceLock::new();
+
+const MAX_TIME_SCALE: f64 = 1000.0;
+
+pub fn time_scale() -> f64 {
+    *TIME_SCALE.get_or_init(|| {
+        let scale = std::env::var("SIMULATION_TIME_SCALE")
+            .ok()
+            .and_then(|scale| scale.parse().ok())
+            .filter(|scale| (1.0..=MAX_TIME_SCALE).contains(scale))
+            .unwrap_or(1.0);
+
+        if scale != 1.0 {
+            eprintln!("Simulation clock running {}x faster than real time", scale);
+        }
+
+        scale
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClockInstant(Instant);
+
+impl ClockInstant {
+    pub fn now() -> Self {
+        Self(Instant::now())
+    }
+
+    // Virtual time since the instant
+    pub fn elapsed(&self) -> Duration {
+        self.0.elapsed().mul_f64(time_scale())
+    }
+}
+
+// Origin of the read timestamps, fixed by the first caller. Real time, unlike
+// the simulation clock, so timestamps of different values line up.
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+pub fn epoch() -> Instant {
+    *EPOCH.get_or_init(Instant::now)
+}
+
+// Milliseconds from the epoch to `at`
+pub fn monotonic_ms(at: Instant) -> f64 {
+    at.saturating_duration_since(epoch()).as_secs_f64() * 1000.0
+}
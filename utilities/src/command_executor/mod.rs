@@ -1,9 +1,16 @@
 use std::{
     io,
-    sync::mpsc::{Receiver, Sender},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{Receiver, Sender},
+    },
+    time::Duration,
 };
 
-use tokio::sync::oneshot;
+use tokio::{sync::oneshot, task::JoinHandle};
+
+const RESTART_DELAY: Duration = Duration::from_secs(1);
 
 pub trait Command: Send {
     type Response: Send;
@@ -43,13 +50,26 @@ impl<C: Command> GenericCommand<C> {
 pub struct CommandExecutor<H: DeviceHandler + Send + 'static> {
     handler: H,
     commands_ch: Receiver<GenericCommand<H::Command>>,
-    sender: Sender<GenericCommand<H::Command>>,
+    sender: CommandSender<H::Command>,
 }
 
 impl<H: DeviceHandler + Send> CommandExecutor<H> {
     pub fn new(handler: H) -> Self {
         let (sender, commands_ch) = std::sync::mpsc::channel();
 
+        Self {
+            handler,
+            commands_ch,
+            sender: CommandSender::new(sender),
+        }
+    }
+
+    // Reuses the senders of a dead executor, so everyone holding one is
+    // talking to the new executor from now on
+    fn replace(handler: H, sender: CommandSender<H::Command>) -> Self {
+        let (raw_sender, commands_ch) = std::sync::mpsc::channel();
+        sender.replace(raw_sender);
+
         Self {
             handler,
             commands_ch,
@@ -58,7 +78,7 @@ impl<H: DeviceHandler + Send> CommandExecutor<H> {
     }
 
     pub fn sender(&self) -> CommandSender<H::Command> {
-        CommandSender::new(self.sender.clone())
+        self.sender.clone()
     }
 
     pub fn run(&mut self) -> io::Result<()> {
@@ -75,16 +95,92 @@ impl<H: DeviceHandler + Send> CommandExecutor<H> {
     pub fn spawn(mut self) -> tokio::task::JoinHandle<io::Result<()>> {
         tokio::task::spawn_blocking(move || self.run())
     }
+
+    // Runs the executor and brings it back with a fresh handler from
+    // `factory` whenever its thread panics or the run loop exits
+    pub fn supervise<F>(self, name: impl Into<String>, factory: F) -> SupervisedExecutor
+    where
+        F: Fn() -> H + Send + 'static,
+        H::Command: 'static,
+    {
+        let name = name.into();
+        let restarts = Arc::new(AtomicUsize::new(0));
+
+        let handle = {
+            let name = name.clone();
+            let restarts = restarts.clone();
+            let sender = self.sender();
+            let mut executor = self;
+
+            tokio::spawn(async move {
+                loop {
+                    match tokio::task::spawn_blocking(move || executor.run()).await {
+                        Ok(Ok(())) => eprintln!("{} executor exited", name),
+                        Ok(Err(e)) => eprintln!("{} executor failed: {}", name, e),
+                        Err(e) => eprintln!("{} executor died: {}", name, e),
+                    }
+
+                    tokio::time::sleep(RESTART_DELAY).await;
+
+                    let count = restarts.fetch_add(1, Ordering::Relaxed) + 1;
+                    eprintln!("Restarting {} executor ({} restarts so far)", name, count);
+
+                    executor = CommandExecutor::replace(factory(), sender.clone());
+                }
+            })
+        };
+
+        SupervisedExecutor {
+            name,
+            restarts,
+            handle,
+        }
+    }
 }
 
-#[derive(Clone)]
+pub struct SupervisedExecutor {
+    name: String,
+    restarts: Arc<AtomicUsize>,
+    handle: JoinHandle<()>,
+}
+
+impl SupervisedExecutor {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    pub fn abort(&self) {
+        self.handle.abort();
+    }
+}
+
+// Clones share the underlying channel, which a supervisor swaps out when it
+// restarts the executor
 pub struct CommandSender<T: Command> {
-    commands_ch: Sender<GenericCommand<T>>,
+    commands_ch: Arc<RwLock<Sender<GenericCommand<T>>>>,
+}
+
+impl<T: Command> Clone for CommandSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            commands_ch: self.commands_ch.clone(),
+        }
+    }
 }
 
 impl<C: Command> CommandSender<C> {
     pub fn new(commands_ch: Sender<GenericCommand<C>>) -> Self {
-        Self { commands_ch }
+        Self {
+            commands_ch: Arc::new(RwLock::new(commands_ch)),
+        }
+    }
+
+    fn replace(&self, commands_ch: Sender<GenericCommand<C>>) {
+        *self.commands_ch.write().unwrap() = commands_ch;
     }
 
     pub async fn send_command(&self, command: C) -> io::Result<C::Response> {
@@ -92,6 +188,8 @@ impl<C: Command> CommandSender<C> {
         let command = GenericCommand::new(command, response_ch);
 
         self.commands_ch
+            .read()
+            .unwrap()
             .send(command)
             .map_err(|_| io::Error::new(io::ErrorKind::Other, "Failed to send command"))?;
 
@@ -0,0 +1,270 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, Read, Write},
+    str::FromStr,
+};
+
+// Frame-level test vectors, written down from the vendor's register tables.
+// One exchange per line:
+//
+//   set_velocity 1000: 01 06 62 03 03 E8 66 CC -> 01 06 62 03 03 E8 66 CC
+//   get_velocity: 01 03 62 03 00 01 6B B2 -> 01 03 02 03 E8 B8 FA = 1000
+//
+// The part before the colon is the driver call with its arguments, the
+// optional `= value` the decoded result, or `= error` for calls that must
+// fail. Calls that need several round trips take one line per exchange,
+// consecutive lines with the same call belong together until one carries a
+// value. Blank lines and lines starting with # are skipped.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exchange {
+    pub request: Vec<u8>,
+    pub response: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Vector {
+    pub call: String,
+    pub exchanges: Vec<Exchange>,
+    pub expected: Option<String>,
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    text.split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| format!("Invalid byte: {}", byte)))
+        .collect()
+}
+
+pub fn parse_vectors(text: &str) -> Result<Vec<Vector>, String> {
+    let mut vectors: Vec<Vector> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: String| format!("Line {}: {}", number + 1, message);
+
+        let (call, frames) = line
+            .split_once(':')
+            .ok_or_else(|| error("missing ':'".to_string()))?;
+        let (frames, expected) = match frames.split_once('=') {
+            Some((frames, expected)) => (frames, Some(expected.trim().to_string())),
+            None => (frames, None),
+        };
+        let (request, response) = frames
+            .split_once("->")
+            .ok_or_else(|| error("missing '->'".to_string()))?;
+
+        let exchange = Exchange {
+            request: parse_hex(request).map_err(error)?,
+            response: parse_hex(response).map_err(error)?,
+        };
+        let call = call.trim();
+
+        match vectors.last_mut() {
+            Some(vector) if vector.call == call && vector.expected.is_none() => {
+                vector.exchanges.push(exchange);
+                vector.expected = expected;
+            }
+            _ => vectors.push(Vector {
+                call: call.to_string(),
+                exchanges: vec![exchange],
+                expected,
+            }),
+        }
+    }
+
+    Ok(vectors)
+}
+
+// Plays the device side of a vector. Every complete request is compared to
+// the next expected one, and only a matching request gets its response.
+pub struct ReplayPort {
+    exchanges: VecDeque<Exchange>,
+    request: Vec<u8>,
+    output: VecDeque<u8>,
+    mismatch: Option<String>,
+}
+
+impl ReplayPort {
+    pub fn new(exchanges: Vec<Exchange>) -> Self {
+        Self {
+            exchanges: exchanges.into(),
+            request: Vec::new(),
+            output: VecDeque::new(),
+            mismatch: None,
+        }
+    }
+
+    // Anything that went wrong on the wire, checked after the driver call
+    fn finish(self) -> Result<(), String> {
+        if let Some(mismatch) = self.mismatch {
+            return Err(mismatch);
+        }
+        if !self.request.is_empty() {
+            return Err(format!("Incomplete request {}", hex(&self.request)));
+        }
+        if !self.exchanges.is_empty() {
+            return Err(format!("{} exchanges were not used", self.exchanges.len()));
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for ReplayPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.request.extend_from_slice(buf);
+
+        let Some(expected) = self.exchanges.front() else {
+            self.mismatch = Some(format!("Unexpected request {}", hex(&self.request)));
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected request",
+            ));
+        };
+
+        if self.request.len() < expected.request.len() {
+            return Ok(buf.len());
+        }
+
+        if self.request != expected.request {
+            self.mismatch = Some(format!(
+                "Request {} does not match {}",
+                hex(&self.request),
+                hex(&expected.request)
+            ));
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected request",
+            ));
+        }
+
+        let exchange = self.exchanges.pop_front().unwrap();
+        self.output.extend(exchange.response);
+        self.request.clear();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for ReplayPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.output.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "no response"));
+        }
+
+        let len = buf.len().min(self.output.len());
+        for (byte, value) in buf.iter_mut().zip(self.output.drain(..len)) {
+            *byte = value;
+        }
+
+        Ok(len)
+    }
+}
+
+// The call's name and its single argument, if any
+pub fn split_call(call: &str) -> (&str, Option<&str>) {
+    let mut parts = call.split_whitespace();
+    (parts.next().unwrap_or_default(), parts.next())
+}
+
+pub fn argument<T: FromStr>(call: &str) -> Result<T, String> {
+    split_call(call)
+        .1
+        .ok_or_else(|| format!("{} needs an argument", call))?
+        .parse()
+        .map_err(|_| format!("Invalid argument in {}", call))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub call: String,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.call, self.message)
+    }
+}
+
+// Runs every vector through `dispatch`, which performs the driver call
+// against the port and returns the decoded result formatted for comparison
+pub fn run_vectors(
+    text: &str,
+    mut dispatch: impl FnMut(&str, &mut ReplayPort) -> Result<String, String>,
+) -> Result<Vec<Failure>, String> {
+    let mut failures = Vec::new();
+
+    for vector in parse_vectors(text)? {
+        let mut port = ReplayPort::new(vector.exchanges);
+        let result = dispatch(&vector.call, &mut port);
+
+        let outcome = port
+            .finish()
+            .and_then(|()| match (result, vector.expected.as_deref()) {
+                (Err(_), Some("error")) => Ok(()),
+                (Ok(value), Some("error")) => Err(format!("Decoded {}, expected an error", value)),
+                (Ok(value), Some(expected)) if value != expected => {
+                    Err(format!("Decoded {}, expected {}", value, expected))
+                }
+                (result, _) => result.map(|_| ()),
+            });
+
+        if let Err(message) = outcome {
+            failures.push(Failure {
+                call: vector.call,
+                message,
+            });
+        }
+    }
+
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A driver that sends its argument as a one byte request and decodes the
+    // one byte response
+    fn echo(call: &str, port: &mut ReplayPort) -> Result<String, String> {
+        let request: u8 = argument(call)?;
+        port.write_all(&[request]).map_err(|e| e.to_string())?;
+
+        let mut response = [0];
+        port.read_exact(&mut response).map_err(|e| e.to_string())?;
+
+        Ok(response[0].to_string())
+    }
+
+    #[test]
+    fn vectors_pass() {
+        let vectors = "\
+            # comment\n\
+            echo 1: 01 -> 02 = 2\n\
+            echo 3: 03 -> = error\n\
+            echo 4: 04 -> 05\n";
+        assert!(run_vectors(vectors, echo).unwrap().is_empty());
+
+        // A request that differs from the table is a failure
+        let failures = run_vectors("echo 1: 02 -> 03 = 3\n", echo).unwrap();
+        assert_eq!(failures.len(), 1);
+    }
+}
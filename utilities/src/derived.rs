@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use motarem::{
+    axis::{Axis, movement_parameters::MovementParams, state_info::AxisStateInfo},
+    motor_controller::MotorController,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::expression::{Expression, find_axis};
+
+// Site-specific attributes computed from other attributes of the same
+// controller, e.g. `Y_Up.position - Y_Down.position` registered as "gap" on
+// Y_Up. Axes are referred to by name or as `axisN`, N being the index in the
+// controller's axis list. Operands always read the underlying axes, so one
+// derived attribute can not refer to another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivedAttributeConfig {
+    // Only needed when several controllers have an axis of the same name
+    #[serde(default)]
+    pub controller: Option<String>,
+    pub axis: String,
+    pub name: String,
+    pub expression: String,
+}
+
+pub struct DerivedAxis {
+    inner: Arc<dyn Axis>,
+    attributes: Vec<(String, Expression)>,
+    // The controller's own axes, operands are looked up here
+    axes: Vec<Arc<dyn Axis>>,
+}
+
+#[async_trait::async_trait]
+impl Axis for DerivedAxis {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
+        self.inner.start(position, parameters).await
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.inner.stop().await
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        self.inner.get_state().await
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        match self
+            .attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+        {
+            Some((_, expression)) => expression
+                .evaluate(&self.axes)
+                .await
+                .map_err(anyhow::Error::msg),
+            None => self.inner.get_attribute(name).await,
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params = self.inner.get_available_params().await?;
+        params.extend(self.attributes.iter().map(|(name, _)| name.clone()));
+        Ok(params)
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        self.inner.get_supported_movement_params().await
+    }
+}
+
+pub struct DerivedController {
+    inner: Arc<dyn MotorController>,
+    axes: Vec<Arc<dyn Axis>>,
+}
+
+#[async_trait::async_trait]
+impl MotorController for DerivedController {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn axes(&self) -> Vec<Arc<dyn Axis>> {
+        self.axes.clone()
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+// Wraps the controller so that the configured attributes show up on its
+// axes. Entries for axes of other controllers are skipped, entries naming
+// this controller must resolve.
+pub fn with_derived_attributes(
+    controller: Arc<dyn MotorController>,
+    configs: &[DerivedAttributeConfig],
+) -> Result<Arc<dyn MotorController>, String> {
+    let axes = controller.axes();
+
+    let configs: Vec<_> = configs
+        .iter()
+        .filter(|config| match &config.controller {
+            Some(name) => name == controller.name(),
+            None => find_axis(&axes, &config.axis).is_ok(),
+        })
+        .collect();
+
+    if configs.is_empty() {
+        return Ok(controller);
+    }
+
+    let mut attributes: Vec<Vec<(String, Expression)>> = vec![Vec::new(); axes.len()];
+    for config in configs {
+        let expression = Expression::parse(&config.expression)
+            .map_err(|e| format!("Invalid expression for {}: {}", config.name, e))?;
+        expression
+            .check_axes(&axes)
+            .map_err(|e| format!("Invalid expression for {}: {}", config.name, e))?;
+
+        let target = find_axis(&axes, &config.axis)
+            .map_err(|e| format!("Invalid axis for {}: {}", config.name, e))?;
+        let index = axes
+            .iter()
+            .position(|axis| Arc::ptr_eq(axis, target))
+            .unwrap();
+
+        attributes[index].push((config.name.clone(), expression));
+    }
+
+    let wrapped = axes
+        .iter()
+        .zip(attributes)
+        .map(|(axis, attributes)| {
+            if attributes.is_empty() {
+                return axis.clone();
+            }
+
+            Arc::new(DerivedAxis {
+                inner: axis.clone(),
+                attributes,
+                axes: axes.clone(),
+            }) as Arc<dyn Axis>
+        })
+        .collect();
+
+    Ok(Arc::new(DerivedController {
+        inner: controller,
+        axes: wrapped,
+    }))
+}
@@ -0,0 +1,162 @@
+use std::{fmt, io};
+
+use crate::modbus::ModbusError;
+
+// Numeric codes shared by every controller and client, so software on the
+// other side of the socket can branch on a number instead of matching the
+// wording of a message. Codes are grouped by hundreds and never reused:
+// 1xx transport, 2xx framing, 3xx device, 4xx request, 5xx motion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum ErrorCode {
+    Io = 100,
+    Timeout = 101,
+    Unreachable = 102,
+    NotConnected = 103,
+
+    ChecksumMismatch = 200,
+    InvalidLength = 201,
+    InvalidResponse = 202,
+    WrongDevice = 203,
+    WrongFunction = 204,
+    DeviceException = 205,
+
+    SensorMissing = 300,
+    OutOfRange = 301,
+    NotConfigured = 302,
+    ReadingRejected = 303,
+
+    InvalidInput = 400,
+    Unsupported = 401,
+    Busy = 402,
+
+    Stopped = 500,
+    TimeLimitExceeded = 501,
+    GapLimit = 502,
+
+    Unknown = 999,
+}
+
+const CODES: [ErrorCode; 21] = [
+    ErrorCode::Io,
+    ErrorCode::Timeout,
+    ErrorCode::Unreachable,
+    ErrorCode::NotConnected,
+    ErrorCode::ChecksumMismatch,
+    ErrorCode::InvalidLength,
+    ErrorCode::InvalidResponse,
+    ErrorCode::WrongDevice,
+    ErrorCode::WrongFunction,
+    ErrorCode::DeviceException,
+    ErrorCode::SensorMissing,
+    ErrorCode::OutOfRange,
+    ErrorCode::NotConfigured,
+    ErrorCode::ReadingRejected,
+    ErrorCode::InvalidInput,
+    ErrorCode::Unsupported,
+    ErrorCode::Busy,
+    ErrorCode::Stopped,
+    ErrorCode::TimeLimitExceeded,
+    ErrorCode::GapLimit,
+    ErrorCode::Unknown,
+];
+
+impl ErrorCode {
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        CODES.into_iter().find(|error| error.code() == code)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ErrorCode::Io => "IO error",
+            ErrorCode::Timeout => "Request timed out",
+            ErrorCode::Unreachable => "Device is unreachable",
+            ErrorCode::NotConnected => "Not connected",
+            ErrorCode::ChecksumMismatch => "Checksum mismatch",
+            ErrorCode::InvalidLength => "Invalid response length",
+            ErrorCode::InvalidResponse => "Invalid response",
+            ErrorCode::WrongDevice => "Response from another device",
+            ErrorCode::WrongFunction => "Response to another request",
+            ErrorCode::DeviceException => "Device reported an exception",
+            ErrorCode::SensorMissing => "Sensor missing",
+            ErrorCode::OutOfRange => "Value out of range",
+            ErrorCode::NotConfigured => "Not configured",
+            ErrorCode::ReadingRejected => "Reading rejected",
+            ErrorCode::InvalidInput => "Invalid input",
+            ErrorCode::Unsupported => "Unsupported",
+            ErrorCode::Busy => "Busy",
+            ErrorCode::Stopped => "Stopped",
+            ErrorCode::TimeLimitExceeded => "Time limit exceeded",
+            ErrorCode::GapLimit => "Stopped at the gap limit",
+            ErrorCode::Unknown => "Unknown error",
+        }
+    }
+
+    // Handlers pass errors on as io::Error. A ModbusError inside is looked
+    // through, anything else is classified by its kind; crates with their own
+    // error types convert those directly.
+    pub fn from_io(error: &io::Error) -> Self {
+        if let Some(error) = error
+            .get_ref()
+            .and_then(|error| error.downcast_ref::<ModbusError>())
+        {
+            return error.into();
+        }
+
+        match error.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => ErrorCode::Timeout,
+            io::ErrorKind::HostUnreachable
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::ConnectionRefused => ErrorCode::Unreachable,
+            io::ErrorKind::NotConnected
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::UnexpectedEof => ErrorCode::NotConnected,
+            io::ErrorKind::InvalidData => ErrorCode::InvalidResponse,
+            io::ErrorKind::InvalidInput => ErrorCode::InvalidInput,
+            io::ErrorKind::Unsupported => ErrorCode::Unsupported,
+            io::ErrorKind::Other => ErrorCode::Unknown,
+            _ => ErrorCode::Io,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{} {}", self.code(), self.description())
+    }
+}
+
+impl From<ErrorCode> for u16 {
+    fn from(error: ErrorCode) -> Self {
+        error.code()
+    }
+}
+
+impl TryFrom<u16> for ErrorCode {
+    type Error = u16;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        ErrorCode::from_code(code).ok_or(code)
+    }
+}
+
+impl From<&ModbusError> for ErrorCode {
+    fn from(error: &ModbusError) -> Self {
+        match error {
+            ModbusError::IoError(error) => ErrorCode::from_io(error),
+            ModbusError::InvalidCrc { .. } => ErrorCode::ChecksumMismatch,
+            ModbusError::InvalidResponseLength { .. } => ErrorCode::InvalidLength,
+            ModbusError::InvalidSlaveId { .. } => ErrorCode::WrongDevice,
+            ModbusError::InvalidFunctionCode { .. } => ErrorCode::WrongFunction,
+            ModbusError::ExceptionResponse { .. } => ErrorCode::DeviceException,
+            ModbusError::Timeout => ErrorCode::Timeout,
+            ModbusError::ProtocolError(_) => ErrorCode::InvalidResponse,
+        }
+    }
+}
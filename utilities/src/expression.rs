@@ -0,0 +1,372 @@
+use std::{collections::HashMap, sync::Arc};
+
+use motarem::axis::{Axis, state::AxisState};
+
+// Expressions over axis attributes shared by sequence conditions and derived
+// attributes, e.g. `Y_Up.position - Y_Down.position` or
+// `axis0.in_position && Y_Down.temperature < 35`. Axes are referred to by
+// name or as `axisN`, N being the index in the controller's axis list.
+// Comparisons and logic give 1 or 0, anything non-zero counts as true.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn apply(self, left: f64, right: f64) -> Result<f64, String> {
+        let truth = |value: bool| value as u8 as f64;
+
+        Ok(match self {
+            BinaryOp::Add => left + right,
+            BinaryOp::Sub => left - right,
+            BinaryOp::Mul => left * right,
+            BinaryOp::Div if right == 0.0 => return Err(format!("Division of {} by zero", left)),
+            BinaryOp::Div => left / right,
+            BinaryOp::Lt => truth(left < right),
+            BinaryOp::Le => truth(left <= right),
+            BinaryOp::Gt => truth(left > right),
+            BinaryOp::Ge => truth(left >= right),
+            BinaryOp::Eq => truth(left == right),
+            BinaryOp::Ne => truth(left != right),
+            BinaryOp::And => truth(left != 0.0 && right != 0.0),
+            BinaryOp::Or => truth(left != 0.0 || right != 0.0),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Operand {
+    pub axis: String,
+    pub attribute: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Number(f64),
+    Attribute(Operand),
+    Neg(Box<Expression>),
+    Not(Box<Expression>),
+    Binary(Box<Expression>, BinaryOp, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Dot,
+    Not,
+    Op(BinaryOp),
+    Open,
+    Close,
+}
+
+// Digits with an optional fraction and exponent, e.g. 35, 0.5 or 1e-3
+fn number_len(chars: &[char]) -> usize {
+    let digits = |from: usize| {
+        chars[from.min(chars.len())..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit() || **c == '.')
+            .count()
+    };
+
+    let mut len = digits(0);
+    if matches!(chars.get(len), Some('e' | 'E')) {
+        let sign = usize::from(matches!(chars.get(len + 1), Some('+' | '-')));
+        let exponent = digits(len + 1 + sign);
+        if exponent > 0 {
+            len += 1 + sign + exponent;
+        }
+    }
+
+    len
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (token, len) = match (chars[i], chars.get(i + 1).copied()) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::Op(BinaryOp::And), 2),
+            ('|', Some('|')) => (Token::Op(BinaryOp::Or), 2),
+            ('<', Some('=')) => (Token::Op(BinaryOp::Le), 2),
+            ('>', Some('=')) => (Token::Op(BinaryOp::Ge), 2),
+            ('=', Some('=')) => (Token::Op(BinaryOp::Eq), 2),
+            ('!', Some('=')) => (Token::Op(BinaryOp::Ne), 2),
+            ('<', _) => (Token::Op(BinaryOp::Lt), 1),
+            ('>', _) => (Token::Op(BinaryOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('+', _) => (Token::Op(BinaryOp::Add), 1),
+            ('-', _) => (Token::Op(BinaryOp::Sub), 1),
+            ('*', _) => (Token::Op(BinaryOp::Mul), 1),
+            ('/', _) => (Token::Op(BinaryOp::Div), 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            ('.', _) => (Token::Dot, 1),
+            (c, _) if c.is_ascii_digit() => {
+                let len = number_len(&chars[i..]);
+                let text: String = chars[i..i + len].iter().collect();
+                let value = text
+                    .parse()
+                    .map_err(|_| format!("Invalid number: {}", text))?;
+                (Token::Number(value), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => return Err(format!("Unexpected character '{}'", c)),
+        };
+
+        tokens.push(token);
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("Expected {:?}, found {:?}", expected, token)),
+        }
+    }
+
+    // Left-associative chain of the operators in `ops` over `operand`
+    fn chain(
+        &mut self,
+        ops: &[BinaryOp],
+        operand: fn(&mut Self) -> Result<Expression, String>,
+    ) -> Result<Expression, String> {
+        let mut expression = operand(self)?;
+
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            if !ops.contains(&op) {
+                break;
+            }
+            self.next();
+            expression = Expression::Binary(Box::new(expression), op, Box::new(operand(self)?));
+        }
+
+        Ok(expression)
+    }
+
+    fn or(&mut self) -> Result<Expression, String> {
+        self.chain(&[BinaryOp::Or], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expression, String> {
+        self.chain(&[BinaryOp::And], Self::not)
+    }
+
+    fn not(&mut self) -> Result<Expression, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Expression::Not(Box::new(self.not()?)));
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expression, String> {
+        let left = self.sum()?;
+
+        match self.peek() {
+            Some(Token::Op(
+                op @ (BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge
+                | BinaryOp::Eq
+                | BinaryOp::Ne),
+            )) => {
+                let op = *op;
+                self.next();
+                Ok(Expression::Binary(
+                    Box::new(left),
+                    op,
+                    Box::new(self.sum()?),
+                ))
+            }
+            _ => Ok(left),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expression, String> {
+        self.chain(&[BinaryOp::Add, BinaryOp::Sub], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expression, String> {
+        self.chain(&[BinaryOp::Mul, BinaryOp::Div], Self::factor)
+    }
+
+    fn factor(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Op(BinaryOp::Sub)) => Ok(Expression::Neg(Box::new(self.factor()?))),
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::Open) => {
+                let expression = self.or()?;
+                self.expect(Token::Close)?;
+                Ok(expression)
+            }
+            Some(Token::Ident(axis)) => {
+                self.expect(Token::Dot)?;
+                match self.next() {
+                    Some(Token::Ident(attribute)) => {
+                        Ok(Expression::Attribute(Operand { axis, attribute }))
+                    }
+                    token => Err(format!("Expected attribute, found {:?}", token)),
+                }
+            }
+            token => Err(format!("Unexpected {:?}", token)),
+        }
+    }
+}
+
+pub fn find_axis<'a>(axes: &'a [Arc<dyn Axis>], name: &str) -> Result<&'a Arc<dyn Axis>, String> {
+    let by_index = name
+        .strip_prefix("axis")
+        .and_then(|index| index.parse::<usize>().ok())
+        .and_then(|index| axes.get(index));
+
+    by_index
+        .or_else(|| axes.iter().find(|axis| axis.name() == name))
+        .ok_or_else(|| format!("Unknown axis: {}", name))
+}
+
+// `in_position` and `moving` come from the axis state, anything else is read
+// as an attribute
+async fn read_operand(axes: &[Arc<dyn Axis>], operand: &Operand) -> Result<f64, String> {
+    let axis = find_axis(axes, &operand.axis)?;
+
+    match operand.attribute.as_str() {
+        "in_position" | "moving" => {
+            let state = axis
+                .get_state()
+                .await
+                .map_err(|e| format!("Failed to get state of {}: {}", axis.name(), e))?;
+            let moving = matches!(state.state, AxisState::Moving);
+
+            Ok(if operand.attribute == "moving" {
+                moving
+            } else {
+                !moving
+            } as u8 as f64)
+        }
+        attribute => axis.get_attribute(attribute).await.map_err(|e| {
+            format!(
+                "Failed to read {}.{}: {}",
+                operand.axis, operand.attribute, e
+            )
+        }),
+    }
+}
+
+impl Expression {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+
+        let expression = parser.or()?;
+
+        match parser.peek() {
+            None => Ok(expression),
+            Some(token) => Err(format!("Unexpected trailing {:?}", token)),
+        }
+    }
+
+    // Every axis the expression refers to has to exist, so a typo fails when
+    // the configuration is loaded rather than on the first evaluation
+    pub fn check_axes(&self, axes: &[Arc<dyn Axis>]) -> Result<(), String> {
+        let mut operands = Vec::new();
+        self.operands(&mut operands);
+
+        operands
+            .into_iter()
+            .try_for_each(|operand| find_axis(axes, &operand.axis).map(|_| ()))
+    }
+
+    fn operands<'a>(&'a self, operands: &mut Vec<&'a Operand>) {
+        match self {
+            Expression::Number(_) => {}
+            Expression::Attribute(operand) => {
+                if !operands.contains(&operand) {
+                    operands.push(operand);
+                }
+            }
+            Expression::Neg(inner) | Expression::Not(inner) => inner.operands(operands),
+            Expression::Binary(left, _, right) => {
+                left.operands(operands);
+                right.operands(operands);
+            }
+        }
+    }
+
+    fn compute(&self, values: &HashMap<&Operand, f64>) -> Result<f64, String> {
+        match self {
+            Expression::Number(value) => Ok(*value),
+            Expression::Attribute(operand) => Ok(values[operand]),
+            Expression::Neg(inner) => Ok(-inner.compute(values)?),
+            Expression::Not(inner) => Ok((inner.compute(values)? == 0.0) as u8 as f64),
+            Expression::Binary(left, op, right) => {
+                op.apply(left.compute(values)?, right.compute(values)?)
+            }
+        }
+    }
+
+    // Reads every attribute the expression refers to once, then computes it
+    pub async fn evaluate(&self, axes: &[Arc<dyn Axis>]) -> Result<f64, String> {
+        let mut operands = Vec::new();
+        self.operands(&mut operands);
+
+        let mut values = HashMap::new();
+        for operand in operands {
+            values.insert(operand, read_operand(axes, operand).await?);
+        }
+
+        self.compute(&values)
+    }
+
+    pub async fn check(&self, axes: &[Arc<dyn Axis>]) -> Result<bool, String> {
+        Ok(self.evaluate(axes).await? != 0.0)
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use motarem::{
+    axis::{
+        Axis, limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
+        state_info::AxisStateInfo,
+    },
+    motor_controller::MotorController,
+};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+// What happens to the rest of a group when one member goes wrong
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupPolicy {
+    // Members are commanded together but otherwise left alone
+    #[default]
+    Independent,
+    // A member that fails to start or stops answering during a group move
+    // stops every member
+    StopAll,
+}
+
+// Named set of axes that answers commands like an axis of its own, so
+// `STOP vertical` stops Y_Up and Y_Down and `GET horizontal ALL` reads every
+// attribute of X_Right and X_Left as "X_Right.position" and so on. "*" stands
+// for all axes of the controller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AxisGroupConfig {
+    // Only needed when several controllers could resolve the group
+    #[serde(default)]
+    pub controller: Option<String>,
+    pub name: String,
+    pub axes: Vec<String>,
+    #[serde(default)]
+    pub policy: GroupPolicy,
+}
+
+fn movement_params(parameters: &Option<MovementParams>, members: &[String]) -> MovementParams {
+    let mut movement_params = MovementParams::default();
+
+    if let Some(parameters) = parameters {
+        movement_params.velocity = parameters.velocity;
+        movement_params.acceleration = parameters.acceleration;
+        movement_params.deceleration = parameters.deceleration;
+
+        for (name, value) in &parameters.custom {
+            if !members.contains(name) {
+                movement_params.custom.insert(name.clone(), *value);
+            }
+        }
+    }
+
+    movement_params
+}
+
+async fn stop_all(members: &[Arc<dyn Axis>]) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for member in members {
+        if let Err(e) = member.stop().await {
+            errors.push(format!("{}: {}", member.name(), e));
+        }
+    }
+
+    errors
+}
+
+// Follows a group move under StopAll until every member has settled
+async fn watch(members: Vec<Arc<dyn Axis>>, message: Arc<Mutex<Option<String>>>) {
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        let mut moving = false;
+        for member in &members {
+            match member.get_state().await {
+                Ok(state) => moving |= matches!(state.state, AxisState::Moving),
+                Err(e) => {
+                    let mut text = format!("Stopped, {} failed: {}", member.name(), e);
+                    let errors = stop_all(&members).await;
+                    if !errors.is_empty() {
+                        text.push_str(&format!(", failed to stop {}", errors.join(", ")));
+                    }
+
+                    eprintln!("[group] {}", text);
+                    *message.lock().unwrap() = Some(text);
+                    return;
+                }
+            }
+        }
+
+        if !moving {
+            return;
+        }
+    }
+}
+
+pub struct GroupAxis {
+    name: String,
+    members: Vec<Arc<dyn Axis>>,
+    member_names: Vec<String>,
+    policy: GroupPolicy,
+
+    message: Arc<Mutex<Option<String>>>,
+    watcher: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl GroupAxis {
+    pub(crate) fn new(name: String, members: Vec<Arc<dyn Axis>>, policy: GroupPolicy) -> Self {
+        let member_names = members
+            .iter()
+            .map(|member| member.name().to_string())
+            .collect();
+
+        Self {
+            name,
+            members,
+            member_names,
+            policy,
+            message: Arc::new(Mutex::new(None)),
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn members(&self) -> &[Arc<dyn Axis>] {
+        &self.members
+    }
+
+    fn member(&self, name: &str) -> Option<&Arc<dyn Axis>> {
+        self.members.iter().find(|member| member.name() == name)
+    }
+
+    fn abort_watcher(&self) {
+        if let Some(handle) = self.watcher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for GroupAxis {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Every member goes to `position`, unless custom parameters named after
+    // members give targets, then only those members move
+    async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
+        let targets: Vec<(&Arc<dyn Axis>, f64)> = match &parameters {
+            Some(parameters)
+                if self
+                    .member_names
+                    .iter()
+                    .any(|name| parameters.custom.contains_key(name)) =>
+            {
+                self.members
+                    .iter()
+                    .filter_map(|member| {
+                        parameters
+                            .custom
+                            .get(member.name())
+                            .map(|target| (member, *target))
+                    })
+                    .collect()
+            }
+            _ => self
+                .members
+                .iter()
+                .map(|member| (member, position))
+                .collect(),
+        };
+
+        self.abort_watcher();
+        *self.message.lock().unwrap() = None;
+
+        let mut errors = Vec::new();
+        for (member, target) in targets {
+            let movement_params = movement_params(&parameters, &self.member_names);
+
+            if let Err(e) = member.start(target, Some(movement_params)).await {
+                errors.push(format!("{}: {}", member.name(), e));
+
+                if self.policy == GroupPolicy::StopAll {
+                    errors.extend(stop_all(&self.members).await);
+                    break;
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            let text = format!("Group move failed, {}", errors.join(", "));
+            *self.message.lock().unwrap() = Some(text.clone());
+            return Err(anyhow::Error::msg(text));
+        }
+
+        if self.policy == GroupPolicy::StopAll {
+            *self.watcher.lock().unwrap() = Some(tokio::spawn(watch(
+                self.members.clone(),
+                self.message.clone(),
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.abort_watcher();
+
+        let errors = stop_all(&self.members).await;
+        if !errors.is_empty() {
+            return Err(anyhow::Error::msg(format!(
+                "Failed to stop {}",
+                errors.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        let mut moving = false;
+        let mut messages = Vec::new();
+
+        for member in &self.members {
+            let state = member.get_state().await?;
+            moving |= matches!(state.state, AxisState::Moving);
+
+            if let Some(message) = state.message {
+                messages.push(format!("{}: {}", member.name(), message));
+            }
+        }
+
+        if let Some(message) = self.message.lock().unwrap().clone() {
+            messages.insert(0, message);
+        }
+
+        Ok(AxisStateInfo {
+            state: if moving {
+                AxisState::Moving
+            } else {
+                AxisState::On
+            },
+            message: (!messages.is_empty()).then(|| messages.join("; ")),
+            limit_switches: LimitSwitches::None,
+        })
+    }
+
+    // "<member>.<attribute>", plus the number of members still moving. The
+    // members have no common position, each one is read as "<member>.position"
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        if name == "position" {
+            return Err(anyhow::Error::msg(format!(
+                "{} is a group, read {}",
+                self.name,
+                self.member_names
+                    .iter()
+                    .map(|member| format!("{}.position", member))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        if name == "moving" {
+            let mut moving = 0;
+            for member in &self.members {
+                if matches!(member.get_state().await?.state, AxisState::Moving) {
+                    moving += 1;
+                }
+            }
+
+            return Ok(moving as f64);
+        }
+
+        let (member, attribute) = name
+            .split_once('.')
+            .and_then(|(member, attribute)| Some((self.member(member)?, attribute)))
+            .ok_or_else(|| anyhow::Error::msg(format!("Unknown attribute: {}", name)))?;
+
+        member.get_attribute(attribute).await
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params = vec!["moving".to_string()];
+
+        for member in &self.members {
+            for param in member.get_available_params().await? {
+                params.push(format!("{}.{}", member.name(), param));
+            }
+        }
+
+        Ok(params)
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params = self.member_names.clone();
+
+        for member in &self.members {
+            for param in member.get_supported_movement_params().await? {
+                if !params.contains(&param) {
+                    params.push(param);
+                }
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+pub struct GroupController {
+    pub(crate) inner: Arc<dyn MotorController>,
+    pub(crate) axes: Vec<Arc<dyn Axis>>,
+}
+
+#[async_trait::async_trait]
+impl MotorController for GroupController {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn axes(&self) -> Vec<Arc<dyn Axis>> {
+        self.axes.clone()
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+// Adds the configured groups to the controller's axes. Like derived
+// attributes, groups naming another controller or axes this one does not
+// have are skipped, groups naming this controller must resolve.
+pub fn with_axis_groups(
+    controller: Arc<dyn MotorController>,
+    configs: &[AxisGroupConfig],
+) -> Result<Arc<dyn MotorController>, String> {
+    let axes = controller.axes();
+    let find = |name: &str| axes.iter().find(|axis| axis.name() == name);
+
+    let configs: Vec<_> = configs
+        .iter()
+        .filter(|config| match &config.controller {
+            Some(name) => name == controller.name(),
+            None => config
+                .axes
+                .iter()
+                .all(|axis| axis == "*" || find(axis).is_some()),
+        })
+        .collect();
+
+    if configs.is_empty() {
+        return Ok(controller);
+    }
+
+    let mut groups = Vec::new();
+    for config in configs {
+        if find(&config.name).is_some() {
+            return Err(format!(
+                "Group {} has the name of an axis of {}",
+                config.name,
+                controller.name()
+            ));
+        }
+
+        let mut members: Vec<Arc<dyn Axis>> = Vec::new();
+        for name in &config.axes {
+            if name == "*" {
+                members.extend(axes.iter().cloned());
+                continue;
+            }
+
+            let axis = find(name)
+                .ok_or_else(|| format!("Unknown axis in group {}: {}", config.name, name))?;
+            members.push(axis.clone());
+        }
+
+        if members.is_empty() {
+            return Err(format!("Group {} has no axes", config.name));
+        }
+
+        groups.push(
+            Arc::new(GroupAxis::new(config.name.clone(), members, config.policy)) as Arc<dyn Axis>,
+        );
+    }
+
+    Ok(Arc::new(GroupController {
+        inner: controller,
+        axes: axes.into_iter().chain(groups).collect(),
+    }))
+}
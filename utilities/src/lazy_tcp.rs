@@ -1,14 +1,85 @@
 use std::io::{Read, Write};
-use std::net::{Shutdown, TcpStream, ToSocketAddrs};
-use std::time::Duration;
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+// After a failed connect, further attempts are refused until the backoff has
+// passed, doubling with every failure. Every request queued behind a dropped
+// gateway would otherwise run its own round of connects.
+const BACKOFF_BASE: Duration = Duration::from_millis(200);
+const BACKOFF_MAX: Duration = Duration::from_secs(10);
+// Failed connects in a row before a stream with a secondary address gives up
+// on the current one
+const FAILOVER_AFTER: u32 = 3;
+
+// Address of a device that can be changed at runtime, e.g. after swapping a
+// gateway. Streams following it switch over before their next request.
+#[derive(Debug, Clone)]
+pub struct Endpoint(Arc<RwLock<SocketAddr>>);
+
+impl Endpoint {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self(Arc::new(RwLock::new(addr)))
+    }
+
+    pub fn get(&self) -> SocketAddr {
+        *self.0.read().unwrap()
+    }
+
+    pub fn set(&self, addr: SocketAddr) {
+        *self.0.write().unwrap() = addr;
+    }
+}
+
+// Which of its two addresses a stream with a secondary one is using, for
+// status reporting after the stream moved into its executor
+#[derive(Debug, Clone, Default)]
+pub struct ActiveEndpoint {
+    secondary: Arc<AtomicBool>,
+    addr: Arc<RwLock<String>>,
+}
+
+impl ActiveEndpoint {
+    pub fn is_secondary(&self) -> bool {
+        self.secondary.load(Ordering::Relaxed)
+    }
+
+    pub fn addr(&self) -> String {
+        self.addr.read().unwrap().clone()
+    }
+
+    fn set(&self, addr: &str, secondary: bool) {
+        *self.addr.write().unwrap() = addr.to_string();
+        self.secondary.store(secondary, Ordering::Relaxed);
+    }
+}
+
+fn resolve<A: ToSocketAddrs>(addr: A) -> String {
+    addr.to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .map(|addr| addr.to_string())
+        .unwrap_or_default()
+}
 
 pub struct LazyTcpStream {
     addr: String,
+    primary: String,
+    // Spare gateway wired in parallel, taken over after FAILOVER_AFTER failed
+    // connects and left the same way
+    secondary: Option<String>,
+    active: ActiveEndpoint,
     stream: Option<TcpStream>,
     max_retries: u32,
     read_timeout: Duration,
     write_timeout: Duration,
     connect_timeout: Duration,
+
+    failed_connects: u32,
+    retry_at: Option<Instant>,
+
+    endpoint: Option<Endpoint>,
 }
 
 impl LazyTcpStream {
@@ -19,22 +90,143 @@ impl LazyTcpStream {
         write_timeout: Duration,
         connect_timeout: Duration,
     ) -> Self {
+        let addr = resolve(addr);
+        let active = ActiveEndpoint::default();
+        active.set(&addr, false);
+
         LazyTcpStream {
-            addr: addr
-                .to_socket_addrs()
-                .ok()
-                .and_then(|mut addrs| addrs.next())
-                .map(|addr| addr.to_string())
-                .unwrap_or_default(),
+            primary: addr.clone(),
+            addr,
+            secondary: None,
+            active,
             stream: None,
             max_retries,
             read_timeout,
             write_timeout,
             connect_timeout,
+            failed_connects: 0,
+            retry_at: None,
+            endpoint: None,
+        }
+    }
+
+    pub fn follow(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn with_secondary<A: ToSocketAddrs>(mut self, addr: A) -> Self {
+        self.secondary = Some(resolve(addr)).filter(|addr| !addr.is_empty());
+        self
+    }
+
+    pub fn active_endpoint(&self) -> ActiveEndpoint {
+        self.active.clone()
+    }
+
+    pub fn read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    // Also applies to the open connection, not only to the next one
+    pub fn set_read_timeout(&mut self, timeout: Duration) -> std::io::Result<()> {
+        self.read_timeout = timeout;
+        match &self.stream {
+            Some(stream) => stream.set_read_timeout(Some(timeout)),
+            None => Ok(()),
+        }
+    }
+
+    // Switches between primary and secondary address once the current one
+    // failed often enough, the other one gets a fresh backoff
+    fn fail_over(&mut self) {
+        let Some(secondary) = self.secondary.clone() else {
+            return;
+        };
+        if self.failed_connects < FAILOVER_AFTER {
+            return;
+        }
+
+        let to_secondary = self.addr != secondary;
+        let addr = if to_secondary {
+            secondary
+        } else {
+            self.primary.clone()
+        };
+
+        eprintln!(
+            "{} failed {} times, failing over to {}",
+            self.addr, self.failed_connects, addr
+        );
+
+        if let Some(stream) = self.stream.take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        self.active.set(&addr, to_secondary);
+        self.addr = addr;
+        self.failed_connects = 0;
+        self.retry_at = None;
+    }
+
+    // Drops the connection to the old address, the backoff belonged to it.
+    // The endpoint stands for the primary address.
+    fn follow_endpoint(&mut self) {
+        let Some(addr) = self
+            .endpoint
+            .as_ref()
+            .map(|endpoint| endpoint.get().to_string())
+        else {
+            return;
+        };
+
+        if addr != self.primary {
+            eprintln!("Switching from {} to {}", self.addr, addr);
+
+            if let Some(stream) = self.stream.take() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+            self.active.set(&addr, false);
+            self.primary = addr.clone();
+            self.addr = addr;
+            self.failed_connects = 0;
+            self.retry_at = None;
         }
     }
 
     fn connect(&mut self) -> std::io::Result<()> {
+        if let Some(wait) = self
+            .retry_at
+            .and_then(|retry_at| retry_at.checked_duration_since(Instant::now()))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!(
+                    "Connection to {} failed, next attempt in {} ms",
+                    self.addr,
+                    wait.as_millis()
+                ),
+            ));
+        }
+
+        let result = self.try_connect();
+
+        if result.is_ok() {
+            self.failed_connects = 0;
+            self.retry_at = None;
+        } else {
+            let backoff = BACKOFF_BASE
+                .saturating_mul(2u32.saturating_pow(self.failed_connects))
+                .min(BACKOFF_MAX);
+            self.failed_connects = self.failed_connects.saturating_add(1);
+            self.retry_at = Some(Instant::now() + backoff);
+
+            self.fail_over();
+        }
+
+        result
+    }
+
+    fn try_connect(&mut self) -> std::io::Result<()> {
         for attempt in 0..=self.max_retries {
             match TcpStream::connect_timeout(
                 &self
@@ -62,6 +254,8 @@ impl LazyTcpStream {
     }
 
     fn ensure_connected(&mut self) -> std::io::Result<()> {
+        self.follow_endpoint();
+
         if self.stream.is_none() {
             self.connect()?;
         }
@@ -0,0 +1,79 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Reservation {
+    token: u64,
+    expires_at: Instant,
+}
+
+// Exclusive motion reservation of a single axis. Holders are identified by an
+// opaque token chosen by the client; the reservation lapses on its own once
+// the lease runs out so a crashed client can't lock an axis forever.
+#[derive(Debug, Default)]
+pub struct Lease {
+    reservation: Mutex<Option<Reservation>>,
+}
+
+impl Lease {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn current(reservation: &mut Option<Reservation>) -> Option<Reservation> {
+        if reservation.is_some_and(|r| r.expires_at <= Instant::now()) {
+            *reservation = None;
+        }
+
+        *reservation
+    }
+
+    fn check_owner(
+        reservation: &mut Option<Reservation>,
+        token: Option<u64>,
+    ) -> Result<(), String> {
+        match Self::current(reservation) {
+            Some(reservation) if Some(reservation.token) != token => Err(format!(
+                "Axis is reserved by another client for {:.1} s",
+                reservation
+                    .expires_at
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn reserve(&self, token: u64, duration: Duration) -> Result<(), String> {
+        let mut reservation = self.reservation.lock().unwrap();
+        Self::check_owner(&mut reservation, Some(token))?;
+
+        *reservation = Some(Reservation {
+            token,
+            expires_at: Instant::now() + duration,
+        });
+
+        Ok(())
+    }
+
+    pub fn release(&self, token: u64) -> Result<(), String> {
+        let mut reservation = self.reservation.lock().unwrap();
+        Self::check_owner(&mut reservation, Some(token))?;
+
+        *reservation = None;
+
+        Ok(())
+    }
+
+    pub fn check(&self, token: Option<u64>) -> Result<(), String> {
+        Self::check_owner(&mut self.reservation.lock().unwrap(), token)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        Self::current(&mut self.reservation.lock().unwrap())
+            .map(|r| r.expires_at.saturating_duration_since(Instant::now()))
+            .unwrap_or_default()
+    }
+}
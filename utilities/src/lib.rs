@@ -1,5 +1,32 @@
+extern crate alloc;
+
+pub mod archiver;
+pub mod axis_mode;
+pub mod binary_frame;
+pub mod build_info;
+pub mod cached_reading;
+pub mod clock;
 pub mod command_executor;
+pub mod conformance;
+pub mod derived;
+pub mod error_code;
+pub mod expression;
+pub mod groups;
 pub mod lazy_tcp;
+pub mod lease;
 pub mod modbus;
 pub mod motor_controller;
+pub mod move_log;
 pub mod moving_average;
+pub mod observer;
+pub mod params;
+pub mod precision;
+pub mod presets;
+pub mod progress;
+pub mod readiness;
+pub mod rfc2217;
+pub mod scheduler;
+pub mod snapshot;
+pub mod systemd;
+pub mod transport;
+pub mod units;
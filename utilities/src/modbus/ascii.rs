@@ -0,0 +1,82 @@
+// Modbus ASCII framing on top of the RTU codec: the RTU frame without its
+// CRC, sent as hex digits between ':' and CRLF and closed by an LRC.
+// Requests are built by the RTU encoders and converted, responses are turned
+// back into RTU frames so codec::parse_response checks them like any other.
+// Like the codec, nothing in here needs std.
+
+use alloc::vec::Vec;
+
+use super::codec::{FrameError, calculate_crc16};
+
+pub const START: u8 = b':';
+pub const END: &[u8] = b"\r\n";
+// ':' + 255 bytes and the LRC as hex + CRLF
+pub const MAX_FRAME_LEN: usize = 1 + 2 * 256 + 2;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+// Two's complement of the byte sum
+pub fn calculate_lrc(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+        .wrapping_neg()
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        _ => None,
+    }
+}
+
+// Takes a complete RTU frame, CRC included
+pub fn encode_frame(rtu_frame: &[u8]) -> Vec<u8> {
+    let data = &rtu_frame[..rtu_frame.len().saturating_sub(2)];
+    let lrc = calculate_lrc(data);
+
+    let mut frame = Vec::with_capacity(1 + 2 * (data.len() + 1) + END.len());
+    frame.push(START);
+    for &byte in data.iter().chain(core::iter::once(&lrc)) {
+        frame.push(HEX_DIGITS[(byte >> 4) as usize]);
+        frame.push(HEX_DIGITS[(byte & 0x0F) as usize]);
+    }
+    frame.extend_from_slice(END);
+
+    frame
+}
+
+// Returns the RTU frame, CRC included, carried by a complete ASCII frame
+pub fn decode_frame(frame: &[u8]) -> Result<Vec<u8>, FrameError> {
+    let digits = frame
+        .strip_prefix(&[START])
+        .and_then(|frame| frame.strip_suffix(END))
+        .ok_or(FrameError::InvalidAscii("missing ':' or CRLF"))?;
+
+    // Slave id, function code and LRC at least
+    if digits.len() % 2 != 0 || digits.len() < 6 {
+        return Err(FrameError::InvalidAscii("truncated frame"));
+    }
+
+    let bytes = digits
+        .chunks(2)
+        .map(|pair| Some(hex_value(pair[0])? << 4 | hex_value(pair[1])?))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(FrameError::InvalidAscii("not a hex digit"))?;
+
+    let (data, lrc) = bytes.split_at(bytes.len() - 1);
+    let expected = calculate_lrc(data);
+    if expected != lrc[0] {
+        return Err(FrameError::InvalidLrc {
+            expected,
+            received: lrc[0],
+        });
+    }
+
+    let mut rtu_frame = Vec::with_capacity(data.len() + 2);
+    rtu_frame.extend_from_slice(data);
+    rtu_frame.extend_from_slice(&calculate_crc16(data).to_le_bytes());
+
+    Ok(rtu_frame)
+}
@@ -0,0 +1,324 @@
+use std::time::Duration;
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _},
+    time::timeout,
+};
+
+use super::{Framing, FunctionCode, ModbusError, ascii, codec, retry::RetryPolicy};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(100);
+// How long a retry waits for leftovers of the failed exchange
+const CLEAR_TIMEOUT: Duration = Duration::from_millis(10);
+
+// Same framing as `Modbus` for transports driven by tokio. Every exchange is
+// bounded by the client's timeout, a stalled bus is a `ModbusError::Timeout`.
+#[derive(Clone)]
+pub struct AsyncModbus {
+    id: u8,
+    timeout: Duration,
+    framing: Framing,
+    retry: RetryPolicy,
+}
+
+// See read_ascii_frame in the blocking client
+async fn read_ascii_frame<T: AsyncRead + Unpin>(client: &mut T) -> Result<Vec<u8>, ModbusError> {
+    let mut frame = Vec::new();
+
+    loop {
+        match client.read_u8().await? {
+            ascii::START => frame = vec![ascii::START],
+            _ if frame.is_empty() => {}
+            b'\n' => {
+                frame.push(b'\n');
+                return Ok(frame);
+            }
+            byte => frame.push(byte),
+        }
+
+        if frame.len() > ascii::MAX_FRAME_LEN {
+            return Err(codec::FrameError::InvalidAscii("frame too long").into());
+        }
+    }
+}
+
+impl AsyncModbus {
+    pub fn new(id: u8) -> Self {
+        Self {
+            id,
+            timeout: DEFAULT_TIMEOUT,
+            framing: Framing::Rtu,
+            retry: RetryPolicy::none(),
+        }
+    }
+
+    // Each attempt gets the full timeout
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    async fn send_receive<T: AsyncRead + AsyncWrite + Unpin>(
+        framing: Framing,
+        client: &mut T,
+        request: &[u8],
+        min_response_len: usize,
+    ) -> Result<Vec<u8>, ModbusError> {
+        match framing {
+            Framing::Rtu => Self::send_receive_rtu(client, request, min_response_len).await,
+            Framing::Ascii => {
+                client.write_all(&ascii::encode_frame(request)).await?;
+                Ok(ascii::decode_frame(&read_ascii_frame(client).await?)?)
+            }
+        }
+    }
+
+    async fn send_receive_rtu<T: AsyncRead + AsyncWrite + Unpin>(
+        client: &mut T,
+        request: &[u8],
+        min_response_len: usize,
+    ) -> Result<Vec<u8>, ModbusError> {
+        client.write_all(request).await?;
+
+        let mut buffer = vec![0; 256];
+
+        client.read_exact(&mut buffer[0..2]).await?;
+
+        let frame_len = if codec::is_exception(buffer[1]) {
+            codec::EXCEPTION_FRAME_LEN
+        } else if codec::is_read_function(buffer[1]) {
+            client.read_exact(&mut buffer[2..3]).await?;
+            codec::read_frame_len(buffer[2], min_response_len)?
+        } else {
+            min_response_len.max(2)
+        };
+
+        let already_read = if codec::is_read_function(buffer[1]) {
+            3
+        } else {
+            2
+        };
+        client
+            .read_exact(&mut buffer[already_read..frame_len])
+            .await?;
+        buffer.truncate(frame_len);
+
+        Ok(buffer)
+    }
+
+    // Drops what is left of a failed exchange, so a late reply is not taken
+    // for the answer to the retry
+    async fn clear_buffer<T: AsyncRead + Unpin>(client: &mut T) -> Result<(), ModbusError> {
+        let mut buf = [0; 256];
+
+        match timeout(CLEAR_TIMEOUT, client.read(&mut buf)).await {
+            Ok(Err(e)) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn transact<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        function: u8,
+        request: &[u8],
+        min_response_len: usize,
+    ) -> Result<Vec<u8>, ModbusError> {
+        let mut attempt = 1;
+
+        loop {
+            let result = timeout(
+                self.timeout,
+                Self::send_receive(self.framing, client, request, min_response_len),
+            )
+            .await
+            .map_err(|_| ModbusError::Timeout)
+            .and_then(|frame| frame)
+            .and_then(|frame| {
+                Ok(codec::parse_response(self.id, function, &frame, min_response_len)?.to_vec())
+            });
+
+            let delay = match &result {
+                Err(e) if codec::is_read_only(function) => self.retry.next_delay(attempt, e),
+                _ => None,
+            };
+
+            match (result, delay) {
+                (Err(_), Some(delay)) => {
+                    tokio::time::sleep(delay).await;
+                    Self::clear_buffer(client).await?;
+                    attempt += 1;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    async fn read_registers<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        function: FunctionCode,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        if count == 0 || count > 125 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid register count. Must be between 1 and 125".to_string(),
+            ));
+        }
+
+        let function = function as u8;
+        let request = codec::encode_read_request(self.id, function, address, count);
+        let payload = self
+            .transact(client, function, &request, 5 + count as usize * 2)
+            .await?;
+
+        Ok(codec::parse_registers(&payload, count)?)
+    }
+
+    pub async fn read_holding_registers<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.read_registers(client, FunctionCode::ReadHoldingRegisters, address, count)
+            .await
+    }
+
+    pub async fn read_input_registers<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.read_registers(client, FunctionCode::ReadInputRegisters, address, count)
+            .await
+    }
+
+    pub async fn write_single_register<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        address: u16,
+        value: u16,
+    ) -> Result<(), ModbusError> {
+        let request = codec::encode_write_single_register(self.id, address, value);
+        let payload = self
+            .transact(
+                client,
+                codec::WRITE_SINGLE_REGISTER,
+                &request,
+                codec::WRITE_RESPONSE_LEN,
+            )
+            .await?;
+
+        Ok(codec::check_echo(
+            &payload,
+            address,
+            value,
+            "register value",
+        )?)
+    }
+
+    pub async fn write_multiple_registers<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        address: u16,
+        values: &[u16],
+    ) -> Result<(), ModbusError> {
+        let count = values.len();
+        if count == 0 || count > 123 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid register count. Must be between 1 and 123".to_string(),
+            ));
+        }
+
+        let request = codec::encode_write_multiple_registers(self.id, address, values);
+        let payload = self
+            .transact(
+                client,
+                codec::WRITE_MULTIPLE_REGISTERS,
+                &request,
+                codec::WRITE_RESPONSE_LEN,
+            )
+            .await?;
+
+        Ok(codec::check_echo(
+            &payload,
+            address,
+            count as u16,
+            "register count",
+        )?)
+    }
+
+    pub async fn mask_write_register<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        address: u16,
+        and_mask: u16,
+        or_mask: u16,
+    ) -> Result<(), ModbusError> {
+        let request = codec::encode_mask_write_register(self.id, address, and_mask, or_mask);
+        let payload = self
+            .transact(
+                client,
+                codec::MASK_WRITE_REGISTER,
+                &request,
+                codec::MASK_WRITE_RESPONSE_LEN,
+            )
+            .await?;
+
+        Ok(codec::check_mask_echo(
+            &payload, address, and_mask, or_mask,
+        )?)
+    }
+
+    pub async fn read_write_multiple_registers<T: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        client: &mut T,
+        read_address: u16,
+        read_count: u16,
+        write_address: u16,
+        values: &[u16],
+    ) -> Result<Vec<u16>, ModbusError> {
+        if read_count == 0 || read_count > 125 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid read count. Must be between 1 and 125".to_string(),
+            ));
+        }
+
+        if values.is_empty() || values.len() > 121 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid write count. Must be between 1 and 121".to_string(),
+            ));
+        }
+
+        let function = FunctionCode::ReadWriteMultipleRegisters as u8;
+        let request = codec::encode_read_write_multiple_registers(
+            self.id,
+            read_address,
+            read_count,
+            write_address,
+            values,
+        );
+        let payload = self
+            .transact(client, function, &request, 5 + read_count as usize * 2)
+            .await?;
+
+        Ok(codec::parse_registers(&payload, read_count)?)
+    }
+}
@@ -0,0 +1,446 @@
+// Pure Modbus RTU framing. Nothing in here touches IO or std-only types, so
+// it can be lifted as is into firmware built on `core` + `alloc`.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+pub const READ_COILS: u8 = 0x01;
+pub const READ_DISCRETE_INPUTS: u8 = 0x02;
+pub const READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const READ_INPUT_REGISTERS: u8 = 0x04;
+pub const WRITE_SINGLE_COIL: u8 = 0x05;
+pub const WRITE_SINGLE_REGISTER: u8 = 0x06;
+pub const WRITE_MULTIPLE_COILS: u8 = 0x0F;
+pub const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+pub const MASK_WRITE_REGISTER: u8 = 0x16;
+pub const READ_WRITE_MULTIPLE_REGISTERS: u8 = 0x17;
+
+// Slave id + function code + CRC
+pub const FRAME_OVERHEAD: usize = 4;
+pub const EXCEPTION_FRAME_LEN: usize = 5;
+// Largest byte count a read response can carry in a 256 byte RTU frame
+pub const MAX_BYTE_COUNT: usize = 250;
+pub const WRITE_RESPONSE_LEN: usize = 8;
+pub const MASK_WRITE_RESPONSE_LEN: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    InvalidCrc {
+        expected: u16,
+        received: u16,
+    },
+    InvalidLength {
+        expected: usize,
+        received: usize,
+    },
+    InvalidSlaveId {
+        expected: u8,
+        received: u8,
+    },
+    InvalidFunctionCode {
+        expected: u8,
+        received: u8,
+    },
+    Exception {
+        function_code: u8,
+        exception_code: u8,
+    },
+    UnexpectedByteCount {
+        expected: usize,
+        received: usize,
+    },
+    UnexpectedEcho {
+        field: &'static str,
+        expected: u16,
+        received: u16,
+    },
+    InvalidLrc {
+        expected: u8,
+        received: u8,
+    },
+    InvalidAscii(&'static str),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::InvalidCrc { expected, received } => write!(
+                f,
+                "CRC error: expected 0x{:04X}, received 0x{:04X}",
+                expected, received
+            ),
+            FrameError::InvalidLength { expected, received } => write!(
+                f,
+                "Invalid frame length: expected {}, received {}",
+                expected, received
+            ),
+            FrameError::InvalidSlaveId { expected, received } => write!(
+                f,
+                "Invalid slave ID: expected {}, received {}",
+                expected, received
+            ),
+            FrameError::InvalidFunctionCode { expected, received } => write!(
+                f,
+                "Invalid function code: expected 0x{:02X}, received 0x{:02X}",
+                expected, received
+            ),
+            FrameError::Exception {
+                function_code,
+                exception_code,
+            } => write!(
+                f,
+                "Modbus exception (function 0x{:02X}): 0x{:02X}",
+                function_code, exception_code
+            ),
+            FrameError::UnexpectedByteCount { expected, received } => write!(
+                f,
+                "Unexpected byte count. Expected {}, received {}",
+                expected, received
+            ),
+            FrameError::UnexpectedEcho {
+                field,
+                expected,
+                received,
+            } => write!(
+                f,
+                "Unexpected {} in response. Expected {}, received {}",
+                field, expected, received
+            ),
+            FrameError::InvalidLrc { expected, received } => write!(
+                f,
+                "LRC error: expected 0x{:02X}, received 0x{:02X}",
+                expected, received
+            ),
+            FrameError::InvalidAscii(reason) => write!(f, "Invalid ASCII frame: {}", reason),
+        }
+    }
+}
+
+pub fn calculate_crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+
+    for &byte in data {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            if (crc & 0x0001) != 0 {
+                crc >>= 1;
+                crc ^= 0xA001;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+
+    crc
+}
+
+pub fn encode_request(slave_id: u8, function: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut request = Vec::with_capacity(pdu.len() + FRAME_OVERHEAD);
+    request.push(slave_id);
+    request.push(function);
+    request.extend_from_slice(pdu);
+
+    let crc = calculate_crc16(&request);
+    request.push((crc & 0xFF) as u8);
+    request.push((crc >> 8) as u8);
+
+    request
+}
+
+// Covers all four read functions, they share the same request layout
+pub fn encode_read_request(slave_id: u8, function: u8, address: u16, count: u16) -> Vec<u8> {
+    let [address_hi, address_lo] = address.to_be_bytes();
+    let [count_hi, count_lo] = count.to_be_bytes();
+
+    encode_request(
+        slave_id,
+        function,
+        &[address_hi, address_lo, count_hi, count_lo],
+    )
+}
+
+pub fn encode_write_single_register(slave_id: u8, address: u16, value: u16) -> Vec<u8> {
+    let [address_hi, address_lo] = address.to_be_bytes();
+    let [value_hi, value_lo] = value.to_be_bytes();
+
+    encode_request(
+        slave_id,
+        WRITE_SINGLE_REGISTER,
+        &[address_hi, address_lo, value_hi, value_lo],
+    )
+}
+
+pub fn encode_write_single_coil(slave_id: u8, address: u16, value: bool) -> Vec<u8> {
+    let [address_hi, address_lo] = address.to_be_bytes();
+    let value_hi = if value { 0xFF } else { 0x00 };
+
+    encode_request(
+        slave_id,
+        WRITE_SINGLE_COIL,
+        &[address_hi, address_lo, value_hi, 0x00],
+    )
+}
+
+pub fn encode_write_multiple_registers(slave_id: u8, address: u16, values: &[u16]) -> Vec<u8> {
+    let count = values.len();
+
+    let mut pdu = Vec::with_capacity(5 + count * 2);
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&(count as u16).to_be_bytes());
+    pdu.push((count * 2) as u8);
+
+    for &value in values {
+        pdu.extend_from_slice(&value.to_be_bytes());
+    }
+
+    encode_request(slave_id, WRITE_MULTIPLE_REGISTERS, &pdu)
+}
+
+pub fn encode_write_multiple_coils(slave_id: u8, address: u16, values: &[bool]) -> Vec<u8> {
+    let count = values.len();
+    let byte_count = count.div_ceil(8);
+
+    let mut pdu = Vec::with_capacity(5 + byte_count);
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&(count as u16).to_be_bytes());
+    pdu.push(byte_count as u8);
+
+    for chunk in values.chunks(8) {
+        let byte = chunk
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value)
+            .fold(0u8, |byte, (bit_index, _)| byte | (1 << bit_index));
+        pdu.push(byte);
+    }
+
+    encode_request(slave_id, WRITE_MULTIPLE_COILS, &pdu)
+}
+
+// The register contents become (current & and_mask) | (or_mask & !and_mask)
+pub fn encode_mask_write_register(
+    slave_id: u8,
+    address: u16,
+    and_mask: u16,
+    or_mask: u16,
+) -> Vec<u8> {
+    let [address_hi, address_lo] = address.to_be_bytes();
+    let [and_hi, and_lo] = and_mask.to_be_bytes();
+    let [or_hi, or_lo] = or_mask.to_be_bytes();
+
+    encode_request(
+        slave_id,
+        MASK_WRITE_REGISTER,
+        &[address_hi, address_lo, and_hi, and_lo, or_hi, or_lo],
+    )
+}
+
+// The write is done before the read
+pub fn encode_read_write_multiple_registers(
+    slave_id: u8,
+    read_address: u16,
+    read_count: u16,
+    write_address: u16,
+    values: &[u16],
+) -> Vec<u8> {
+    let count = values.len();
+
+    let mut pdu = Vec::with_capacity(9 + count * 2);
+    pdu.extend_from_slice(&read_address.to_be_bytes());
+    pdu.extend_from_slice(&read_count.to_be_bytes());
+    pdu.extend_from_slice(&write_address.to_be_bytes());
+    pdu.extend_from_slice(&(count as u16).to_be_bytes());
+    pdu.push((count * 2) as u8);
+
+    for &value in values {
+        pdu.extend_from_slice(&value.to_be_bytes());
+    }
+
+    encode_request(slave_id, READ_WRITE_MULTIPLE_REGISTERS, &pdu)
+}
+
+// Functions whose response carries a byte count after the function code
+pub fn is_read_function(function: u8) -> bool {
+    matches!(
+        function,
+        READ_COILS
+            | READ_DISCRETE_INPUTS
+            | READ_HOLDING_REGISTERS
+            | READ_INPUT_REGISTERS
+            | READ_WRITE_MULTIPLE_REGISTERS
+    )
+}
+
+// Length of a read response from its byte count, which has to match what
+// the request asked for. A garbled count is refused before the rest of the
+// frame is read.
+pub fn read_frame_len(byte_count: u8, expected_len: usize) -> Result<usize, FrameError> {
+    let frame_len = byte_count as usize + 5;
+
+    if byte_count as usize > MAX_BYTE_COUNT || frame_len != expected_len {
+        return Err(FrameError::InvalidLength {
+            expected: expected_len,
+            received: frame_len,
+        });
+    }
+
+    Ok(frame_len)
+}
+
+// Functions that change nothing on the slave, so asking again is safe
+pub fn is_read_only(function: u8) -> bool {
+    matches!(
+        function,
+        READ_COILS | READ_DISCRETE_INPUTS | READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS
+    )
+}
+
+pub fn is_exception(function: u8) -> bool {
+    function & 0x80 == 0x80
+}
+
+fn check_crc(frame: &[u8]) -> Result<(), FrameError> {
+    let data_len = frame.len() - 2;
+    let received = u16::from_le_bytes([frame[data_len], frame[data_len + 1]]);
+    let expected = calculate_crc16(&frame[..data_len]);
+
+    if expected != received {
+        return Err(FrameError::InvalidCrc { expected, received });
+    }
+
+    Ok(())
+}
+
+// Validates a complete response frame and returns the bytes between the
+// function code and the CRC
+pub fn parse_response(
+    slave_id: u8,
+    function: u8,
+    frame: &[u8],
+    min_len: usize,
+) -> Result<&[u8], FrameError> {
+    if frame.len() >= EXCEPTION_FRAME_LEN && is_exception(frame[1]) {
+        check_crc(&frame[..EXCEPTION_FRAME_LEN])?;
+
+        return Err(FrameError::Exception {
+            function_code: frame[1] & 0x7F,
+            exception_code: frame[2],
+        });
+    }
+
+    if frame.len() < min_len.max(FRAME_OVERHEAD) {
+        return Err(FrameError::InvalidLength {
+            expected: min_len.max(FRAME_OVERHEAD),
+            received: frame.len(),
+        });
+    }
+
+    if frame[0] != slave_id {
+        return Err(FrameError::InvalidSlaveId {
+            expected: slave_id,
+            received: frame[0],
+        });
+    }
+
+    check_crc(frame)?;
+
+    if frame[1] != function {
+        return Err(FrameError::InvalidFunctionCode {
+            expected: function,
+            received: frame[1],
+        });
+    }
+
+    Ok(&frame[2..frame.len() - 2])
+}
+
+pub fn parse_registers(payload: &[u8], count: u16) -> Result<Vec<u16>, FrameError> {
+    let expected = count as usize * 2;
+    let received = payload.first().copied().unwrap_or_default() as usize;
+
+    if received != expected || payload.len() < expected + 1 {
+        return Err(FrameError::UnexpectedByteCount { expected, received });
+    }
+
+    Ok(payload[1..=expected]
+        .chunks(2)
+        .map(|chunk| u16::from_be_bytes([chunk[0], chunk[1]]))
+        .collect())
+}
+
+pub fn parse_bits(payload: &[u8], count: u16) -> Result<Vec<bool>, FrameError> {
+    let expected = (count as usize).div_ceil(8);
+    let received = payload.first().copied().unwrap_or_default() as usize;
+
+    if received != expected || payload.len() < expected + 1 {
+        return Err(FrameError::UnexpectedByteCount { expected, received });
+    }
+
+    Ok((0..count as usize)
+        .map(|i| payload[1 + i / 8] & (1 << (i % 8)) != 0)
+        .collect())
+}
+
+// Write responses echo back the address and the value or quantity written
+pub fn check_echo(
+    payload: &[u8],
+    address: u16,
+    value: u16,
+    value_field: &'static str,
+) -> Result<(), FrameError> {
+    if payload.len() < 4 {
+        return Err(FrameError::InvalidLength {
+            expected: WRITE_RESPONSE_LEN,
+            received: payload.len() + FRAME_OVERHEAD,
+        });
+    }
+
+    let received_address = u16::from_be_bytes([payload[0], payload[1]]);
+    if received_address != address {
+        return Err(FrameError::UnexpectedEcho {
+            field: "address",
+            expected: address,
+            received: received_address,
+        });
+    }
+
+    let received_value = u16::from_be_bytes([payload[2], payload[3]]);
+    if received_value != value {
+        return Err(FrameError::UnexpectedEcho {
+            field: value_field,
+            expected: value,
+            received: received_value,
+        });
+    }
+
+    Ok(())
+}
+
+// Mask write responses echo the whole request
+pub fn check_mask_echo(
+    payload: &[u8],
+    address: u16,
+    and_mask: u16,
+    or_mask: u16,
+) -> Result<(), FrameError> {
+    if payload.len() < 6 {
+        return Err(FrameError::InvalidLength {
+            expected: MASK_WRITE_RESPONSE_LEN,
+            received: payload.len() + FRAME_OVERHEAD,
+        });
+    }
+
+    check_echo(&payload[..4], address, and_mask, "AND mask")?;
+
+    let received = u16::from_be_bytes([payload[4], payload[5]]);
+    if received != or_mask {
+        return Err(FrameError::UnexpectedEcho {
+            field: "OR mask",
+            expected: or_mask,
+            received,
+        });
+    }
+
+    Ok(())
+}
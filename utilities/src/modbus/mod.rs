@@ -0,0 +1,621 @@
+pub mod ascii;
+pub mod async_client;
+pub mod codec;
+pub mod retry;
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use codec::FrameError;
+pub use codec::calculate_crc16;
+use retry::RetryPolicy;
+use serde::{Deserialize, Serialize};
+
+// How frames go over the line. Some serial gateways only pass ASCII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Framing {
+    #[default]
+    Rtu,
+    Ascii,
+}
+
+#[derive(Debug)]
+pub enum ModbusError {
+    IoError(std::io::Error),
+    InvalidCrc {
+        expected: u16,
+        received: u16,
+    },
+    InvalidResponseLength {
+        expected: usize,
+        received: usize,
+    },
+    InvalidSlaveId {
+        expected: u8,
+        received: u8,
+    },
+    InvalidFunctionCode {
+        expected: u8,
+        received: u8,
+    },
+    ExceptionResponse {
+        function_code: u8,
+        exception_code: u8,
+    },
+    Timeout,
+    ProtocolError(String),
+}
+
+impl fmt::Display for ModbusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModbusError::IoError(err) => write!(f, "IO error: {}", err),
+            ModbusError::InvalidCrc { expected, received } => {
+                write!(
+                    f,
+                    "CRC error: expected 0x{:04X}, received 0x{:04X}",
+                    expected, received
+                )
+            }
+            ModbusError::InvalidResponseLength { expected, received } => {
+                write!(
+                    f,
+                    "Invalid response length: expected {}, received {}",
+                    expected, received
+                )
+            }
+            ModbusError::InvalidSlaveId { expected, received } => {
+                write!(
+                    f,
+                    "Invalid slave ID: expected {}, received {}",
+                    expected, received
+                )
+            }
+            ModbusError::InvalidFunctionCode { expected, received } => {
+                write!(
+                    f,
+                    "Invalid function code: expected 0x{:02X}, received 0x{:02X}",
+                    expected, received
+                )
+            }
+            ModbusError::ExceptionResponse {
+                function_code,
+                exception_code,
+            } => {
+                let exception_msg = match exception_code {
+                    0x01 => "Illegal Function",
+                    0x02 => "Illegal Data Address",
+                    0x03 => "Illegal Data Value",
+                    0x04 => "Slave Device Failure",
+                    0x05 => "Acknowledge",
+                    0x06 => "Slave Device Busy",
+                    0x07 => "Negative Acknowledge",
+                    0x08 => "Memory Parity Error",
+                    0x0A => "Gateway Path Unavailable",
+                    0x0B => "Gateway Target Device Failed To Respond",
+                    _ => "Unknown Exception",
+                };
+                write!(
+                    f,
+                    "Modbus exception (function 0x{:02X}): {} (0x{:02X})",
+                    function_code, exception_msg, exception_code
+                )
+            }
+            ModbusError::Timeout => write!(f, "Request timed out"),
+            ModbusError::ProtocolError(msg) => write!(f, "Protocol error: {}", msg),
+        }
+    }
+}
+
+impl Error for ModbusError {}
+
+// The ModbusError is kept inside so ErrorCode::from_io still finds it
+impl From<ModbusError> for std::io::Error {
+    fn from(error: ModbusError) -> Self {
+        match error {
+            ModbusError::IoError(error) => std::io::Error::from(error),
+            _ => std::io::Error::new(std::io::ErrorKind::Other, error),
+        }
+    }
+}
+
+impl From<FrameError> for ModbusError {
+    fn from(error: FrameError) -> Self {
+        match error {
+            FrameError::InvalidCrc { expected, received } => {
+                ModbusError::InvalidCrc { expected, received }
+            }
+            FrameError::InvalidLength { expected, received } => {
+                ModbusError::InvalidResponseLength { expected, received }
+            }
+            FrameError::InvalidSlaveId { expected, received } => {
+                ModbusError::InvalidSlaveId { expected, received }
+            }
+            FrameError::InvalidFunctionCode { expected, received } => {
+                ModbusError::InvalidFunctionCode { expected, received }
+            }
+            FrameError::Exception {
+                function_code,
+                exception_code,
+            } => ModbusError::ExceptionResponse {
+                function_code,
+                exception_code,
+            },
+            error => ModbusError::ProtocolError(error.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ModbusError {
+    fn from(error: std::io::Error) -> Self {
+        ModbusError::IoError(error)
+    }
+}
+
+pub enum FunctionCode {
+    ReadCoils = 0x01,
+    ReadDiscreteInputs = 0x02,
+    ReadHoldingRegisters = 0x03,
+    ReadInputRegisters = 0x04,
+    WriteSingleCoil = 0x05,
+    WriteSingleRegister = 0x06,
+    WriteMultipleCoils = 0x0F,
+    WriteMultipleRegisters = 0x10,
+    MaskWriteRegister = 0x16,
+    ReadWriteMultipleRegisters = 0x17,
+}
+
+#[derive(Clone)]
+pub struct Modbus {
+    id: u8,
+    framing: Framing,
+    retry: RetryPolicy,
+}
+
+// Reads up to and including the LF, whatever comes before the ':' is line
+// noise from an earlier exchange and dropped
+fn read_ascii_frame<T: Read>(client: &mut T) -> Result<Vec<u8>, ModbusError> {
+    let mut frame = Vec::new();
+    let mut byte = [0u8];
+
+    loop {
+        client.read_exact(&mut byte)?;
+
+        match byte[0] {
+            ascii::START => frame = vec![ascii::START],
+            _ if frame.is_empty() => {}
+            b'\n' => {
+                frame.push(b'\n');
+                return Ok(frame);
+            }
+            byte => frame.push(byte),
+        }
+
+        if frame.len() > ascii::MAX_FRAME_LEN {
+            return Err(FrameError::InvalidAscii("frame too long").into());
+        }
+    }
+}
+
+// Drops what is left of a failed exchange, so a late reply is not taken for
+// the answer to the retry
+fn clear_buffer<T: Read>(client: &mut T) -> io::Result<()> {
+    let mut buf = [0; 256];
+
+    match client.read(&mut buf) {
+        Ok(_) => Ok(()),
+        Err(ref e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+impl Modbus {
+    pub fn new(id: u8) -> Self {
+        Self {
+            id,
+            framing: Framing::Rtu,
+            retry: RetryPolicy::none(),
+        }
+    }
+
+    // Applied to every exchange of this client
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    pub fn with_framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: u8) -> &mut Self {
+        self.id = id;
+        self
+    }
+
+    // Requests and responses stay RTU frames on this side, ASCII is only
+    // the line encoding
+    fn send_receive<T: Read + Write>(
+        &self,
+        client: &mut T,
+        request: &[u8],
+        min_response_len: usize,
+    ) -> Result<Vec<u8>, ModbusError> {
+        match self.framing {
+            Framing::Rtu => self.send_receive_rtu(client, request, min_response_len),
+            Framing::Ascii => {
+                client.write_all(&ascii::encode_frame(request))?;
+                Ok(ascii::decode_frame(&read_ascii_frame(client)?)?)
+            }
+        }
+    }
+
+    fn send_receive_rtu<T: Read + Write>(
+        &self,
+        client: &mut T,
+        request: &[u8],
+        min_response_len: usize,
+    ) -> Result<Vec<u8>, ModbusError> {
+        client.write_all(request)?;
+
+        let mut buffer = vec![0; 256];
+
+        client.read_exact(&mut buffer[0..2])?;
+
+        let frame_len = if codec::is_exception(buffer[1]) {
+            codec::EXCEPTION_FRAME_LEN
+        } else if codec::is_read_function(buffer[1]) {
+            client.read_exact(&mut buffer[2..3])?;
+            codec::read_frame_len(buffer[2], min_response_len)?
+        } else {
+            min_response_len.max(2)
+        };
+
+        let already_read = if codec::is_read_function(buffer[1]) {
+            3
+        } else {
+            2
+        };
+        client.read_exact(&mut buffer[already_read..frame_len])?;
+        buffer.truncate(frame_len);
+
+        Ok(buffer)
+    }
+
+    fn transact<T: Read + Write>(
+        &self,
+        client: &mut T,
+        function: u8,
+        request: &[u8],
+        min_response_len: usize,
+    ) -> Result<Vec<u8>, ModbusError> {
+        let mut attempt = 1;
+
+        loop {
+            let result = self
+                .send_receive(client, request, min_response_len)
+                .and_then(|frame| {
+                    Ok(
+                        codec::parse_response(self.id, function, &frame, min_response_len)?
+                            .to_vec(),
+                    )
+                });
+
+            let delay = match &result {
+                Err(e) if codec::is_read_only(function) => self.retry.next_delay(attempt, e),
+                _ => None,
+            };
+
+            match (result, delay) {
+                (Err(_), Some(delay)) => {
+                    std::thread::sleep(delay);
+                    clear_buffer(client)?;
+                    attempt += 1;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    fn read_registers<T: Read + Write>(
+        &self,
+        client: &mut T,
+        function: FunctionCode,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        if count == 0 || count > 125 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid register count. Must be between 1 and 125".to_string(),
+            ));
+        }
+
+        let function = function as u8;
+        let request = codec::encode_read_request(self.id, function, address, count);
+        let payload = self.transact(client, function, &request, 5 + count as usize * 2)?;
+
+        Ok(codec::parse_registers(&payload, count)?)
+    }
+
+    fn read_bits<T: Read + Write>(
+        &self,
+        client: &mut T,
+        function: FunctionCode,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        let function = function as u8;
+        let request = codec::encode_read_request(self.id, function, address, count);
+        let payload =
+            self.transact(client, function, &request, 5 + (count as usize).div_ceil(8))?;
+
+        Ok(codec::parse_bits(&payload, count)?)
+    }
+
+    pub fn read_holding_registers<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.read_registers(client, FunctionCode::ReadHoldingRegisters, address, count)
+    }
+
+    pub fn read_holding_register<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+    ) -> Result<u16, ModbusError> {
+        let registers = self.read_holding_registers(client, address, 1)?;
+        Ok(registers[0])
+    }
+
+    pub fn read_input_registers<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, ModbusError> {
+        self.read_registers(client, FunctionCode::ReadInputRegisters, address, count)
+    }
+
+    pub fn read_input_register<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+    ) -> Result<u16, ModbusError> {
+        let registers = self.read_input_registers(client, address, 1)?;
+        Ok(registers[0])
+    }
+
+    pub fn read_coils<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        if count == 0 || count > 2000 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid coil count. Must be between 1 and 2000".to_string(),
+            ));
+        }
+
+        self.read_bits(client, FunctionCode::ReadCoils, address, count)
+    }
+
+    pub fn read_coil<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+    ) -> Result<bool, ModbusError> {
+        let coils = self.read_coils(client, address, 1)?;
+        Ok(coils[0])
+    }
+
+    pub fn read_discrete_inputs<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<bool>, ModbusError> {
+        if count == 0 || count > 2000 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid input count. Must be between 1 and 2000".to_string(),
+            ));
+        }
+
+        self.read_bits(client, FunctionCode::ReadDiscreteInputs, address, count)
+    }
+
+    pub fn read_discrete_input<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+    ) -> Result<bool, ModbusError> {
+        let inputs = self.read_discrete_inputs(client, address, 1)?;
+        Ok(inputs[0])
+    }
+
+    pub fn write_single_register<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        value: u16,
+    ) -> Result<(), ModbusError> {
+        let request = codec::encode_write_single_register(self.id, address, value);
+        let payload = self.transact(
+            client,
+            codec::WRITE_SINGLE_REGISTER,
+            &request,
+            codec::WRITE_RESPONSE_LEN,
+        )?;
+
+        Ok(codec::check_echo(
+            &payload,
+            address,
+            value,
+            "register value",
+        )?)
+    }
+
+    pub fn write_single_coil<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        value: bool,
+    ) -> Result<(), ModbusError> {
+        let request = codec::encode_write_single_coil(self.id, address, value);
+        let payload = self.transact(
+            client,
+            codec::WRITE_SINGLE_COIL,
+            &request,
+            codec::WRITE_RESPONSE_LEN,
+        )?;
+
+        let raw_value = if value { 0xFF00 } else { 0x0000 };
+        Ok(codec::check_echo(
+            &payload,
+            address,
+            raw_value,
+            "coil value",
+        )?)
+    }
+
+    pub fn write_multiple_registers<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        values: &[u16],
+    ) -> Result<(), ModbusError> {
+        let count = values.len();
+        if count == 0 || count > 123 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid register count. Must be between 1 and 123".to_string(),
+            ));
+        }
+
+        let request = codec::encode_write_multiple_registers(self.id, address, values);
+        let payload = self.transact(
+            client,
+            codec::WRITE_MULTIPLE_REGISTERS,
+            &request,
+            codec::WRITE_RESPONSE_LEN,
+        )?;
+
+        Ok(codec::check_echo(
+            &payload,
+            address,
+            count as u16,
+            "register count",
+        )?)
+    }
+
+    pub fn write_multiple_coils<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        values: &[bool],
+    ) -> Result<(), ModbusError> {
+        let count = values.len();
+        if count == 0 || count > 1968 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid coil count. Must be between 1 and 1968".to_string(),
+            ));
+        }
+
+        let request = codec::encode_write_multiple_coils(self.id, address, values);
+        let payload = self.transact(
+            client,
+            codec::WRITE_MULTIPLE_COILS,
+            &request,
+            codec::WRITE_RESPONSE_LEN,
+        )?;
+
+        Ok(codec::check_echo(
+            &payload,
+            address,
+            count as u16,
+            "coil count",
+        )?)
+    }
+
+    // Changes only the bits cleared in and_mask, to the bits of or_mask, in
+    // one exchange. Nothing written in between by someone else is lost, as
+    // it would be with a read followed by a write.
+    pub fn mask_write_register<T: Read + Write>(
+        &self,
+        client: &mut T,
+        address: u16,
+        and_mask: u16,
+        or_mask: u16,
+    ) -> Result<(), ModbusError> {
+        let request = codec::encode_mask_write_register(self.id, address, and_mask, or_mask);
+        let payload = self.transact(
+            client,
+            codec::MASK_WRITE_REGISTER,
+            &request,
+            codec::MASK_WRITE_RESPONSE_LEN,
+        )?;
+
+        Ok(codec::check_mask_echo(
+            &payload, address, and_mask, or_mask,
+        )?)
+    }
+
+    // Writes `values` at write_address, then reads read_count registers at
+    // read_address
+    pub fn read_write_multiple_registers<T: Read + Write>(
+        &self,
+        client: &mut T,
+        read_address: u16,
+        read_count: u16,
+        write_address: u16,
+        values: &[u16],
+    ) -> Result<Vec<u16>, ModbusError> {
+        if read_count == 0 || read_count > 125 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid read count. Must be between 1 and 125".to_string(),
+            ));
+        }
+
+        if values.is_empty() || values.len() > 121 {
+            return Err(ModbusError::ProtocolError(
+                "Invalid write count. Must be between 1 and 121".to_string(),
+            ));
+        }
+
+        let function = FunctionCode::ReadWriteMultipleRegisters as u8;
+        let request = codec::encode_read_write_multiple_registers(
+            self.id,
+            read_address,
+            read_count,
+            write_address,
+            values,
+        );
+        let payload = self.transact(client, function, &request, 5 + read_count as usize * 2)?;
+
+        Ok(codec::parse_registers(&payload, read_count)?)
+    }
+}
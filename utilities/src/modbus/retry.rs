@@ -0,0 +1,95 @@
+use std::{io, time::Duration};
+
+use super::ModbusError;
+
+// Exception code of a slave still busy with an earlier request
+const SLAVE_DEVICE_BUSY: u8 = 0x06;
+
+// Errors worth another attempt, the bus rather than the request was at fault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryOn {
+    // No complete reply in time, from the client's timeout or the stream's
+    Timeout,
+    // Reply garbled on the line
+    InvalidCrc,
+    // Exception 0x06, the slave asks to be asked again
+    SlaveBusy,
+    // Any other IO error, e.g. a gateway that dropped the connection
+    Io,
+}
+
+impl RetryOn {
+    fn matches(self, error: &ModbusError) -> bool {
+        match (self, error) {
+            (RetryOn::Timeout, ModbusError::Timeout) => true,
+            (RetryOn::Timeout, ModbusError::IoError(e)) => {
+                matches!(
+                    e.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+                )
+            }
+            (RetryOn::InvalidCrc, ModbusError::InvalidCrc { .. }) => true,
+            (RetryOn::SlaveBusy, ModbusError::ExceptionResponse { exception_code, .. }) => {
+                *exception_code == SLAVE_DEVICE_BUSY
+            }
+            (RetryOn::Io, ModbusError::IoError(_)) => !RetryOn::Timeout.matches(error),
+            _ => false,
+        }
+    }
+}
+
+// How a client repeats a failed exchange. The first retry waits `backoff`,
+// every further one twice as long as the one before. Only reads are
+// repeated, a write whose reply got lost may already have been applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    // Exchanges in total, the first one included
+    pub attempts: u8,
+    pub backoff: Duration,
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl RetryPolicy {
+    // Errors go straight to the caller
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    // Retries timeouts and CRC errors without waiting in between
+    pub fn new(attempts: u8) -> Self {
+        Self {
+            attempts: attempts.max(1),
+            backoff: Duration::ZERO,
+            retry_on: vec![RetryOn::Timeout, RetryOn::InvalidCrc],
+        }
+    }
+
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: Vec<RetryOn>) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    // The wait before the next attempt, None once the error is final.
+    // `attempt` counts from 1.
+    pub fn next_delay(&self, attempt: u8, error: &ModbusError) -> Option<Duration> {
+        if attempt >= self.attempts || !self.retry_on.iter().any(|on| on.matches(error)) {
+            return None;
+        }
+
+        Some(
+            self.backoff
+                .saturating_mul(1 << u32::from(attempt - 1).min(16)),
+        )
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use crate::progress::ActionStatus;
+
+// Moves remembered for MOVE_STATUS style polling, the oldest go first
+const CAPACITY: usize = 64;
+
+pub const LAST_MOVE_ID: &str = "last_move_id";
+pub const MOVE_STATUS_PREFIX: &str = "move_status_";
+
+#[derive(Debug, Clone)]
+pub struct MoveEntry {
+    pub id: u64,
+    pub status: ActionStatus,
+    // Why the move failed
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Moves {
+    entries: VecDeque<MoveEntry>,
+    last_id: u64,
+}
+
+// Outcome of recent moves, so a client can start a move, drop the
+// connection and ask later how it ended. A client picks the id with the
+// "move_id" parameter or reads the assigned one from last_move_id, then
+// polls or subscribes to move_status_<id>: 0 unknown, then an ActionStatus.
+#[derive(Clone, Default)]
+pub struct MoveLog {
+    moves: Arc<Mutex<Moves>>,
+}
+
+impl MoveLog {
+    // Starting a move under an id still in the log replaces its entry
+    pub fn start(&self, requested: Option<u64>) -> u64 {
+        let mut moves = self.moves.lock().unwrap();
+
+        let id = requested.unwrap_or(moves.last_id + 1);
+        moves.entries.retain(|entry| entry.id != id);
+        if moves.entries.len() == CAPACITY {
+            moves.entries.pop_front();
+        }
+
+        moves.entries.push_back(MoveEntry {
+            id,
+            status: ActionStatus::Running,
+            error: None,
+        });
+        moves.last_id = moves.last_id.max(id);
+
+        id
+    }
+
+    pub fn finish(&self, id: u64, result: &Result<(), String>) {
+        let mut moves = self.moves.lock().unwrap();
+
+        if let Some(entry) = moves
+            .entries
+            .iter_mut()
+            .find(|entry| entry.id == id && entry.status == ActionStatus::Running)
+        {
+            entry.status = match result {
+                Ok(()) => ActionStatus::Done,
+                Err(_) => ActionStatus::Failed,
+            };
+            entry.error = result.as_ref().err().cloned();
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<MoveEntry> {
+        self.moves
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .cloned()
+    }
+
+    pub fn last_id(&self) -> u64 {
+        self.moves.lock().unwrap().last_id
+    }
+
+    // last_move_id and move_status_<id>, None for other names
+    pub fn attribute(&self, name: &str) -> Option<f64> {
+        if name == LAST_MOVE_ID {
+            return Some(self.last_id() as f64);
+        }
+
+        let id = name.strip_prefix(MOVE_STATUS_PREFIX)?.parse().ok()?;
+        Some(self.get(id).map_or(0.0, |entry| entry.status as u8 as f64))
+    }
+}
@@ -0,0 +1,123 @@
+use std::{sync::Arc, time::Duration};
+
+use motarem::{
+    axis::{Axis, movement_parameters::MovementParams, state_info::AxisStateInfo},
+    controller_manager::{ControllerManager, config::ManagerConfig},
+    motor_controller::MotorController,
+    socket_server::{SocketServer, config::SocketServerConfig},
+};
+
+// Axis as seen from the observer socket, reads pass through and anything
+// that would move hardware is refused
+pub struct ReadOnlyAxis {
+    inner: Arc<dyn Axis>,
+}
+
+#[async_trait::async_trait]
+impl Axis for ReadOnlyAxis {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn start(
+        &self,
+        _position: f64,
+        _parameters: Option<MovementParams>,
+    ) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "{} is read-only on this socket",
+            self.name()
+        ))
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "{} is read-only on this socket",
+            self.name()
+        ))
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        self.inner.get_state().await
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        self.inner.get_attribute(name).await
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        self.inner.get_available_params().await
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+pub struct ReadOnlyController {
+    inner: Arc<dyn MotorController>,
+    axes: Vec<Arc<dyn Axis>>,
+}
+
+impl ReadOnlyController {
+    pub fn new(inner: Arc<dyn MotorController>) -> Self {
+        let axes = inner
+            .axes()
+            .into_iter()
+            .map(|axis| Arc::new(ReadOnlyAxis { inner: axis }) as Arc<dyn Axis>)
+            .collect();
+
+        Self { inner, axes }
+    }
+}
+
+#[async_trait::async_trait]
+impl MotorController for ReadOnlyController {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn axes(&self) -> Vec<Arc<dyn Axis>> {
+        self.axes.clone()
+    }
+
+    // The operator side owns the hardware and shuts it down
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// Second socket for status displays and remote users. It has its own
+// manager, so nothing sent there can reach the operator's controllers
+// except through the read-only wrappers.
+pub async fn start_observer_socket(
+    socket_path: &str,
+    controllers: &[Arc<dyn MotorController>],
+) -> anyhow::Result<SocketServer> {
+    let manager_config = ManagerConfig {
+        default_ttl: Duration::from_secs(1),
+        cache_capacity: 1000,
+    };
+
+    let manager = Arc::new(ControllerManager::new(manager_config));
+
+    for controller in controllers {
+        manager
+            .register_controller(
+                controller.name().to_string(),
+                Arc::new(ReadOnlyController::new(controller.clone())),
+            )
+            .await?;
+    }
+
+    let socket_config = SocketServerConfig {
+        socket_path: socket_path.to_string(),
+        max_connections: 50,
+        buffer_size: 8192,
+    };
+
+    let mut socket_server = SocketServer::new(socket_config, manager);
+    socket_server.start().await?;
+
+    Ok(socket_server)
+}
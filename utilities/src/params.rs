@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use motarem::axis::movement_parameters::MovementParams;
+
+// Movement parameters are whatever a client on the socket typed, "-1",
+// "nan" and "1e400" included. These turn them into values or errors, never
+// into a panic or a window no position can satisfy.
+
+pub fn finite(name: &str, value: f64) -> Result<f64, String> {
+    if !value.is_finite() {
+        return Err(format!("Invalid {}: {}", name, value));
+    }
+
+    Ok(value)
+}
+
+pub fn seconds(name: &str, value: f64) -> Result<Duration, String> {
+    Duration::try_from_secs_f64(value).map_err(|_| format!("Invalid {}: {} s", name, value))
+}
+
+pub fn custom_finite(params: &MovementParams, name: &str) -> Result<Option<f64>, String> {
+    params
+        .custom
+        .get(name)
+        .map(|value| finite(name, *value))
+        .transpose()
+}
+
+pub fn custom_seconds(params: &MovementParams, name: &str) -> Result<Option<Duration>, String> {
+    params
+        .custom
+        .get(name)
+        .map(|value| seconds(name, *value))
+        .transpose()
+}
+
+// Ids up to 2^53, above that a f64 no longer holds every integer
+pub fn custom_id(params: &MovementParams, name: &str) -> Result<Option<u64>, String> {
+    params
+        .custom
+        .get(name)
+        .map(|value| {
+            if value.is_finite()
+                && *value >= 0.0
+                && value.fract() == 0.0
+                && *value <= 9007199254740992.0
+            {
+                Ok(*value as u64)
+            } else {
+                Err(format!("Invalid {}: {}", name, value))
+            }
+        })
+        .transpose()
+}
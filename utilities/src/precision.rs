@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use motarem::{
+    axis::{Axis, movement_parameters::MovementParams, state_info::AxisStateInfo},
+    motor_controller::MotorController,
+};
+
+// Decimals reported for an attribute, by name. Group members' attributes
+// ("Y_Up.position") go by the part after the last dot.
+pub type Precision = BTreeMap<String, u32>;
+
+pub fn default_precision() -> Precision {
+    BTreeMap::from([("position".to_string(), 4), ("temperature".to_string(), 1)])
+}
+
+// Values are rounded rather than formatted here, the socket server prints the
+// shortest form of the rounded number the same way whatever the locale
+fn round(value: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (value * scale).round() / scale
+}
+
+pub struct PrecisionAxis {
+    inner: Arc<dyn Axis>,
+    precision: Arc<Precision>,
+}
+
+#[async_trait::async_trait]
+impl Axis for PrecisionAxis {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
+        self.inner.start(position, parameters).await
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.inner.stop().await
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        self.inner.get_state().await
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        let value = self.inner.get_attribute(name).await?;
+        let attribute = name.rsplit('.').next().unwrap_or(name);
+
+        Ok(match self.precision.get(attribute) {
+            Some(decimals) => round(value, *decimals),
+            None => value,
+        })
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        self.inner.get_available_params().await
+    }
+
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        self.inner.get_supported_movement_params().await
+    }
+}
+
+pub struct PrecisionController {
+    inner: Arc<dyn MotorController>,
+    axes: Vec<Arc<dyn Axis>>,
+}
+
+#[async_trait::async_trait]
+impl MotorController for PrecisionController {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn axes(&self) -> Vec<Arc<dyn Axis>> {
+        self.axes.clone()
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+// Outermost wrapper, so derived, group and preset attributes are rounded too
+pub fn with_precision(
+    controller: Arc<dyn MotorController>,
+    precision: &Precision,
+) -> Arc<dyn MotorController> {
+    if precision.is_empty() {
+        return controller;
+    }
+
+    let precision = Arc::new(precision.clone());
+    let axes = controller
+        .axes()
+        .into_iter()
+        .map(|axis| {
+            Arc::new(PrecisionAxis {
+                inner: axis,
+                precision: precision.clone(),
+            }) as Arc<dyn Axis>
+        })
+        .collect();
+
+    Arc::new(PrecisionController {
+        inner: controller,
+        axes,
+    })
+}
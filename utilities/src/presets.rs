@@ -0,0 +1,222 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use motarem::{
+    axis::{Axis, movement_parameters::MovementParams, state_info::AxisStateInfo},
+    motor_controller::MotorController,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::groups::{GroupAxis, GroupController, GroupPolicy};
+
+// Axis the presets of a controller are served under
+pub const PRESET_AXIS: &str = "preset";
+
+fn default_tolerance() -> f64 {
+    0.005
+}
+
+// Named set of targets, e.g. a beam size, moved to with one command:
+// `START preset 2` moves to the third preset in the configuration, and
+// `GET preset position` reads which preset the axes are at, -1 for none.
+// "preset:<name>" reads the index of a preset by name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetConfig {
+    // Only needed when several controllers could resolve the preset
+    #[serde(default)]
+    pub controller: Option<String>,
+    pub name: String,
+    pub targets: BTreeMap<String, f64>,
+    // A preset is active while every axis is this close to its target
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+struct Preset {
+    name: String,
+    targets: Vec<(String, f64)>,
+    tolerance: f64,
+}
+
+// Moves through a StopAll group of every axis named in a preset, so a
+// preset either lands on all of its targets or stops every axis
+pub struct PresetAxis {
+    group: GroupAxis,
+    presets: Vec<Preset>,
+}
+
+impl PresetAxis {
+    async fn active(&self) -> anyhow::Result<Option<usize>> {
+        let mut positions = BTreeMap::new();
+        for member in self.group.members() {
+            positions.insert(member.name(), member.get_attribute("position").await?);
+        }
+
+        Ok(self.presets.iter().position(|preset| {
+            preset.targets.iter().all(|(axis, target)| {
+                positions
+                    .get(axis.as_str())
+                    .is_some_and(|position| (position - target).abs() <= preset.tolerance)
+            })
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Axis for PresetAxis {
+    fn name(&self) -> &str {
+        PRESET_AXIS
+    }
+
+    async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
+        let preset = (position >= 0.0 && position.fract() == 0.0)
+            .then(|| self.presets.get(position as usize))
+            .flatten()
+            .ok_or_else(|| anyhow::Error::msg(format!("Unknown preset: {}", position)))?;
+
+        let mut parameters = parameters.unwrap_or_default();
+        for (axis, target) in &preset.targets {
+            parameters.custom.insert(axis.clone(), *target);
+        }
+
+        self.group.start(position, Some(parameters)).await
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.group.stop().await
+    }
+
+    async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        let mut state = self.group.get_state().await?;
+
+        if let Some(index) = self.active().await? {
+            let active = format!("At {}", self.presets[index].name);
+            state.message = Some(match state.message {
+                Some(message) => format!("{}; {}", active, message),
+                None => active,
+            });
+        }
+
+        Ok(state)
+    }
+
+    async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
+        match name {
+            "position" | "active_preset" => {
+                Ok(self.active().await?.map_or(-1.0, |index| index as f64))
+            }
+            "moving" => self.group.get_attribute("moving").await,
+            _ => name
+                .strip_prefix("preset:")
+                .and_then(|name| self.presets.iter().position(|preset| preset.name == name))
+                .map(|index| index as f64)
+                .ok_or_else(|| anyhow::Error::msg(format!("Unknown attribute: {}", name))),
+        }
+    }
+
+    async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
+        let mut params = vec![
+            "position".to_string(),
+            "active_preset".to_string(),
+            "moving".to_string(),
+        ];
+        params.extend(
+            self.presets
+                .iter()
+                .map(|preset| format!("preset:{}", preset.name)),
+        );
+
+        Ok(params)
+    }
+
+    // Targets come from the preset, only the shared parameters are left
+    async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
+        let members: Vec<&str> = self
+            .group
+            .members()
+            .iter()
+            .map(|member| member.name())
+            .collect();
+
+        Ok(self
+            .group
+            .get_supported_movement_params()
+            .await?
+            .into_iter()
+            .filter(|param| !members.contains(&param.as_str()))
+            .collect())
+    }
+}
+
+// Adds a preset axis when presets resolve against the controller, skipping
+// presets for other controllers the same way axis groups do
+pub fn with_presets(
+    controller: Arc<dyn MotorController>,
+    configs: &[PresetConfig],
+) -> Result<Arc<dyn MotorController>, String> {
+    let axes = controller.axes();
+    let find = |name: &str| axes.iter().find(|axis| axis.name() == name);
+
+    let configs: Vec<_> = configs
+        .iter()
+        .filter(|config| match &config.controller {
+            Some(name) => name == controller.name(),
+            None => config.targets.keys().all(|axis| find(axis).is_some()),
+        })
+        .collect();
+
+    if configs.is_empty() {
+        return Ok(controller);
+    }
+
+    if find(PRESET_AXIS).is_some() {
+        return Err(format!(
+            "{} already has an axis named {}",
+            controller.name(),
+            PRESET_AXIS
+        ));
+    }
+
+    let mut members: Vec<Arc<dyn Axis>> = Vec::new();
+    let mut presets: Vec<Preset> = Vec::new();
+    for config in configs {
+        if presets.iter().any(|preset| preset.name == config.name) {
+            return Err(format!("Preset {} is configured twice", config.name));
+        }
+
+        if config.targets.is_empty() {
+            return Err(format!("Preset {} has no targets", config.name));
+        }
+
+        for name in config.targets.keys() {
+            let axis = find(name)
+                .ok_or_else(|| format!("Unknown axis in preset {}: {}", config.name, name))?;
+
+            if !members.iter().any(|member| member.name() == name) {
+                members.push(axis.clone());
+            }
+        }
+
+        presets.push(Preset {
+            name: config.name.clone(),
+            targets: config
+                .targets
+                .iter()
+                .map(|(axis, target)| (axis.clone(), *target))
+                .collect(),
+            tolerance: config.tolerance,
+        });
+    }
+
+    let preset = PresetAxis {
+        group: GroupAxis::new(PRESET_AXIS.to_string(), members, GroupPolicy::StopAll),
+        presets,
+    };
+
+    Ok(Arc::new(GroupController {
+        inner: controller,
+        axes: axes
+            .into_iter()
+            .chain(std::iter::once(Arc::new(preset) as Arc<dyn Axis>))
+            .collect(),
+    }))
+}
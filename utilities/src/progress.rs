@@ -0,0 +1,129 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Attributes every axis with long actions answers, see ActionProgress::attribute
+pub const ATTRIBUTES: [&str; 3] = ["action_status", "action_progress", "action_elapsed"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionStatus {
+    Running = 1,
+    Done = 2,
+    Failed = 3,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionReport {
+    // "homing", "calibration"
+    pub action: &'static str,
+    pub status: ActionStatus,
+    // What the action is doing, or why it failed
+    pub stage: String,
+    // 0 to 1 when the action knows how far along it is
+    pub fraction: Option<f64>,
+    started: Instant,
+    finished: Option<Instant>,
+}
+
+impl ActionReport {
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(Instant::now) - self.started
+    }
+}
+
+// Where a long action of an axis stands. Clients see it in the state
+// message while it runs and after it failed, and as numbers through
+// ATTRIBUTES, so a subscription follows it like any other attribute.
+#[derive(Clone, Default)]
+pub struct ActionProgress {
+    report: Arc<Mutex<Option<ActionReport>>>,
+}
+
+impl ActionProgress {
+    pub fn start(&self, action: &'static str, stage: &str) {
+        *self.report.lock().unwrap() = Some(ActionReport {
+            action,
+            status: ActionStatus::Running,
+            stage: stage.to_string(),
+            fraction: None,
+            started: Instant::now(),
+            finished: None,
+        });
+    }
+
+    // Ignored unless an action is running
+    pub fn stage(&self, stage: &str, fraction: Option<f64>) {
+        if let Some(report) = self.report.lock().unwrap().as_mut() {
+            if report.status == ActionStatus::Running {
+                report.stage = stage.to_string();
+                report.fraction = fraction.map(|fraction| fraction.clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    pub fn finish(&self) {
+        self.end(ActionStatus::Done, "done".to_string());
+    }
+
+    pub fn fail(&self, reason: impl ToString) {
+        self.end(ActionStatus::Failed, reason.to_string());
+    }
+
+    fn end(&self, status: ActionStatus, stage: String) {
+        if let Some(report) = self.report.lock().unwrap().as_mut() {
+            if report.status == ActionStatus::Running {
+                report.status = status;
+                report.stage = stage;
+                report.fraction = (status == ActionStatus::Done).then_some(1.0);
+                report.finished = Some(Instant::now());
+            }
+        }
+    }
+
+    pub fn report(&self) -> Option<ActionReport> {
+        self.report.lock().unwrap().clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.report()
+            .is_some_and(|report| report.status == ActionStatus::Running)
+    }
+
+    // "homing: backing off", "calibration: point 3 of 8 (38%)",
+    // "homing failed: limit switch not found", None when idle or done
+    pub fn message(&self) -> Option<String> {
+        let report = self.report()?;
+
+        match report.status {
+            ActionStatus::Running => Some(match report.fraction {
+                Some(fraction) => format!(
+                    "{}: {} ({:.0}%)",
+                    report.action,
+                    report.stage,
+                    fraction * 100.0
+                ),
+                None => format!("{}: {}", report.action, report.stage),
+            }),
+            ActionStatus::Failed => Some(format!("{} failed: {}", report.action, report.stage)),
+            ActionStatus::Done => None,
+        }
+    }
+
+    // action_status is 0 before the first action, then an ActionStatus,
+    // action_progress is in percent
+    pub fn attribute(&self, name: &str) -> Option<f64> {
+        let report = self.report();
+
+        match name {
+            "action_status" => Some(report.map_or(0.0, |report| report.status as u8 as f64)),
+            "action_progress" => Some(
+                report
+                    .and_then(|report| report.fraction)
+                    .map_or(0.0, |fraction| fraction * 100.0),
+            ),
+            "action_elapsed" => Some(report.map_or(0.0, |report| report.elapsed().as_secs_f64())),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use motarem::{axis::Axis, motor_controller::MotorController};
+use tokio::task::JoinSet;
+
+const PROBE_INTERVAL: Duration = Duration::from_millis(200);
+
+pub struct AxisReadiness {
+    pub controller: String,
+    pub axis: String,
+    // Why the last probe failed, None once the axis answered
+    pub error: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl AxisReadiness {
+    pub fn is_ready(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl fmt::Display for AxisReadiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            None => write!(
+                f,
+                "{}/{}: ready after {} ms",
+                self.controller,
+                self.axis,
+                self.elapsed.as_millis()
+            ),
+            Some(error) => write!(f, "{}/{}: not ready: {}", self.controller, self.axis, error),
+        }
+    }
+}
+
+// An axis is ready once its state and, where it has one, its position could
+// be read, i.e. the executors behind it reached their devices
+async fn probe(axis: &Arc<dyn Axis>) -> Result<(), String> {
+    axis.get_state().await.map_err(|e| e.to_string())?;
+
+    let params = axis
+        .get_available_params()
+        .await
+        .map_err(|e| e.to_string())?;
+    if params.iter().any(|param| param == "position") {
+        axis.get_attribute("position")
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Probes every axis until it answers or `timeout` has passed. Listeners are
+// started afterwards, so the first clients do not see the channel errors of
+// executors that are still connecting. Axes that did not answer in time are
+// reported and served anyway.
+pub async fn wait_until_ready(
+    controllers: &[Arc<dyn MotorController>],
+    timeout: Duration,
+) -> Vec<AxisReadiness> {
+    let started = Instant::now();
+    let mut probes = JoinSet::new();
+
+    for controller in controllers {
+        for axis in controller.axes() {
+            let controller = controller.name().to_string();
+
+            probes.spawn(async move {
+                let error = loop {
+                    let error = match tokio::time::timeout(
+                        timeout.saturating_sub(started.elapsed()),
+                        probe(&axis),
+                    )
+                    .await
+                    {
+                        Ok(Ok(())) => break None,
+                        Ok(Err(e)) => e,
+                        Err(_) => "probe timed out".to_string(),
+                    };
+
+                    if started.elapsed() + PROBE_INTERVAL >= timeout {
+                        break Some(error);
+                    }
+                    tokio::time::sleep(PROBE_INTERVAL).await;
+                };
+
+                AxisReadiness {
+                    controller,
+                    axis: axis.name().to_string(),
+                    error,
+                    elapsed: started.elapsed(),
+                }
+            });
+        }
+    }
+
+    let mut readiness = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        if let Ok(axis) = result {
+            eprintln!("{}", axis);
+            readiness.push(axis);
+        }
+    }
+
+    let ready = readiness.iter().filter(|axis| axis.is_ready()).count();
+    eprintln!(
+        "{} of {} axes ready after {} ms",
+        ready,
+        readiness.len(),
+        started.elapsed().as_millis()
+    );
+
+    readiness
+}
@@ -0,0 +1,108 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+// Telnet COM port control (RFC 2217), just enough to move a serial bridge to
+// another baudrate. The bridge takes the commands on a port of its own, the
+// data connection stays raw.
+const IAC: u8 = 255;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const COM_PORT_OPTION: u8 = 44;
+const SET_BAUDRATE: u8 = 1;
+// Server replies use the client command code plus 100
+const SERVER_OFFSET: u8 = 100;
+
+fn set_baudrate_request(baudrate: u32) -> Vec<u8> {
+    let mut request = vec![
+        IAC,
+        WILL,
+        COM_PORT_OPTION,
+        IAC,
+        SB,
+        COM_PORT_OPTION,
+        SET_BAUDRATE,
+    ];
+
+    for byte in baudrate.to_be_bytes() {
+        request.push(byte);
+        if byte == IAC {
+            request.push(IAC);
+        }
+    }
+    request.extend([IAC, SE]);
+
+    request
+}
+
+// The rate from the server's SET-BAUDRATE reply, None until it is complete
+fn confirmed_baudrate(reply: &[u8]) -> Option<u32> {
+    let header = [IAC, SB, COM_PORT_OPTION, SET_BAUDRATE + SERVER_OFFSET];
+    let mut i = reply.windows(header.len()).position(|w| w == header)? + header.len();
+
+    let mut value = [0; 4];
+    for byte in &mut value {
+        *byte = *reply.get(i)?;
+        i += 1;
+
+        if *byte == IAC {
+            if *reply.get(i)? != IAC {
+                return None;
+            }
+            i += 1;
+        }
+    }
+
+    Some(u32::from_be_bytes(value))
+}
+
+// Sets the serial side of the bridge at `addr` and waits until it confirms
+// the rate it runs at
+pub fn set_baudrate(addr: &str, baudrate: u32, timeout: Duration) -> io::Result<()> {
+    let addr = addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Invalid bridge address"))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    stream.write_all(&set_baudrate_request(baudrate))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    let mut buf = [0; 64];
+
+    loop {
+        if let Some(confirmed) = confirmed_baudrate(&reply) {
+            if confirmed != baudrate {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Bridge runs at {} baud instead of {}", confirmed, baudrate),
+                ));
+            }
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            return Err(io::Error::new(
+                ErrorKind::TimedOut,
+                "Bridge did not confirm the baudrate",
+            ));
+        }
+
+        match stream.read(&mut buf)? {
+            0 => {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "Bridge closed the control connection",
+                ));
+            }
+            n => reply.extend_from_slice(&buf[..n]),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use tokio::{task::JoinHandle, time::Instant};
+
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// One periodic job, typically a read through a CommandSender and whatever is
+// done with the result. State that has to survive between polls lives in
+// the implementor.
+#[async_trait::async_trait]
+pub trait Poll: Send {
+    async fn poll(&mut self) -> Result<(), String>;
+}
+
+pub struct PollTask {
+    name: String,
+    interval: Duration,
+    // Higher runs first when several tasks are due together
+    priority: u8,
+    max_backoff: Duration,
+    poll: Box<dyn Poll>,
+
+    next_due: Instant,
+    failures: u32,
+}
+
+impl PollTask {
+    pub fn new(name: impl Into<String>, interval: Duration, poll: impl Poll + 'static) -> Self {
+        Self {
+            name: name.into(),
+            interval,
+            priority: 0,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            poll: Box::new(poll),
+            next_due: Instant::now(),
+            failures: 0,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    // Failed polls are retried after twice the previous delay, up to this
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    fn backoff(&self) -> Duration {
+        let factor = 1u32 << self.failures.min(16);
+
+        self.interval
+            .saturating_mul(factor)
+            .min(self.max_backoff.max(self.interval))
+    }
+
+    async fn run(&mut self) {
+        match self.poll.poll().await {
+            Ok(()) => {
+                if self.failures > 0 {
+                    eprintln!(
+                        "{} recovered after {} failed polls",
+                        self.name, self.failures
+                    );
+                }
+                self.failures = 0;
+                self.next_due = Instant::now() + self.interval;
+            }
+            Err(e) => {
+                self.failures = self.failures.saturating_add(1);
+                let backoff = self.backoff();
+                eprintln!("{}: {}, retrying in {:?}", self.name, e, backoff);
+                self.next_due = Instant::now() + backoff;
+            }
+        }
+    }
+}
+
+// Drives a set of poll tasks from a single loop, so device reads keep their
+// own pace without each caller growing its own sleep loop. Polls run one at
+// a time, the command executors behind them serialize anyway.
+#[derive(Default)]
+pub struct PollScheduler {
+    tasks: Vec<PollTask>,
+}
+
+impl PollScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_task(mut self, task: PollTask) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            if self.tasks.is_empty() {
+                return;
+            }
+
+            loop {
+                let next_due = self.tasks.iter().map(|task| task.next_due).min().unwrap();
+                tokio::time::sleep_until(next_due).await;
+
+                let now = Instant::now();
+                let mut due = self
+                    .tasks
+                    .iter_mut()
+                    .filter(|task| task.next_due <= now)
+                    .collect::<Vec<_>>();
+                due.sort_by(|a, b| b.priority.cmp(&a.priority));
+
+                for task in due {
+                    task.run().await;
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+// Relative tolerance, values go through f32 and unit conversions on the way
+const TOLERANCE: f64 = 1e-4;
+
+pub struct ParameterReading {
+    pub device: String,
+    pub parameter: String,
+    // None when the configuration has no opinion on the value
+    pub expected: Option<f64>,
+    pub actual: Result<f64, String>,
+}
+
+impl ParameterReading {
+    pub fn matches(&self) -> bool {
+        match (self.expected, &self.actual) {
+            (Some(expected), Ok(actual)) => {
+                (expected - actual).abs() <= TOLERANCE * expected.abs().max(1.0)
+            }
+            (None, Ok(_)) => true,
+            (_, Err(_)) => false,
+        }
+    }
+}
+
+// Hardware parameters read back in one go and compared with what the
+// configuration expects, for the pre-beamtime check
+#[derive(Default)]
+pub struct ParameterSnapshot {
+    readings: Vec<ParameterReading>,
+}
+
+impl ParameterSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, device: &str, parameter: &str, expected: Option<f64>, actual: f64) {
+        self.push(device, parameter, expected, Ok(actual));
+    }
+
+    // The parameter could not be read, which always counts as a mismatch
+    pub fn record_error(&mut self, device: &str, parameter: &str, error: impl fmt::Display) {
+        self.push(device, parameter, None, Err(error.to_string()));
+    }
+
+    fn push(
+        &mut self,
+        device: &str,
+        parameter: &str,
+        expected: Option<f64>,
+        actual: Result<f64, String>,
+    ) {
+        self.readings.push(ParameterReading {
+            device: device.to_string(),
+            parameter: parameter.to_string(),
+            expected,
+            actual,
+        });
+    }
+
+    pub fn readings(&self) -> &[ParameterReading] {
+        &self.readings
+    }
+
+    pub fn mismatches(&self) -> impl Iterator<Item = &ParameterReading> {
+        self.readings.iter().filter(|reading| !reading.matches())
+    }
+}
+
+impl fmt::Display for ParameterSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for reading in &self.readings {
+            let expected = match reading.expected {
+                Some(expected) => expected.to_string(),
+                None => "-".to_string(),
+            };
+            let (actual, status) = match &reading.actual {
+                Ok(actual) if reading.matches() => (actual.to_string(), "ok"),
+                Ok(actual) => (actual.to_string(), "MISMATCH"),
+                Err(e) => (e.clone(), "UNREADABLE"),
+            };
+
+            writeln!(
+                f,
+                "{:<12} {:<20} {:>12} {:>12}  {}",
+                reading.device, reading.parameter, expected, actual, status
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use std::{
+    env,
+    os::{
+        linux::net::SocketAddrExt as _,
+        unix::net::{SocketAddr, UnixDatagram},
+    },
+};
+
+// sd_notify, a no-op outside of a Type=notify unit. The control and observer
+// sockets are bound by motarem's server itself, so they are not taken over
+// from socket units.
+pub fn notify(state: &str) {
+    let Ok(path) = env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let result = UnixDatagram::unbound().and_then(|socket| match path.strip_prefix('@') {
+        Some(name) => {
+            let address = SocketAddr::from_abstract_name(name.as_bytes())?;
+            socket.send_to_addr(state.as_bytes(), &address)
+        }
+        None => socket.send_to(state.as_bytes(), &path),
+    });
+
+    if let Err(e) = result {
+        eprintln!("[systemd] Failed to notify {}: {}", path, e);
+    }
+}
+
+pub fn ready() {
+    notify("READY=1");
+}
+
+pub fn status(text: &str) {
+    notify(&format!("STATUS={}", text));
+}
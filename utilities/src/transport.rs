@@ -0,0 +1,132 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::lazy_tcp::LazyTcpStream;
+
+// Byte link to a device bus. Handlers are generic over it, so the same code
+// runs against a gateway, a serial port or a fake.
+pub trait Transport: Read + Write {
+    // Drops the link and opens it again, whatever was buffered is lost
+    fn reconnect(&mut self) -> io::Result<()>;
+}
+
+impl Transport for LazyTcpStream {
+    fn reconnect(&mut self) -> io::Result<()> {
+        LazyTcpStream::reconnect(self)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait AsyncTransport: AsyncRead + AsyncWrite + Unpin + Send {
+    async fn reconnect(&mut self) -> io::Result<()>;
+}
+
+// Device that answers with whatever was queued, for driving handlers
+// without sockets. Reading with nothing queued times out like a silent bus.
+#[derive(Debug, Default)]
+pub struct FakeTransport {
+    replies: VecDeque<u8>,
+    written: Vec<u8>,
+    reconnects: u32,
+}
+
+impl FakeTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn queue_reply(&mut self, reply: &[u8]) {
+        self.replies.extend(reply);
+    }
+
+    // Everything written since the last call
+    pub fn take_written(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.written)
+    }
+
+    pub fn reconnects(&self) -> u32 {
+        self.reconnects
+    }
+
+    fn read_replies(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.replies.is_empty() && !buf.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "No reply queued"));
+        }
+
+        let n = buf.len().min(self.replies.len());
+        for (byte, reply) in buf.iter_mut().zip(self.replies.drain(..n)) {
+            *byte = reply;
+        }
+
+        Ok(n)
+    }
+}
+
+impl Read for FakeTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_replies(buf)
+    }
+}
+
+impl Write for FakeTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport for FakeTransport {
+    fn reconnect(&mut self) -> io::Result<()> {
+        self.replies.clear();
+        self.reconnects += 1;
+        Ok(())
+    }
+}
+
+impl AsyncRead for FakeTransport {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let n = this.read_replies(buf.initialize_unfilled())?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for FakeTransport {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncTransport for FakeTransport {
+    async fn reconnect(&mut self) -> io::Result<()> {
+        Transport::reconnect(self)
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Unit {
+    #[default]
+    #[serde(alias = "mm")]
+    Millimeter,
+    #[serde(alias = "um", alias = "µm")]
+    Micrometer,
+    #[serde(alias = "deg")]
+    Degree,
+    #[serde(alias = "mdeg")]
+    Millidegree,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Angle,
+}
+
+impl Unit {
+    // Size of the unit in mm or degrees
+    fn scale(self) -> (Dimension, f64) {
+        match self {
+            Unit::Millimeter => (Dimension::Length, 1.0),
+            Unit::Micrometer => (Dimension::Length, 1e-3),
+            Unit::Degree => (Dimension::Angle, 1.0),
+            Unit::Millidegree => (Dimension::Angle, 1e-3),
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Millimeter => "mm",
+            Unit::Micrometer => "µm",
+            Unit::Degree => "deg",
+            Unit::Millidegree => "mdeg",
+        }
+    }
+}
+
+// Conversion between the unit an axis works in internally and the unit its
+// clients use. All supported units are pure scalings, so the same factor
+// applies to positions and to differences such as windows or errors.
+#[derive(Debug, Clone, Copy)]
+pub struct AxisUnits {
+    native: Unit,
+    public: Unit,
+    factor: f64,
+}
+
+impl AxisUnits {
+    pub fn new(native: Unit, public: Unit) -> Result<Self, String> {
+        let (native_dimension, native_scale) = native.scale();
+        let (public_dimension, public_scale) = public.scale();
+
+        if native_dimension != public_dimension {
+            return Err(format!(
+                "Can't express {} positions in {}",
+                native.symbol(),
+                public.symbol()
+            ));
+        }
+
+        Ok(Self {
+            native,
+            public,
+            factor: public_scale / native_scale,
+        })
+    }
+
+    pub fn native(&self) -> Unit {
+        self.native
+    }
+
+    pub fn public(&self) -> Unit {
+        self.public
+    }
+
+    pub fn to_native(&self, value: f64) -> f64 {
+        value * self.factor
+    }
+
+    pub fn from_native(&self, value: f64) -> f64 {
+        value / self.factor
+    }
+}
@@ -1,5 +1,5 @@
 use crate::command_executor::motor::commands::{CommandResponse, MotorCommand};
-use em2rs::StateParams;
+use em2rs::{Identity, StateParams};
 use std::io;
 use utilities::command_executor::CommandSender;
 
@@ -102,4 +102,49 @@ impl Em2rsCommandSender {
             )),
         }
     }
+
+    pub async fn get_drive_temperature(&self, axis: usize) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetDriveTemperature { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Value(value) => Ok(value),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn get_bus_voltage(&self, axis: usize) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::GetBusVoltage { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Value(value) => Ok(value),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn identify(&self, axis: usize) -> io::Result<Identity> {
+        let response = self
+            .sender
+            .send_command(MotorCommand::Identify { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Identity(identity) => Ok(identity),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
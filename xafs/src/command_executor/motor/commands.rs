@@ -1,4 +1,4 @@
-use em2rs::StateParams;
+use em2rs::{Identity, StateParams};
 use std::io;
 use utilities::command_executor::Command;
 
@@ -12,11 +12,16 @@ pub enum MotorCommand {
     SetDeceleration { axis: usize, deceleration: u16 },
     Stop { axis: usize },
     Move { axis: usize, steps: i32 },
+    GetDriveTemperature { axis: usize },
+    GetBusVoltage { axis: usize },
+    Identify { axis: usize },
 }
 
 pub enum CommandResponse {
     None,
     State(StateParams),
+    Value(f32),
+    Identity(Identity),
     Ok,
 }
 
@@ -36,6 +41,9 @@ impl Command for MotorCommand {
             }
             MotorCommand::Stop { axis } => handler.stop(axis),
             MotorCommand::Move { axis, steps } => handler.move_relative(axis, steps),
+            MotorCommand::GetDriveTemperature { axis } => handler.get_drive_temperature(axis),
+            MotorCommand::GetBusVoltage { axis } => handler.get_bus_voltage(axis),
+            MotorCommand::Identify { axis } => handler.identify(axis),
         }
     }
 }
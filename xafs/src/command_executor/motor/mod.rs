@@ -1,12 +1,12 @@
 use crate::command_executor::motor::commands::{CommandResponse, MotorCommand};
 use em2rs::Em2rs;
 use std::io;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream};
+use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, transport::Transport};
 pub mod command_sender;
 pub mod commands;
 
-pub struct Em2rsHandler {
-    tcp_stream: LazyTcpStream,
+pub struct Em2rsHandler<S = LazyTcpStream> {
+    tcp_stream: S,
     em2rs: [Em2rs; 5],
 }
 
@@ -14,8 +14,8 @@ impl DeviceHandler for Em2rsHandler {
     type Command = MotorCommand;
 }
 
-impl Em2rsHandler {
-    pub fn new(tcp_stream: LazyTcpStream, em2rs: [Em2rs; 5]) -> Self {
+impl<S: Transport> Em2rsHandler<S> {
+    pub fn new(tcp_stream: S, em2rs: [Em2rs; 5]) -> Self {
         Self { tcp_stream, em2rs }
     }
 
@@ -90,4 +90,34 @@ impl Em2rsHandler {
         em2rs.set_deceleration(&mut self.tcp_stream, deceleration)?;
         Ok(CommandResponse::Ok)
     }
+
+    pub fn get_drive_temperature(&mut self, axis: usize) -> io::Result<CommandResponse> {
+        let em2rs = self
+            .em2rs
+            .get(axis)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid axis"))?;
+
+        let temperature = em2rs.get_drive_temperature(&mut self.tcp_stream)?;
+        Ok(CommandResponse::Value(temperature))
+    }
+
+    pub fn get_bus_voltage(&mut self, axis: usize) -> io::Result<CommandResponse> {
+        let em2rs = self
+            .em2rs
+            .get(axis)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid axis"))?;
+
+        let voltage = em2rs.get_bus_voltage(&mut self.tcp_stream)?;
+        Ok(CommandResponse::Value(voltage))
+    }
+
+    pub fn identify(&mut self, axis: usize) -> io::Result<CommandResponse> {
+        let em2rs = self
+            .em2rs
+            .get(axis)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid axis"))?;
+
+        let identity = em2rs.identify(&mut self.tcp_stream)?;
+        Ok(CommandResponse::Identity(identity))
+    }
 }
@@ -41,4 +41,94 @@ impl SensorsCommandSender {
             )),
         }
     }
+
+    pub async fn get_setpoint(&self, axis: u8) -> io::Result<f32> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::GetSetpoint { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Setpoint(setpoint) => Ok(setpoint),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_setpoint(&self, axis: u8, setpoint: f32) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::SetSetpoint { axis, setpoint })
+            .await?;
+
+        match response {
+            CommandResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn is_control_enabled(&self, axis: u8) -> io::Result<bool> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::GetControl { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Control(enabled) => Ok(enabled),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_control_enabled(&self, axis: u8, enabled: bool) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::SetControl { axis, enabled })
+            .await?;
+
+        match response {
+            CommandResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn is_relay_on(&self, axis: u8) -> io::Result<bool> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::GetRelay { axis })
+            .await?;
+
+        match response {
+            CommandResponse::Relay(on) => Ok(on),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
+
+    pub async fn set_relay(&self, axis: u8, on: bool) -> io::Result<()> {
+        let response = self
+            .sender
+            .send_command(SensorsCommand::SetRelay { axis, on })
+            .await?;
+
+        match response {
+            CommandResponse::Ok => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unexpected response type",
+            )),
+        }
+    }
 }
@@ -7,6 +7,12 @@ use utilities::command_executor::Command;
 pub enum SensorsCommand {
     Position { axis: u8 },
     Temperature { axis: u8 },
+    GetSetpoint { axis: u8 },
+    SetSetpoint { axis: u8, setpoint: f32 },
+    GetControl { axis: u8 },
+    SetControl { axis: u8, enabled: bool },
+    GetRelay { axis: u8 },
+    SetRelay { axis: u8, on: bool },
 }
 
 #[derive(Debug)]
@@ -14,6 +20,9 @@ pub enum CommandResponse {
     None,
     Temperature(f32),
     Position(f32),
+    Setpoint(f32),
+    Control(bool),
+    Relay(bool),
     Ok,
 }
 
@@ -29,6 +38,24 @@ impl Command for SensorsCommand {
             SensorsCommand::Temperature { axis } => handler
                 .get_temperature(axis)
                 .map(|temperature| CommandResponse::Temperature(temperature)),
+            SensorsCommand::GetSetpoint { axis } => handler
+                .get_setpoint(axis)
+                .map(|setpoint| CommandResponse::Setpoint(setpoint)),
+            SensorsCommand::SetSetpoint { axis, setpoint } => handler
+                .set_setpoint(axis, setpoint)
+                .map(|_| CommandResponse::Ok),
+            SensorsCommand::GetControl { axis } => handler
+                .is_control_enabled(axis)
+                .map(|enabled| CommandResponse::Control(enabled)),
+            SensorsCommand::SetControl { axis, enabled } => handler
+                .set_control_enabled(axis, enabled)
+                .map(|_| CommandResponse::Ok),
+            SensorsCommand::GetRelay { axis } => handler
+                .is_relay_on(axis)
+                .map(|on| CommandResponse::Relay(on)),
+            SensorsCommand::SetRelay { axis, on } => {
+                handler.set_relay(axis, on).map(|_| CommandResponse::Ok)
+            }
         }
     }
 }
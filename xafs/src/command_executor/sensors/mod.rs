@@ -2,18 +2,21 @@ use crate::command_executor::sensors::commands::SensorsCommand;
 use lir::LIR;
 use std::io;
 use trid::Trid;
-use utilities::{command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, modbus::ModbusError};
+use utilities::{
+    command_executor::DeviceHandler, lazy_tcp::LazyTcpStream, modbus::ModbusError,
+    transport::Transport,
+};
 pub mod command_sender;
 pub mod commands;
 
-pub struct SensorsHandler {
-    tcp_stream: LazyTcpStream,
+pub struct SensorsHandler<S = LazyTcpStream> {
+    tcp_stream: S,
     encoders: Vec<LIR>,
     temperature: Vec<Trid>,
 }
 
-impl SensorsHandler {
-    pub fn new(tcp_stream: LazyTcpStream, encoders: Vec<LIR>, temperature: Vec<Trid>) -> Self {
+impl<S: Transport> SensorsHandler<S> {
+    pub fn new(tcp_stream: S, encoders: Vec<LIR>, temperature: Vec<Trid>) -> Self {
         Self {
             tcp_stream,
             encoders,
@@ -25,7 +28,7 @@ impl SensorsHandler {
         self.encoders
             .get(axis as usize)
             .ok_or_else(|| io::Error::new(io::ErrorKind::Other, format!("Invalid axis")))?
-            .get_current_measurement(&mut self.tcp_stream, 3)
+            .get_current_measurement(&mut self.tcp_stream)
             .map_err(|e| match e {
                 ModbusError::IoError(error) => io::Error::from(error),
                 _ => io::Error::new(io::ErrorKind::Other, format!("{e}")),
@@ -33,15 +36,55 @@ impl SensorsHandler {
     }
 
     fn get_temperature(&mut self, axis: u8) -> io::Result<f32> {
-        let trid = self.temperature.get(axis as usize).ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                format!("Invalid Trid ID: {}", axis),
-            )
-        })?;
+        trid(&mut self.temperature, axis)?
+            .read_data(&mut self.tcp_stream)
+            .map_err(Into::into)
+    }
+
+    fn get_setpoint(&mut self, axis: u8) -> io::Result<f32> {
+        trid(&mut self.temperature, axis)?
+            .read_setpoint(&mut self.tcp_stream)
+            .map_err(Into::into)
+    }
+
+    fn set_setpoint(&mut self, axis: u8, setpoint: f32) -> io::Result<()> {
+        trid(&mut self.temperature, axis)?
+            .write_setpoint(&mut self.tcp_stream, setpoint)
+            .map_err(Into::into)
+    }
+
+    fn is_control_enabled(&mut self, axis: u8) -> io::Result<bool> {
+        trid(&mut self.temperature, axis)?
+            .is_control_enabled(&mut self.tcp_stream)
+            .map_err(Into::into)
+    }
 
-        trid.read_data(&mut self.tcp_stream)
+    fn set_control_enabled(&mut self, axis: u8, enabled: bool) -> io::Result<()> {
+        trid(&mut self.temperature, axis)?
+            .set_control_enabled(&mut self.tcp_stream, enabled)
+            .map_err(Into::into)
     }
+
+    fn is_relay_on(&mut self, axis: u8) -> io::Result<bool> {
+        trid(&mut self.temperature, axis)?
+            .is_relay_on(&mut self.tcp_stream)
+            .map_err(Into::into)
+    }
+
+    fn set_relay(&mut self, axis: u8, on: bool) -> io::Result<()> {
+        trid(&mut self.temperature, axis)?
+            .set_relay(&mut self.tcp_stream, on)
+            .map_err(Into::into)
+    }
+}
+
+fn trid(temperature: &mut [Trid], axis: u8) -> io::Result<&mut Trid> {
+    temperature.get_mut(axis as usize).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid Trid ID: {}", axis),
+        )
+    })
 }
 
 impl DeviceHandler for SensorsHandler {
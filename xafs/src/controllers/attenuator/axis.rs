@@ -27,6 +27,7 @@ pub struct AttenuatorAxis {
     is_moving: Arc<AtomicBool>,
 
     steps_per_mm: i32,
+    inverted: bool,
 }
 
 impl AttenuatorAxis {
@@ -45,8 +46,14 @@ impl AttenuatorAxis {
             move_thread: Arc::new(Mutex::new(None)),
             is_moving: Arc::new(AtomicBool::new(false)),
             steps_per_mm,
+            inverted: false,
         }
     }
+
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -56,7 +63,10 @@ impl Axis for AttenuatorAxis {
     }
 
     async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
-        let motor_params = parameters.unwrap_or_default().into();
+        let motor_params: MotorParameters = parameters
+            .unwrap_or_default()
+            .try_into()
+            .map_err(anyhow::Error::msg)?;
 
         self.move_to(position as f32, motor_params)
             .await
@@ -84,10 +94,17 @@ impl Axis for AttenuatorAxis {
             AxisState::On
         };
 
-        let limit_switches = match (
-            motor_state.low_limit_triggered(),
-            motor_state.high_limit_triggered(),
-        ) {
+        let (lower_switch, upper_switch) = match self.inverted {
+            false => (
+                motor_state.low_limit_triggered(),
+                motor_state.high_limit_triggered(),
+            ),
+            true => (
+                motor_state.high_limit_triggered(),
+                motor_state.low_limit_triggered(),
+            ),
+        };
+        let limit_switches = match (lower_switch, upper_switch) {
             (true, true) => LimitSwitches::Both,
             (true, false) => LimitSwitches::Lower,
             (false, true) => LimitSwitches::Upper,
@@ -112,12 +129,42 @@ impl Axis for AttenuatorAxis {
                 .await
                 .map(|pos| pos as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get position: {}", err))),
+            "drive_temperature" => self
+                .motor_cs
+                .get_drive_temperature(self.axis)
+                .await
+                .map(|temp| temp as f64)
+                .map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to get drive temperature: {}", err))
+                }),
+            "bus_voltage" => self
+                .motor_cs
+                .get_bus_voltage(self.axis)
+                .await
+                .map(|voltage| voltage as f64)
+                .map_err(|err| anyhow::Error::msg(format!("Failed to get bus voltage: {}", err))),
+            "drive_model" | "drive_firmware" => {
+                let identity = self.motor_cs.identify(self.axis).await.map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to identify drive: {}", err))
+                })?;
+
+                Ok(match name {
+                    "drive_model" => identity.model,
+                    _ => identity.firmware,
+                } as f64)
+            }
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
 
     async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec!["position".to_string()])
+        Ok(vec![
+            "position".to_string(),
+            "drive_temperature".to_string(),
+            "bus_voltage".to_string(),
+            "drive_model".to_string(),
+            "drive_firmware".to_string(),
+        ])
     }
 
     async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
@@ -203,7 +250,8 @@ impl MotorHolder for AttenuatorAxis {
             parameters.time_limit,
             self.is_moving.clone(),
             self.steps_per_mm,
-        );
+        )
+        .with_inverted(self.inverted);
 
         let handle = tokio::spawn(async move { move_thread.run().await });
 
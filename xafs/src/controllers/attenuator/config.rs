@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use utilities::axis_mode::AxisMode;
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct AttenuatorAxisConfig {
@@ -10,6 +11,14 @@ pub struct AttenuatorAxisConfig {
     pub em2rs_high_limit: u8,
 
     pub steps_per_mm: i32,
+    // Motor mounted the other way round: steps are sent with the opposite
+    // sign and its limit switches swap places, steps_per_mm stays positive
+    #[serde(default)]
+    pub inverted: bool,
+
+    // hardware, simulated or disabled, e.g. while the device is out for repairs
+    #[serde(default)]
+    pub mode: AxisMode,
 }
 
 #[derive(Deserialize, Debug, Serialize)]
@@ -28,6 +37,8 @@ impl Default for AttenuatorControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                mode: AxisMode::Hardware,
             },
         }
     }
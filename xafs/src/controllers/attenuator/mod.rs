@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use utilities::axis_mode::with_mode;
+
 use crate::{
     command_executor::{
         motor::command_sender::Em2rsCommandSender, sensors::command_sender::SensorsCommandSender,
@@ -26,7 +28,8 @@ pub fn create_controller(
         sensors_command_sender,
         em2rs_command_sender,
         config.axis.steps_per_mm,
-    );
+    )
+    .with_inverted(config.axis.inverted);
 
-    AttenuatorController::new(Arc::new(axis))
+    AttenuatorController::new(with_mode(Arc::new(axis), config.axis.mode))
 }
@@ -31,6 +31,7 @@ pub struct AttenuatorMotor {
     is_moving: Arc<AtomicBool>,
     start_time: Instant,
     steps_per_mm: i32,
+    inverted: bool,
 }
 
 impl Drop for AttenuatorMotor {
@@ -65,10 +66,17 @@ impl AttenuatorMotor {
             is_moving: moving,
             start_time: Instant::now(),
             steps_per_mm,
+            inverted: false,
         }
     }
 
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
     async fn send_steps(&self, steps: i32) -> std::io::Result<()> {
+        let steps = if self.inverted { -steps } else { steps };
         self.em2rs_cs.send_steps(self.axis, steps).await
     }
 }
@@ -85,7 +93,7 @@ impl Motor for AttenuatorMotor {
         self.em2rs_cs
             .get_state(self.axis)
             .await
-            .map(|state| Em2rsState(state))
+            .map(|state| Em2rsState(state, self.inverted))
             .map_err(|err| err.to_string())
     }
 
@@ -144,15 +152,23 @@ impl Motor for AttenuatorMotor {
     }
 }
 
-struct Em2rsState(StateParams);
+// The flag is set for an inverted motor, whose high limit is at the start of
+// the axis
+struct Em2rsState(StateParams, bool);
 
 impl MotorState for Em2rsState {
     fn start_switch(&self) -> bool {
-        self.0.low_limit_triggered()
+        match self.1 {
+            false => self.0.low_limit_triggered(),
+            true => self.0.high_limit_triggered(),
+        }
     }
 
     fn end_switch(&self) -> bool {
-        self.0.high_limit_triggered()
+        match self.1 {
+            false => self.0.high_limit_triggered(),
+            true => self.0.low_limit_triggered(),
+        }
     }
 
     fn is_moving(&self) -> bool {
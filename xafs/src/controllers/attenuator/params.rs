@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use motarem::axis::movement_parameters::MovementParams;
+use utilities::params::{custom_finite, custom_seconds};
 
 #[derive(Debug)]
 pub struct MotorParameters {
@@ -23,8 +24,10 @@ impl Default for MotorParameters {
     }
 }
 
-impl From<MovementParams> for MotorParameters {
-    fn from(value: MovementParams) -> Self {
+impl TryFrom<MovementParams> for MotorParameters {
+    type Error = String;
+
+    fn try_from(value: MovementParams) -> Result<Self, Self::Error> {
         let mut params = Self::default();
 
         if let Some(acceleration) = value.acceleration {
@@ -36,13 +39,13 @@ impl From<MovementParams> for MotorParameters {
         if let Some(velocity) = value.velocity {
             params.velocity = velocity as u16;
         }
-        if let Some(position_window) = value.custom.get("position_window") {
-            params.position_window = *position_window as f32;
+        if let Some(position_window) = custom_finite(&value, "position_window")? {
+            params.position_window = position_window as f32;
         }
-        if let Some(time_limit) = value.custom.get("time_limit") {
-            params.time_limit = Duration::from_secs_f64(*time_limit);
+        if let Some(time_limit) = custom_seconds(&value, "time_limit")? {
+            params.time_limit = time_limit;
         }
 
-        params
+        Ok(params)
     }
 }
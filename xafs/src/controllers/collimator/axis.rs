@@ -2,6 +2,7 @@ use motarem::axis::{
     Axis, limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
     state_info::AxisStateInfo,
 };
+use trid::TridError;
 use utilities::motor_controller::MotorHolder;
 
 use crate::command_executor::sensors::command_sender::SensorsCommandSender;
@@ -28,6 +29,20 @@ impl CollimatorAxis {
             .await
             .map_err(|e| format!("Failed to get temperature: {}", e))
     }
+
+    pub async fn is_relay_on(&self) -> Result<bool, String> {
+        self.sensors_cs
+            .is_relay_on(self.axis as u8)
+            .await
+            .map_err(|e| format!("Failed to get relay state: {}", e))
+    }
+
+    pub async fn set_relay(&self, on: bool) -> Result<(), String> {
+        self.sensors_cs
+            .set_relay(self.axis as u8, on)
+            .await
+            .map_err(|e| format!("Failed to switch relay: {}", e))
+    }
 }
 
 #[async_trait::async_trait]
@@ -36,11 +51,19 @@ impl Axis for CollimatorAxis {
         &self.name
     }
 
+    // Nothing to move, only the "relay" parameter switches the interlock
+    // output by hand
     async fn start(
         &self,
         _position: f64,
-        _parameters: Option<MovementParams>,
+        parameters: Option<MovementParams>,
     ) -> anyhow::Result<()> {
+        if let Some(relay) = parameters.and_then(|p| p.custom.get("relay").copied()) {
+            self.set_relay(relay != 0.0)
+                .await
+                .map_err(anyhow::Error::msg)?;
+        }
+
         Ok(())
     }
 
@@ -49,9 +72,18 @@ impl Axis for CollimatorAxis {
     }
 
     async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        // Only a disconnected probe changes the state, other read failures
+        // surface through the attributes
+        let message = match self.sensors_cs.get_temperature(self.axis as u8).await {
+            Err(e) if TridError::is_sensor_missing(&e) => {
+                Some("Temperature sensor missing".to_string())
+            }
+            _ => None,
+        };
+
         Ok(AxisStateInfo {
             state: AxisState::On,
-            message: None,
+            message,
             limit_switches: LimitSwitches::None,
         })
     }
@@ -68,16 +100,21 @@ impl Axis for CollimatorAxis {
                 .await
                 .map(|temp| temp as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get temperature: {}", err))),
+            "relay" => self
+                .is_relay_on()
+                .await
+                .map(|on| on as u8 as f64)
+                .map_err(anyhow::Error::msg),
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
 
     async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec!["temperature".to_string()])
+        Ok(vec!["temperature".to_string(), "relay".to_string()])
     }
 
     async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec![])
+        Ok(vec!["relay".to_string()])
     }
 }
 
@@ -1,8 +1,18 @@
 use serde::{Deserialize, Serialize};
+use utilities::axis_mode::AxisMode;
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct CollimatorConfig {
     pub trid_axis: u16,
+    // Interlock relay, switched on above relay_limit
+    #[serde(default)]
+    pub relay_coil: Option<u16>,
+    #[serde(default)]
+    pub relay_limit: Option<f32>,
+
+    // hardware, simulated or disabled, e.g. while the device is out for repairs
+    #[serde(default)]
+    pub mode: AxisMode,
 }
 
 #[derive(Deserialize, Debug, Serialize)]
@@ -16,8 +26,18 @@ impl Default for CollimatorControllerConfig {
     fn default() -> Self {
         Self {
             trid_id: 1,
-            input_axis: CollimatorConfig { trid_axis: 1 },
-            output_axis: CollimatorConfig { trid_axis: 2 },
+            input_axis: CollimatorConfig {
+                trid_axis: 1,
+                relay_coil: None,
+                relay_limit: None,
+                mode: AxisMode::Hardware,
+            },
+            output_axis: CollimatorConfig {
+                trid_axis: 2,
+                relay_coil: None,
+                relay_limit: None,
+                mode: AxisMode::Hardware,
+            },
         }
     }
 }
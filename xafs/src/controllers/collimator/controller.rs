@@ -1,19 +1,28 @@
 use std::sync::Arc;
 
 use motarem::{axis::Axis, motor_controller::MotorController};
+use tokio::task::JoinHandle;
 
 pub struct CollimatorController {
     axes: Vec<Arc<dyn Axis>>,
+    interlocks: Vec<JoinHandle<()>>,
 }
 
 impl CollimatorController {
     pub fn new() -> Self {
-        Self { axes: Vec::new() }
+        Self {
+            axes: Vec::new(),
+            interlocks: Vec::new(),
+        }
     }
 
     pub fn add_axis(&mut self, axis: Arc<dyn Axis>) {
         self.axes.push(axis);
     }
+
+    pub fn add_interlock(&mut self, interlock: JoinHandle<()>) {
+        self.interlocks.push(interlock);
+    }
 }
 
 #[async_trait::async_trait]
@@ -31,6 +40,10 @@ impl MotorController for CollimatorController {
             axis.stop().await?;
         }
 
+        for interlock in &self.interlocks {
+            interlock.abort();
+        }
+
         Ok(())
     }
 }
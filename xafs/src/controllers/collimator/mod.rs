@@ -1,8 +1,16 @@
 use std::sync::Arc;
 
+use utilities::axis_mode::{AxisMode, with_mode};
+
 use crate::{
     command_executor::sensors::command_sender::SensorsCommandSender,
-    controllers::collimator::{axis::CollimatorAxis, controller::CollimatorController},
+    controllers::{
+        collimator::{
+            axis::CollimatorAxis, config::CollimatorControllerConfig,
+            controller::CollimatorController,
+        },
+        interlock::spawn_interlock,
+    },
 };
 
 pub mod axis;
@@ -10,7 +18,7 @@ pub mod config;
 pub mod controller;
 
 pub fn create_controller(
-    // config: CollimatorControllerConfig,
+    config: &CollimatorControllerConfig,
     sensors_command_sender: SensorsCommandSender,
 ) -> CollimatorController {
     let axis1 = CollimatorAxis::new(
@@ -18,11 +26,33 @@ pub fn create_controller(
         9,
         sensors_command_sender.clone(),
     );
-    let axis2 = CollimatorAxis::new("TemperatureOutput".to_string(), 10, sensors_command_sender);
+    let axis2 = CollimatorAxis::new(
+        "TemperatureOutput".to_string(),
+        10,
+        sensors_command_sender.clone(),
+    );
 
     let mut controller = CollimatorController::new();
-    controller.add_axis(Arc::new(axis1));
-    controller.add_axis(Arc::new(axis2));
+    controller.add_axis(with_mode(Arc::new(axis1), config.input_axis.mode));
+    controller.add_axis(with_mode(Arc::new(axis2), config.output_axis.mode));
+
+    for (name, axis, axis_config) in [
+        ("Collimator input", 9, &config.input_axis),
+        ("Collimator output", 10, &config.output_axis),
+    ] {
+        // The interlock reads the device itself
+        if axis_config.mode != AxisMode::Hardware {
+            continue;
+        }
+        if let Some(limit) = axis_config.relay_limit {
+            controller.add_interlock(spawn_interlock(
+                name.to_string(),
+                sensors_command_sender.clone(),
+                axis,
+                limit,
+            ));
+        }
+    }
 
     controller
 }
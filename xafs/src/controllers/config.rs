@@ -1,4 +1,8 @@
+use lir::LirLayout;
 use serde::{Deserialize, Serialize};
+use utilities::{
+    archiver::ArchiverConfig, derived::DerivedAttributeConfig, groups::AxisGroupConfig,
+};
 
 use crate::controllers::{
     attenuator::config::AttenuatorControllerConfig, collimator::config::CollimatorControllerConfig,
@@ -11,6 +15,10 @@ use super::cooled_slit::config::CooledSlitControllerConfig;
 pub struct XafsConfig {
     pub sensors_ip: String,
     pub sensors_port: u16,
+    // Register layout of the LIR interface modules, the commissioned one
+    // when unset
+    #[serde(default)]
+    pub lir_layout: LirLayout,
 
     pub em2rs_ip: String,
     pub em2rs_port: u16,
@@ -19,6 +27,23 @@ pub struct XafsConfig {
     pub attenuator: AttenuatorControllerConfig,
     pub collimator: CollimatorControllerConfig,
     pub water_input: WaterInputControllerConfig,
+
+    // Attributes computed from other attributes of the same controller, the
+    // optional controller name picks between equally named axes
+    #[serde(default)]
+    pub derived_attributes: Vec<DerivedAttributeConfig>,
+
+    // Named sets of axes addressed like a single axis, e.g. `STOP vertical`
+    #[serde(default)]
+    pub axis_groups: Vec<AxisGroupConfig>,
+
+    // Read-only socket for status displays, motion commands are refused there
+    #[serde(default)]
+    pub observer_socket: Option<String>,
+
+    // Periodic axis samples sent to the facility archive
+    #[serde(default)]
+    pub archiver: Option<ArchiverConfig>,
 }
 
 impl Default for XafsConfig {
@@ -26,6 +51,7 @@ impl Default for XafsConfig {
         Self {
             sensors_ip: "127.0.0.1".to_string(),
             sensors_port: 50051,
+            lir_layout: LirLayout::default(),
 
             em2rs_ip: "127.0.0.1".to_string(),
             em2rs_port: 50052,
@@ -34,6 +60,11 @@ impl Default for XafsConfig {
             attenuator: AttenuatorControllerConfig::default(),
             collimator: CollimatorControllerConfig::default(),
             water_input: WaterInputControllerConfig::default(),
+
+            derived_attributes: Vec::new(),
+            axis_groups: Vec::new(),
+            observer_socket: None,
+            archiver: None,
         }
     }
 }
@@ -1,6 +1,9 @@
-use std::sync::{
-    Arc,
-    atomic::{AtomicBool, Ordering},
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
 
 use em2rs::StateParams;
@@ -9,7 +12,10 @@ use motarem::axis::{
     state_info::AxisStateInfo,
 };
 use tokio::{sync::Mutex, task::JoinHandle};
-use utilities::motor_controller::{Motor as _, MotorHolder};
+use utilities::{
+    cached_reading::CachedReading,
+    motor_controller::{Motor as _, MotorHolder},
+};
 
 use super::{motor::CooledSlitMotor, params::MotorParameters};
 
@@ -17,6 +23,11 @@ use crate::command_executor::{
     motor::command_sender::Em2rsCommandSender, sensors::command_sender::SensorsCommandSender,
 };
 
+// GETs share one reading per value through a watch channel, so clients
+// polling the same axis don't each queue a sensors read behind the move loop
+const CACHE_MAX_AGE: Duration = Duration::from_millis(20);
+const READ_DEADLINE: Duration = Duration::from_millis(50);
+
 pub struct CooledSlitAxis {
     pub name: String,
     axis: usize,
@@ -28,6 +39,10 @@ pub struct CooledSlitAxis {
     is_moving: Arc<AtomicBool>,
 
     steps_per_mm: i32,
+    inverted: bool,
+
+    position: CachedReading,
+    temperature: CachedReading,
 }
 
 impl CooledSlitAxis {
@@ -46,14 +61,45 @@ impl CooledSlitAxis {
             move_thread: Arc::new(Mutex::new(None)),
             is_moving: Arc::new(AtomicBool::new(false)),
             steps_per_mm,
+            inverted: false,
+            position: CachedReading::new(CACHE_MAX_AGE, READ_DEADLINE),
+            temperature: CachedReading::new(CACHE_MAX_AGE, READ_DEADLINE),
         }
     }
 
-    pub async fn get_temperature(&self) -> Result<f32, String> {
-        self.sensors_cs
-            .get_temperature(self.axis as u8)
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    async fn cached_position(&self) -> Result<f32, String> {
+        let sensors_cs = self.sensors_cs.clone();
+        let axis = self.axis as u8;
+
+        self.position
+            .read(async move {
+                sensors_cs
+                    .get_position(axis)
+                    .await
+                    .map_err(|e| format!("Failed to get position: {}", e))
+            })
+            .await
+            .map(|reading| reading.value)
+    }
+
+    async fn cached_temperature(&self) -> Result<f32, String> {
+        let sensors_cs = self.sensors_cs.clone();
+        let axis = self.axis as u8;
+
+        self.temperature
+            .read(async move {
+                sensors_cs
+                    .get_temperature(axis)
+                    .await
+                    .map_err(|e| format!("Failed to get temperature: {}", e))
+            })
             .await
-            .map_err(|e| format!("Failed to get temperature: {}", e))
+            .map(|reading| reading.value)
     }
 
     pub async fn get_water_output_temperature(&self) -> Result<f32, String> {
@@ -71,7 +117,10 @@ impl Axis for CooledSlitAxis {
     }
 
     async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
-        let motor_params = parameters.unwrap_or_default().into();
+        let motor_params: MotorParameters = parameters
+            .unwrap_or_default()
+            .try_into()
+            .map_err(anyhow::Error::msg)?;
 
         self.move_to(position as f32, motor_params)
             .await
@@ -99,10 +148,17 @@ impl Axis for CooledSlitAxis {
             AxisState::On
         };
 
-        let limit_switches = match (
-            motor_state.low_limit_triggered(),
-            motor_state.high_limit_triggered(),
-        ) {
+        let (lower_switch, upper_switch) = match self.inverted {
+            false => (
+                motor_state.low_limit_triggered(),
+                motor_state.high_limit_triggered(),
+            ),
+            true => (
+                motor_state.high_limit_triggered(),
+                motor_state.low_limit_triggered(),
+            ),
+        };
+        let limit_switches = match (lower_switch, upper_switch) {
             (true, true) => LimitSwitches::Both,
             (true, false) => LimitSwitches::Lower,
             (false, true) => LimitSwitches::Upper,
@@ -122,12 +178,13 @@ impl Axis for CooledSlitAxis {
     }
     async fn get_attribute(&self, name: &str) -> anyhow::Result<f64> {
         match name {
-            "position" => MotorHolder::get_position(self)
+            "position" => self
+                .cached_position()
                 .await
                 .map(|pos| pos as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get position: {}", err))),
             "temperature" => self
-                .get_temperature()
+                .cached_temperature()
                 .await
                 .map(|temp| temp as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get temperature: {}", err))),
@@ -138,6 +195,30 @@ impl Axis for CooledSlitAxis {
                 .map_err(|err| {
                     anyhow::Error::msg(format!("Failed to get water output temperature: {}", err))
                 }),
+            "drive_temperature" => self
+                .motor_cs
+                .get_drive_temperature(self.axis)
+                .await
+                .map(|temp| temp as f64)
+                .map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to get drive temperature: {}", err))
+                }),
+            "bus_voltage" => self
+                .motor_cs
+                .get_bus_voltage(self.axis)
+                .await
+                .map(|voltage| voltage as f64)
+                .map_err(|err| anyhow::Error::msg(format!("Failed to get bus voltage: {}", err))),
+            "drive_model" | "drive_firmware" => {
+                let identity = self.motor_cs.identify(self.axis).await.map_err(|err| {
+                    anyhow::Error::msg(format!("Failed to identify drive: {}", err))
+                })?;
+
+                Ok(match name {
+                    "drive_model" => identity.model,
+                    _ => identity.firmware,
+                } as f64)
+            }
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
@@ -147,6 +228,10 @@ impl Axis for CooledSlitAxis {
             "position".to_string(),
             "temperature".to_string(),
             "water_output_temperature".to_string(),
+            "drive_temperature".to_string(),
+            "bus_voltage".to_string(),
+            "drive_model".to_string(),
+            "drive_firmware".to_string(),
         ])
     }
 
@@ -233,7 +318,8 @@ impl MotorHolder for CooledSlitAxis {
             parameters.time_limit,
             self.is_moving.clone(),
             self.steps_per_mm,
-        );
+        )
+        .with_inverted(self.inverted);
 
         let handle = tokio::spawn(async move { move_thread.run().await });
 
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use utilities::axis_mode::AxisMode;
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct CooledSlitAxisConfig {
@@ -13,6 +14,14 @@ pub struct CooledSlitAxisConfig {
     pub em2rs_high_limit: u8,
 
     pub steps_per_mm: i32,
+    // Motor mounted the other way round: steps are sent with the opposite
+    // sign and its limit switches swap places, steps_per_mm stays positive
+    #[serde(default)]
+    pub inverted: bool,
+
+    // hardware, simulated or disabled, e.g. while the device is out for repairs
+    #[serde(default)]
+    pub mode: AxisMode,
 }
 
 #[derive(Deserialize, Debug, Serialize)]
@@ -43,6 +52,8 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                mode: AxisMode::Hardware,
             },
             lower_axis: CooledSlitAxisConfig {
                 lir_id: 2,
@@ -55,6 +66,8 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                mode: AxisMode::Hardware,
             },
             left_axis: CooledSlitAxisConfig {
                 lir_id: 3,
@@ -67,6 +80,8 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                mode: AxisMode::Hardware,
             },
             right_axis: CooledSlitAxisConfig {
                 lir_id: 4,
@@ -79,6 +94,8 @@ impl Default for CooledSlitControllerConfig {
                 em2rs_low_limit: 0,
                 em2rs_high_limit: 100,
                 steps_per_mm: 100,
+                inverted: false,
+                mode: AxisMode::Hardware,
             },
         }
     }
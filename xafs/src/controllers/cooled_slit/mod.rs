@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use utilities::axis_mode::with_mode;
+
 use crate::command_executor::motor::command_sender::Em2rsCommandSender;
 use crate::controllers::SensorsCommandSender;
 use crate::controllers::cooled_slit::config::CooledSlitControllerConfig;
@@ -33,34 +35,38 @@ pub fn create_controller(
         sensors_command_sender.clone(),
         em2rs_command_sender.clone(),
         config.upper_axis.steps_per_mm,
-    );
+    )
+    .with_inverted(config.upper_axis.inverted);
     let lower_axis = CooledSlitAxis::new(
         "Y_Down".to_string(),
         1,
         sensors_command_sender.clone(),
         em2rs_command_sender.clone(),
         config.lower_axis.steps_per_mm,
-    );
+    )
+    .with_inverted(config.lower_axis.inverted);
     let left_axis = CooledSlitAxis::new(
         "X_Left".to_string(),
         2,
         sensors_command_sender.clone(),
         em2rs_command_sender.clone(),
         config.left_axis.steps_per_mm,
-    );
+    )
+    .with_inverted(config.left_axis.inverted);
     let right_axis = CooledSlitAxis::new(
         "X_Right".to_string(),
         3,
         sensors_command_sender.clone(),
         em2rs_command_sender.clone(),
         config.right_axis.steps_per_mm,
-    );
+    )
+    .with_inverted(config.right_axis.inverted);
 
     let mut controller = CooledSlitController::new();
-    controller.add_axis(Arc::new(upper_axis));
-    controller.add_axis(Arc::new(lower_axis));
-    controller.add_axis(Arc::new(left_axis));
-    controller.add_axis(Arc::new(right_axis));
+    controller.add_axis(with_mode(Arc::new(upper_axis), config.upper_axis.mode));
+    controller.add_axis(with_mode(Arc::new(lower_axis), config.lower_axis.mode));
+    controller.add_axis(with_mode(Arc::new(left_axis), config.left_axis.mode));
+    controller.add_axis(with_mode(Arc::new(right_axis), config.right_axis.mode));
 
     controller
 }
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use trid::TridError;
+use utilities::scheduler::{Poll, PollScheduler, PollTask};
+
+use crate::command_executor::sensors::command_sender::SensorsCommandSender;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// A failing bridge is retried often, the relay is what protects the optics
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+// Keeps the relay from chattering while the temperature hovers at the limit
+const HYSTERESIS: f32 = 1.0;
+
+// Switches the TRID relay on above the limit and off again once the
+// temperature has fallen HYSTERESIS below it. A disconnected probe trips the
+// relay as well. The relay is only written on changes, so a manual switch
+// holds until the temperature crosses a threshold.
+struct Interlock {
+    name: String,
+    sensors_cs: SensorsCommandSender,
+    axis: u8,
+    limit: f32,
+    tripped: Option<bool>,
+}
+
+#[async_trait::async_trait]
+impl Poll for Interlock {
+    async fn poll(&mut self) -> Result<(), String> {
+        let trip = match self.sensors_cs.get_temperature(self.axis).await {
+            Ok(temperature) if temperature > self.limit => Some(true),
+            Ok(temperature) if temperature < self.limit - HYSTERESIS => Some(false),
+            Ok(_) => None,
+            Err(e) if TridError::is_sensor_missing(&e) => Some(true),
+            Err(e) => return Err(format!("failed to read temperature: {}", e)),
+        };
+
+        if let Some(trip) = trip.filter(|trip| self.tripped != Some(*trip)) {
+            self.sensors_cs
+                .set_relay(self.axis, trip)
+                .await
+                .map_err(|e| format!("failed to switch relay: {}", e))?;
+
+            eprintln!(
+                "{} interlock relay switched {}",
+                self.name,
+                if trip { "on" } else { "off" }
+            );
+            self.tripped = Some(trip);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn spawn_interlock(
+    name: String,
+    sensors_cs: SensorsCommandSender,
+    axis: u8,
+    limit: f32,
+) -> JoinHandle<()> {
+    let task_name = format!("{} interlock", name);
+    let interlock = Interlock {
+        name,
+        sensors_cs,
+        axis,
+        limit,
+        tripped: None,
+    };
+
+    PollScheduler::new()
+        .with_task(PollTask::new(task_name, POLL_INTERVAL, interlock).with_max_backoff(MAX_BACKOFF))
+        .spawn()
+}
@@ -11,9 +11,11 @@ use crate::{
         sensors::{SensorsHandler, command_sender::SensorsCommandSender},
     },
     controllers::{
-        attenuator::controller::AttenuatorController, collimator::controller::CollimatorController,
-        config::XafsConfig, cooled_slit::controller::CooledSlitController,
-        water_input::controller::WaterInputController,
+        attenuator::controller::AttenuatorController,
+        collimator::{config::CollimatorConfig, controller::CollimatorController},
+        config::XafsConfig,
+        cooled_slit::controller::CooledSlitController,
+        water_input::{config::WaterInputConfig, controller::WaterInputController},
     },
 };
 
@@ -21,6 +23,7 @@ pub mod attenuator;
 pub mod collimator;
 pub mod config;
 pub mod cooled_slit;
+pub mod interlock;
 pub mod water_input;
 
 const READ_TIMEOUT: Duration = Duration::from_millis(100);
@@ -48,23 +51,28 @@ pub fn create_sensors(
             LIR::new(
                 config.slit_controller.upper_axis.lir_id,
                 config.slit_controller.upper_axis.lir_step,
-            ),
+            )
+            .with_layout(config.lir_layout),
             LIR::new(
                 config.slit_controller.lower_axis.lir_id,
                 config.slit_controller.lower_axis.lir_step,
-            ),
+            )
+            .with_layout(config.lir_layout),
             LIR::new(
                 config.slit_controller.right_axis.lir_id,
                 config.slit_controller.right_axis.lir_step,
-            ),
+            )
+            .with_layout(config.lir_layout),
             LIR::new(
                 config.slit_controller.left_axis.lir_id,
                 config.slit_controller.left_axis.lir_step,
-            ),
+            )
+            .with_layout(config.lir_layout),
             LIR::new(
                 config.attenuator.axis.lir_id,
                 config.attenuator.axis.lir_step,
-            ),
+            )
+            .with_layout(config.lir_layout),
         ],
         vec![
             // Knifes temperature
@@ -102,19 +110,10 @@ pub fn create_sensors(
                 config.slit_controller.left_axis.water_trid_axis,
             ),
             // Water input temperature
-            Trid::new(
-                config.water_input.trid_id,
-                config.water_input.axis.trid_axis,
-            ),
+            water_input_trid(&config.water_input.axis, config.water_input.trid_id),
             // Collimator temperature
-            Trid::new(
-                config.collimator.trid_id,
-                config.collimator.input_axis.trid_axis,
-            ),
-            Trid::new(
-                config.collimator.trid_id,
-                config.collimator.output_axis.trid_axis,
-            ),
+            collimator_trid(&config.collimator.input_axis, config.collimator.trid_id),
+            collimator_trid(&config.collimator.output_axis, config.collimator.trid_id),
         ],
     );
 
@@ -124,6 +123,31 @@ pub fn create_sensors(
     (sensors_command_executor, sensors_command_sender)
 }
 
+fn water_input_trid(config: &WaterInputConfig, trid_id: u8) -> Trid {
+    let mut trid = Trid::new(trid_id, config.trid_axis);
+
+    if let Some(register) = config.setpoint_register {
+        trid = trid.with_setpoint_register(register);
+    }
+    if let Some(register) = config.control_register {
+        trid = trid.with_control_register(register);
+    }
+    if let Some(coil) = config.relay_coil {
+        trid = trid.with_relay_coil(coil);
+    }
+
+    trid
+}
+
+fn collimator_trid(config: &CollimatorConfig, trid_id: u8) -> Trid {
+    let trid = Trid::new(trid_id, config.trid_axis);
+
+    match config.relay_coil {
+        Some(coil) => trid.with_relay_coil(coil),
+        None => trid,
+    }
+}
+
 pub fn create_em2rs(config: &XafsConfig) -> (CommandExecutor<Em2rsHandler>, Em2rsCommandSender) {
     let em2rs_socket_addr = SocketAddr::new(config.em2rs_ip.parse().unwrap(), config.em2rs_port);
     let em2rs_tcp_stream = LazyTcpStream::new(
@@ -173,20 +197,46 @@ pub fn create_em2rs(config: &XafsConfig) -> (CommandExecutor<Em2rsHandler>, Em2r
     (em2rs_command_executor, em2rs_command_sender)
 }
 
+// Mixed drive revisions on one bus show up in the startup log. Answered once
+// the executor runs.
+fn log_em2rs_identities(em2rs_cs: Em2rsCommandSender) {
+    tokio::spawn(async move {
+        for (axis, name) in ["Y_Up", "Y_Down", "X_Left", "X_Right", "Attenuator"]
+            .into_iter()
+            .enumerate()
+        {
+            match em2rs_cs.identify(axis).await {
+                Ok(identity) => eprintln!("EM2RS {}: {}", name, identity),
+                Err(e) => eprintln!("Failed to identify EM2RS {}: {}", name, e),
+            }
+        }
+    });
+}
+
 pub fn create_controllers(
     config: &XafsConfig,
-) -> (
-    CollimatorController,
-    CooledSlitController,
-    AttenuatorController,
-    WaterInputController,
-    CommandExecutor<Em2rsHandler>,
-    CommandExecutor<SensorsHandler>,
-) {
+) -> Result<
+    (
+        CollimatorController,
+        CooledSlitController,
+        AttenuatorController,
+        WaterInputController,
+        CommandExecutor<Em2rsHandler>,
+        CommandExecutor<SensorsHandler>,
+    ),
+    String,
+> {
+    config
+        .lir_layout
+        .validate()
+        .map_err(|e| format!("Invalid lir_layout: {}", e))?;
+
     let (em2rs_command_executor, em2rs_command_sender) = create_em2rs(config);
     let (sensors_command_executor, sensors_command_sender) = create_sensors(config);
+    log_em2rs_identities(em2rs_command_sender.clone());
 
-    let collimator_controller = collimator::create_controller(sensors_command_sender.clone());
+    let collimator_controller =
+        collimator::create_controller(&config.collimator, sensors_command_sender.clone());
     let slit_controller = cooled_slit::create_controller(
         &config.slit_controller,
         em2rs_command_sender.clone(),
@@ -197,14 +247,15 @@ pub fn create_controllers(
         em2rs_command_sender.clone(),
         sensors_command_sender.clone(),
     );
-    let water_input_controller = water_input::create_controller(sensors_command_sender.clone());
+    let water_input_controller =
+        water_input::create_controller(&config.water_input, sensors_command_sender.clone());
 
-    (
+    Ok((
         collimator_controller,
         slit_controller,
         attenuator_controller,
         water_input_controller,
         em2rs_command_executor,
         sensors_command_executor,
-    )
+    ))
 }
@@ -2,15 +2,20 @@ use motarem::axis::{
     Axis, limit_switches::LimitSwitches, movement_parameters::MovementParams, state::AxisState,
     state_info::AxisStateInfo,
 };
+use trid::TridError;
 use utilities::motor_controller::MotorHolder;
 
-use crate::command_executor::sensors::command_sender::SensorsCommandSender;
+use crate::{
+    command_executor::sensors::command_sender::SensorsCommandSender,
+    controllers::water_input::condensation::Condensation,
+};
 
 pub struct WaterInputAxis {
     pub name: String,
     axis: usize,
 
     sensors_cs: SensorsCommandSender,
+    condensation: Option<Condensation>,
 }
 
 impl WaterInputAxis {
@@ -19,15 +24,74 @@ impl WaterInputAxis {
             name,
             axis,
             sensors_cs,
+            condensation: None,
         }
     }
 
+    pub fn with_condensation(mut self, condensation: Condensation) -> Self {
+        self.condensation = Some(condensation);
+        self
+    }
+
+    fn condensation(&self) -> Result<&Condensation, String> {
+        self.condensation
+            .as_ref()
+            .ok_or_else(|| "Ambient conditions are not configured".to_string())
+    }
+
+    pub async fn is_condensation_risk(&self) -> Result<bool, String> {
+        let condensation = self.condensation()?;
+        Ok(condensation.is_at_risk(self.get_temperature().await?))
+    }
+
     pub async fn get_temperature(&self) -> Result<f32, String> {
         self.sensors_cs
             .get_temperature(self.axis as u8)
             .await
             .map_err(|e| format!("Failed to get temperature: {}", e))
     }
+
+    pub async fn get_setpoint(&self) -> Result<f32, String> {
+        self.sensors_cs
+            .get_setpoint(self.axis as u8)
+            .await
+            .map_err(|e| format!("Failed to get setpoint: {}", e))
+    }
+
+    pub async fn set_setpoint(&self, setpoint: f32) -> Result<(), String> {
+        self.sensors_cs
+            .set_setpoint(self.axis as u8, setpoint)
+            .await
+            .map_err(|e| format!("Failed to set setpoint: {}", e))
+    }
+
+    pub async fn is_control_enabled(&self) -> Result<bool, String> {
+        self.sensors_cs
+            .is_control_enabled(self.axis as u8)
+            .await
+            .map_err(|e| format!("Failed to get control state: {}", e))
+    }
+
+    pub async fn set_control_enabled(&self, enabled: bool) -> Result<(), String> {
+        self.sensors_cs
+            .set_control_enabled(self.axis as u8, enabled)
+            .await
+            .map_err(|e| format!("Failed to switch control: {}", e))
+    }
+
+    pub async fn is_relay_on(&self) -> Result<bool, String> {
+        self.sensors_cs
+            .is_relay_on(self.axis as u8)
+            .await
+            .map_err(|e| format!("Failed to get relay state: {}", e))
+    }
+
+    pub async fn set_relay(&self, on: bool) -> Result<(), String> {
+        self.sensors_cs
+            .set_relay(self.axis as u8, on)
+            .await
+            .map_err(|e| format!("Failed to switch relay: {}", e))
+    }
 }
 
 #[async_trait::async_trait]
@@ -36,11 +100,28 @@ impl Axis for WaterInputAxis {
         &self.name
     }
 
-    async fn start(
-        &self,
-        _position: f64,
-        _parameters: Option<MovementParams>,
-    ) -> anyhow::Result<()> {
+    // Moving the axis commands the bath setpoint, "control" switches the
+    // regulator on (1) or off (0) and "relay" the interlock output
+    async fn start(&self, position: f64, parameters: Option<MovementParams>) -> anyhow::Result<()> {
+        self.set_setpoint(position as f32)
+            .await
+            .map_err(anyhow::Error::msg)?;
+
+        if let Some(control) = parameters
+            .as_ref()
+            .and_then(|p| p.custom.get("control").copied())
+        {
+            self.set_control_enabled(control != 0.0)
+                .await
+                .map_err(anyhow::Error::msg)?;
+        }
+
+        if let Some(relay) = parameters.and_then(|p| p.custom.get("relay").copied()) {
+            self.set_relay(relay != 0.0)
+                .await
+                .map_err(anyhow::Error::msg)?;
+        }
+
         Ok(())
     }
 
@@ -49,9 +130,28 @@ impl Axis for WaterInputAxis {
     }
 
     async fn get_state(&self) -> anyhow::Result<AxisStateInfo> {
+        // Only a disconnected probe or a condensation risk changes the state,
+        // other read failures surface through the attributes
+        let message = match self.sensors_cs.get_temperature(self.axis as u8).await {
+            Err(e) if TridError::is_sensor_missing(&e) => {
+                Some("Temperature sensor missing".to_string())
+            }
+            Ok(temperature) => self
+                .condensation
+                .filter(|condensation| condensation.is_at_risk(temperature))
+                .map(|condensation| {
+                    format!(
+                        "Condensation risk: water at {:.1}, dew point {:.1}",
+                        temperature,
+                        condensation.dew_point()
+                    )
+                }),
+            _ => None,
+        };
+
         Ok(AxisStateInfo {
             state: AxisState::On,
-            message: None,
+            message,
             limit_switches: LimitSwitches::None,
         })
     }
@@ -68,16 +168,47 @@ impl Axis for WaterInputAxis {
                 .await
                 .map(|temp| temp as f64)
                 .map_err(|err| anyhow::Error::msg(format!("Failed to get temperature: {}", err))),
+            "setpoint" => self
+                .get_setpoint()
+                .await
+                .map(|setpoint| setpoint as f64)
+                .map_err(anyhow::Error::msg),
+            "control" => self
+                .is_control_enabled()
+                .await
+                .map(|enabled| enabled as u8 as f64)
+                .map_err(anyhow::Error::msg),
+            "dew_point" => self
+                .condensation()
+                .map(|condensation| condensation.dew_point() as f64)
+                .map_err(anyhow::Error::msg),
+            "condensation_risk" => self
+                .is_condensation_risk()
+                .await
+                .map(|risk| risk as u8 as f64)
+                .map_err(anyhow::Error::msg),
+            "relay" => self
+                .is_relay_on()
+                .await
+                .map(|on| on as u8 as f64)
+                .map_err(anyhow::Error::msg),
             _ => Err(anyhow::Error::msg(format!("Unknown attribute: {}", name))),
         }
     }
 
     async fn get_available_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec!["temperature".to_string()])
+        Ok(vec![
+            "temperature".to_string(),
+            "setpoint".to_string(),
+            "control".to_string(),
+            "relay".to_string(),
+            "dew_point".to_string(),
+            "condensation_risk".to_string(),
+        ])
     }
 
     async fn get_supported_movement_params(&self) -> anyhow::Result<Vec<String>> {
-        Ok(vec![])
+        Ok(vec!["control".to_string(), "relay".to_string()])
     }
 }
 
@@ -0,0 +1,34 @@
+use crate::controllers::water_input::config::AmbientConfig;
+
+// Magnus formula coefficients, good to a few tenths of a degree between
+// -40 and 50 degrees
+const MAGNUS_B: f32 = 17.62;
+const MAGNUS_C: f32 = 243.12;
+
+// Condensation check for the cooling water. The hutch has no humidity probe
+// wired in, so the ambient conditions come from the configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct Condensation {
+    dew_point: f32,
+    margin: f32,
+}
+
+impl Condensation {
+    pub fn new(config: &AmbientConfig) -> Self {
+        let humidity = config.humidity.clamp(1.0, 100.0) / 100.0;
+        let gamma = humidity.ln() + MAGNUS_B * config.temperature / (MAGNUS_C + config.temperature);
+
+        Self {
+            dew_point: MAGNUS_C * gamma / (MAGNUS_B - gamma),
+            margin: config.margin,
+        }
+    }
+
+    pub fn dew_point(&self) -> f32 {
+        self.dew_point
+    }
+
+    pub fn is_at_risk(&self, water_temperature: f32) -> bool {
+        water_temperature < self.dew_point + self.margin
+    }
+}
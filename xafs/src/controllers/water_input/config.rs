@@ -1,21 +1,60 @@
 use serde::{Deserialize, Serialize};
+use utilities::axis_mode::AxisMode;
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct WaterInputConfig {
     pub trid_axis: u16,
+    // Set on regulator TRIDs driving the water bath
+    #[serde(default)]
+    pub setpoint_register: Option<u16>,
+    #[serde(default)]
+    pub control_register: Option<u16>,
+    // Interlock relay, switched on above relay_limit
+    #[serde(default)]
+    pub relay_coil: Option<u16>,
+    #[serde(default)]
+    pub relay_limit: Option<f32>,
+
+    // hardware, simulated or disabled, e.g. while the device is out for repairs
+    #[serde(default)]
+    pub mode: AxisMode,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct AmbientConfig {
+    // Degrees and percent relative humidity at the slit
+    pub temperature: f32,
+    pub humidity: f32,
+    // Warn this many degrees before the water reaches the dew point
+    #[serde(default = "default_margin")]
+    pub margin: f32,
+}
+
+fn default_margin() -> f32 {
+    2.0
 }
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct WaterInputControllerConfig {
     pub trid_id: u8,
     pub axis: WaterInputConfig,
+    #[serde(default)]
+    pub ambient: Option<AmbientConfig>,
 }
 
 impl Default for WaterInputControllerConfig {
     fn default() -> Self {
         Self {
             trid_id: 1,
-            axis: WaterInputConfig { trid_axis: 1 },
+            axis: WaterInputConfig {
+                trid_axis: 1,
+                setpoint_register: None,
+                control_register: None,
+                relay_coil: None,
+                relay_limit: None,
+                mode: AxisMode::Hardware,
+            },
+            ambient: None,
         }
     }
 }
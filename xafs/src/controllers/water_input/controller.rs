@@ -1,14 +1,23 @@
 use std::sync::Arc;
 
 use motarem::{axis::Axis, motor_controller::MotorController};
+use tokio::task::JoinHandle;
 
 pub struct WaterInputController {
     axis: Arc<dyn Axis>,
+    interlocks: Vec<JoinHandle<()>>,
 }
 
 impl WaterInputController {
     pub fn new(axis: Arc<dyn Axis>) -> Self {
-        Self { axis }
+        Self {
+            axis,
+            interlocks: Vec::new(),
+        }
+    }
+
+    pub fn add_interlock(&mut self, interlock: JoinHandle<()>) {
+        self.interlocks.push(interlock);
     }
 }
 
@@ -25,6 +34,10 @@ impl MotorController for WaterInputController {
     async fn shutdown(&self) -> anyhow::Result<()> {
         self.axis.stop().await?;
 
+        for interlock in &self.interlocks {
+            interlock.abort();
+        }
+
         Ok(())
     }
 }
@@ -1,21 +1,45 @@
 use std::sync::Arc;
 
+use utilities::axis_mode::{AxisMode, with_mode};
+
 use crate::{
     command_executor::sensors::command_sender::SensorsCommandSender,
-    controllers::water_input::{axis::WaterInputAxis, controller::WaterInputController},
+    controllers::{
+        interlock::spawn_interlock,
+        water_input::{
+            axis::WaterInputAxis, condensation::Condensation, config::WaterInputControllerConfig,
+            controller::WaterInputController,
+        },
+    },
 };
 
 pub mod axis;
+pub mod condensation;
 pub mod config;
 pub mod controller;
 
 pub fn create_controller(
-    // config: &WaterInputControllerConfig,
+    config: &WaterInputControllerConfig,
     sensors_command_sender: SensorsCommandSender,
 ) -> WaterInputController {
-    let axis = WaterInputAxis::new("Temperature".to_string(), 8, sensors_command_sender);
+    // Index of the water input TRID in the sensors handler
+    let mut axis =
+        WaterInputAxis::new("Temperature".to_string(), 8, sensors_command_sender.clone());
+    if let Some(ambient) = &config.ambient {
+        axis = axis.with_condensation(Condensation::new(ambient));
+    }
+
+    let mut controller = WaterInputController::new(with_mode(Arc::new(axis), config.axis.mode));
 
-    let controller = WaterInputController::new(Arc::new(axis));
+    // The interlock reads the device itself
+    if let (Some(limit), AxisMode::Hardware) = (config.axis.relay_limit, config.axis.mode) {
+        controller.add_interlock(spawn_interlock(
+            "Water input".to_string(),
+            sensors_command_sender,
+            8,
+            limit,
+        ));
+    }
 
     controller
 }
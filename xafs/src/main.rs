@@ -9,12 +9,19 @@ use motarem::{
     socket_server::{SocketServer, config::SocketServerConfig},
 };
 use std::{path::PathBuf, sync::Arc, time::Duration};
+use utilities::{
+    archiver::start_archiver, build_info, build_info::with_build_info, clock,
+    derived::with_derived_attributes, groups::with_axis_groups, observer::start_observer_socket,
+    readiness::wait_until_ready, systemd,
+};
 
 pub mod command_executor;
 pub mod config;
 pub mod controllers;
 pub mod logging;
 
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
 fn should_create_config() -> bool {
     std::env::var("CREATE_CONFIG")
         .map(|val| val == "1" || val.to_lowercase() == "true")
@@ -25,6 +32,11 @@ fn should_create_config() -> bool {
 async fn main() -> anyhow::Result<()> {
     logging::init();
 
+    if build_info::version_requested() {
+        println!("{}", build_info!());
+        return Ok(());
+    }
+
     if should_create_config() {
         create_default_config(None::<PathBuf>)?;
     }
@@ -35,6 +47,12 @@ async fn main() -> anyhow::Result<()> {
         e
     })?;
 
+    let build = build_info!()
+        .with_feature("simulation", clock::time_scale() != 1.0)
+        .with_feature("observer", config.observer_socket.is_some())
+        .with_feature("archiver", config.archiver.is_some());
+    eprintln!("Starting {}", build);
+
     let (
         collimator,
         cooled_slit,
@@ -42,7 +60,7 @@ async fn main() -> anyhow::Result<()> {
         water_input,
         mut em2rs_command_executor,
         mut sensors_command_executor,
-    ) = create_controllers(&config);
+    ) = create_controllers(&config).map_err(anyhow::Error::msg)?;
 
     let manager_config = ManagerConfig {
         default_ttl: Duration::from_secs(1),
@@ -51,18 +69,26 @@ async fn main() -> anyhow::Result<()> {
 
     let manager = Arc::new(ControllerManager::new(manager_config));
 
-    manager
-        .register_controller(collimator.name().to_string(), Arc::new(collimator))
-        .await?;
-    manager
-        .register_controller(cooled_slit.name().to_string(), Arc::new(cooled_slit))
-        .await?;
-    manager
-        .register_controller(attenuator.name().to_string(), Arc::new(attenuator))
-        .await?;
-    manager
-        .register_controller(water_input.name().to_string(), Arc::new(water_input))
-        .await?;
+    let controllers: [Arc<dyn MotorController>; 4] = [
+        Arc::new(collimator),
+        Arc::new(cooled_slit),
+        Arc::new(attenuator),
+        Arc::new(water_input),
+    ];
+
+    let mut registered = Vec::new();
+    for controller in controllers {
+        let controller = with_derived_attributes(controller, &config.derived_attributes)
+            .map_err(anyhow::Error::msg)?;
+        let controller =
+            with_axis_groups(controller, &config.axis_groups).map_err(anyhow::Error::msg)?;
+        let controller = with_build_info(controller, &build).map_err(anyhow::Error::msg)?;
+
+        manager
+            .register_controller(controller.name().to_string(), controller.clone())
+            .await?;
+        registered.push(controller);
+    }
 
     let socket_config = SocketServerConfig {
         socket_path: "/tmp/xafs_controller.sock".to_string(),
@@ -73,9 +99,26 @@ async fn main() -> anyhow::Result<()> {
     let sensors_handle = tokio::task::spawn_blocking(move || sensors_command_executor.run());
     let em2rs_handle = tokio::task::spawn_blocking(move || em2rs_command_executor.run());
 
+    // Clients connect once every axis answered or the probe gave up, not while
+    // the executors are still reaching their devices
+    systemd::status("Waiting for axes");
+    wait_until_ready(&registered, READY_TIMEOUT).await;
+
     let mut socket_server = SocketServer::new(socket_config, manager.clone());
     socket_server.start().await?;
 
+    let _archiver = match &config.archiver {
+        Some(archiver) => Some(start_archiver(archiver, &registered).map_err(anyhow::Error::msg)?),
+        None => None,
+    };
+
+    let _observer_server = match &config.observer_socket {
+        Some(socket_path) => Some(start_observer_socket(socket_path, &registered).await?),
+        None => None,
+    };
+
+    systemd::ready();
+
     let _sensors_handle = sensors_handle.await?;
     let _em2rs_handle = em2rs_handle.await?;
 